@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+use crate::EdgeCase;
+
+/// Iterator of pathological transaction rows, to exercise every rejection and
+/// record error in `transact`.
+///
+/// Each step generates the rows of one [`EdgeCase`] for its own client,
+/// cycling through [`EdgeCase::ALL`]. Rows may not be valid transactions, so
+/// they are returned as CSV lines without a trailing newline.
+#[derive(Debug)]
+pub struct AdversarialRows {
+    /// Number of steps to generate, each of which produces one edge case.
+    steps: u64,
+    /// Index of the next step.
+    step: u64,
+    /// Next transaction ID to use.
+    tx: u32,
+    /// Rows generated by the current step that have not been returned.
+    pending: VecDeque<String>,
+}
+
+impl AdversarialRows {
+    /// Number of transaction IDs reserved for each step.
+    const TX_IDS_PER_STEP: u32 = 4;
+
+    /// Returns a new `AdversarialRows`.
+    ///
+    /// # Parameters
+    ///
+    /// * `steps`: Number of edge cases to generate. Every edge case is
+    ///   generated when this is at least the length of [`EdgeCase::ALL`].
+    pub fn new(steps: u64) -> Self {
+        Self {
+            steps,
+            step: 0,
+            tx: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Returns the number of steps generated so far.
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+}
+
+impl Iterator for AdversarialRows {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.pending.is_empty() && self.step < self.steps {
+            let edge_case = EdgeCase::ALL[(self.step % EdgeCase::ALL.len() as u64) as usize];
+            // Clients wrap around after `u16::MAX` steps, so later edge cases
+            // may also be affected by earlier ones.
+            let client = self.step as u16;
+            self.pending.extend(edge_case.rows(client, self.tx));
+
+            // Transaction IDs wrap around after `u32::MAX` rows.
+            self.tx = self.tx.wrapping_add(Self::TX_IDS_PER_STEP);
+            self.step += 1;
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdversarialRows;
+    use crate::EdgeCase;
+
+    #[test]
+    fn generates_each_edge_case_for_its_own_client() {
+        let rows = AdversarialRows::new(EdgeCase::ALL.len() as u64).collect::<Vec<_>>();
+
+        assert_eq!(Some(&"deposit, 0, 0, 1.0".to_string()), rows.first());
+        assert!(rows.contains(&format!("deposit, 5, 20, {}", EdgeCase::AMOUNT_NEAR_MAX)));
+        assert_eq!(Some(&"deposit, 8, 35, one".to_string()), rows.last());
+    }
+}
@@ -0,0 +1,88 @@
+use std::{fmt, str::FromStr};
+
+/// Amount of a deposit or withdrawal, with four decimal places of precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Amount(u64);
+
+impl Amount {
+    /// Number of units in `1.0`.
+    pub const UNITS_PER_WHOLE: u64 = 10_000;
+
+    /// Returns an amount of whole units, e.g. `150` for `150.0`.
+    pub const fn from_whole(whole: u64) -> Self {
+        Self(whole * Self::UNITS_PER_WHOLE)
+    }
+
+    /// Returns an amount in ten-thousandths, e.g. `15` for `0.0015`.
+    pub const fn from_units(units: u64) -> Self {
+        Self(units)
+    }
+
+    /// Returns the amount in ten-thousandths.
+    pub const fn units(self) -> u64 {
+        self.0
+    }
+
+    /// Returns the sum of the amounts, or `None` on overflow.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Returns the difference of the amounts, or `None` if `other` is
+    /// greater.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+}
+
+impl fmt::Display for Amount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let whole = self.0 / Self::UNITS_PER_WHOLE;
+        let fraction = self.0 % Self::UNITS_PER_WHOLE;
+        if fraction == 0 {
+            write!(f, "{whole}.0")
+        } else {
+            let fraction = format!("{fraction:04}");
+            write!(f, "{whole}.{}", fraction.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid amount `{s}`, expected e.g. `150.0` or `0.0015`.");
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if fraction.len() > 4 || !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let whole = whole.parse::<u64>().map_err(|_| invalid())?;
+        let fraction = format!("{fraction:0<4}")
+            .parse::<u64>()
+            .map_err(|_| invalid())?;
+
+        whole
+            .checked_mul(Self::UNITS_PER_WHOLE)
+            .and_then(|units| units.checked_add(fraction))
+            .map(Self)
+            .ok_or_else(invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Amount;
+
+    #[test]
+    fn from_str_parses_display_output() -> Result<(), String> {
+        ["150.0", "0.0015", "12.5"].into_iter().try_for_each(|s| {
+            assert_eq!(s, s.parse::<Amount>()?.to_string());
+            Ok::<_, String>(())
+        })?;
+        assert_eq!(Amount::from_whole(7), "7".parse::<Amount>()?);
+        assert!("1.00001".parse::<Amount>().is_err());
+        assert!("-1.0".parse::<Amount>().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+
+use crate::Amount;
+
+/// Range that amounts are chosen from uniformly, inclusive of both ends.
+///
+/// Parsed from `min..max`, e.g. `10.0..150.0`, or a single amount for a fixed
+/// amount.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AmountRange {
+    /// Smallest amount.
+    min: Amount,
+    /// Largest amount.
+    max: Amount,
+}
+
+impl AmountRange {
+    /// Returns a new `AmountRange`, or `None` if `min` is greater than `max`.
+    pub fn new(min: Amount, max: Amount) -> Option<Self> {
+        (min <= max).then_some(Self { min, max })
+    }
+
+    /// Returns a range with a single amount.
+    pub const fn fixed(amount: Amount) -> Self {
+        Self {
+            min: amount,
+            max: amount,
+        }
+    }
+
+    /// Returns the smallest amount.
+    pub fn min(self) -> Amount {
+        self.min
+    }
+
+    /// Returns the largest amount.
+    pub fn max(self) -> Amount {
+        self.max
+    }
+
+    /// Returns an amount within this range.
+    pub(crate) fn sample<R>(self, rng: &mut R) -> Amount
+    where
+        R: Rng,
+    {
+        if self.min == self.max {
+            self.min
+        } else {
+            Amount::from_units(rng.random_range(self.min.units()..=self.max.units()))
+        }
+    }
+}
+
+impl fmt::Display for AmountRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}..{}", self.min, self.max)
+        }
+    }
+}
+
+impl FromStr for AmountRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once("..") {
+            Some((min, max)) => Self::new(min.parse()?, max.parse()?)
+                .ok_or_else(|| format!("Amount range `{s}` has a minimum above its maximum.")),
+            None => s.parse().map(Self::fixed),
+        }
+    }
+}
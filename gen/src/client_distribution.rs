@@ -0,0 +1,47 @@
+use std::{fmt, str::FromStr};
+
+/// How transactions after the initial deposits are spread across clients.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ClientDistribution {
+    /// Each client is equally likely to be chosen.
+    #[default]
+    Uniform,
+    /// Clients with lower IDs are chosen more often, following Zipf's law.
+    ///
+    /// The nth client is chosen with a probability proportional to
+    /// `1 / n^exponent`, so a few clients receive most transactions, as in
+    /// real payment traffic. An exponent of `1.0` is typical.
+    Zipf {
+        /// Skew of the distribution. Higher values concentrate transactions
+        /// on fewer clients.
+        exponent: f64,
+    },
+}
+
+impl fmt::Display for ClientDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Uniform => write!(f, "uniform"),
+            Self::Zipf { exponent } => write!(f, "zipf:{exponent}"),
+        }
+    }
+}
+
+impl FromStr for ClientDistribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!("Unknown client distribution `{s}`, expected `uniform`, `zipf`, or `zipf:<exponent>`.")
+        };
+        match s.split_once(':') {
+            None if s == "uniform" => Ok(Self::Uniform),
+            None if s == "zipf" => Ok(Self::Zipf { exponent: 1.0 }),
+            Some(("zipf", exponent)) => exponent
+                .parse::<f64>()
+                .map(|exponent| Self::Zipf { exponent })
+                .map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+/// Pathological records generated by [`AdversarialRows`].
+///
+/// Each edge case produces a group of rows for one client, so that each
+/// rejection and record error that a transactions file can cause is reached
+/// regardless of the other rows.
+///
+/// [`AdversarialRows`]: crate::AdversarialRows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeCase {
+    /// Two deposits with the same transaction ID.
+    DuplicateTxId,
+    /// Dispute of a deposit that comes after it.
+    DisputeBeforeDeposit,
+    /// Dispute, resolve, and chargeback of another client's deposit.
+    ///
+    /// The resolve and chargeback are rejected as not in dispute, as the
+    /// other client's dispute is rejected.
+    OtherClient,
+    /// Resolve and chargeback of a deposit that is not disputed.
+    NotInDispute,
+    /// Withdrawal and dispute of more than the available funds.
+    InsufficientFunds,
+    /// Deposits that overflow the available and total funds.
+    AmountNearMax,
+    /// Deposit and withdrawal of negative amounts.
+    NegativeAmount,
+    /// Deposit to an account locked by a chargeback.
+    LockedAccount,
+    /// Rows that cannot be parsed, or are missing amounts.
+    MalformedRow,
+}
+
+impl EdgeCase {
+    /// All edge cases, in the order they are generated.
+    pub const ALL: [Self; 9] = [
+        Self::DuplicateTxId,
+        Self::DisputeBeforeDeposit,
+        Self::OtherClient,
+        Self::NotInDispute,
+        Self::InsufficientFunds,
+        Self::AmountNearMax,
+        Self::NegativeAmount,
+        Self::LockedAccount,
+        Self::MalformedRow,
+    ];
+
+    /// Amount more than half of `Decimal::MAX`, so two of these overflow.
+    ///
+    /// Amounts are read as floats, so `Decimal::MAX` itself is out of range
+    /// when parsed.
+    pub const AMOUNT_NEAR_MAX: &'static str = "50000000000000000000000000000.0";
+
+    /// Returns the rows for this edge case.
+    ///
+    /// # Parameters
+    ///
+    /// * `client`: Client whose account the rows are applied to.
+    /// * `tx`: First transaction ID to use. IDs from `tx` to `tx + 3` may be
+    ///   used.
+    pub fn rows(self, client: u16, tx: u32) -> Vec<String> {
+        let other_client = client.wrapping_add(1);
+        let [tx_1, tx_2, tx_3, tx_4] = [tx, tx + 1, tx + 2, tx + 3];
+        let max = Self::AMOUNT_NEAR_MAX;
+
+        match self {
+            Self::DuplicateTxId => vec![
+                format!("deposit, {client}, {tx_1}, 1.0"),
+                format!("deposit, {client}, {tx_1}, 2.0"),
+            ],
+            Self::DisputeBeforeDeposit => vec![
+                format!("dispute, {client}, {tx_1},"),
+                format!("deposit, {client}, {tx_1}, 1.0"),
+            ],
+            Self::OtherClient => vec![
+                format!("deposit, {client}, {tx_1}, 1.0"),
+                format!("dispute, {other_client}, {tx_1},"),
+                format!("dispute, {client}, {tx_1},"),
+                format!("resolve, {other_client}, {tx_1},"),
+                format!("chargeback, {other_client}, {tx_1},"),
+            ],
+            Self::NotInDispute => vec![
+                format!("deposit, {client}, {tx_1}, 1.0"),
+                format!("resolve, {client}, {tx_1},"),
+                format!("chargeback, {client}, {tx_1},"),
+            ],
+            Self::InsufficientFunds => vec![
+                format!("deposit, {client}, {tx_1}, 1.0"),
+                format!("withdrawal, {client}, {tx_2}, 2.0"),
+                format!("withdrawal, {client}, {tx_3}, 1.0"),
+                format!("dispute, {client}, {tx_1},"),
+            ],
+            Self::AmountNearMax => vec![
+                format!("deposit, {client}, {tx_1}, {max}"),
+                format!("deposit, {client}, {tx_2}, {max}"),
+                format!("dispute, {client}, {tx_1},"),
+                format!("deposit, {client}, {tx_3}, {max}"),
+            ],
+            Self::NegativeAmount => vec![
+                format!("deposit, {client}, {tx_1}, -1.0"),
+                format!("withdrawal, {client}, {tx_2}, -1.0"),
+            ],
+            Self::LockedAccount => vec![
+                format!("deposit, {client}, {tx_1}, 1.0"),
+                format!("dispute, {client}, {tx_1},"),
+                format!("chargeback, {client}, {tx_1},"),
+                format!("deposit, {client}, {tx_2}, 1.0"),
+            ],
+            Self::MalformedRow => vec![
+                format!("deposit, {client}, {tx_1},"),
+                format!("withdrawal, {client}, {tx_2},"),
+                format!("refund, {client}, {tx_3}, 1.0"),
+                format!("deposit, client, {tx_4}, 1.0"),
+                format!("deposit, {client}, {tx_4}, one"),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for EdgeCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateTxId => write!(f, "duplicate-tx-id"),
+            Self::DisputeBeforeDeposit => write!(f, "dispute-before-deposit"),
+            Self::OtherClient => write!(f, "other-client"),
+            Self::NotInDispute => write!(f, "not-in-dispute"),
+            Self::InsufficientFunds => write!(f, "insufficient-funds"),
+            Self::AmountNearMax => write!(f, "amount-near-max"),
+            Self::NegativeAmount => write!(f, "negative-amount"),
+            Self::LockedAccount => write!(f, "locked-account"),
+            Self::MalformedRow => write!(f, "malformed-row"),
+        }
+    }
+}
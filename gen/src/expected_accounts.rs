@@ -0,0 +1,251 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+};
+
+use crate::{Amount, TxKind, TxRow};
+
+/// Reference computation of the final accounts for generated rows.
+///
+/// Rows are applied with plain integer arithmetic on [`Amount`]s, following
+/// the same rules as `transact`, so its output can be compared against this
+/// for workloads that are too large to check by hand:
+///
+/// * Rows for locked accounts are rejected.
+/// * Withdrawals and disputes are rejected when the available amount is too
+///   low.
+/// * Disputes are rejected when the transaction is already in dispute.
+/// * Resolves and chargebacks are rejected when the transaction is not in
+///   dispute for the client.
+/// * Chargebacks lock the account.
+///
+/// The client and amount of each deposit are kept to look up when processing
+/// disputes.
+///
+/// # Examples
+///
+/// ```rust
+/// use gen::{ExpectedAccounts, Scenario};
+///
+/// let scenario = Scenario::builder().clients(10).rows(100).build()?;
+/// let expected_accounts = ExpectedAccounts::from_rows(scenario.rows());
+///
+/// assert_eq!(10, expected_accounts.accounts().count());
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExpectedAccounts {
+    /// Accounts by client ID.
+    accounts: BTreeMap<u16, ExpectedAccount>,
+    /// Transactions in dispute, by client ID.
+    disputed_txs: HashMap<u16, HashSet<u32>>,
+    /// Client and amount of each deposit, by transaction ID.
+    deposits: HashMap<u32, (u16, Amount)>,
+}
+
+impl ExpectedAccounts {
+    /// Header row of an accounts CSV file, as written by `transact`.
+    pub const HEADER: &'static str = "client,available,held,total,locked";
+
+    /// Returns empty `ExpectedAccounts`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the expected accounts after applying all rows.
+    pub fn from_rows<I>(tx_rows: I) -> Self
+    where
+        I: IntoIterator<Item = TxRow>,
+    {
+        let mut expected_accounts = Self::new();
+        tx_rows.into_iter().for_each(|tx_row| {
+            expected_accounts.apply(&tx_row);
+        });
+        expected_accounts
+    }
+
+    /// Applies a row, returning whether it was accepted.
+    ///
+    /// Amounts that do not fit in an [`Amount`] are rejected, whereas
+    /// `transact` only rejects amounts beyond its decimal range.
+    pub fn apply(&mut self, tx_row: &TxRow) -> bool {
+        let TxRow {
+            kind,
+            client,
+            tx,
+            amount,
+        } = *tx_row;
+
+        if let (TxKind::Deposit, Some(amount)) = (kind, amount) {
+            self.deposits.entry(tx).or_insert((client, amount));
+        }
+
+        let account = self
+            .accounts
+            .entry(client)
+            .or_insert_with(|| ExpectedAccount::new(client));
+        if account.locked {
+            return false;
+        }
+
+        let disputed_txs = self.disputed_txs.entry(client).or_default();
+        let disputed_amount = || {
+            self.deposits
+                .get(&tx)
+                .filter(|(deposit_client, _)| *deposit_client == client)
+                .map(|(_, amount)| *amount)
+        };
+
+        match kind {
+            TxKind::Deposit => amount
+                .filter(|amount| account.total().checked_add(*amount).is_some())
+                .and_then(|amount| account.available.checked_add(amount))
+                .map(|available| account.available = available)
+                .is_some(),
+            TxKind::Withdrawal => amount
+                .and_then(|amount| account.available.checked_sub(amount))
+                .map(|available| account.available = available)
+                .is_some(),
+            TxKind::Dispute => (!disputed_txs.contains(&tx))
+                .then(disputed_amount)
+                .flatten()
+                .and_then(|amount| {
+                    let available = account.available.checked_sub(amount)?;
+                    let held = account.held.checked_add(amount)?;
+                    Some((available, held))
+                })
+                .map(|(available, held)| {
+                    account.available = available;
+                    account.held = held;
+                    disputed_txs.insert(tx);
+                })
+                .is_some(),
+            TxKind::Resolve | TxKind::Chargeback => disputed_txs
+                .contains(&tx)
+                .then(disputed_amount)
+                .flatten()
+                .and_then(|amount| {
+                    let held = account.held.checked_sub(amount)?;
+                    let available = match kind {
+                        TxKind::Resolve => account.available.checked_add(amount)?,
+                        _ => account.available,
+                    };
+                    Some((available, held))
+                })
+                .map(|(available, held)| {
+                    account.available = available;
+                    account.held = held;
+                    account.locked = kind == TxKind::Chargeback;
+                    disputed_txs.remove(&tx);
+                })
+                .is_some(),
+        }
+    }
+
+    /// Returns the accounts, ordered by client ID.
+    pub fn accounts(&self) -> impl Iterator<Item = &ExpectedAccount> {
+        self.accounts.values()
+    }
+
+    /// Returns the account for a client, if it has any rows.
+    pub fn account(&self, client: u16) -> Option<&ExpectedAccount> {
+        self.accounts.get(&client)
+    }
+}
+
+/// Expected final state of a client's account.
+///
+/// The [`Display`] implementation writes the row in the format of a
+/// `transact` accounts CSV file, without a trailing newline, e.g.
+/// `1,140.0,10.0,150.0,false`.
+///
+/// [`Display`]: std::fmt::Display
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpectedAccount {
+    /// Client ID.
+    pub client: u16,
+    /// Amount that may be withdrawn.
+    pub available: Amount,
+    /// Amount held by disputes.
+    pub held: Amount,
+    /// Whether the account is locked by a chargeback.
+    pub locked: bool,
+}
+
+impl ExpectedAccount {
+    /// Returns an empty, unlocked account.
+    fn new(client: u16) -> Self {
+        Self {
+            client,
+            available: Amount::from_units(0),
+            held: Amount::from_units(0),
+            locked: false,
+        }
+    }
+
+    /// Returns the sum of the available and held amounts.
+    pub fn total(&self) -> Amount {
+        // Deposits that would overflow the total are rejected.
+        Amount::from_units(self.available.units().saturating_add(self.held.units()))
+    }
+}
+
+impl fmt::Display for ExpectedAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            client,
+            available,
+            held,
+            locked,
+        } = self;
+        let total = self.total();
+        write!(f, "{client},{available},{held},{total},{locked}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ExpectedAccounts;
+    use crate::{Amount, TxKind, TxRow};
+
+    fn row(kind: TxKind, tx: u32, amount: Option<u64>) -> TxRow {
+        TxRow {
+            kind,
+            client: 1,
+            tx,
+            amount: amount.map(Amount::from_whole),
+        }
+    }
+
+    #[test]
+    fn apply_follows_transaction_rules() {
+        let mut expected_accounts = ExpectedAccounts::new();
+
+        let accepted = [
+            row(TxKind::Deposit, 1, Some(10)),
+            row(TxKind::Deposit, 2, Some(5)),
+            row(TxKind::Withdrawal, 3, Some(20)),
+            row(TxKind::Dispute, 1, None),
+            row(TxKind::Withdrawal, 4, Some(6)),
+            row(TxKind::Resolve, 2, None),
+            row(TxKind::Dispute, 2, None),
+            row(TxKind::Chargeback, 1, None),
+            row(TxKind::Deposit, 5, Some(1)),
+        ]
+        .iter()
+        .map(|tx_row| expected_accounts.apply(tx_row))
+        .collect::<Vec<bool>>();
+
+        assert_eq!(
+            vec![true, true, false, true, false, false, true, true, false],
+            accepted
+        );
+        assert_eq!(
+            "1,0.0,5.0,5.0,true",
+            expected_accounts
+                .account(1)
+                .expect("Test data invalid.")
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Parser, ValueEnum};
+use gen::{AmountRange, ClientDistribution, Profile, TxRows, Workload};
+
+/// Generates a transactions file to stdout.
+///
+/// e.g. `cargo run --package gen --release -- 1000000 > transactions.csv`
+///
+/// Options for the workload shape override the values from `--profile`.
+#[derive(Debug, Parser)]
+#[command(group(ArgGroup::new("step_count").args(["steps", "rows"]).required(true)))]
+pub struct GenArgs {
+    /// Number of steps to generate. Steps with disputes produce more than one
+    /// row.
+    ///
+    /// This must be at least the number of clients, so that each client
+    /// receives an initial deposit, unless `--adversarial` is passed.
+    steps: Option<u64>,
+    /// Number of steps to generate, same as `STEPS`.
+    #[arg(long)]
+    rows: Option<u64>,
+    /// File to write to, instead of stdout.
+    ///
+    /// The file is gzip compressed if the name ends with `.gz`, e.g.
+    /// `transactions.csv.gz`. Progress is shown on stderr.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// Number of files to partition rows into by client, e.g. `16`.
+    ///
+    /// Each client's rows are written to the same file, so disputes are in
+    /// the same file as their deposits. The shard number is inserted before
+    /// the extension of `--output`, e.g. `transactions-0.csv`,
+    /// `transactions-1.csv`.
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u16).range(1..),
+        requires = "output"
+    )]
+    pub shards: u16,
+    /// File to write the expected final accounts to, in the format of a
+    /// `transact` accounts CSV file.
+    ///
+    /// The accounts are computed with reference arithmetic on the generated
+    /// rows, and may be compared with `transact diff`. The file is gzip
+    /// compressed if the name ends with `.gz`.
+    #[arg(long)]
+    pub expected: Option<PathBuf>,
+    /// Format to write rows in.
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    pub format: Format,
+    /// Generate pathological rows to exercise every rejection and record
+    /// error, instead of a workload.
+    ///
+    /// Each step is one edge case, such as duplicated transaction IDs,
+    /// disputes before their deposits, amounts near the maximum, negative
+    /// amounts, and malformed rows.
+    #[arg(long, conflicts_with_all = [
+        "expected",
+        "format",
+        "shards",
+        "seed",
+        "profile",
+        "clients",
+        "client_distribution",
+        "dispute_rate",
+        "deposit_ratio",
+        "resolve_rate",
+        "chargeback_rate",
+        "deposit_amount",
+        "withdrawal_amount",
+    ])]
+    pub adversarial: bool,
+    /// Seed for the random number generator.
+    ///
+    /// The same seed, number of steps, and workload always produce the same
+    /// file.
+    #[arg(long, default_value_t = TxRows::SEED_DEFAULT)]
+    pub seed: u64,
+    /// Preset workload shape: `standard`, `dispute-heavy`, or
+    /// `withdrawal-heavy`.
+    #[arg(long, default_value_t = Profile::Standard)]
+    profile: Profile,
+    /// Number of clients, from 1 to 65536.
+    #[arg(long)]
+    clients: Option<u32>,
+    /// How transactions are spread across clients: `uniform`, or `zipf` for a
+    /// few clients to receive most transactions.
+    ///
+    /// The Zipf exponent defaults to `1.0`, and may be set with
+    /// `zipf:<exponent>`, e.g. `zipf:1.2`.
+    #[arg(long)]
+    client_distribution: Option<ClientDistribution>,
+    /// Probability that a step is a deposit that is disputed.
+    #[arg(long)]
+    dispute_rate: Option<f64>,
+    /// Probability that a step without a dispute is a deposit instead of a
+    /// withdrawal.
+    #[arg(long)]
+    deposit_ratio: Option<f64>,
+    /// Probability that a dispute is resolved.
+    #[arg(long)]
+    resolve_rate: Option<f64>,
+    /// Probability that a dispute is charged back.
+    #[arg(long)]
+    chargeback_rate: Option<f64>,
+    /// Deposit amounts, as a fixed amount or a range, e.g. `10.0..150.0`.
+    #[arg(long)]
+    deposit_amount: Option<AmountRange>,
+    /// Withdrawal amounts, as a fixed amount or a range, e.g. `1.0..10.0`.
+    #[arg(long)]
+    withdrawal_amount: Option<AmountRange>,
+}
+
+/// Format to write rows in.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    /// CSV with a header row.
+    Csv,
+    /// One JSON object per line.
+    Jsonl,
+}
+
+impl GenArgs {
+    /// Returns the number of steps to generate.
+    pub fn steps(&self) -> u64 {
+        // `clap` requires one of these to be passed.
+        self.steps.or(self.rows).unwrap_or_default()
+    }
+
+    /// Returns the workload from the profile, with the given overrides.
+    pub fn workload(&self) -> Workload {
+        let workload = Workload::from(self.profile);
+        Workload {
+            client_count: self.clients.unwrap_or(workload.client_count),
+            client_distribution: self
+                .client_distribution
+                .unwrap_or(workload.client_distribution),
+            dispute_rate: self.dispute_rate.unwrap_or(workload.dispute_rate),
+            deposit_ratio: self.deposit_ratio.unwrap_or(workload.deposit_ratio),
+            resolve_rate: self.resolve_rate.unwrap_or(workload.resolve_rate),
+            chargeback_rate: self.chargeback_rate.unwrap_or(workload.chargeback_rate),
+            initial_deposit: workload.initial_deposit,
+            deposit_amount: self.deposit_amount.unwrap_or(workload.deposit_amount),
+            withdrawal_amount: self.withdrawal_amount.unwrap_or(workload.withdrawal_amount),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Generates transactions to test and benchmark `transact`.
+//!
+//! Rows are produced lazily by [`TxRows`], so large workloads can be written
+//! to a file or streamed into `transact` without holding them in memory.
+//! [`Scenario::builder`] configures a workload in code, for benches and
+//! tests, and [`ExpectedAccounts`] computes the accounts that processing the
+//! rows should produce. With the `tokio` feature, [`RowFeed`] sends rows into
+//! a channel at a configurable rate.
+
+#![deny(missing_docs, missing_debug_implementations)]
+
+pub use crate::{
+    adversarial_rows::AdversarialRows,
+    amount::Amount,
+    amount_range::AmountRange,
+    client_distribution::ClientDistribution,
+    edge_case::EdgeCase,
+    expected_accounts::{ExpectedAccount, ExpectedAccounts},
+    profile::Profile,
+    scenario::{Scenario, ScenarioBuilder},
+    tx_row::{TxKind, TxRow, TxRowJsonl},
+    tx_rows::TxRows,
+    workload::Workload,
+};
+
+#[cfg(feature = "tokio")]
+pub use crate::row_feed::RowFeed;
+
+mod adversarial_rows;
+mod amount;
+mod amount_range;
+mod client_distribution;
+mod edge_case;
+mod expected_accounts;
+mod profile;
+#[cfg(feature = "tokio")]
+mod row_feed;
+mod scenario;
+mod tx_row;
+mod tx_rows;
+mod workload;
+
+/// Header row of a transactions CSV file.
+pub const HEADER: &str = "type, client, tx, amount";
@@ -1,55 +1,105 @@
-fn main() {
-    if let Some(limit) = std::env::args()
-        .nth(1)
-        .and_then(|s| s.parse::<usize>().ok())
-    {
-        if limit < 65536 {
-            eprintln!("{limit} is too small, choose 65536 or greater.");
-            std::process::exit(1);
-        }
+use std::io::{self, Write};
 
-        println!("type, client, tx, amount");
-        for i in 0..65536 {
-            println!("deposit, {i}, {i}, 100000.0");
-        }
-        for i in 65536..limit {
-            // deposit, 1, 1, 1.0
-            // deposit, 2, 2, 2.0
-            // deposit, 1, 3, 2.0
-            // withdrawal, 1, 4, 1.5
-            // dispute, 2, 4,
-            // chargeback, 2, 4,
-            // withdrawal, 2, 5, 3.0
-
-            let client = i % 65536;
-            if i % 31 == 0 {
-                let tx = i;
-
-                println!("deposit, {client}, {tx}, 150.0");
-                println!("dispute, {client}, {tx},");
-                if tx % 8 == 0 {
-                    println!("chargeback, {client}, {tx},");
-                } else if tx % 4 == 0 {
-                    println!("resolve, {client}, {tx},");
-                }
-            } else if i % 2 == 0 {
-                println!("deposit, {client}, {i}, 150.0");
-            } else {
-                println!("withdrawal, {client}, {i}, 10.0");
+use clap::Parser;
+use gen::{AdversarialRows, ExpectedAccounts, TxRows, HEADER};
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::{
+    gen_args::{Format, GenArgs},
+    out_writer::OutWriter,
+};
+
+mod gen_args;
+mod out_writer;
+
+/// Number of rows between progress updates.
+const PROGRESS_INTERVAL: u64 = 64 * 1024;
+
+fn main() -> io::Result<()> {
+    let gen_args = GenArgs::parse();
+    let steps = gen_args.steps();
+    let progress_bar = if gen_args.output.is_some() {
+        progress_bar(steps)
+    } else {
+        ProgressBar::hidden()
+    };
+
+    if gen_args.adversarial {
+        let mut out = OutWriter::try_new(gen_args.output.as_deref())?;
+        writeln!(out, "{HEADER}")?;
+        let mut rows = AdversarialRows::new(steps);
+        let mut row_count = 0u64;
+        while let Some(row) = rows.next() {
+            writeln!(out, "{row}")?;
+            row_count += 1;
+            if row_count.is_multiple_of(PROGRESS_INTERVAL) {
+                progress_bar.set_position(rows.step());
             }
         }
-    } else {
+        progress_bar.finish();
+        return out.finish();
+    }
+
+    let workload = gen_args.workload();
+    if let Err(message) = workload.validate() {
+        eprintln!("{message}");
+        std::process::exit(1);
+    }
+    if steps < u64::from(workload.client_count) {
         eprintln!(
-            r#"Example usage:
+            "{steps} steps is too few, choose at least the number of clients: {}.",
+            workload.client_count
+        );
+        std::process::exit(1);
+    }
 
-```bash
-gen 1000 > transactions.csv  # or
-cargo run --package gen --release -- 1000000 > transactions.csv
-```
+    let mut tx_rows = TxRows::new(steps, workload).with_seed(gen_args.seed);
+    let mut expected_accounts = gen_args.expected.as_ref().map(|_| ExpectedAccounts::new());
+    let mut outs = OutWriter::try_new_shards(gen_args.output.as_deref(), gen_args.shards)?;
+    if let Format::Csv = gen_args.format {
+        outs.iter_mut()
+            .try_for_each(|out| writeln!(out, "{HEADER}"))?;
+    }
+    let mut row_count = 0u64;
+    while let Some(tx_row) = tx_rows.next() {
+        // Rows for a client are always in the same shard.
+        let shard = usize::from(tx_row.client) % outs.len();
+        let out = &mut outs[shard];
+        match gen_args.format {
+            Format::Csv => writeln!(out, "{tx_row}")?,
+            Format::Jsonl => writeln!(out, "{}", tx_row.jsonl())?,
+        }
+        if let Some(expected_accounts) = expected_accounts.as_mut() {
+            expected_accounts.apply(&tx_row);
+        }
+        row_count += 1;
+        if row_count.is_multiple_of(PROGRESS_INTERVAL) {
+            progress_bar.set_position(tx_rows.step());
+        }
+    }
+    progress_bar.finish();
+    outs.into_iter().try_for_each(OutWriter::finish)?;
 
-Expected one argument specifying the number of transactions."#
-        );
+    if let (Some(expected_path), Some(expected_accounts)) =
+        (gen_args.expected.as_deref(), expected_accounts)
+    {
+        let mut expected_out = OutWriter::try_new(Some(expected_path))?;
+        writeln!(expected_out, "{}", ExpectedAccounts::HEADER)?;
+        expected_accounts
+            .accounts()
+            .try_for_each(|expected_account| writeln!(expected_out, "{expected_account}"))?;
+        expected_out.finish()?;
+    }
+    Ok(())
+}
 
-        std::process::exit(1);
+/// Returns a progress bar of steps generated, drawn on stderr.
+fn progress_bar(steps: u64) -> ProgressBar {
+    let progress_bar = ProgressBar::new(steps);
+    if let Ok(progress_style) = ProgressStyle::with_template(
+        "{elapsed_precise} {bar:40} {human_pos}/{human_len} steps, ETA {eta}",
+    ) {
+        progress_bar.set_style(progress_style);
     }
+    progress_bar
 }
@@ -0,0 +1,90 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, StdoutLock, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{write::GzEncoder, Compression};
+
+/// Destination of generated rows.
+#[derive(Debug)]
+pub enum OutWriter {
+    /// Writes to stdout.
+    Stdout(BufWriter<StdoutLock<'static>>),
+    /// Writes to a file.
+    File(BufWriter<File>),
+    /// Writes to a gzip compressed file.
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl OutWriter {
+    /// Returns an `OutWriter` for the given path, or stdout if there is none.
+    ///
+    /// Files whose names end with `.gz` are gzip compressed.
+    pub fn try_new(path: Option<&Path>) -> io::Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self::Stdout(BufWriter::new(io::stdout().lock())));
+        };
+
+        let file = BufWriter::new(File::create(path)?);
+        if path.extension().is_some_and(|extension| extension == "gz") {
+            Ok(Self::Gzip(GzEncoder::new(file, Compression::default())))
+        } else {
+            Ok(Self::File(file))
+        }
+    }
+
+    /// Returns an `OutWriter` for each shard of the given path, or only the
+    /// path if there is one shard.
+    ///
+    /// See [`OutWriter::shard_path`] for the shard file names.
+    pub fn try_new_shards(path: Option<&Path>, shards: u16) -> io::Result<Vec<Self>> {
+        match path {
+            Some(path) if shards > 1 => (0..shards)
+                .map(|shard| Self::try_new(Some(&Self::shard_path(path, shard))))
+                .collect(),
+            path => Self::try_new(path).map(|out_writer| vec![out_writer]),
+        }
+    }
+
+    /// Returns the path with the shard number inserted before the
+    /// extensions, e.g. `transactions-3.csv.gz` for `transactions.csv.gz`.
+    fn shard_path(path: &Path, shard: u16) -> PathBuf {
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let shard_file_name = match file_name.split_once('.') {
+            Some((stem, extensions)) => format!("{stem}-{shard}.{extensions}"),
+            None => format!("{file_name}-{shard}"),
+        };
+        path.with_file_name(shard_file_name)
+    }
+
+    /// Flushes buffered rows, and writes the gzip trailer if compressed.
+    pub fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Stdout(mut stdout) => stdout.flush(),
+            Self::File(mut file) => file.flush(),
+            Self::Gzip(gz_encoder) => gz_encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for OutWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Stdout(stdout) => stdout.write(buf),
+            Self::File(file) => file.write(buf),
+            Self::Gzip(gz_encoder) => gz_encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(stdout) => stdout.flush(),
+            Self::File(file) => file.flush(),
+            Self::Gzip(gz_encoder) => gz_encoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::{fmt, str::FromStr};
+
+/// Preset shape of a generated workload.
+///
+/// See [`Workload`] for the rates of each profile.
+///
+/// [`Workload`]: crate::Workload
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Profile {
+    /// Mostly deposits and withdrawals, with a dispute in about one of every
+    /// 31 steps.
+    #[default]
+    Standard,
+    /// A dispute in about one of every 3 steps, to stress transaction lookups
+    /// in the block store.
+    DisputeHeavy,
+    /// Four withdrawals for every deposit, to stress insufficient funds
+    /// rejections.
+    WithdrawalHeavy,
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => write!(f, "standard"),
+            Self::DisputeHeavy => write!(f, "dispute-heavy"),
+            Self::WithdrawalHeavy => write!(f, "withdrawal-heavy"),
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Self::Standard),
+            "dispute-heavy" => Ok(Self::DisputeHeavy),
+            "withdrawal-heavy" => Ok(Self::WithdrawalHeavy),
+            _ => Err(format!(
+                "Unknown profile `{s}`, expected `standard`, `dispute-heavy`, or `withdrawal-heavy`."
+            )),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{num::NonZeroU32, time::Duration};
+
+use tokio::{
+    sync::mpsc,
+    task::JoinHandle,
+    time::{self, Instant},
+};
+
+use crate::TxRow;
+
+/// Sends generated rows into a `tokio` [`mpsc`] channel, at an optional rate.
+///
+/// This drives streaming ingestion in soak tests without writing the rows to
+/// a file first.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::num::NonZeroU32;
+///
+/// use gen::{RowFeed, Scenario};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() -> Result<(), String> {
+/// let scenario = Scenario::builder().clients(10).rows(100).build()?;
+/// let rows_per_second = NonZeroU32::new(1_000_000).ok_or("rate is zero")?;
+/// let (mut receiver, _join_handle) = RowFeed::new(scenario.rows())
+///     .with_rate(rows_per_second)
+///     .spawn(64);
+///
+/// let mut row_count = 0;
+/// while let Some(_tx_row) = receiver.recv().await {
+///     row_count += 1;
+/// }
+/// assert!(row_count >= 100);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RowFeed<I> {
+    /// Rows to send.
+    rows: I,
+    /// Maximum number of rows to send each second, unlimited if `None`.
+    rows_per_second: Option<NonZeroU32>,
+}
+
+impl<I> RowFeed<I>
+where
+    I: Iterator<Item = TxRow>,
+{
+    /// Returns a new `RowFeed` that sends rows as fast as they are received.
+    pub fn new(rows: I) -> Self {
+        Self {
+            rows,
+            rows_per_second: None,
+        }
+    }
+
+    /// Limits the number of rows sent each second.
+    ///
+    /// Rows are sent evenly over each second. If the receiver falls behind,
+    /// rows are sent without waiting until the feed catches up to the rate.
+    pub fn with_rate(mut self, rows_per_second: NonZeroU32) -> Self {
+        self.rows_per_second = Some(rows_per_second);
+        self
+    }
+
+    /// Sends all rows into the channel, returning the number of rows sent.
+    ///
+    /// This stops early if the receiver is dropped.
+    pub async fn send(self, sender: mpsc::Sender<TxRow>) -> u64 {
+        let Self {
+            rows,
+            rows_per_second,
+        } = self;
+        let start = Instant::now();
+        let mut row_count = 0u64;
+
+        for tx_row in rows {
+            if let Some(rows_per_second) = rows_per_second {
+                let due = start
+                    + Duration::from_secs_f64(row_count as f64 / f64::from(rows_per_second.get()));
+                if due > Instant::now() {
+                    time::sleep_until(due).await;
+                }
+            }
+            if sender.send(tx_row).await.is_err() {
+                break;
+            }
+            row_count += 1;
+        }
+
+        row_count
+    }
+
+    /// Spawns a task that sends all rows, returning the receiver of a channel
+    /// with the given capacity.
+    ///
+    /// The task resolves to the number of rows sent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero, or this is called outside a `tokio`
+    /// runtime.
+    pub fn spawn(self, capacity: usize) -> (mpsc::Receiver<TxRow>, JoinHandle<u64>)
+    where
+        I: Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(capacity);
+        let join_handle = tokio::spawn(self.send(sender));
+        (receiver, join_handle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{num::NonZeroU32, time::Duration};
+
+    use tokio::{sync::mpsc, time::Instant};
+
+    use super::RowFeed;
+    use crate::Scenario;
+
+    #[tokio::test(start_paused = true)]
+    async fn send_limits_rows_per_second() -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = Scenario::builder().clients(10).rows(10).build()?;
+        let rows_per_second = NonZeroU32::new(5).expect("Test data invalid.");
+        let start = Instant::now();
+
+        let (mut receiver, join_handle) = RowFeed::new(scenario.rows())
+            .with_rate(rows_per_second)
+            .spawn(1);
+        let mut rows = Vec::new();
+        while let Some(tx_row) = receiver.recv().await {
+            rows.push(tx_row);
+        }
+
+        assert_eq!(10, join_handle.await?);
+        assert_eq!(scenario.rows().collect::<Vec<_>>(), rows);
+        assert!(start.elapsed() >= Duration::from_millis(1_800));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn send_stops_when_receiver_is_dropped() -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = Scenario::builder().clients(10).rows(100).build()?;
+        let (sender, mut receiver) = mpsc::channel(1);
+
+        let send = tokio::spawn(RowFeed::new(scenario.rows()).send(sender));
+        receiver.recv().await;
+        drop(receiver);
+
+        assert!(send.await? < 100);
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::{AmountRange, ClientDistribution, Profile, TxRow, TxRows, Workload};
+
+/// Generated workload, built with [`Scenario::builder`].
+///
+/// # Examples
+///
+/// ```rust
+/// use gen::Scenario;
+///
+/// let scenario = Scenario::builder()
+///     .clients(100)
+///     .rows(1_000)
+///     .dispute_rate(0.1)
+///     .seed(42)
+///     .build()?;
+///
+/// assert!(scenario.into_iter().count() >= 1_000);
+/// # Ok::<(), String>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scenario {
+    /// Number of steps to generate.
+    steps: u64,
+    /// Shape of the workload.
+    workload: Workload,
+    /// Seed for the random number generator.
+    seed: u64,
+}
+
+impl Scenario {
+    /// Returns a [`ScenarioBuilder`], starting from the standard profile.
+    pub fn builder() -> ScenarioBuilder {
+        ScenarioBuilder::default()
+    }
+
+    /// Returns the number of steps to generate.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Returns the shape of the workload.
+    pub fn workload(&self) -> Workload {
+        self.workload
+    }
+
+    /// Returns the seed for the random number generator.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns an iterator of the generated rows.
+    ///
+    /// The same scenario always produces the same rows.
+    pub fn rows(&self) -> TxRows {
+        TxRows::new(self.steps, self.workload).with_seed(self.seed)
+    }
+}
+
+impl IntoIterator for Scenario {
+    type IntoIter = TxRows;
+    type Item = TxRow;
+
+    fn into_iter(self) -> TxRows {
+        self.rows()
+    }
+}
+
+/// Builds a [`Scenario`].
+///
+/// Options that are not set take their values from the profile, which
+/// defaults to [`Profile::Standard`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScenarioBuilder {
+    /// Number of steps to generate.
+    steps: Option<u64>,
+    /// Shape of the workload.
+    workload: Workload,
+    /// Seed for the random number generator.
+    seed: u64,
+}
+
+impl ScenarioBuilder {
+    /// Sets the workload to the profile's, replacing options set before this.
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.workload = Workload::from(profile);
+        self
+    }
+
+    /// Sets the number of clients, from 1 to [`TxRows::CLIENT_COUNT`].
+    pub fn clients(mut self, client_count: u32) -> Self {
+        self.workload.client_count = client_count;
+        self
+    }
+
+    /// Sets the number of steps to generate.
+    ///
+    /// Steps with disputes produce more than one row. Defaults to the number
+    /// of clients, which only generates the initial deposits.
+    pub fn rows(mut self, steps: u64) -> Self {
+        self.steps = Some(steps);
+        self
+    }
+
+    /// Sets how transactions are spread across clients.
+    pub fn client_distribution(mut self, client_distribution: ClientDistribution) -> Self {
+        self.workload.client_distribution = client_distribution;
+        self
+    }
+
+    /// Sets the probability that a step is a deposit that is disputed.
+    pub fn dispute_rate(mut self, dispute_rate: f64) -> Self {
+        self.workload.dispute_rate = dispute_rate;
+        self
+    }
+
+    /// Sets the probability that a step without a dispute is a deposit
+    /// instead of a withdrawal.
+    pub fn deposit_ratio(mut self, deposit_ratio: f64) -> Self {
+        self.workload.deposit_ratio = deposit_ratio;
+        self
+    }
+
+    /// Sets the probability that a dispute is resolved.
+    pub fn resolve_rate(mut self, resolve_rate: f64) -> Self {
+        self.workload.resolve_rate = resolve_rate;
+        self
+    }
+
+    /// Sets the probability that a dispute is charged back.
+    pub fn chargeback_rate(mut self, chargeback_rate: f64) -> Self {
+        self.workload.chargeback_rate = chargeback_rate;
+        self
+    }
+
+    /// Sets the amounts of deposits after the initial deposit.
+    pub fn deposit_amount(mut self, deposit_amount: AmountRange) -> Self {
+        self.workload.deposit_amount = deposit_amount;
+        self
+    }
+
+    /// Sets the amounts of withdrawals.
+    pub fn withdrawal_amount(mut self, withdrawal_amount: AmountRange) -> Self {
+        self.workload.withdrawal_amount = withdrawal_amount;
+        self
+    }
+
+    /// Sets the seed for the random number generator.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the [`Scenario`], or an error if an option is out of range.
+    pub fn build(self) -> Result<Scenario, String> {
+        let Self {
+            steps,
+            workload,
+            seed,
+        } = self;
+        workload.validate()?;
+
+        Ok(Scenario {
+            steps: steps.unwrap_or(u64::from(workload.client_count)),
+            workload,
+            seed,
+        })
+    }
+}
+
+impl Default for ScenarioBuilder {
+    fn default() -> Self {
+        Self {
+            steps: None,
+            workload: Workload::default(),
+            seed: TxRows::SEED_DEFAULT,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Scenario;
+    use crate::TxKind;
+
+    #[test]
+    fn build_returns_scenario_with_options() -> Result<(), String> {
+        let scenario = Scenario::builder()
+            .clients(10)
+            .rows(110)
+            .deposit_ratio(1.0)
+            .dispute_rate(0.0)
+            .build()?;
+
+        let rows = scenario.into_iter().collect::<Vec<_>>();
+
+        assert_eq!(110, rows.len());
+        assert!(rows
+            .iter()
+            .all(|tx_row| tx_row.kind == TxKind::Deposit && tx_row.client < 10));
+        assert_eq!(rows, scenario.rows().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn build_returns_error_for_invalid_rate() {
+        assert_eq!(
+            Err("Dispute rate 2 must be from 0.0 to 1.0.".to_string()),
+            Scenario::builder().dispute_rate(2.0).build()
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::Amount;
+
+/// Types of transactions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxKind {
+    /// Credit to the client's account.
+    Deposit,
+    /// Debit to the client's account.
+    Withdrawal,
+    /// Claim that a deposit was erroneous.
+    Dispute,
+    /// Releases the funds held by a dispute.
+    Resolve,
+    /// Reverses a disputed deposit, and locks the account.
+    Chargeback,
+}
+
+impl fmt::Display for TxKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Deposit => write!(f, "deposit"),
+            Self::Withdrawal => write!(f, "withdrawal"),
+            Self::Dispute => write!(f, "dispute"),
+            Self::Resolve => write!(f, "resolve"),
+            Self::Chargeback => write!(f, "chargeback"),
+        }
+    }
+}
+
+/// Row in a transactions CSV file.
+///
+/// The [`Display`] implementation writes the row without a trailing newline,
+/// e.g. `deposit, 1, 1, 150.0` or `dispute, 1, 1,`.
+///
+/// [`Display`]: std::fmt::Display
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TxRow {
+    /// Type of transaction.
+    pub kind: TxKind,
+    /// Client ID.
+    pub client: u16,
+    /// Transaction ID.
+    pub tx: u32,
+    /// Amount, for deposits and withdrawals.
+    pub amount: Option<Amount>,
+}
+
+impl TxRow {
+    /// Returns this row as a JSON object, for a JSON Lines file.
+    ///
+    /// The [`Display`] implementation writes the object without a trailing
+    /// newline, e.g. `{"type":"deposit","client":1,"tx":1,"amount":150.0}`
+    /// or `{"type":"dispute","client":1,"tx":1,"amount":null}`.
+    ///
+    /// [`Display`]: std::fmt::Display
+    pub fn jsonl(&self) -> TxRowJsonl<'_> {
+        TxRowJsonl(self)
+    }
+}
+
+impl fmt::Display for TxRow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            kind,
+            client,
+            tx,
+            amount,
+        } = self;
+        match amount {
+            Some(amount) => write!(f, "{kind}, {client}, {tx}, {amount}"),
+            None => write!(f, "{kind}, {client}, {tx},"),
+        }
+    }
+}
+
+/// Displays a [`TxRow`] as a JSON object, see [`TxRow::jsonl`].
+#[derive(Clone, Copy, Debug)]
+pub struct TxRowJsonl<'row>(&'row TxRow);
+
+impl fmt::Display for TxRowJsonl<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TxRow {
+            kind,
+            client,
+            tx,
+            amount,
+        } = self.0;
+        write!(
+            f,
+            r#"{{"type":"{kind}","client":{client},"tx":{tx},"amount":"#
+        )?;
+        match amount {
+            Some(amount) => write!(f, "{amount}}}"),
+            None => write!(f, "null}}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TxKind, TxRow};
+    use crate::Amount;
+
+    #[test]
+    fn jsonl_writes_json_object() {
+        let deposit = TxRow {
+            kind: TxKind::Deposit,
+            client: 1,
+            tx: 2,
+            amount: Some(Amount::from_units(15)),
+        };
+        let dispute = TxRow {
+            kind: TxKind::Dispute,
+            amount: None,
+            ..deposit
+        };
+
+        assert_eq!(
+            r#"{"type":"deposit","client":1,"tx":2,"amount":0.0015}"#,
+            deposit.jsonl().to_string()
+        );
+        assert_eq!(
+            r#"{"type":"dispute","client":1,"tx":2,"amount":null}"#,
+            dispute.jsonl().to_string()
+        );
+    }
+}
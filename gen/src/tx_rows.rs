@@ -0,0 +1,244 @@
+use std::collections::VecDeque;
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::Zipf;
+
+use crate::{ClientDistribution, TxKind, TxRow, Workload};
+
+/// Iterator of generated transaction rows.
+///
+/// Every client first receives a deposit, followed by deposits, withdrawals,
+/// and disputed deposits that are resolved or charged back, for randomly
+/// chosen clients.
+///
+/// Rows are generated from a seeded random number generator, so the same
+/// seed, number of steps, and workload always produce the same rows.
+#[derive(Debug)]
+pub struct TxRows {
+    /// Shape of the workload.
+    workload: Workload,
+    /// Chooses clients and transaction types.
+    rng: ChaCha8Rng,
+    /// Distribution of client ranks, when clients are not chosen uniformly.
+    client_zipf: Option<Zipf<f64>>,
+    /// Number of steps to generate, each of which produces one or more rows.
+    steps: u64,
+    /// Index of the next step.
+    step: u64,
+    /// Rows generated by the current step that have not been returned.
+    pending: VecDeque<TxRow>,
+}
+
+impl TxRows {
+    /// Maximum number of clients, as client IDs are `u16`s.
+    pub const CLIENT_COUNT: u64 = 65_536;
+    /// Seed used when none is given.
+    pub const SEED_DEFAULT: u64 = 0;
+
+    /// Returns a new `TxRows`, seeded with [`Self::SEED_DEFAULT`].
+    ///
+    /// # Parameters
+    ///
+    /// * `steps`: Number of steps to generate. Steps with disputes produce more
+    ///   than one row. Steps below the workload's client count are the initial
+    ///   deposits.
+    /// * `workload`: Shape of the workload, or a [`Profile`] preset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the workload is invalid, see [`Workload::validate`].
+    ///
+    /// [`Profile`]: crate::Profile
+    pub fn new<W>(steps: u64, workload: W) -> Self
+    where
+        W: Into<Workload>,
+    {
+        let workload = workload.into();
+        if let Err(message) = workload.validate() {
+            panic!("{message}");
+        }
+
+        let client_zipf = match workload.client_distribution {
+            ClientDistribution::Uniform => None,
+            ClientDistribution::Zipf { exponent } => Some(
+                Zipf::new(f64::from(workload.client_count), exponent)
+                    .expect("Workload was validated."),
+            ),
+        };
+
+        Self {
+            workload,
+            client_zipf,
+            rng: ChaCha8Rng::seed_from_u64(Self::SEED_DEFAULT),
+            steps,
+            step: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Sets the seed of the random number generator.
+    ///
+    /// Rows are only reproducible with the same seed, number of steps, and
+    /// workload.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+        self
+    }
+
+    /// Generates the rows for a step.
+    fn generate(&mut self, step: u64) {
+        let Workload {
+            client_count,
+            client_distribution: _,
+            dispute_rate,
+            deposit_ratio,
+            resolve_rate,
+            chargeback_rate,
+            initial_deposit,
+            deposit_amount,
+            withdrawal_amount,
+        } = self.workload;
+        let client_count = u64::from(client_count);
+        // Client counts are at most `CLIENT_COUNT`, so this does not truncate.
+        let client = if step < client_count {
+            step as u16
+        } else {
+            match self.client_zipf {
+                // Ranks start from 1, and the lowest rank is the most active.
+                Some(client_zipf) => (self.rng.sample(client_zipf) as u64 - 1) as u16,
+                None => self.rng.random_range(0..client_count) as u16,
+            }
+        };
+        // Transaction IDs wrap around after `u32::MAX` steps.
+        let tx = step as u32;
+        let row = |kind, amount| TxRow {
+            kind,
+            client,
+            tx,
+            amount,
+        };
+
+        if step < client_count {
+            self.pending
+                .push_back(row(TxKind::Deposit, Some(initial_deposit)));
+        } else if self.rng.random_bool(dispute_rate) {
+            let amount = deposit_amount.sample(&mut self.rng);
+            self.pending.push_back(row(TxKind::Deposit, Some(amount)));
+            self.pending.push_back(row(TxKind::Dispute, None));
+            // Disputes that are neither charged back nor resolved stay
+            // disputed.
+            let outcome = self.rng.random::<f64>();
+            if outcome < chargeback_rate {
+                self.pending.push_back(row(TxKind::Chargeback, None));
+            } else if outcome < chargeback_rate + resolve_rate {
+                self.pending.push_back(row(TxKind::Resolve, None));
+            }
+        } else if self.rng.random_bool(deposit_ratio) {
+            let amount = deposit_amount.sample(&mut self.rng);
+            self.pending.push_back(row(TxKind::Deposit, Some(amount)));
+        } else {
+            let amount = withdrawal_amount.sample(&mut self.rng);
+            self.pending
+                .push_back(row(TxKind::Withdrawal, Some(amount)));
+        }
+    }
+
+    /// Returns the number of steps generated so far.
+    pub fn step(&self) -> u64 {
+        self.step
+    }
+}
+
+impl Iterator for TxRows {
+    type Item = TxRow;
+
+    fn next(&mut self) -> Option<TxRow> {
+        if self.pending.is_empty() && self.step < self.steps {
+            self.generate(self.step);
+            self.step += 1;
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TxRows;
+    use crate::{AmountRange, ClientDistribution, Profile, TxKind, Workload};
+
+    #[test]
+    fn generates_initial_deposit_for_each_client() {
+        let rows = TxRows::new(TxRows::CLIENT_COUNT + 100, Profile::DisputeHeavy)
+            .take(TxRows::CLIENT_COUNT as usize)
+            .collect::<Vec<_>>();
+
+        assert!(rows.iter().enumerate().all(|(index, tx_row)| {
+            tx_row.kind == TxKind::Deposit
+                && usize::from(tx_row.client) == index
+                && tx_row.tx as usize == index
+        }));
+    }
+
+    #[test]
+    fn generates_same_rows_for_same_seed() {
+        let rows = |seed| {
+            TxRows::new(TxRows::CLIENT_COUNT + 1_000, Profile::DisputeHeavy)
+                .with_seed(seed)
+                .skip(TxRows::CLIENT_COUNT as usize)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(rows(7), rows(7));
+        assert_ne!(rows(7), rows(8));
+        assert!(rows(7).iter().any(|tx_row| tx_row.kind == TxKind::Dispute));
+    }
+
+    #[test]
+    fn generates_rows_in_workload_shape() -> Result<(), String> {
+        let workload = Workload {
+            client_count: 10,
+            dispute_rate: 0.0,
+            deposit_ratio: 0.0,
+            withdrawal_amount: "1.0..2.0".parse::<AmountRange>()?,
+            ..Workload::from(Profile::Standard)
+        };
+
+        let rows = TxRows::new(1_010, workload).skip(10).collect::<Vec<_>>();
+
+        assert_eq!(1_000, rows.len());
+        assert!(rows.iter().all(|tx_row| {
+            tx_row.kind == TxKind::Withdrawal
+                && tx_row.client < 10
+                && tx_row
+                    .amount
+                    .is_some_and(|amount| (10_000..=20_000).contains(&amount.units()))
+        }));
+        Ok(())
+    }
+
+    #[test]
+    fn zipf_distribution_concentrates_rows_on_few_clients() {
+        let client_count = 1_000;
+        let workload = Workload {
+            client_count,
+            client_distribution: ClientDistribution::Zipf { exponent: 1.0 },
+            dispute_rate: 0.0,
+            ..Workload::from(Profile::Standard)
+        };
+
+        let rows = TxRows::new(u64::from(client_count) + 10_000, workload)
+            .skip(client_count as usize)
+            .collect::<Vec<_>>();
+        let top_client_rows = rows.iter().filter(|tx_row| tx_row.client < 10).count();
+
+        // With an exponent of 1.0, the top 1% of clients receive about 39% of
+        // rows.
+        assert!(
+            (3_000..5_000).contains(&top_client_rows),
+            "Expected about 3900 rows for the top 10 clients, got {top_client_rows}."
+        );
+        assert!(rows.iter().all(|tx_row| tx_row.client < 1_000));
+    }
+}
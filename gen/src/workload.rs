@@ -0,0 +1,135 @@
+use crate::{Amount, AmountRange, ClientDistribution, Profile, TxRows};
+
+/// Shape of a generated workload.
+///
+/// Rates are probabilities from `0.0` to `1.0`. A [`Profile`] provides a
+/// preset workload, which may be adjusted by setting fields.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Workload {
+    /// Number of clients, each of which receives an initial deposit.
+    ///
+    /// This is at most [`TxRows::CLIENT_COUNT`].
+    pub client_count: u32,
+    /// How transactions after the initial deposits are spread across
+    /// clients.
+    pub client_distribution: ClientDistribution,
+    /// Probability that a step is a deposit that is disputed.
+    pub dispute_rate: f64,
+    /// Probability that a step without a dispute is a deposit instead of a
+    /// withdrawal.
+    pub deposit_ratio: f64,
+    /// Probability that a dispute is resolved.
+    pub resolve_rate: f64,
+    /// Probability that a dispute is charged back.
+    ///
+    /// Disputes that are neither resolved nor charged back stay disputed.
+    pub chargeback_rate: f64,
+    /// Amount of each client's initial deposit.
+    pub initial_deposit: Amount,
+    /// Amounts of deposits after the initial deposit.
+    pub deposit_amount: AmountRange,
+    /// Amounts of withdrawals.
+    pub withdrawal_amount: AmountRange,
+}
+
+impl Workload {
+    /// Returns an error if the client count or a rate is out of range.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=TxRows::CLIENT_COUNT).contains(&u64::from(self.client_count)) {
+            return Err(format!(
+                "Client count {} must be from 1 to {}.",
+                self.client_count,
+                TxRows::CLIENT_COUNT
+            ));
+        }
+        if let ClientDistribution::Zipf { exponent } = self.client_distribution {
+            if !(exponent.is_finite() && exponent >= 0.0) {
+                return Err(format!(
+                    "Zipf exponent {exponent} must be a number of 0.0 or greater."
+                ));
+            }
+        }
+        [
+            ("Dispute rate", self.dispute_rate),
+            ("Deposit ratio", self.deposit_ratio),
+            ("Resolve rate", self.resolve_rate),
+            ("Chargeback rate", self.chargeback_rate),
+            (
+                "Resolve and chargeback rate sum",
+                self.resolve_rate + self.chargeback_rate,
+            ),
+        ]
+        .into_iter()
+        .try_for_each(|(name, rate)| {
+            if (0.0..=1.0).contains(&rate) {
+                Ok(())
+            } else {
+                Err(format!("{name} {rate} must be from 0.0 to 1.0."))
+            }
+        })
+    }
+}
+
+impl Default for Workload {
+    fn default() -> Self {
+        Self::from(Profile::default())
+    }
+}
+
+impl From<Profile> for Workload {
+    fn from(profile: Profile) -> Self {
+        let standard = Self {
+            client_count: TxRows::CLIENT_COUNT as u32,
+            client_distribution: ClientDistribution::Uniform,
+            dispute_rate: 1.0 / 31.0,
+            deposit_ratio: 0.5,
+            resolve_rate: 0.125,
+            chargeback_rate: 0.125,
+            initial_deposit: Amount::from_whole(100_000),
+            deposit_amount: AmountRange::fixed(Amount::from_whole(150)),
+            withdrawal_amount: AmountRange::fixed(Amount::from_whole(10)),
+        };
+
+        match profile {
+            Profile::Standard => standard,
+            Profile::DisputeHeavy => Self {
+                dispute_rate: 1.0 / 3.0,
+                ..standard
+            },
+            Profile::WithdrawalHeavy => Self {
+                deposit_ratio: 0.2,
+                ..standard
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Workload;
+    use crate::Profile;
+
+    #[test]
+    fn validate_rejects_rates_out_of_range() {
+        let workload = Workload::from(Profile::Standard);
+
+        assert_eq!(Ok(()), workload.validate());
+        assert_eq!(
+            Err("Resolve and chargeback rate sum 1.5 must be from 0.0 to 1.0.".to_string()),
+            Workload {
+                resolve_rate: 0.75,
+                chargeback_rate: 0.75,
+                ..workload
+            }
+            .validate()
+        );
+        assert_eq!(
+            Err("Client count 0 must be from 1 to 65536.".to_string()),
+            Workload {
+                client_count: 0,
+                ..workload
+            }
+            .validate()
+        );
+    }
+}
@@ -0,0 +1,288 @@
+use std::{
+    collections::HashMap,
+    io,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use tempfile::TempDir;
+
+use crate::{
+    model::{Account, AccountState, Accounts, ClientId},
+    AccountStore, Error,
+};
+
+/// Number of accounts stored in each page file.
+const ACCOUNTS_PER_PAGE: u16 = 64;
+
+/// Stores accounts in page files in a temporary directory, keeping the most
+/// recently used pages in memory.
+///
+/// Accounts are grouped into pages of consecutive client IDs. When a page that
+/// is not in memory is needed and the cache is full, the least recently used
+/// page is written to its page file and dropped from memory.
+///
+/// Page files have one [`AccountState`] JSON object per line, so amounts and
+/// open disputes are restored exactly.
+#[derive(Debug)]
+pub struct AccountPageStore {
+    /// Directory of page files, removed when the store is dropped.
+    temp_dir: TempDir,
+    /// Maximum number of pages to keep in memory.
+    pages_cached_max: usize,
+    /// Pages in memory, by page ID.
+    pages: HashMap<u16, AccountPage>,
+    /// Incremented each time a page is used, to find the least recently used
+    /// page.
+    tick: u64,
+}
+
+/// Accounts of consecutive client IDs held in memory.
+#[derive(Debug, Default)]
+struct AccountPage {
+    /// Accounts in this page.
+    accounts: HashMap<ClientId, Account>,
+    /// Tick when this page was last used.
+    last_used: u64,
+    /// Whether the accounts changed since the page was read.
+    dirty: bool,
+}
+
+impl AccountPageStore {
+    /// Initializes a new account page store.
+    ///
+    /// # Parameters
+    ///
+    /// * `account_cache_capacity`: Number of accounts to keep in memory, which
+    ///   is rounded up to whole pages.
+    pub fn try_new(account_cache_capacity: NonZeroUsize) -> Result<Self, Error> {
+        let temp_dir = tempfile::tempdir().map_err(Error::AccountPageDirCreate)?;
+        Ok(Self::new(temp_dir, account_cache_capacity))
+    }
+
+    /// Initializes a new account page store in the given directory.
+    ///
+    /// # Parameters
+    ///
+    /// * `dir`: Directory to create the page directory in.
+    /// * `account_cache_capacity`: Number of accounts to keep in memory, which
+    ///   is rounded up to whole pages.
+    pub fn try_new_in(dir: &Path, account_cache_capacity: NonZeroUsize) -> Result<Self, Error> {
+        let temp_dir = tempfile::tempdir_in(dir).map_err(Error::AccountPageDirCreate)?;
+        Ok(Self::new(temp_dir, account_cache_capacity))
+    }
+
+    fn new(temp_dir: TempDir, account_cache_capacity: NonZeroUsize) -> Self {
+        Self {
+            temp_dir,
+            pages_cached_max: account_cache_capacity
+                .get()
+                .div_ceil(usize::from(ACCOUNTS_PER_PAGE)),
+            pages: HashMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// Returns the page holding the client's account, reading it from its
+    /// page file if it is not in memory.
+    async fn page_mut(&mut self, client: ClientId) -> Result<&mut AccountPage, Error> {
+        let page_id = client.into_inner() / ACCOUNTS_PER_PAGE;
+        self.tick += 1;
+
+        if !self.pages.contains_key(&page_id) {
+            if self.pages.len() >= self.pages_cached_max {
+                self.evict_least_recently_used().await?;
+            }
+            let accounts = Self::page_read(&self.page_path(page_id)).await?;
+            self.pages.insert(
+                page_id,
+                AccountPage {
+                    accounts,
+                    last_used: 0,
+                    dirty: false,
+                },
+            );
+        }
+
+        let page = self.pages.get_mut(&page_id).expect("Page inserted above.");
+        page.last_used = self.tick;
+        Ok(page)
+    }
+
+    /// Writes the least recently used page to its page file if it changed, and
+    /// drops it from memory.
+    async fn evict_least_recently_used(&mut self) -> Result<(), Error> {
+        let page_id = self
+            .pages
+            .iter()
+            .min_by_key(|(_page_id, page)| page.last_used)
+            .map(|(page_id, _page)| *page_id);
+        let Some((page_id, page)) =
+            page_id.and_then(|page_id| Some((page_id, self.pages.remove(&page_id)?)))
+        else {
+            return Ok(());
+        };
+
+        if page.dirty {
+            Self::page_write(&self.page_path(page_id), &page.accounts).await
+        } else {
+            Ok(())
+        }
+    }
+
+    fn page_path(&self, page_id: u16) -> PathBuf {
+        self.temp_dir.path().join(format!("{page_id}.jsonl"))
+    }
+
+    /// Returns the page ID of a page file, if the file is a page file.
+    fn page_id(path: &Path) -> Option<u16> {
+        if path.extension()? != "jsonl" {
+            return None;
+        }
+        path.file_stem()?.to_str()?.parse::<u16>().ok()
+    }
+
+    /// Reads the accounts in a page file, or no accounts if the file does not
+    /// exist.
+    async fn page_read(path: &Path) -> Result<HashMap<ClientId, Account>, Error> {
+        let page_read = |error| Error::AccountPageRead {
+            path: path.to_path_buf(),
+            error,
+        };
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(error) => return Err(page_read(error)),
+        };
+
+        content
+            .lines()
+            .map(|line| {
+                let account_state = serde_json::from_str::<AccountState>(line)
+                    .map_err(|error| page_read(io::Error::from(error)))?;
+                let account = Account::try_from(account_state).map_err(|_| {
+                    page_read(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Account total overflows.",
+                    ))
+                })?;
+                Ok((account.client(), account))
+            })
+            .collect()
+    }
+
+    /// Writes the accounts to a page file, replacing its content.
+    async fn page_write(path: &Path, accounts: &HashMap<ClientId, Account>) -> Result<(), Error> {
+        let page_write = |error| Error::AccountPageWrite {
+            path: path.to_path_buf(),
+            error,
+        };
+        let mut content = Vec::new();
+        for account in accounts.values() {
+            serde_json::to_writer(&mut content, &AccountState::from(account))
+                .map_err(|error| page_write(io::Error::from(error)))?;
+            content.push(b'\n');
+        }
+
+        tokio::fs::write(path, content).await.map_err(page_write)
+    }
+}
+
+impl AccountStore for AccountPageStore {
+    async fn take_account(&mut self, client: ClientId) -> Result<Account, Error> {
+        let page = self.page_mut(client).await?;
+        let account = page.accounts.remove(&client);
+        page.dirty |= account.is_some();
+
+        Ok(account.unwrap_or_else(|| Account::empty(client)))
+    }
+
+    async fn put_account(&mut self, account: Account) -> Result<(), Error> {
+        let page = self.page_mut(account.client()).await?;
+        page.accounts.insert(account.client(), account);
+        page.dirty = true;
+
+        Ok(())
+    }
+
+    /// Returns all stored accounts, reading the page files of pages that are
+    /// not in memory.
+    async fn into_accounts(self) -> Result<Accounts, Error> {
+        let dir_read = |error| Error::AccountPageRead {
+            path: self.temp_dir.path().to_path_buf(),
+            error,
+        };
+        let mut accounts = Accounts::new();
+        let mut read_dir = tokio::fs::read_dir(self.temp_dir.path())
+            .await
+            .map_err(dir_read)?;
+        while let Some(dir_entry) = read_dir.next_entry().await.map_err(dir_read)? {
+            let path = dir_entry.path();
+            let page_cached =
+                Self::page_id(&path).is_none_or(|page_id| self.pages.contains_key(&page_id));
+            if !page_cached {
+                accounts.extend(Self::page_read(&path).await?);
+            }
+        }
+
+        self.pages
+            .into_values()
+            .for_each(|page| accounts.extend(page.accounts));
+
+        Ok(accounts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, num::NonZeroUsize};
+
+    use rust_decimal_macros::dec;
+
+    use super::AccountPageStore;
+    use crate::{
+        model::{Account, ClientId, TxId},
+        AccountStore,
+    };
+
+    #[tokio::test]
+    async fn restores_accounts_from_evicted_pages() -> Result<(), Box<dyn std::error::Error>> {
+        let clients = [ClientId::new(1), ClientId::new(2000), ClientId::new(65535)];
+        let mut account_page_store =
+            AccountPageStore::try_new(NonZeroUsize::new(1).expect("Test data invalid."))?;
+
+        // Each client is in a different page, and only one page is kept in memory.
+        for (index, client) in clients.into_iter().enumerate() {
+            let account = account_page_store.take_account(client).await?;
+            assert_eq!(Account::empty(client), account);
+
+            let account = Account::try_new(
+                client,
+                dec!(1.5),
+                dec!(0.25),
+                index == 1,
+                HashSet::from([TxId::new(u32::from(client.into_inner()))]),
+            )
+            .expect("Test data invalid.");
+            account_page_store.put_account(account).await?;
+        }
+        let account_first = account_page_store.take_account(clients[0]).await?;
+        account_page_store.put_account(account_first).await?;
+
+        let accounts = account_page_store.into_accounts().await?;
+
+        assert_eq!(3, accounts.len());
+        clients.into_iter().enumerate().for_each(|(index, client)| {
+            let account_expected = Account::try_new(
+                client,
+                dec!(1.5),
+                dec!(0.25),
+                index == 1,
+                HashSet::from([TxId::new(u32::from(client.into_inner()))]),
+            )
+            .expect("Test data invalid.");
+            assert_eq!(Some(&account_expected), accounts.get(client));
+        });
+        Ok(())
+    }
+}
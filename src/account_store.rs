@@ -0,0 +1,40 @@
+use std::{fmt, future::Future};
+
+use crate::{
+    model::{Account, Accounts, ClientId},
+    Error,
+};
+
+/// Stores accounts while transactions are processed.
+///
+/// Each transaction takes its client's account out of the store, and puts it
+/// back once processed, so implementations may keep only some accounts in
+/// memory.
+pub trait AccountStore: fmt::Debug {
+    /// Removes and returns the client's account, or an empty account if the
+    /// client has none.
+    fn take_account(&mut self, client: ClientId) -> impl Future<Output = Result<Account, Error>>;
+
+    /// Stores the account, replacing any account for the same client.
+    fn put_account(&mut self, account: Account) -> impl Future<Output = Result<(), Error>>;
+
+    /// Returns all stored accounts.
+    fn into_accounts(self) -> impl Future<Output = Result<Accounts, Error>>;
+}
+
+impl AccountStore for Accounts {
+    async fn take_account(&mut self, client: ClientId) -> Result<Account, Error> {
+        Ok(self
+            .remove(&client)
+            .unwrap_or_else(|| Account::empty(client)))
+    }
+
+    async fn put_account(&mut self, account: Account) -> Result<(), Error> {
+        self.insert(account.client(), account);
+        Ok(())
+    }
+
+    async fn into_accounts(self) -> Result<Accounts, Error> {
+        Ok(self)
+    }
+}
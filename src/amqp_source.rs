@@ -0,0 +1,209 @@
+//! Applies transactions consumed from an AMQP queue, such as on RabbitMQ.
+//!
+//! Each delivery is acknowledged once its transaction is applied to a
+//! [`Processor`]. Deliveries that were not acknowledged, such as when the
+//! application stopped while applying them, are delivered again by the
+//! broker.
+//!
+//! Each delivery's payload is a transactions CSV row without a header, e.g.
+//! `deposit, 1, 1, 2.0`.
+//!
+//! Deliveries that cannot be converted to a transaction are rejected without
+//! being requeued, so the broker moves them to the queue's dead letter
+//! exchange, if one is configured with `x-dead-letter-exchange`.
+//!
+//! Consuming may be limited to a [`RateLimit`], in which case deliveries wait
+//! in the queue until they may be applied.
+
+use std::{future::Future, pin::pin};
+
+use futures::{
+    future::{self, Either},
+    StreamExt,
+};
+use lapin::{
+    message::Delivery,
+    options::{BasicAckOptions, BasicConsumeOptions, BasicNackOptions, BasicQosOptions},
+    types::FieldTable,
+    Channel, Connection, ConnectionProperties, Consumer,
+};
+
+use crate::{
+    csv::TransactCsv, rate_limit::RateLimiter, Error, IngestSummary, Processor, RateLimit, TxStore,
+};
+
+/// Consumes transactions from an AMQP queue.
+#[derive(Debug)]
+pub struct AmqpSource {
+    /// Connection to the broker.
+    connection: Connection,
+    /// Channel that deliveries are consumed on.
+    channel: Channel,
+    /// Deliveries from the queue.
+    consumer: Consumer,
+    /// Limits the rate that deliveries are applied.
+    rate_limiter: RateLimiter,
+}
+
+impl AmqpSource {
+    /// Default maximum number of unacknowledged deliveries.
+    pub const PREFETCH_COUNT_DEFAULT: u16 = 100;
+    /// AMQP reply code for closing a channel or connection normally.
+    const REPLY_CODE_SUCCESS: u16 = 200;
+
+    /// Connects to the broker, and starts consuming from the queue.
+    ///
+    /// The broker stops delivering messages while `prefetch_count` deliveries
+    /// are unacknowledged, so messages are not received faster than they are
+    /// applied.
+    ///
+    /// # Parameters
+    ///
+    /// * `url`: AMQP connection URL, e.g. `amqp://127.0.0.1:5672/%2f`.
+    /// * `queue`: Name of the queue to consume from.
+    /// * `consumer_tag`: Tag identifying this consumer to the broker.
+    /// * `prefetch_count`: Maximum number of unacknowledged deliveries, see
+    ///   [`AmqpSource::PREFETCH_COUNT_DEFAULT`].
+    pub async fn connect(
+        url: &str,
+        queue: &str,
+        consumer_tag: &str,
+        prefetch_count: u16,
+    ) -> Result<Self, Error> {
+        let connection = Connection::connect(url, ConnectionProperties::default())
+            .await
+            .map_err(Error::AmqpConnect)?;
+        let channel = connection
+            .create_channel()
+            .await
+            .map_err(Error::AmqpConnect)?;
+        channel
+            .basic_qos(prefetch_count, BasicQosOptions::default())
+            .await
+            .map_err(Error::AmqpConsume)?;
+        let consumer = channel
+            .basic_consume(
+                queue,
+                consumer_tag,
+                BasicConsumeOptions::default(),
+                FieldTable::default(),
+            )
+            .await
+            .map_err(Error::AmqpConsume)?;
+
+        Ok(Self {
+            connection,
+            channel,
+            consumer,
+            rate_limiter: RateLimiter::new(RateLimit::new()),
+        })
+    }
+
+    /// Limits the rate that deliveries are applied.
+    ///
+    /// While waiting, at most the prefetch count of deliveries are held
+    /// unacknowledged, and the rest stay in the queue. A delivery that was
+    /// received but not applied when `shutdown` completes is rejected and
+    /// requeued.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limiter = RateLimiter::new(rate_limit);
+        self
+    }
+
+    /// Applies transactions from the queue until `shutdown` completes.
+    ///
+    /// Each delivery is acknowledged after its transaction is applied or
+    /// rejected. Malformed deliveries are counted as record errors, and
+    /// rejected without being requeued so they are dead lettered. If applying
+    /// a transaction fails, the error is returned and the delivery is not
+    /// acknowledged, so it is delivered again.
+    ///
+    /// `shutdown` does not interrupt applying a transaction, so each delivery
+    /// is either applied and acknowledged, or requeued.
+    ///
+    /// # Parameters
+    ///
+    /// * `processor`: Processor to apply transactions with.
+    /// * `shutdown`: Future that completes when consuming should stop.
+    pub async fn run<S, F>(
+        &mut self,
+        processor: &mut Processor<S>,
+        shutdown: F,
+    ) -> Result<IngestSummary, Error>
+    where
+        S: TxStore,
+        F: Future<Output = ()>,
+    {
+        let mut shutdown = pin!(shutdown);
+        let mut ingest_summary = IngestSummary::default();
+
+        loop {
+            let delivery = match future::select(self.consumer.next(), shutdown.as_mut()).await {
+                Either::Left((Some(delivery), _)) => delivery.map_err(Error::AmqpRead)?,
+                Either::Left((None, _)) | Either::Right(((), _)) => return Ok(ingest_summary),
+            };
+            if !self
+                .rate_limiter
+                .acquire_or_shutdown(1, delivery.data.len() as u64, shutdown.as_mut())
+                .await
+            {
+                Self::requeue(&delivery).await?;
+                return Ok(ingest_summary);
+            }
+
+            match TransactCsv::row_transaction(&delivery.data).await {
+                Some(Ok(transaction)) => {
+                    let tx_result = processor.apply(transaction).await?;
+                    ingest_summary.record_tx_result(Some(tx_result));
+                    delivery
+                        .ack(BasicAckOptions::default())
+                        .await
+                        .map_err(Error::AmqpAck)?;
+                }
+                Some(Err(error)) if error.is_record_error() => {
+                    ingest_summary.record_error(error);
+                    Self::dead_letter(&delivery).await?;
+                }
+                Some(Err(error)) => return Err(error),
+                None => Self::dead_letter(&delivery).await?,
+            }
+        }
+    }
+
+    /// Closes the channel and the connection to the broker.
+    ///
+    /// Deliveries that were not acknowledged are requeued by the broker.
+    pub async fn close(self) -> Result<(), Error> {
+        self.channel
+            .close(Self::REPLY_CODE_SUCCESS, "Consumer closed.")
+            .await
+            .map_err(Error::AmqpClose)?;
+        self.connection
+            .close(Self::REPLY_CODE_SUCCESS, "Consumer closed.")
+            .await
+            .map_err(Error::AmqpClose)
+    }
+
+    /// Rejects a delivery and requeues it, so it is delivered again.
+    async fn requeue(delivery: &Delivery) -> Result<(), Error> {
+        delivery
+            .nack(BasicNackOptions {
+                multiple: false,
+                requeue: true,
+            })
+            .await
+            .map_err(Error::AmqpAck)
+    }
+
+    /// Rejects a delivery without requeueing it, so the broker moves it to
+    /// the queue's dead letter exchange.
+    async fn dead_letter(delivery: &Delivery) -> Result<(), Error> {
+        delivery
+            .nack(BasicNackOptions {
+                multiple: false,
+                requeue: false,
+            })
+            .await
+            .map_err(Error::AmqpAck)
+    }
+}
@@ -0,0 +1,216 @@
+//! Strategies and [`Arbitrary`] implementations for property based tests.
+//!
+//! These are enabled by the `proptest` feature. Amounts are mostly small
+//! values with up to four decimal places, with some zero amounts, and some
+//! near [`Decimal::MAX`] to exercise overflow handling.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use proptest::{arbitrary::any, prop_assert_eq, test_runner::TestRunner};
+//! use transact::{csv::TxRecord, model::Transaction};
+//!
+//! let mut test_runner = TestRunner::default();
+//! test_runner.run(&any::<Transaction>(), |transaction| {
+//!     let tx_record = TxRecord::from(transaction.clone());
+//!
+//!     prop_assert_eq!(transaction, Transaction::try_from(tx_record)?);
+//!     Ok(())
+//! })?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    collection, option, prop_oneof,
+    strategy::{BoxedStrategy, Just, Strategy},
+};
+use rust_decimal::Decimal;
+
+use crate::{
+    csv::{TxRecord, TxType},
+    model::{
+        Account, AccountState, Chargeback, ClientId, Deposit, Dispute, Resolve, Transaction, TxId,
+        Withdrawal,
+    },
+};
+
+/// Maximum number of disputed or charged back transactions in generated
+/// account states.
+const ACCOUNT_TXS_MAX: usize = 4;
+
+/// Returns a strategy for non-negative amounts.
+///
+/// Most amounts have up to four decimal places, and some are zero or within
+/// [`u32::MAX`] of [`Decimal::MAX`].
+pub fn amount() -> impl Strategy<Value = Decimal> {
+    prop_oneof![
+        8 => (0..=1_000_000_000_000i64, 0..=4u32)
+            .prop_map(|(mantissa, scale)| Decimal::new(mantissa, scale)),
+        1 => Just(Decimal::ZERO),
+        1 => any::<u32>().prop_map(|offset| Decimal::MAX - Decimal::from(offset)),
+    ]
+}
+
+/// Returns a strategy for client IDs.
+pub fn client_id() -> impl Strategy<Value = ClientId> {
+    any::<u16>().prop_map(ClientId::new)
+}
+
+/// Returns a strategy for transaction IDs.
+pub fn tx_id() -> impl Strategy<Value = TxId> {
+    any::<u32>().prop_map(TxId::new)
+}
+
+/// Returns a strategy for transaction types.
+pub fn tx_type() -> impl Strategy<Value = TxType> {
+    prop_oneof![
+        Just(TxType::Deposit),
+        Just(TxType::Withdrawal),
+        Just(TxType::Dispute),
+        Just(TxType::Resolve),
+        Just(TxType::Chargeback),
+    ]
+}
+
+/// Returns a strategy for transactions of any type.
+pub fn transaction() -> impl Strategy<Value = Transaction> {
+    prop_oneof![
+        (client_id(), tx_id(), amount())
+            .prop_map(|(client, tx, amount)| Transaction::from(Deposit::new(client, tx, amount))),
+        (client_id(), tx_id(), amount()).prop_map(|(client, tx, amount)| {
+            Transaction::from(Withdrawal::new(client, tx, amount))
+        }),
+        (client_id(), tx_id()).prop_map(|(client, tx)| Transaction::from(Dispute::new(client, tx))),
+        (client_id(), tx_id()).prop_map(|(client, tx)| Transaction::from(Resolve::new(client, tx))),
+        (client_id(), tx_id())
+            .prop_map(|(client, tx)| Transaction::from(Chargeback::new(client, tx))),
+    ]
+}
+
+/// Returns a strategy for transaction records.
+///
+/// Records may be malformed, such as a deposit without an amount, as they
+/// would be read from a transactions file.
+pub fn tx_record() -> impl Strategy<Value = TxRecord> {
+    (tx_type(), client_id(), tx_id(), option::of(amount()))
+        .prop_map(|(r#type, client, tx, amount)| TxRecord::new(r#type, client, tx, amount))
+}
+
+/// Returns a strategy for account states.
+///
+/// Available and held funds that overflow when added together are not
+/// generated.
+pub fn account_state() -> impl Strategy<Value = AccountState> {
+    (
+        client_id(),
+        amount(),
+        amount(),
+        any::<bool>(),
+        collection::hash_set(tx_id(), 0..ACCOUNT_TXS_MAX),
+        collection::hash_set(tx_id(), 0..ACCOUNT_TXS_MAX),
+    )
+        .prop_filter_map(
+            "Available and held funds overflow when added together.",
+            |(client, available, held, locked, disputed_txs, charged_back_txs)| {
+                Account::try_new(client, available, held, locked, disputed_txs)
+                    .ok()
+                    .map(|account| {
+                        AccountState::from(&account.with_charged_back_txs(charged_back_txs))
+                    })
+            },
+        )
+}
+
+impl Arbitrary for ClientId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        client_id().boxed()
+    }
+}
+
+impl Arbitrary for TxId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        tx_id().boxed()
+    }
+}
+
+impl Arbitrary for TxType {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        tx_type().boxed()
+    }
+}
+
+impl Arbitrary for Transaction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        transaction().boxed()
+    }
+}
+
+impl Arbitrary for TxRecord {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        tx_record().boxed()
+    }
+}
+
+impl Arbitrary for AccountState {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        account_state().boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{arbitrary::any, prop_assert, prop_assert_eq, proptest};
+
+    use crate::{
+        csv::{TxRecord, TxType},
+        model::{Account, AccountState, Transaction},
+    };
+
+    proptest! {
+        #[test]
+        fn tx_record_converts_back_into_transaction(transaction in any::<Transaction>()) {
+            let tx_record = TxRecord::from(transaction.clone());
+
+            prop_assert_eq!(Some(transaction.kind()), Some(tx_record.r#type()));
+            prop_assert_eq!(transaction, Transaction::try_from(tx_record)?);
+        }
+
+        #[test]
+        fn account_state_round_trips_through_account(account_state in any::<AccountState>()) {
+            let account = Account::try_from(account_state.clone())
+                .expect("Generated account state overflows.");
+
+            prop_assert_eq!(account_state, AccountState::from(&account));
+        }
+
+        #[test]
+        fn malformed_tx_record_is_rejected(tx_record in any::<TxRecord>()) {
+            let amount_required = matches!(
+                tx_record.r#type(),
+                TxType::Deposit | TxType::Withdrawal
+            );
+            let result = Transaction::try_from(tx_record.clone());
+
+            prop_assert!(result.is_ok() || (amount_required && tx_record.amount().is_none()));
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Synchronous API for consumers that do not use an async runtime.
+//!
+//! These functions create a runtime internally, and block until processing is
+//! complete.
+
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{model::Accounts, Error, ProcessOptions, ProcessSummary};
+
+/// Processes transactions and outputs them to the given writer.
+///
+/// This is the blocking equivalent of [`crate::process`].
+pub fn process<W>(path: &Path, out_stream: W, options: ProcessOptions) -> Result<(), Error>
+where
+    W: Write + Unpin,
+{
+    runtime()?.block_on(crate::process(path, BlockingWriter(out_stream), options))
+}
+
+/// Processes transactions and returns the final account state.
+///
+/// This is the blocking equivalent of [`crate::process_to_accounts`].
+pub fn process_to_accounts(path: &Path, options: ProcessOptions) -> Result<ProcessSummary, Error> {
+    runtime()?.block_on(crate::process_to_accounts(path, options))
+}
+
+/// Processes transactions of several ledgers, and returns the final account
+/// state of each ledger.
+///
+/// This is the blocking equivalent of [`crate::process_ledgers_to_accounts`].
+pub fn process_ledgers_to_accounts(
+    path: &Path,
+    options: ProcessOptions,
+) -> Result<BTreeMap<String, ProcessSummary>, Error> {
+    runtime()?.block_on(crate::process_ledgers_to_accounts(path, options))
+}
+
+/// Processes a new transactions file on top of the state in a snapshot, and
+/// outputs the accounts to the given writer.
+///
+/// This is the blocking equivalent of [`crate::process_incremental`].
+pub fn process_incremental<W>(
+    snapshot: &Path,
+    path: &Path,
+    out_stream: W,
+    options: ProcessOptions,
+) -> Result<(), Error>
+where
+    W: Write + Unpin,
+{
+    runtime()?.block_on(crate::process_incremental(
+        snapshot,
+        path,
+        BlockingWriter(out_stream),
+        options,
+    ))
+}
+
+/// Processes a new transactions file on top of the state in a snapshot, and
+/// returns the final account state.
+///
+/// This is the blocking equivalent of
+/// [`crate::process_incremental_to_accounts`].
+pub fn process_incremental_to_accounts(
+    snapshot: &Path,
+    path: &Path,
+    options: ProcessOptions,
+) -> Result<ProcessSummary, Error> {
+    runtime()?.block_on(crate::process_incremental_to_accounts(
+        snapshot, path, options,
+    ))
+}
+
+/// Reads accounts from an accounts CSV file.
+///
+/// This is the blocking equivalent of [`crate::read_accounts`].
+pub fn read_accounts(path: &Path) -> Result<Accounts, Error> {
+    runtime()?.block_on(crate::read_accounts(path))
+}
+
+/// Returns a runtime to drive processing on the current thread.
+///
+/// Timers are enabled for the run and lookup timeouts.
+fn runtime() -> Result<tokio::runtime::Runtime, Error> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .map_err(Error::RuntimeCreate)
+}
+
+/// Adapts a [`Write`] to be used as a [`tokio::io::AsyncWrite`].
+///
+/// Writes block the current thread, which is acceptable as the runtime only
+/// drives this processing.
+#[derive(Debug)]
+struct BlockingWriter<W>(W);
+
+impl<W> tokio::io::AsyncWrite for BlockingWriter<W>
+where
+    W: Write + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Poll::Ready(self.get_mut().0.write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Poll::Ready(self.get_mut().0.flush())
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, time::Duration};
+
+    use rust_decimal_macros::dec;
+
+    use crate::ProcessOptions;
+
+    #[test]
+    fn process_writes_accounts_to_writer() -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n"
+        )?;
+        let mut output = Vec::new();
+
+        super::process(
+            transactions_file.path(),
+            &mut output,
+            ProcessOptions::default(),
+        )?;
+
+        assert_eq!(
+            "client,available,held,total,locked\n\
+             1,2.0,0.0,2.0,false\n",
+            String::from_utf8(output)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn process_to_accounts_applies_timeouts() -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             dispute, 1, 1,\n"
+        )?;
+
+        let summary = super::process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::default()
+                .with_run_timeout(Duration::from_secs(60))
+                .with_lookup_timeout(Duration::from_secs(60)),
+        )?;
+
+        let account = summary.accounts().iter_sorted().next();
+        assert_eq!(Some(dec!(2.0)), account.map(|account| account.held()));
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+//! Types to read transactions from change data capture (CDC) events.
+//!
+//! Events are in the [Debezium] change event format, where `payload.after` is
+//! the row image of a row inserted into the transactions table. Row images
+//! are read as [`TxRecord`]s by default, or as any type that implements
+//! [`IntoTransaction`].
+//!
+//! ```json
+//! {"schema":{},"payload":{"before":null,"after":{"type":"deposit","client":1,"tx":1,"amount":"2.0"},"op":"c"}}
+//! ```
+//!
+//! Amounts may be JSON numbers or strings, so Debezium's
+//! `decimal.handling.mode` should be `string` or `double`.
+//!
+//! [Debezium]: https://debezium.io/documentation/reference/stable/connectors/postgresql.html#postgresql-events
+//! [`TxRecord`]: crate::csv::TxRecord
+//! [`IntoTransaction`]: crate::csv::IntoTransaction
+
+pub use self::{
+    change_event::{ChangeEvent, ChangeOp},
+    transact_cdc::TransactCdc,
+};
+
+mod change_event;
+mod transact_cdc;
@@ -0,0 +1,134 @@
+use serde::Deserialize;
+
+use crate::{
+    csv::{IntoTransaction, TxRecord},
+    model::Transaction,
+    Error,
+};
+
+/// Debezium change event for a row of the transactions table.
+///
+/// Only inserted rows are transactions to apply. Updates and deletes of
+/// existing rows are not applied, as transactions may not be changed once
+/// they are made.
+#[derive(Debug, Deserialize)]
+pub struct ChangeEvent<Rec = TxRecord> {
+    /// Body of the event, `None` for tombstones.
+    payload: Option<ChangePayload<Rec>>,
+}
+
+/// Body of a Debezium change event.
+#[derive(Debug, Deserialize)]
+struct ChangePayload<Rec> {
+    /// Operation that changed the row.
+    op: ChangeOp,
+    /// Row image after the change, `None` for deletes.
+    after: Option<Rec>,
+}
+
+/// Operation that changed a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ChangeOp {
+    /// Row was inserted.
+    #[serde(rename = "c")]
+    Create,
+    /// Row was read while taking the initial snapshot of the table.
+    #[serde(rename = "r")]
+    Read,
+    /// Row was updated.
+    #[serde(rename = "u")]
+    Update,
+    /// Row was deleted.
+    #[serde(rename = "d")]
+    Delete,
+    /// Table was truncated.
+    #[serde(rename = "t")]
+    Truncate,
+}
+
+impl<Rec> ChangeEvent<Rec>
+where
+    Rec: IntoTransaction,
+{
+    /// Returns the operation that changed the row, or `None` for tombstones.
+    pub fn op(&self) -> Option<ChangeOp> {
+        self.payload
+            .as_ref()
+            .map(|change_payload| change_payload.op)
+    }
+
+    /// Returns the transaction for an inserted or snapshot row, or `None` for
+    /// other events.
+    pub fn into_transaction(self) -> Option<Result<Transaction, Error>> {
+        let ChangePayload { op, after } = self.payload?;
+        match op {
+            ChangeOp::Create | ChangeOp::Read => after.map(IntoTransaction::into_transaction),
+            ChangeOp::Update | ChangeOp::Delete | ChangeOp::Truncate => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::{ChangeEvent, ChangeOp};
+    use crate::{
+        csv::TxRecord,
+        model::{ClientId, Deposit, Transaction, TxId},
+    };
+
+    #[test]
+    fn into_transaction_returns_inserted_rows_only() -> Result<(), Box<dyn std::error::Error>> {
+        let create = serde_json::from_str::<ChangeEvent>(
+            r#"{"schema":{"type":"struct"},"payload":{"before":null,"after":{"type":"deposit","client":1,"tx":1,"amount":"2.0"},"op":"c","ts_ms":1}}"#,
+        )?;
+        let read = serde_json::from_str::<ChangeEvent>(
+            r#"{"payload":{"before":null,"after":{"type":"deposit","client":1,"tx":2,"amount":1.5},"op":"r"}}"#,
+        )?;
+        let update = serde_json::from_str::<ChangeEvent>(
+            r#"{"payload":{"before":{"type":"deposit","client":1,"tx":1,"amount":"2.0"},"after":{"type":"deposit","client":1,"tx":1,"amount":"3.0"},"op":"u"}}"#,
+        )?;
+        let delete = serde_json::from_str::<ChangeEvent>(
+            r#"{"payload":{"before":{"type":"deposit","client":1,"tx":1,"amount":"2.0"},"after":null,"op":"d"}}"#,
+        )?;
+        let tombstone = serde_json::from_str::<ChangeEvent>(r#"{"schema":null,"payload":null}"#)?;
+
+        assert_eq!(
+            [
+                Some(ChangeOp::Create),
+                Some(ChangeOp::Read),
+                Some(ChangeOp::Update),
+                Some(ChangeOp::Delete),
+                None
+            ],
+            [&create, &read, &update, &delete, &tombstone].map(ChangeEvent::op)
+        );
+        let client = ClientId::new(1);
+        assert_eq!(
+            vec![
+                Some(TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(2.0)
+                )))),
+                Some(TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(2),
+                    dec!(1.5)
+                )))),
+                None,
+                None,
+                None,
+            ],
+            [create, read, update, delete, tombstone]
+                .into_iter()
+                .map(|change_event| change_event
+                    .into_transaction()
+                    .transpose()
+                    .map(|transaction| transaction.map(TxRecord::from)))
+                .collect::<Result<Vec<_>, _>>()?
+        );
+        Ok(())
+    }
+}
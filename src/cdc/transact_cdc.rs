@@ -0,0 +1,118 @@
+#[cfg(not(feature = "tokio"))]
+use futures::io::{AsyncBufReadExt, BufReader};
+use futures::{stream, TryStream};
+use serde::de::DeserializeOwned;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::{cdc::ChangeEvent, csv::IntoTransaction, io::AsyncRead, model::Transaction, Error};
+
+/// Reads transactions from Debezium change events.
+#[derive(Debug)]
+pub struct TransactCdc;
+
+impl TransactCdc {
+    /// Returns a [`TryStream`] of [`Transaction`]s read from change events,
+    /// with one event per line, deserializing each row image as a `Rec`.
+    ///
+    /// Events other than inserted rows are skipped, see
+    /// [`ChangeEvent::into_transaction`]. Empty lines and `null` lines, such
+    /// as Kafka tombstones printed by a console consumer, are also skipped.
+    ///
+    /// # Parameters
+    ///
+    /// * `reader`: Reader of change events as JSON lines.
+    pub fn stream_reader<'r, Rec, R>(
+        reader: R,
+    ) -> impl TryStream<Ok = Transaction, Error = Error> + 'r
+    where
+        Rec: DeserializeOwned + IntoTransaction + 'r,
+        R: AsyncRead + Unpin + Send + 'r,
+    {
+        let lines = (BufReader::new(reader), String::new(), 0u64);
+        stream::unfold(
+            lines,
+            |(mut reader, mut line, mut line_number)| async move {
+                loop {
+                    line.clear();
+                    match reader.read_line(&mut line).await {
+                        Ok(0) => return None,
+                        Ok(_) => line_number += 1,
+                        Err(error) => {
+                            return Some((
+                                Err(Error::ChangeEventRead(error)),
+                                (reader, line, line_number),
+                            ));
+                        }
+                    }
+
+                    let event = line.trim();
+                    if event.is_empty() {
+                        continue;
+                    }
+                    let transaction = match serde_json::from_str::<Option<ChangeEvent<Rec>>>(event)
+                    {
+                        Ok(change_event) => change_event.and_then(ChangeEvent::into_transaction),
+                        Err(error) => Some(Err(Error::ChangeEventDeserialize {
+                            line: line_number,
+                            error,
+                        })),
+                    };
+                    if let Some(transaction) = transaction {
+                        return Some((transaction, (reader, line, line_number)));
+                    }
+                }
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{StreamExt, TryStreamExt};
+    use rust_decimal_macros::dec;
+
+    use super::TransactCdc;
+    use crate::{
+        csv::TxRecord,
+        model::{ClientId, Deposit, Dispute, Transaction, TxId},
+        Error,
+    };
+
+    #[tokio::test]
+    async fn stream_reader_reads_inserted_rows() -> Result<(), Box<dyn std::error::Error>> {
+        let events = r#"{"payload":{"before":null,"after":{"type":"deposit","client":1,"tx":1,"amount":"2.0"},"op":"c"}}
+
+null
+{"payload":{"before":{"type":"deposit","client":1,"tx":1,"amount":"2.0"},"after":null,"op":"d"}}
+{"payload":{"before":null,"after":{"type":"dispute","client":1,"tx":1,"amount":null},"op":"c"}}
+{"payload":
+"#;
+
+        let transactions = TransactCdc::stream_reader::<TxRecord, _>(events.as_bytes())
+            .into_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        let client = ClientId::new(1);
+        assert_eq!(
+            vec![
+                TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(2.0)
+                ))),
+                TxRecord::from(Transaction::from(Dispute::new(client, TxId::new(1)))),
+            ],
+            transactions
+                .iter()
+                .filter_map(|transaction| transaction.as_ref().ok().cloned().map(TxRecord::from))
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            transactions.last(),
+            Some(Err(Error::ChangeEventDeserialize { line: 6, .. }))
+        ));
+        Ok(())
+    }
+}
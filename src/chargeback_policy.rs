@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+/// How to handle chargebacks of transactions that are not in dispute.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargebackPolicy {
+    /// Reject the chargeback with [`TxError::ChargebackTxNotInDispute`].
+    ///
+    /// [`TxError::ChargebackTxNotInDispute`]: crate::TxError::ChargebackTxNotInDispute
+    #[default]
+    RequireDispute,
+    /// Dispute the transaction before applying the chargeback, so its funds
+    /// are held and then reversed.
+    ///
+    /// The implicit dispute is processed as a separate dispute transaction. If
+    /// it is rejected, the chargeback is not applied.
+    ImplicitDispute,
+}
@@ -0,0 +1,314 @@
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    path::{Path, PathBuf},
+};
+
+use crate::{processor_snapshot::SnapshotState, Error, Processor, TxStore};
+
+/// Prefix of checkpoint file names.
+const CHECKPOINT_PREFIX: &str = "checkpoint-";
+
+/// Extension of checkpoint file names.
+const CHECKPOINT_EXTENSION: &str = "jsonl";
+
+/// Writes [`Processor`] snapshots to a directory every `interval`
+/// transactions, keeping only the most recent checkpoints.
+///
+/// Each checkpoint is a [`Processor::snapshot`], which holds the accounts,
+/// the deposits in the transaction store, and the processor's position in the
+/// input. Checkpoint files are named by position, e.g.
+/// `checkpoint-00000000000000100000.jsonl`, so the latest checkpoint is the
+/// last in name order.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn checkpointed() -> Result<(), transact::Error> {
+/// use std::{num::NonZeroU64, path::PathBuf};
+///
+/// use transact::{Checkpointer, MemTxStore};
+///
+/// let checkpointer = Checkpointer::new(
+///     PathBuf::from("checkpoints"),
+///     NonZeroU64::new(100_000).expect("Interval is non-zero."),
+/// );
+/// let mut processor = checkpointer.restore(MemTxStore::new()).await?;
+/// // Skip the first `processor.position()` transactions in the input, then
+/// // call `checkpointer.checkpoint_if_due(&mut processor)` after each
+/// // `processor.apply(transaction)`.
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Checkpointer {
+    /// Directory to write checkpoints to.
+    dir: PathBuf,
+    /// Number of transactions between checkpoints.
+    interval: NonZeroU64,
+    /// Number of checkpoints to keep.
+    retain: NonZeroUsize,
+}
+
+impl Checkpointer {
+    /// Returns a new `Checkpointer` writing to `dir` every `interval`
+    /// transactions.
+    ///
+    /// The two most recent checkpoints are kept, so that one remains if the
+    /// latest is unreadable.
+    pub fn new(dir: PathBuf, interval: NonZeroU64) -> Self {
+        Self {
+            dir,
+            interval,
+            retain: NonZeroUsize::new(2).expect("`2` is non-zero."),
+        }
+    }
+
+    /// Sets the number of checkpoints to keep.
+    pub fn with_retain(mut self, retain: NonZeroUsize) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    /// Returns the directory to write checkpoints to.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the number of transactions between checkpoints.
+    pub fn interval(&self) -> NonZeroU64 {
+        self.interval
+    }
+
+    /// Returns the number of checkpoints to keep.
+    pub fn retain(&self) -> NonZeroUsize {
+        self.retain
+    }
+
+    /// Returns a processor restored from the latest checkpoint, or a new
+    /// processor if there are no checkpoints.
+    ///
+    /// When the latest checkpoint cannot be read, e.g. it was only partially
+    /// copied, the older checkpoints are tried in turn. The error from the
+    /// latest checkpoint is returned if none of them can be read.
+    pub async fn restore<S>(&self, tx_store: S) -> Result<Processor<S>, Error>
+    where
+        S: TxStore,
+    {
+        let mut latest_error = None;
+        for checkpoint_path in self.checkpoint_paths().await?.iter().rev() {
+            match SnapshotState::read(checkpoint_path).await {
+                Ok(snapshot_state) => {
+                    return Processor::from_snapshot_state(tx_store, snapshot_state).await;
+                }
+                Err(error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        path = %checkpoint_path.display(),
+                        %error,
+                        "Checkpoint unreadable, trying an older checkpoint."
+                    );
+                    latest_error.get_or_insert(error);
+                }
+            }
+        }
+
+        match latest_error {
+            Some(error) => Err(error),
+            None => Ok(Processor::new(tx_store)),
+        }
+    }
+
+    /// Writes a checkpoint if the processor's position is a multiple of the
+    /// interval, returning the checkpoint's path if one was written.
+    pub async fn checkpoint_if_due<S>(
+        &self,
+        processor: &mut Processor<S>,
+    ) -> Result<Option<PathBuf>, Error>
+    where
+        S: TxStore,
+    {
+        let position = processor.position();
+        if position != 0 && position.is_multiple_of(self.interval.get()) {
+            self.checkpoint(processor).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Writes a checkpoint of the processor, then removes all but the most
+    /// recent checkpoints.
+    pub async fn checkpoint<S>(&self, processor: &mut Processor<S>) -> Result<PathBuf, Error>
+    where
+        S: TxStore,
+    {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(|error| Error::CheckpointDirCreate {
+                path: self.dir.clone(),
+                error,
+            })?;
+        let checkpoint_path = self.dir.join(format!(
+            "{CHECKPOINT_PREFIX}{position:020}.{CHECKPOINT_EXTENSION}",
+            position = processor.position()
+        ));
+        processor.snapshot(&checkpoint_path).await?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(path = %checkpoint_path.display(), "Checkpoint written.");
+
+        let checkpoint_paths = self.checkpoint_paths().await?;
+        let prune_count = checkpoint_paths.len().saturating_sub(self.retain.get());
+        for checkpoint_path_old in &checkpoint_paths[..prune_count] {
+            tokio::fs::remove_file(checkpoint_path_old)
+                .await
+                .map_err(|error| Error::CheckpointPrune {
+                    path: checkpoint_path_old.clone(),
+                    error,
+                })?;
+        }
+
+        Ok(checkpoint_path)
+    }
+
+    /// Returns the path to the latest checkpoint, if any.
+    pub async fn latest(&self) -> Result<Option<PathBuf>, Error> {
+        Ok(self.checkpoint_paths().await?.pop())
+    }
+
+    /// Returns the paths to the checkpoints in the directory, oldest first.
+    async fn checkpoint_paths(&self) -> Result<Vec<PathBuf>, Error> {
+        let dir_read = |error| Error::CheckpointDirRead {
+            path: self.dir.clone(),
+            error,
+        };
+        let mut read_dir = match tokio::fs::read_dir(&self.dir).await {
+            Ok(read_dir) => read_dir,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(dir_read(error)),
+        };
+
+        let mut checkpoint_paths = Vec::new();
+        while let Some(dir_entry) = read_dir.next_entry().await.map_err(dir_read)? {
+            let path = dir_entry.path();
+            let is_checkpoint = path
+                .file_stem()
+                .and_then(|file_stem| file_stem.to_str())
+                .is_some_and(|file_stem| file_stem.starts_with(CHECKPOINT_PREFIX))
+                && path
+                    .extension()
+                    .is_some_and(|extension| extension == CHECKPOINT_EXTENSION);
+            if is_checkpoint {
+                checkpoint_paths.push(path);
+            }
+        }
+        // Positions are zero padded, so name order is position order.
+        checkpoint_paths.sort();
+
+        Ok(checkpoint_paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::{NonZeroU64, NonZeroUsize};
+
+    use rust_decimal_macros::dec;
+
+    use super::Checkpointer;
+    use crate::{
+        model::{ClientId, Deposit, Transaction, TxId},
+        MemTxStore,
+    };
+
+    #[tokio::test]
+    async fn checkpoint_if_due_writes_every_interval_and_prunes_old_checkpoints()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let checkpoint_dir = tempfile::tempdir()?;
+        let checkpointer = Checkpointer::new(
+            checkpoint_dir.path().join("checkpoints"),
+            NonZeroU64::new(2).expect("Test data invalid."),
+        )
+        .with_retain(NonZeroUsize::new(2).expect("Test data invalid."));
+        let mut processor = checkpointer.restore(MemTxStore::new()).await?;
+
+        for tx in 1..=7 {
+            processor
+                .apply(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(tx),
+                    dec!(1.0),
+                )))
+                .await??;
+            checkpointer.checkpoint_if_due(&mut processor).await?;
+        }
+        let processor_restored = checkpointer.restore(MemTxStore::new()).await?;
+
+        let mut checkpoint_names = std::fs::read_dir(checkpointer.dir())?
+            .map(|dir_entry| dir_entry.map(|dir_entry| dir_entry.file_name()))
+            .collect::<Result<Vec<_>, _>>()?;
+        checkpoint_names.sort();
+        assert_eq!(
+            vec![
+                "checkpoint-00000000000000000004.jsonl",
+                "checkpoint-00000000000000000006.jsonl",
+            ],
+            checkpoint_names
+        );
+        assert_eq!(6, processor_restored.position());
+        assert_eq!(
+            Some(dec!(6.0)),
+            processor_restored
+                .accounts()
+                .get(client)
+                .map(|account| account.available())
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn restore_falls_back_to_older_checkpoint_when_latest_is_unreadable()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let checkpoint_dir = tempfile::tempdir()?;
+        let checkpointer = Checkpointer::new(
+            checkpoint_dir.path().to_path_buf(),
+            NonZeroU64::new(2).expect("Test data invalid."),
+        );
+        let mut processor = checkpointer.restore(MemTxStore::new()).await?;
+
+        for tx in 1..=4 {
+            processor
+                .apply(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(tx),
+                    dec!(1.0),
+                )))
+                .await??;
+            checkpointer.checkpoint_if_due(&mut processor).await?;
+        }
+        let checkpoint_latest = checkpointer
+            .latest()
+            .await?
+            .expect("Checkpoint was written.");
+        std::fs::write(&checkpoint_latest, "{\"truncated\":")?;
+        let processor_restored = checkpointer.restore(MemTxStore::new()).await?;
+
+        assert_eq!(2, processor_restored.position());
+        assert_eq!(
+            Some(dec!(2.0)),
+            processor_restored
+                .accounts()
+                .get(client)
+                .map(|account| account.available())
+        );
+
+        std::fs::write(
+            checkpoint_dir
+                .path()
+                .join("checkpoint-00000000000000000002.jsonl"),
+            "{\"truncated\":",
+        )?;
+        assert!(checkpointer.restore(MemTxStore::new()).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Command line interface for the `transact` application.
+
+#[cfg(feature = "otel")]
+pub use self::trace_export::TraceExport;
+pub use self::{
+    account_diff::AccountDiff,
+    account_discrepancy::AccountDiscrepancy,
+    account_event_observer::AccountEventObserver,
+    audit_trail_observer::AuditTrailObserver,
+    bench_cmd::BenchCmd,
+    cli_args::{
+        BenchArgs, CliArgs, CliCommand, DiffArgs, DisputesArgs, JournalVerifyArgs, LedgersArgs,
+        LogFormat, OutputFormat, ProcessArgs, QueryArgs, ReconcileArgs, ReplayArgs, SimulateArgs,
+        StatsArgs, ValidateArgs, VerifyArgs,
+    },
+    cli_error::CliError,
+    diff_cmd::DiffCmd,
+    dispute_ledger_entry::DisputeLedgerEntry,
+    dispute_ledger_observer::DisputeLedgerObserver,
+    disputes_cmd::DisputesCmd,
+    extended_account::ExtendedAccount,
+    fixed_format_layout::{FixedFormatColumn, FixedFormatLayout, FixedFormatRecord},
+    fixed_format_observer::FixedFormatObserver,
+    follow_cmd::FollowCmd,
+    journal_chain::JournalChain,
+    journal_observer::JournalObserver,
+    journal_verify_cmd::JournalVerifyCmd,
+    latency_summary_observer::LatencySummaryObserver,
+    ledger_observer::LedgerObserver,
+    ledgers_cmd::LedgersCmd,
+    locked_accounts_observer::LockedAccountsObserver,
+    logging::init_logging,
+    output_commit::OutputCommit,
+    process_cmd::ProcessCmd,
+    process_config::ProcessConfig,
+    query_cmd::QueryCmd,
+    reconcile_cmd::ReconcileCmd,
+    rejection_observer::RejectionObserver,
+    rejection_summary_observer::RejectionSummaryObserver,
+    repl_cmd::ReplCmd,
+    repl_command::ReplCommand,
+    replay_cmd::ReplayCmd,
+    report_writer::ReportWriter,
+    runtime::runtime,
+    shutdown_signal::{cancel_on_shutdown_signal, shutdown_signal},
+    simulate_cmd::SimulateCmd,
+    stats_cmd::StatsCmd,
+    tx_stats::TxStats,
+    validate_cmd::ValidateCmd,
+    validation_issue::ValidationIssue,
+    verify_cmd::VerifyCmd,
+};
+#[cfg(feature = "server")]
+pub use self::{cli_args::ServeArgs, serve_cmd::ServeCmd};
+#[cfg(feature = "tui")]
+pub use self::{dashboard::Dashboard, dashboard_observer::DashboardObserver};
+
+mod account_diff;
+mod account_discrepancy;
+mod account_event_observer;
+mod audit_trail_observer;
+mod bench_cmd;
+mod cli_args;
+mod cli_error;
+#[cfg(feature = "tui")]
+mod dashboard;
+#[cfg(feature = "tui")]
+mod dashboard_observer;
+mod diff_cmd;
+mod dispute_ledger_entry;
+mod dispute_ledger_observer;
+mod disputes_cmd;
+mod extended_account;
+mod fixed_format_layout;
+mod fixed_format_observer;
+mod follow_cmd;
+mod journal_chain;
+mod journal_observer;
+mod journal_verify_cmd;
+mod latency_summary_observer;
+mod ledger_observer;
+mod ledgers_cmd;
+mod locked_accounts_observer;
+mod logging;
+mod output_commit;
+mod process_cmd;
+mod process_config;
+mod query_cmd;
+mod reconcile_cmd;
+mod rejection_observer;
+mod rejection_summary_observer;
+mod repl_cmd;
+mod repl_command;
+mod replay_cmd;
+mod report_writer;
+mod runtime;
+#[cfg(feature = "server")]
+mod serve_cmd;
+mod shutdown_signal;
+mod simulate_cmd;
+mod stats_cmd;
+#[cfg(feature = "otel")]
+mod trace_export;
+mod tx_stats;
+mod validate_cmd;
+mod validation_issue;
+mod verify_cmd;
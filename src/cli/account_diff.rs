@@ -0,0 +1,65 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+use transact::model::ClientId;
+
+/// Difference between a client's account in two accounts files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountDiff {
+    /// Account's amount differs by more than the tolerance.
+    AmountDiff {
+        /// Client ID.
+        client: ClientId,
+        /// Name of the amount, e.g. `"available"`.
+        field: &'static str,
+        /// Amount in the first accounts file.
+        amount_a: Decimal,
+        /// Amount in the second accounts file.
+        amount_b: Decimal,
+    },
+    /// Account is locked in one file, but not the other.
+    LockedDiff {
+        /// Client ID.
+        client: ClientId,
+        /// Whether the account is locked in the first accounts file.
+        locked_a: bool,
+        /// Whether the account is locked in the second accounts file.
+        locked_b: bool,
+    },
+    /// Account is only in the first accounts file.
+    OnlyInA {
+        /// Client ID.
+        client: ClientId,
+    },
+    /// Account is only in the second accounts file.
+    OnlyInB {
+        /// Client ID.
+        client: ClientId,
+    },
+}
+
+impl fmt::Display for AccountDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AmountDiff {
+                client,
+                field,
+                amount_a,
+                amount_b,
+            } => {
+                write!(f, "client {client}: {field} {amount_a} -> {amount_b}")?;
+                match amount_b.checked_sub(*amount_a) {
+                    Some(difference) => write!(f, " ({difference:+})"),
+                    None => Ok(()),
+                }
+            }
+            Self::LockedDiff {
+                client,
+                locked_a,
+                locked_b,
+            } => write!(f, "client {client}: locked {locked_a} -> {locked_b}"),
+            Self::OnlyInA { client } => write!(f, "client {client}: only in first file"),
+            Self::OnlyInB { client } => write!(f, "client {client}: only in second file"),
+        }
+    }
+}
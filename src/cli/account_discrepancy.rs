@@ -0,0 +1,103 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+use transact::model::ClientId;
+
+/// Difference between an account in an accounts file, and the account derived
+/// from processing transactions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountDiscrepancy {
+    /// Account's total does not equal its available and held amounts.
+    TotalMismatch {
+        /// Client ID.
+        client: ClientId,
+        /// Available amount in the accounts file.
+        available: Decimal,
+        /// Held amount in the accounts file.
+        held: Decimal,
+        /// Total amount in the accounts file.
+        total: Decimal,
+    },
+    /// Account's held amount is negative.
+    HeldNegative {
+        /// Client ID.
+        client: ClientId,
+        /// Held amount in the accounts file.
+        held: Decimal,
+    },
+    /// Account is locked, but no chargeback was applied for the client.
+    LockedWithoutChargeback {
+        /// Client ID.
+        client: ClientId,
+    },
+    /// Account is not locked, but a chargeback was applied for the client.
+    ChargebackNotLocked {
+        /// Client ID.
+        client: ClientId,
+    },
+    /// Account's amount differs from the derived amount.
+    AmountMismatch {
+        /// Client ID.
+        client: ClientId,
+        /// Name of the amount, e.g. `"available"`.
+        field: &'static str,
+        /// Amount derived from the transactions.
+        expected: Decimal,
+        /// Amount in the accounts file.
+        actual: Decimal,
+    },
+    /// Account derived from the transactions is not in the accounts file.
+    AccountMissing {
+        /// Client ID.
+        client: ClientId,
+    },
+    /// Account in the accounts file has no transactions.
+    AccountUnexpected {
+        /// Client ID.
+        client: ClientId,
+    },
+}
+
+impl fmt::Display for AccountDiscrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TotalMismatch {
+                client,
+                available,
+                held,
+                total,
+            } => write!(
+                f,
+                "client {client}: total {total} does not equal available {available} + held {held}."
+            ),
+            Self::HeldNegative { client, held } => {
+                write!(f, "client {client}: held amount {held} is negative.")
+            }
+            Self::LockedWithoutChargeback { client } => write!(
+                f,
+                "client {client}: account is locked, but no chargeback was applied."
+            ),
+            Self::ChargebackNotLocked { client } => write!(
+                f,
+                "client {client}: a chargeback was applied, but the account is not locked."
+            ),
+            Self::AmountMismatch {
+                client,
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "client {client}: {field} is {actual}, expected {expected}."
+            ),
+            Self::AccountMissing { client } => write!(
+                f,
+                "client {client}: account is missing from the accounts file."
+            ),
+            Self::AccountUnexpected { client } => write!(
+                f,
+                "client {client}: account has no transactions in the transactions file."
+            ),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use rust_decimal::Decimal;
+use transact::{
+    model::{Account, AccountEvent, ClientId, Transaction},
+    ProcessObserver,
+};
+
+use crate::cli::{CliError, ReportWriter};
+
+/// Writes the account events of each applied transaction to an events file.
+///
+/// Each line is a JSON object of an [`AccountEvent`], in the order they were
+/// applied, for loading into an event store.
+#[derive(Debug)]
+pub struct AccountEventObserver {
+    /// Path to the events file.
+    path: PathBuf,
+    /// Writer to the events file.
+    report_writer: ReportWriter,
+    /// Held funds of each account after its last applied transaction.
+    held_by_client: Mutex<HashMap<ClientId, Decimal>>,
+}
+
+impl AccountEventObserver {
+    /// Returns a new `AccountEventObserver` writing to the file at `path`.
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn try_new(path: PathBuf) -> Result<Self, CliError> {
+        match ReportWriter::try_new(&path) {
+            Ok(report_writer) => Ok(Self {
+                path,
+                report_writer,
+                held_by_client: Mutex::new(HashMap::new()),
+            }),
+            Err(error) => Err(CliError::EventsFileCreate { path, error }),
+        }
+    }
+
+    /// Flushes the events file, returning the first error writing to it.
+    pub fn finish(&self) -> Result<(), CliError> {
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::EventsWrite {
+                path: self.path.clone(),
+                error,
+            })
+    }
+}
+
+impl ProcessObserver for AccountEventObserver {
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        let held_before = self
+            .held_by_client
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(account.client(), account.held())
+            .unwrap_or_default();
+
+        AccountEvent::from_applied(transaction, held_before, account)
+            .iter()
+            // Serializing these types to a string does not fail.
+            .filter_map(|account_event| serde_json::to_string(account_event).ok())
+            .for_each(|entry| self.report_writer.write_line(&entry));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::stream;
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{ClientId, Deposit, Dispute, Resolve, Transaction, TxId, Withdrawal},
+        MemTxStore, ProcessOptions,
+    };
+
+    use super::AccountEventObserver;
+
+    #[tokio::test]
+    async fn writes_events_of_applied_transactions() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let transactions = stream::iter([
+            Ok(Transaction::from(Deposit::new(
+                client,
+                TxId::new(1),
+                dec!(3.0),
+            ))),
+            Ok(Transaction::from(Withdrawal::new(
+                client,
+                TxId::new(2),
+                dec!(5.0),
+            ))),
+            Ok(Transaction::from(Dispute::new(client, TxId::new(1)))),
+            Ok(Transaction::from(Resolve::new(client, TxId::new(1)))),
+        ]);
+        let events_dir = tempfile::tempdir()?;
+        let events_path = events_dir.path().join("events.jsonl");
+        let account_event_observer = Arc::new(AccountEventObserver::try_new(events_path.clone())?);
+
+        transact::process_transactions(
+            transactions,
+            &MemTxStore::new(),
+            ProcessOptions::new().with_observer(account_event_observer.clone()),
+        )
+        .await?;
+        account_event_observer.finish()?;
+
+        assert_eq!(
+            "{\"event\":\"FundsDeposited\",\"client\":1,\"tx\":1,\"amount\":\"3\"}\n\
+             {\"event\":\"FundsHeld\",\"client\":1,\"tx\":1,\"amount\":\"3\"}\n\
+             {\"event\":\"FundsReleased\",\"client\":1,\"tx\":1,\"amount\":\"3\"}\n",
+            std::fs::read_to_string(&events_path)?
+        );
+        Ok(())
+    }
+}
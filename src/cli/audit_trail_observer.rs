@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use transact::{
+    csv::TxRecord,
+    model::{Account, Transaction},
+    Error, ProcessObserver, TxError,
+};
+
+use crate::cli::{CliError, ReportWriter};
+
+/// Writes the outcome of every input record to an audit trail.
+///
+/// The trail is CSV with the columns in [`AuditTrailObserver::CSV_HEADER`]:
+///
+/// * `outcome`: `applied`, `rejected`, or `malformed`.
+/// * `code`: Rejection or record error code, empty when applied.
+/// * `type`, `client`, `tx`, `amount`: The transaction, empty when malformed.
+/// * `available`, `held`, `total`, `locked`: The account after the transaction,
+///   empty when malformed.
+///
+/// Transactions are written in the order they are processed. Records are read
+/// ahead of processing, so malformed records may be written before earlier
+/// transactions.
+#[derive(Debug)]
+pub struct AuditTrailObserver {
+    /// Path to the audit trail file.
+    path: PathBuf,
+    /// Writer to the audit trail file.
+    report_writer: ReportWriter,
+}
+
+impl AuditTrailObserver {
+    /// CSV header of the audit trail.
+    pub const CSV_HEADER: &'static str =
+        "outcome,code,type,client,tx,amount,available,held,total,locked";
+
+    /// Returns a new `AuditTrailObserver` writing to the file at `path`.
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn try_new(path: PathBuf) -> Result<Self, CliError> {
+        match ReportWriter::try_new(&path) {
+            Ok(report_writer) => {
+                report_writer.write_line(Self::CSV_HEADER);
+                Ok(Self {
+                    path,
+                    report_writer,
+                })
+            }
+            Err(error) => Err(CliError::AuditTrailFileCreate { path, error }),
+        }
+    }
+
+    /// Flushes the audit trail, returning the first error writing to it.
+    pub fn finish(&self) -> Result<(), CliError> {
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::AuditTrailWrite {
+                path: self.path.clone(),
+                error,
+            })
+    }
+
+    /// Returns the `type` column value for a transaction.
+    fn tx_type(transaction: &Transaction) -> &'static str {
+        match transaction {
+            Transaction::Deposit(_) => "deposit",
+            Transaction::Withdrawal(_) => "withdrawal",
+            Transaction::Dispute(_) => "dispute",
+            Transaction::Resolve(_) => "resolve",
+            Transaction::Chargeback(_) => "chargeback",
+        }
+    }
+}
+
+impl ProcessObserver for AuditTrailObserver {
+    fn on_transaction_processed(
+        &self,
+        transaction: &Transaction,
+        tx_result: Result<(), &TxError>,
+        account: &Account,
+    ) {
+        let (outcome, code) = match tx_result {
+            Ok(()) => ("applied", ""),
+            Err(tx_error) => ("rejected", tx_error.code()),
+        };
+        let tx_type = Self::tx_type(transaction);
+        let tx_record = TxRecord::from(transaction.clone());
+        let client = tx_record.client();
+        let tx = tx_record.tx();
+        let amount = tx_record
+            .amount()
+            .map(|amount| amount.to_string())
+            .unwrap_or_default();
+        let available = account.available();
+        let held = account.held();
+        let total = account.total();
+        let locked = account.locked();
+
+        self.report_writer.write_line(&format!(
+            "{outcome},{code},{tx_type},{client},{tx},{amount},\
+             {available},{held},{total},{locked}"
+        ));
+    }
+
+    fn on_record_error(&self, error: &Error) {
+        self.report_writer
+            .write_line(&format!("malformed,{},,,,,,,,", error.code()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::stream;
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{ClientId, Deposit, Dispute, Transaction, TxId, Withdrawal},
+        Error, MemTxStore, ProcessOptions,
+    };
+
+    use super::AuditTrailObserver;
+
+    #[tokio::test]
+    async fn writes_outcome_of_every_record() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let trail_dir = tempfile::tempdir()?;
+        let trail_path = trail_dir.path().join("audit.csv");
+        let audit_trail_observer = Arc::new(AuditTrailObserver::try_new(trail_path.clone())?);
+
+        transact::process_transactions(
+            stream::iter([
+                Ok(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(3.0),
+                ))),
+                Err(Error::DepositAmountNotProvided {
+                    client,
+                    tx: TxId::new(2),
+                }),
+                Ok(Transaction::from(Withdrawal::new(
+                    client,
+                    TxId::new(3),
+                    dec!(5.0),
+                ))),
+                Ok(Transaction::from(Dispute::new(client, TxId::new(1)))),
+            ]),
+            &MemTxStore::new(),
+            ProcessOptions::new().with_observer(audit_trail_observer.clone()),
+        )
+        .await?;
+        audit_trail_observer.finish()?;
+
+        assert_eq!(
+            "outcome,code,type,client,tx,amount,available,held,total,locked\n\
+             malformed,DEPOSIT_AMOUNT_NOT_PROVIDED,,,,,,,,\n\
+             applied,,deposit,1,1,3,3,0,3,false\n\
+             rejected,WITHDRAWAL_INSUFFICIENT_AVAILABLE,withdrawal,1,3,5,3,0,3,false\n\
+             applied,,dispute,1,1,,0,3,3,false\n",
+            std::fs::read_to_string(&trail_path)?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use gen::{TxRows, HEADER};
+use tokio::io::AsyncWriteExt;
+use transact::{
+    csv::TransactCsv,
+    model::{Account, Transaction},
+    Error, ProcessObserver, ProcessOptions, TxBlockStore, TxError,
+};
+
+use crate::cli::{runtime, BenchArgs, CliError};
+
+/// Generates a workload in memory, and reports how quickly it is processed.
+#[derive(Debug)]
+pub struct BenchCmd;
+
+/// Counts processing events during a benchmark.
+#[derive(Debug, Default)]
+struct BenchObserver {
+    /// Number of transactions applied.
+    applied: AtomicU64,
+    /// Number of transactions rejected.
+    rejected: AtomicU64,
+    /// Number of blocks persisted to the block store.
+    blocks: AtomicU64,
+    /// Number of transactions in persisted blocks.
+    block_transactions: AtomicU64,
+}
+
+impl ProcessObserver for BenchObserver {
+    fn on_transaction_applied(&self, _transaction: &Transaction, _account: &Account) {
+        self.applied.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_transaction_rejected(&self, _transaction: &Transaction, _tx_error: &TxError) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_block_persisted(&self, transactions: &[Transaction]) {
+        self.blocks.fetch_add(1, Ordering::Relaxed);
+        self.block_transactions
+            .fetch_add(transactions.len() as u64, Ordering::Relaxed);
+    }
+}
+
+impl BenchCmd {
+    /// Size of the buffer between the generator and the CSV reader.
+    const PIPE_CAPACITY: usize = 64 * 1024;
+
+    /// Runs the `bench` command.
+    ///
+    /// Rows are generated as CSV and streamed into the CSV reader, so parsing,
+    /// the block store, and account updates are all measured.
+    pub fn run(bench_args: BenchArgs) -> Result<(), CliError> {
+        let BenchArgs {
+            rows,
+            profile,
+            seed,
+            block_size,
+        } = bench_args;
+
+        let bench_observer = Arc::new(BenchObserver::default());
+        let mut options = ProcessOptions::new().with_observer(bench_observer.clone());
+        if let Some(block_size) = block_size {
+            options = options.with_block_size(block_size);
+        }
+
+        let (summary, row_count, duration) = runtime()?.block_on(async {
+            let tx_block_store = TxBlockStore::try_new()?;
+            let (reader, writer) = tokio::io::duplex(Self::PIPE_CAPACITY);
+            let generator = tokio::spawn(Self::generate(
+                writer,
+                TxRows::new(rows, profile).with_seed(seed),
+            ));
+
+            let start = Instant::now();
+            let summary = transact::process_transactions(
+                TransactCsv::stream_reader(reader),
+                &tx_block_store,
+                options,
+            )
+            .await?;
+            let duration = start.elapsed();
+            // The generator only stops early if the reader is dropped, which
+            // happens after processing fails.
+            let row_count = generator.await.unwrap_or_default();
+
+            Ok::<_, Error>((summary, row_count, duration))
+        })?;
+
+        println!("profile: {profile}");
+        println!("seed: {seed}");
+        println!("rows: {row_count}");
+        println!("duration: {:.3}s", duration.as_secs_f64());
+        println!(
+            "throughput: {} rows/s",
+            Self::throughput(row_count, duration)
+        );
+        match Self::peak_rss_kib() {
+            Some(peak_rss_kib) => println!("peak RSS: {} MiB", peak_rss_kib / 1024),
+            None => println!("peak RSS: unavailable"),
+        }
+        println!("accounts: {}", summary.accounts().len());
+        println!(
+            "transactions applied: {}",
+            bench_observer.applied.load(Ordering::Relaxed)
+        );
+        println!(
+            "transactions rejected: {}",
+            bench_observer.rejected.load(Ordering::Relaxed)
+        );
+        println!(
+            "blocks persisted: {}",
+            bench_observer.blocks.load(Ordering::Relaxed)
+        );
+        println!(
+            "transactions persisted: {}",
+            bench_observer.block_transactions.load(Ordering::Relaxed)
+        );
+
+        Ok(())
+    }
+
+    /// Writes the generated rows as CSV, returning the number of rows written.
+    async fn generate(mut writer: tokio::io::DuplexStream, tx_rows: TxRows) -> u64 {
+        let mut buffer = format!("{HEADER}\n");
+        let mut row_count = 0;
+        for tx_row in tx_rows {
+            buffer.push_str(&tx_row.to_string());
+            buffer.push('\n');
+            row_count += 1;
+
+            if buffer.len() >= Self::PIPE_CAPACITY {
+                if writer.write_all(buffer.as_bytes()).await.is_err() {
+                    return row_count;
+                }
+                buffer.clear();
+            }
+        }
+        // Errors mean the reader has stopped, so there is nothing to report.
+        let _ = writer.write_all(buffer.as_bytes()).await;
+        let _ = writer.shutdown().await;
+
+        row_count
+    }
+
+    /// Returns the number of rows processed per second.
+    fn throughput(row_count: u64, duration: Duration) -> u64 {
+        let millis = duration.as_millis().max(1);
+        u64::try_from(u128::from(row_count) * 1000 / millis).unwrap_or(u64::MAX)
+    }
+
+    /// Returns the peak resident set size of this process in KiB.
+    ///
+    /// This is only available on Linux.
+    fn peak_rss_kib() -> Option<u64> {
+        let status = std::fs::read_to_string("/proc/self/status").ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))
+            .and_then(|peak_rss| peak_rss.trim().trim_end_matches("kB").trim().parse().ok())
+    }
+}
@@ -0,0 +1,650 @@
+#[cfg(feature = "server")]
+use std::net::SocketAddr;
+#[cfg(feature = "otel")]
+use std::path::Path;
+use std::{
+    num::{NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+};
+
+use rust_decimal::Decimal;
+
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use gen::{Profile, TxRows};
+use serde::Deserialize;
+
+/// Processes transactions and outputs the final state of client accounts.
+#[derive(Debug, Parser)]
+#[command(version, arg_required_else_help = true)]
+pub struct CliArgs {
+    /// Command to run.
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+    /// Transactions CSV file to process.
+    ///
+    /// This is equivalent to `transact process <INPUT>`.
+    pub input: Option<PathBuf>,
+    /// Log more detail to stderr.
+    ///
+    /// `-v` logs rejected transactions, `-vv` adds block store activity, and
+    /// `-vvv` adds every applied transaction. Without this, the filter is read
+    /// from `TRANSACT_LOG`, and defaults to warnings only.
+    #[arg(long, short, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+    /// Format to write logs in.
+    #[arg(long, value_enum, default_value_t = LogFormat::Text, global = true)]
+    pub log_format: LogFormat,
+    /// ID of this run, exported with spans as `transact.run_id`.
+    ///
+    /// Spans are exported over OTLP when `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+    /// Defaults to an ID derived from the start time and process ID.
+    #[cfg(feature = "otel")]
+    #[arg(long, env = "TRANSACT_RUN_ID", global = true)]
+    pub run_id: Option<String>,
+}
+
+/// Commands for the `transact` application.
+#[derive(Debug, Subcommand)]
+pub enum CliCommand {
+    /// Processes transactions and outputs the final state of client accounts.
+    Process(Box<ProcessArgs>),
+    /// Verifies an accounts file against the transactions it was derived from.
+    ///
+    /// Balances are re-derived from the transactions, and account invariants
+    /// are checked. Discrepancies are printed, and the command fails if there
+    /// are any.
+    Verify(VerifyArgs),
+    /// Processes a generated workload, and reports throughput and resource
+    /// usage.
+    ///
+    /// The workload is generated in memory, so no input file is needed.
+    Bench(BenchArgs),
+    /// Processes transactions from stdin as they arrive, writing each updated
+    /// account to stdout.
+    ///
+    /// e.g. `tail -f transactions.csv | transact follow`
+    Follow,
+    /// Checks that a transactions file can be processed, without processing
+    /// it.
+    ///
+    /// The header, each record, and amount signs and precision are checked,
+    /// without storing or applying transactions. Issues are printed, and the
+    /// command fails if there are any.
+    Validate(ValidateArgs),
+    /// Reports statistics of a transactions file without applying it.
+    ///
+    /// This includes counts per transaction type, distinct clients, the
+    /// transaction ID range, amount percentiles, and malformed records.
+    Stats(StatsArgs),
+    /// Compares two accounts files.
+    ///
+    /// Per-client differences are printed, and the command fails if there are
+    /// any.
+    Diff(DiffArgs),
+    /// Processes transactions, and compares the accounts with an expected
+    /// balances file.
+    ///
+    /// Per-client differences are printed, and the command fails if there are
+    /// any.
+    Reconcile(ReconcileArgs),
+    /// Processes transactions of several ledgers, and writes each ledger's
+    /// accounts to its own file.
+    ///
+    /// The ledger of each transaction is read from the `ledger` or `tenant`
+    /// column, and transactions without one are in the `default` ledger. Each
+    /// ledger is processed independently, so client and transaction IDs may
+    /// repeat across ledgers.
+    Ledgers(LedgersArgs),
+    /// Processes transactions for a single client, and prints its account.
+    Query(QueryArgs),
+    /// Prints the dispute history from a dispute ledger.
+    ///
+    /// The ledger is written by `transact process --disputes`, and holds every
+    /// dispute opened, resolved, and charged back, with its amount.
+    Disputes(DisputesArgs),
+    /// Applies hypothetical transactions to an accounts snapshot, and reports
+    /// which would be accepted or rejected, and the resulting balances.
+    ///
+    /// Nothing is written, so the snapshot is unchanged.
+    Simulate(SimulateArgs),
+    /// Applies transactions entered one at a time, printing the result of
+    /// each.
+    ///
+    /// e.g. `deposit 1 100 50.0`, `dispute 1 100`. Enter `help` for the
+    /// available commands.
+    Repl,
+    /// Reconstructs accounts from an audit journal.
+    ///
+    /// The journal is written by `transact process --journal`, and contains
+    /// the applied transactions in the order they were applied. Replaying it
+    /// reproduces the same accounts, and fails if any transaction is rejected,
+    /// as that means the journal does not match a processing run.
+    Replay(ReplayArgs),
+    /// Checks that an audit journal was not altered after it was written.
+    ///
+    /// Each row of a journal written by `transact process --journal` is
+    /// chained to the rows before it by a hash. The hashes are checked, and the
+    /// final hash compared with the root written next to the journal, or the
+    /// one passed with `--root`.
+    JournalVerify(JournalVerifyArgs),
+    /// Serves HTTP endpoints to ingest transactions and query accounts.
+    ///
+    /// * `POST /transactions`: Applies transactions CSV in the request body.
+    /// * `GET /accounts`: Returns accounts, filtered by `locked`, and paged by
+    ///   `offset` and `limit`.
+    /// * `GET /accounts/{client}`: Returns the client's account.
+    /// * `GET /accounts/{client}/disputes`: Returns the client's disputed and
+    ///   charged back transactions.
+    /// * `GET /accounts/updates`: WebSocket that pushes account updates.
+    /// * `GET /healthz`: Returns `200 OK` while the server is running.
+    /// * `GET /readyz`: Returns ingestion progress and snapshot age. The status
+    ///   is `503 Service Unavailable` when not ready.
+    #[cfg(feature = "server")]
+    Serve(ServeArgs),
+}
+
+impl CliCommand {
+    /// Returns the transactions, journal, or ledger file that the command
+    /// reads, if any.
+    #[cfg(feature = "otel")]
+    pub fn input_file(&self) -> Option<&Path> {
+        match self {
+            Self::Process(process_args) => Some(&process_args.input),
+            Self::Verify(VerifyArgs { transactions, .. })
+            | Self::Validate(ValidateArgs { transactions, .. })
+            | Self::Simulate(SimulateArgs { transactions, .. })
+            | Self::Stats(StatsArgs { transactions, .. })
+            | Self::Query(QueryArgs { transactions, .. })
+            | Self::Reconcile(ReconcileArgs { transactions, .. })
+            | Self::Ledgers(LedgersArgs { transactions, .. }) => Some(transactions),
+            Self::Replay(ReplayArgs { journal, .. })
+            | Self::JournalVerify(JournalVerifyArgs { journal, .. }) => Some(journal),
+            Self::Disputes(DisputesArgs { ledger, .. }) => Some(ledger),
+            Self::Bench(_) | Self::Follow | Self::Diff(_) | Self::Repl => None,
+            #[cfg(feature = "server")]
+            Self::Serve(_) => None,
+        }
+    }
+}
+
+/// Arguments for the `process` command.
+#[derive(Debug, Args)]
+pub struct ProcessArgs {
+    /// Transactions CSV file to process.
+    pub input: PathBuf,
+    /// File to write accounts to. Defaults to stdout.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// Format to write accounts in. Defaults to `csv`.
+    #[arg(long, value_enum, env = "TRANSACT_FORMAT")]
+    pub format: Option<OutputFormat>,
+    /// Include `rejected` and `last_rejection` columns in the accounts
+    /// output, with the number of each account's rejected transactions and
+    /// the code of the last rejection.
+    #[arg(long)]
+    pub extended: bool,
+    /// Number of transactions to persist per block.
+    #[arg(long, env = "TRANSACT_BLOCK_SIZE")]
+    pub block_size: Option<NonZeroUsize>,
+    /// Directory to store transaction blocks in. Defaults to the system
+    /// temporary directory.
+    #[arg(long, env = "TRANSACT_BLOCK_STORE_DIR")]
+    pub block_store_dir: Option<PathBuf>,
+    /// Number of accounts to keep in memory while processing.
+    ///
+    /// Other accounts are stored in page files in the block store directory.
+    /// Defaults to keeping all accounts in memory.
+    #[arg(long, env = "TRANSACT_ACCOUNT_CACHE_CAPACITY")]
+    pub account_cache_capacity: Option<NonZeroUsize>,
+    /// Number of partitions to process transactions in concurrently.
+    ///
+    /// Transactions are partitioned by client, and each client's
+    /// transactions are still applied in input order. Defaults to `1`.
+    #[arg(long, env = "TRANSACT_PARTITIONS")]
+    pub partitions: Option<NonZeroUsize>,
+    /// Number of blocks to persist concurrently.
+    ///
+    /// Defaults to the available parallelism, up to `4`.
+    #[arg(long, env = "TRANSACT_PERSIST_CONCURRENCY")]
+    pub persist_concurrency: Option<NonZeroUsize>,
+    /// Number of disputed transactions to look up concurrently.
+    ///
+    /// Defaults to the available parallelism.
+    #[arg(long, env = "TRANSACT_LOOKUP_CONCURRENCY")]
+    pub lookup_concurrency: Option<NonZeroUsize>,
+    /// Number of transactions buffered between reading and applying them.
+    ///
+    /// Defaults to the number of transactions in the blocks persisted
+    /// concurrently.
+    #[arg(long, env = "TRANSACT_CHANNEL_CAPACITY")]
+    pub channel_capacity: Option<NonZeroUsize>,
+    /// Approximate number of bytes to hold in memory while processing.
+    ///
+    /// Blocks and buffered transactions are reduced to fit, and accounts
+    /// beyond the budget are stored in page files in the block store
+    /// directory. Defaults to no budget.
+    #[arg(long, env = "TRANSACT_MEMORY_BUDGET")]
+    pub memory_budget: Option<NonZeroUsize>,
+    /// Stop on the first rejected transaction or malformed record.
+    ///
+    /// The command fails, and the rejection is written to the rejection
+    /// report if `--rejections` is passed.
+    #[arg(long)]
+    pub strict: bool,
+    /// Hold disputes of deposits that have not been applied yet, and the
+    /// resolves and chargebacks after them, and apply them once the disputed
+    /// deposit is applied.
+    ///
+    /// This is for input merged from several sources that is only
+    /// approximately ordered. Disputes whose deposit never comes are rejected
+    /// at the end of the input.
+    #[arg(long)]
+    pub park_early_disputes: bool,
+    /// Apply a chargeback of a transaction that is not in dispute as a
+    /// dispute followed by the chargeback, instead of rejecting it.
+    ///
+    /// This is for processors that send chargebacks without a preceding
+    /// dispute.
+    #[arg(long)]
+    pub implicit_disputes: bool,
+    /// Sort transactions by transaction ID before applying them.
+    ///
+    /// This is for input whose producers cannot guarantee chronological
+    /// order. The whole input is read and sorted first, spilling to files in
+    /// the block store directory for large inputs.
+    #[arg(long)]
+    pub sort_by_tx: bool,
+    /// Parse the input on a blocking thread with the synchronous CSV parser.
+    ///
+    /// This is faster when parsing dominates the run time, such as for large
+    /// files on fast local disks.
+    #[arg(long)]
+    pub blocking_parse: bool,
+    /// Parse the input with SIMD accelerated field splitting on a blocking
+    /// thread.
+    ///
+    /// This is for replays of very large files with a
+    /// `type,client,tx,amount` header.
+    #[cfg(feature = "simd-csv")]
+    #[arg(long)]
+    pub simd_parse: bool,
+    /// Treat a deposit or withdrawal whose transaction ID is not greater than
+    /// the previous one as a malformed record.
+    ///
+    /// This is for input whose producer assigns transaction IDs in order, to
+    /// catch corrupted exports.
+    #[arg(long)]
+    pub require_increasing_tx: bool,
+    /// Write identical output for identical input, across runs and numbers of
+    /// partitions.
+    ///
+    /// Accounts are written in order of client ID, and the rejection report
+    /// in order of transaction ID. Other reports are in processing order,
+    /// which is only the same across runs with one partition.
+    #[arg(long)]
+    pub deterministic: bool,
+    /// TOML file with options, overridden by options on the command line and
+    /// environment variables.
+    ///
+    /// Supported keys are `block_size`, `error_policy` (`strict`, `lenient`,
+    /// or `collect`), `chargeback_policy` (`require_dispute` or
+    /// `implicit_dispute`), and `format` (`csv` or `json`).
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+    /// File to write applied transactions to, to replay later.
+    #[arg(long)]
+    pub journal: Option<PathBuf>,
+    /// File to write rejected transactions to, as JSON lines.
+    #[arg(long)]
+    pub rejections: Option<PathBuf>,
+    /// Print the number of rejected transactions and the amount involved for
+    /// each rejection code to stderr, at the end of the run.
+    #[arg(long)]
+    pub rejection_summary: bool,
+    /// Print the count, mean, median, 99th percentile, and maximum processing
+    /// latency for each transaction type to stderr, at the end of the run.
+    #[arg(long)]
+    pub latency_summary: bool,
+    /// Log accounts locked by chargebacks and negative balances as alerts to
+    /// stderr.
+    #[arg(long)]
+    pub notify: bool,
+    /// File to write account events to, as JSON lines.
+    ///
+    /// Events are `FundsDeposited`, `FundsWithdrawn`, `FundsHeld`,
+    /// `FundsReleased`, `FundsReversed`, and `AccountLocked`, and only
+    /// describe applied changes.
+    #[arg(long)]
+    pub events: Option<PathBuf>,
+    /// File to write the outcome of every input record to, as CSV.
+    ///
+    /// Each row has whether the record was `applied`, `rejected`, or
+    /// `malformed`, the error code if any, the transaction, and the account
+    /// after the transaction.
+    #[arg(long)]
+    pub audit_trail: Option<PathBuf>,
+    /// File to write a report of accounts locked by chargebacks to, as CSV.
+    ///
+    /// Each row has the client, the charged back deposit and amount, and the
+    /// account's remaining funds.
+    #[arg(long)]
+    pub locked_accounts: Option<PathBuf>,
+    /// File to write final balances and dispute activity to, in a
+    /// fixed-width format.
+    ///
+    /// Each line is one client's record, laid out by `--fixed-format-layout`.
+    #[arg(long, requires = "fixed_format_layout")]
+    pub fixed_format: Option<PathBuf>,
+    /// TOML file with the fields of each `--fixed-format` record.
+    ///
+    /// Each `[[fields]]` entry has a `column` and `width`, and optionally
+    /// `align` (`left` or `right`), `pad`, `scale` for amounts, and `value`
+    /// for `text` columns. Columns are `text`, `client`, `available`, `held`,
+    /// `total`, `locked`, `disputes_opened`, `disputes_resolved`,
+    /// `chargebacks`, and `amount_charged_back`.
+    #[arg(long, requires = "fixed_format")]
+    pub fixed_format_layout: Option<PathBuf>,
+    /// File to append disputes, resolves, and chargebacks to, as JSON lines.
+    ///
+    /// Unlike other reports, this is appended to and not staged, so it keeps
+    /// the dispute history across runs. Query it with `transact disputes`.
+    #[arg(long)]
+    pub disputes: Option<PathBuf>,
+    /// Snapshot to continue processing from, for incremental runs.
+    ///
+    /// Accounts and deposits are loaded from the snapshot if it exists, and
+    /// the input is applied on top of them. When the run succeeds, the
+    /// snapshot is replaced with the updated state.
+    #[arg(long)]
+    pub snapshot: Option<PathBuf>,
+    /// Show a dashboard of progress on stderr while processing.
+    ///
+    /// Press `q` to stop processing. Requires `--output`, so that accounts
+    /// are not written over the dashboard.
+    #[cfg(feature = "tui")]
+    #[arg(long, requires = "output")]
+    pub tui: bool,
+}
+
+impl ProcessArgs {
+    /// Returns `ProcessArgs` with default options for the given input.
+    pub fn new(input: PathBuf) -> Self {
+        Self {
+            input,
+            output: None,
+            format: None,
+            extended: false,
+            block_size: None,
+            block_store_dir: None,
+            account_cache_capacity: None,
+            partitions: None,
+            persist_concurrency: None,
+            lookup_concurrency: None,
+            channel_capacity: None,
+            memory_budget: None,
+            strict: false,
+            park_early_disputes: false,
+            implicit_disputes: false,
+            sort_by_tx: false,
+            blocking_parse: false,
+            #[cfg(feature = "simd-csv")]
+            simd_parse: false,
+            require_increasing_tx: false,
+            deterministic: false,
+            config: None,
+            journal: None,
+            rejections: None,
+            rejection_summary: false,
+            latency_summary: false,
+            notify: false,
+            events: None,
+            audit_trail: None,
+            locked_accounts: None,
+            fixed_format: None,
+            fixed_format_layout: None,
+            disputes: None,
+            snapshot: None,
+            #[cfg(feature = "tui")]
+            tui: false,
+        }
+    }
+}
+
+/// Arguments for the `verify` command.
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Accounts CSV file to verify.
+    pub accounts: PathBuf,
+    /// Transactions CSV file the accounts were derived from.
+    pub transactions: PathBuf,
+}
+
+/// Arguments for the `bench` command.
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    /// Number of generated steps. Steps with disputes produce more than one
+    /// row.
+    #[arg(long, default_value_t = 1_000_000, value_parser = clap::value_parser!(u64).range(TxRows::CLIENT_COUNT..))]
+    pub rows: u64,
+    /// Shape of the workload: `standard`, `dispute-heavy`, or
+    /// `withdrawal-heavy`.
+    #[arg(long, default_value_t = Profile::Standard)]
+    pub profile: Profile,
+    /// Seed for the workload generator, so runs can be compared on the same
+    /// workload.
+    #[arg(long, default_value_t = TxRows::SEED_DEFAULT)]
+    pub seed: u64,
+    /// Number of transactions to persist per block.
+    #[arg(long)]
+    pub block_size: Option<NonZeroUsize>,
+}
+
+/// Arguments for the `validate` command.
+#[derive(Debug, Args)]
+pub struct ValidateArgs {
+    /// Transactions CSV file to validate.
+    pub transactions: PathBuf,
+}
+
+/// Arguments for the `simulate` command.
+#[derive(Debug, Args)]
+pub struct SimulateArgs {
+    /// Accounts CSV file to start from, such as the output of `process`.
+    pub accounts: PathBuf,
+    /// Hypothetical transactions CSV file to apply.
+    pub transactions: PathBuf,
+    /// Transactions CSV file the accounts were derived from.
+    ///
+    /// Deposits in this file may be disputed by the hypothetical
+    /// transactions. Disputes in this file are not open in the simulation, so
+    /// a hypothetical resolve or chargeback must follow its dispute.
+    #[arg(long)]
+    pub deposits: Option<PathBuf>,
+}
+
+/// Arguments for the `stats` command.
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Transactions CSV file to report statistics for.
+    pub transactions: PathBuf,
+}
+
+/// Arguments for the `diff` command.
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// First accounts CSV file to compare.
+    pub accounts_a: PathBuf,
+    /// Second accounts CSV file to compare.
+    pub accounts_b: PathBuf,
+    /// Maximum difference between amounts to treat as equal.
+    #[arg(long, default_value_t = Decimal::ZERO)]
+    pub tolerance: Decimal,
+}
+
+/// Arguments for the `reconcile` command.
+#[derive(Debug, Args)]
+pub struct ReconcileArgs {
+    /// Transactions CSV file to process.
+    pub transactions: PathBuf,
+    /// Accounts CSV file with the balances each client is expected to have.
+    pub expected_balances: PathBuf,
+    /// Maximum difference between amounts to treat as equal.
+    #[arg(long, env = "TRANSACT_RECONCILE_TOLERANCE", default_value_t = Decimal::ZERO)]
+    pub tolerance: Decimal,
+}
+
+/// Arguments for the `ledgers` command.
+#[derive(Debug, Args)]
+pub struct LedgersArgs {
+    /// Transactions CSV file to process, with a `ledger` or `tenant` column.
+    pub transactions: PathBuf,
+    /// Directory to write accounts to, as one `<ledger>.csv` or
+    /// `<ledger>.json` file per ledger.
+    #[arg(long)]
+    pub output_dir: PathBuf,
+    /// Format to write accounts in.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    pub format: OutputFormat,
+    /// Number of transactions to persist per block.
+    #[arg(long, env = "TRANSACT_BLOCK_SIZE")]
+    pub block_size: Option<NonZeroUsize>,
+    /// Directory to store ledger files and transaction blocks in. Defaults to
+    /// the system temporary directory.
+    #[arg(long, env = "TRANSACT_BLOCK_STORE_DIR")]
+    pub block_store_dir: Option<PathBuf>,
+    /// Stop processing on the first rejected transaction or malformed record.
+    #[arg(long)]
+    pub strict: bool,
+}
+
+/// Arguments for the `disputes` command.
+#[derive(Debug, Args)]
+pub struct DisputesArgs {
+    /// Dispute ledger file written by `transact process --disputes`.
+    pub ledger: PathBuf,
+    /// Only print entries for this client.
+    #[arg(long)]
+    pub client: Option<u16>,
+}
+
+/// Arguments for the `journal-verify` command.
+#[derive(Debug, Args)]
+pub struct JournalVerifyArgs {
+    /// Journal file written by `transact process --journal`.
+    pub journal: PathBuf,
+    /// Expected root hash, as hex. Defaults to the contents of the journal's
+    /// `.root` file.
+    #[arg(long)]
+    pub root: Option<String>,
+}
+
+/// Arguments for the `query` command.
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// Transactions CSV file to process.
+    pub transactions: PathBuf,
+    /// ID of the client to query.
+    #[arg(long)]
+    pub client: u16,
+    /// Print the client's deposit with this transaction ID instead of the
+    /// account.
+    #[arg(long)]
+    pub tx: Option<u32>,
+    /// Print each applied transaction and the resulting account state.
+    #[arg(long)]
+    pub ledger: bool,
+}
+
+/// Arguments for the `replay` command.
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Journal file written by `transact process --journal`.
+    pub journal: PathBuf,
+    /// File to write accounts to. Defaults to stdout.
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// Format to write accounts in.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    pub format: OutputFormat,
+    /// Directory to write checkpoints to, and resume from.
+    ///
+    /// Checkpoints hold the accounts, the deposits that may be disputed, and
+    /// the position in the journal. When the directory has checkpoints, the
+    /// replay resumes from the latest one. When interrupted, a checkpoint is
+    /// written before exiting.
+    #[arg(long)]
+    pub checkpoint_dir: Option<PathBuf>,
+    /// Number of transactions between checkpoints.
+    #[arg(long, default_value = "100000", requires = "checkpoint_dir")]
+    pub checkpoint_interval: NonZeroU64,
+    /// Number of checkpoints to keep.
+    #[arg(long, default_value = "2", requires = "checkpoint_dir")]
+    pub checkpoint_retain: NonZeroUsize,
+}
+
+/// Arguments for the `serve` command.
+#[cfg(feature = "server")]
+#[derive(Debug, Args)]
+pub struct ServeArgs {
+    /// Address to listen on.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub listen: SocketAddr,
+    /// Snapshot file to restore from, and write snapshots to.
+    ///
+    /// When the file exists, the server starts from the snapshot. Snapshots
+    /// are written periodically, and when the server stops.
+    #[arg(long)]
+    pub snapshot: Option<PathBuf>,
+    /// Number of seconds between snapshots.
+    #[arg(long, default_value = "60", requires = "snapshot")]
+    pub snapshot_interval: NonZeroU64,
+    /// Maximum number of transactions to apply per second.
+    ///
+    /// Requests over the limit wait before they are applied.
+    #[arg(long)]
+    pub max_records_per_sec: Option<NonZeroU64>,
+    /// Maximum number of request body bytes to apply per second.
+    ///
+    /// Requests over the limit wait before they are applied.
+    #[arg(long)]
+    pub max_bytes_per_sec: Option<NonZeroU64>,
+    /// Number of seconds to wait for in-progress requests after a shutdown
+    /// signal.
+    ///
+    /// The snapshot is written once requests finish or this passes,
+    /// whichever is first.
+    #[arg(long, default_value = "30")]
+    pub drain_timeout: u64,
+}
+
+/// Format to write accounts in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Comma separated values, with a header row.
+    #[default]
+    Csv,
+    /// JSON array of accounts.
+    Json,
+}
+
+/// Format to write logs in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line.
+    Json,
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::CliArgs;
+
+    #[test]
+    fn cli_args_are_valid() {
+        CliArgs::command().debug_assert();
+    }
+}
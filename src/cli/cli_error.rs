@@ -0,0 +1,678 @@
+use std::{fmt, path::PathBuf};
+
+use transact::model::{ClientId, TxId};
+
+use crate::cli::FixedFormatColumn;
+
+/// Errors when running the `transact` application.
+#[derive(Debug)]
+pub enum CliError {
+    /// Error processing transactions.
+    Process(transact::Error),
+    /// Error reading the configuration file.
+    ConfigRead {
+        /// Path to the configuration file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error parsing the configuration file.
+    ConfigParse {
+        /// Path to the configuration file.
+        path: PathBuf,
+        /// Underlying TOML error.
+        error: toml::de::Error,
+    },
+    /// Error creating the output file.
+    OutputFileCreate {
+        /// Path to the output file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error creating the directory to write each ledger's accounts to.
+    LedgerOutputDirCreate {
+        /// Path to the output directory.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Ledger name cannot be used as a file name.
+    LedgerNameInvalid {
+        /// Name of the ledger.
+        ledger: String,
+    },
+    /// Error creating the journal file.
+    JournalFileCreate {
+        /// Path to the journal file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the journal file.
+    JournalWrite {
+        /// Path to the journal file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error reading the journal file or its root hash file.
+    JournalRead {
+        /// Path to the file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Journal row's hash does not match the rows before it.
+    JournalChainBroken {
+        /// Path to the journal file.
+        path: PathBuf,
+        /// Line number of the first row that does not match, starting at 1.
+        line: usize,
+    },
+    /// Journal's root hash does not match the expected root hash.
+    JournalRootMismatch {
+        /// Expected root hash.
+        expected: String,
+        /// Root hash computed from the journal.
+        actual: String,
+    },
+    /// Error creating the rejection report file.
+    RejectionsFileCreate {
+        /// Path to the rejection report file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the rejection report file.
+    RejectionsWrite {
+        /// Path to the rejection report file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error creating the account events file.
+    EventsFileCreate {
+        /// Path to the account events file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the account events file.
+    EventsWrite {
+        /// Path to the account events file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error creating the audit trail file.
+    AuditTrailFileCreate {
+        /// Path to the audit trail file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the audit trail file.
+    AuditTrailWrite {
+        /// Path to the audit trail file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error creating the locked accounts report file.
+    LockedAccountsFileCreate {
+        /// Path to the locked accounts report file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the locked accounts report file.
+    LockedAccountsWrite {
+        /// Path to the locked accounts report file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error reading the fixed-width export layout file.
+    FixedFormatLayoutRead {
+        /// Path to the layout file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error parsing the fixed-width export layout file.
+    FixedFormatLayoutParse {
+        /// Path to the layout file.
+        path: PathBuf,
+        /// Underlying TOML error.
+        error: toml::de::Error,
+    },
+    /// Error creating the fixed-width export file.
+    FixedFormatFileCreate {
+        /// Path to the fixed-width export file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the fixed-width export file.
+    FixedFormatWrite {
+        /// Path to the fixed-width export file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Value is wider than its field in the fixed-width export layout.
+    FixedFormatFieldOverflow {
+        /// Client ID of the record.
+        client: ClientId,
+        /// Column of the field.
+        column: FixedFormatColumn,
+        /// Value that does not fit.
+        value: String,
+        /// Width of the field.
+        width: usize,
+    },
+    /// Error opening the dispute ledger file to append to.
+    DisputesFileOpen {
+        /// Path to the dispute ledger file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing to the dispute ledger file.
+    DisputesWrite {
+        /// Path to the dispute ledger file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error reading the dispute ledger file.
+    DisputesRead {
+        /// Path to the dispute ledger file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Entry in the dispute ledger file is not valid.
+    DisputesEntryInvalid {
+        /// Path to the dispute ledger file.
+        path: PathBuf,
+        /// Line number of the entry, starting from 1.
+        line: usize,
+        /// Underlying JSON error.
+        error: serde_json::Error,
+    },
+    /// Error promoting a staged output file to its path.
+    OutputPromote {
+        /// Path the output file is promoted to.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing or removing the run success marker.
+    RunSuccessMarkerWrite {
+        /// Path to the run success marker.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error reading from stdin.
+    InputRead(std::io::Error),
+    /// Error writing accounts as JSON.
+    OutputJsonWrite(serde_json::Error),
+    /// Error flushing the output.
+    OutputFlush(std::io::Error),
+    /// Error showing the dashboard in the terminal.
+    #[cfg(feature = "tui")]
+    DashboardTerminal(std::io::Error),
+    /// Error building the exporter for OTLP spans.
+    #[cfg(feature = "otel")]
+    TraceExporterBuild(opentelemetry_otlp::ExporterBuildError),
+    /// Accounts file does not match the transactions it was derived from.
+    VerifyFailed {
+        /// Number of discrepancies found.
+        discrepancy_count: usize,
+    },
+    /// Transactions file has issues that would prevent it being processed.
+    ValidateFailed {
+        /// Number of issues found.
+        issue_count: usize,
+    },
+    /// Accounts files are different.
+    DiffFound {
+        /// Number of differences found.
+        diff_count: usize,
+    },
+    /// Processed accounts differ from the expected balances.
+    ReconcileFailed {
+        /// Number of differences found.
+        diff_count: usize,
+    },
+    /// Processing was stopped by a shutdown signal, or from the dashboard.
+    ///
+    /// Accounts were written for the transactions read before processing was
+    /// stopped.
+    Interrupted,
+    /// Queried client has no transactions.
+    ClientNotFound {
+        /// Client ID.
+        client: ClientId,
+    },
+    /// Queried deposit is not in the client's transactions.
+    TransactionNotFound {
+        /// Client ID.
+        client: ClientId,
+        /// Transaction ID.
+        tx: TxId,
+    },
+}
+
+impl CliError {
+    /// Exit code when the command fails for a reason without its own code.
+    pub const EXIT_CODE_FAILURE: u8 = 1;
+    /// Exit code when the input could not be parsed.
+    ///
+    /// This is also used by `clap` for invalid command line arguments.
+    pub const EXIT_CODE_INPUT_PARSE: u8 = 2;
+    /// Exit code when reading or writing files or the block store fails.
+    ///
+    /// These may succeed when retried.
+    pub const EXIT_CODE_IO: u8 = 3;
+    /// Exit code when a transaction is rejected in strict mode.
+    pub const EXIT_CODE_TRANSACTION_REJECTED: u8 = 4;
+    /// Exit code when processing is stopped by `SIGINT` or `SIGTERM`.
+    pub const EXIT_CODE_INTERRUPTED: u8 = 130;
+
+    /// Returns the process exit code for this error.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Self::Process(error) => match error {
+                transact::Error::TransactionDeserialize(error)
+                | transact::Error::AccountDeserialize(error) => {
+                    if error.is_io_error() {
+                        Self::EXIT_CODE_IO
+                    } else {
+                        Self::EXIT_CODE_INPUT_PARSE
+                    }
+                }
+                transact::Error::TransactionDeserializeBlocking(error) => {
+                    if error.is_io_error() {
+                        Self::EXIT_CODE_IO
+                    } else {
+                        Self::EXIT_CODE_INPUT_PARSE
+                    }
+                }
+                #[cfg(feature = "cdc")]
+                transact::Error::ChangeEventRead(_) => Self::EXIT_CODE_IO,
+                #[cfg(feature = "cdc")]
+                transact::Error::ChangeEventDeserialize { .. } => Self::EXIT_CODE_INPUT_PARSE,
+                transact::Error::DepositAmountNotProvided { .. }
+                | transact::Error::WithdrawalAmountNotProvided { .. }
+                | transact::Error::TxIdNotIncreasing { .. } => Self::EXIT_CODE_INPUT_PARSE,
+                transact::Error::TransactionRejected(_) => Self::EXIT_CODE_TRANSACTION_REJECTED,
+                transact::Error::BlockStoreDirCreate(_)
+                | transact::Error::BlockStoreDirRead(_)
+                | transact::Error::BlockFileCreate(_)
+                | transact::Error::BlockFileFlush(_)
+                | transact::Error::BlockFileRename { .. }
+                | transact::Error::BlockFileNameInvalid { .. }
+                | transact::Error::BlockTxWrite(_)
+                | transact::Error::SortDirCreate(_)
+                | transact::Error::SortRunCreate(_)
+                | transact::Error::SortRunWrite(_)
+                | transact::Error::SortRunFlush(_)
+                | transact::Error::LedgerDirCreate(_)
+                | transact::Error::LedgerFileCreate(_)
+                | transact::Error::LedgerFileWrite(_)
+                | transact::Error::LedgerFileFlush(_)
+                | transact::Error::AccountPageDirCreate(_)
+                | transact::Error::AccountPageRead { .. }
+                | transact::Error::AccountPageWrite { .. }
+                | transact::Error::BlockStoreLookupTimeout { .. }
+                | transact::Error::RunTimeout { .. }
+                | transact::Error::TransactCsvOpen { .. }
+                | transact::Error::AccountsCsvOpen { .. }
+                | transact::Error::RuntimeCreate(_)
+                | transact::Error::ServerBind { .. }
+                | transact::Error::ServerServe(_)
+                | transact::Error::ServerDrainTimeout { .. }
+                | transact::Error::OutputWrite(_)
+                | transact::Error::OutputFlush(_)
+                | transact::Error::SnapshotWrite { .. }
+                | transact::Error::SnapshotRead { .. }
+                | transact::Error::CheckpointDirCreate { .. }
+                | transact::Error::CheckpointDirRead { .. }
+                | transact::Error::CheckpointPrune { .. } => Self::EXIT_CODE_IO,
+                #[cfg(feature = "redis")]
+                transact::Error::RedisConnect(_)
+                | transact::Error::RedisGroupCreate(_)
+                | transact::Error::RedisRead(_)
+                | transact::Error::RedisAck(_) => Self::EXIT_CODE_IO,
+                #[cfg(feature = "nats")]
+                transact::Error::NatsConnect(_)
+                | transact::Error::NatsStreamGet(_)
+                | transact::Error::NatsConsumerCreate(_)
+                | transact::Error::NatsSubscribe(_)
+                | transact::Error::NatsRead(_)
+                | transact::Error::NatsMessageInfo(_)
+                | transact::Error::NatsAck(_) => Self::EXIT_CODE_IO,
+                #[cfg(feature = "amqp")]
+                transact::Error::AmqpConnect(_)
+                | transact::Error::AmqpConsume(_)
+                | transact::Error::AmqpRead(_)
+                | transact::Error::AmqpAck(_)
+                | transact::Error::AmqpClose(_) => Self::EXIT_CODE_IO,
+                transact::Error::SnapshotRecordInvalid { .. } => Self::EXIT_CODE_INPUT_PARSE,
+            },
+            Self::ConfigParse { .. }
+            | Self::FixedFormatLayoutParse { .. }
+            | Self::ValidateFailed { .. }
+            | Self::DisputesEntryInvalid { .. }
+            | Self::LedgerNameInvalid { .. } => Self::EXIT_CODE_INPUT_PARSE,
+            Self::ConfigRead { .. }
+            | Self::OutputFileCreate { .. }
+            | Self::LedgerOutputDirCreate { .. }
+            | Self::JournalFileCreate { .. }
+            | Self::JournalWrite { .. }
+            | Self::JournalRead { .. }
+            | Self::RejectionsFileCreate { .. }
+            | Self::RejectionsWrite { .. }
+            | Self::EventsFileCreate { .. }
+            | Self::EventsWrite { .. }
+            | Self::AuditTrailFileCreate { .. }
+            | Self::AuditTrailWrite { .. }
+            | Self::LockedAccountsFileCreate { .. }
+            | Self::LockedAccountsWrite { .. }
+            | Self::FixedFormatLayoutRead { .. }
+            | Self::FixedFormatFileCreate { .. }
+            | Self::FixedFormatWrite { .. }
+            | Self::DisputesFileOpen { .. }
+            | Self::DisputesWrite { .. }
+            | Self::DisputesRead { .. }
+            | Self::OutputPromote { .. }
+            | Self::RunSuccessMarkerWrite { .. }
+            | Self::InputRead(_)
+            | Self::OutputJsonWrite(_)
+            | Self::OutputFlush(_) => Self::EXIT_CODE_IO,
+            #[cfg(feature = "tui")]
+            Self::DashboardTerminal(_) => Self::EXIT_CODE_IO,
+            #[cfg(feature = "otel")]
+            Self::TraceExporterBuild(_) => Self::EXIT_CODE_FAILURE,
+            Self::Interrupted => Self::EXIT_CODE_INTERRUPTED,
+            Self::VerifyFailed { .. }
+            | Self::DiffFound { .. }
+            | Self::ReconcileFailed { .. }
+            | Self::ClientNotFound { .. }
+            | Self::TransactionNotFound { .. }
+            | Self::JournalChainBroken { .. }
+            | Self::JournalRootMismatch { .. }
+            | Self::FixedFormatFieldOverflow { .. } => Self::EXIT_CODE_FAILURE,
+        }
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Process(error) => write!(f, "{error}"),
+            Self::ConfigRead { path, .. } => {
+                write!(f, "Error reading configuration file: {}", path.display())
+            }
+            Self::ConfigParse { path, .. } => {
+                write!(f, "Error parsing configuration file: {}", path.display())
+            }
+            Self::OutputFileCreate { path, .. } => {
+                write!(f, "Error creating output file: {}", path.display())
+            }
+            Self::LedgerOutputDirCreate { path, .. } => {
+                write!(f, "Error creating ledger output directory: {}", path.display())
+            }
+            Self::LedgerNameInvalid { ledger } => write!(
+                f,
+                "Ledger name `{ledger}` cannot be used as a file name."
+            ),
+            Self::JournalFileCreate { path, .. } => {
+                write!(f, "Error creating journal file: {}", path.display())
+            }
+            Self::JournalWrite { path, .. } => {
+                write!(f, "Error writing to journal file: {}", path.display())
+            }
+            Self::JournalRead { path, .. } => {
+                write!(f, "Error reading journal file: {}", path.display())
+            }
+            Self::JournalChainBroken { path, line } => {
+                write!(
+                    f,
+                    "Journal hash chain is broken at line {line}, the journal was altered: {}",
+                    path.display()
+                )
+            }
+            Self::JournalRootMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "Journal root hash `{actual}` does not match the expected root hash `{expected}`"
+                )
+            }
+            Self::RejectionsFileCreate { path, .. } => {
+                write!(
+                    f,
+                    "Error creating rejection report file: {}",
+                    path.display()
+                )
+            }
+            Self::RejectionsWrite { path, .. } => {
+                write!(
+                    f,
+                    "Error writing to rejection report file: {}",
+                    path.display()
+                )
+            }
+            Self::EventsFileCreate { path, .. } => {
+                write!(f, "Error creating account events file: {}", path.display())
+            }
+            Self::EventsWrite { path, .. } => {
+                write!(f, "Error writing to account events file: {}", path.display())
+            }
+            Self::AuditTrailFileCreate { path, .. } => {
+                write!(f, "Error creating audit trail file: {}", path.display())
+            }
+            Self::AuditTrailWrite { path, .. } => {
+                write!(f, "Error writing to audit trail file: {}", path.display())
+            }
+            Self::LockedAccountsFileCreate { path, .. } => write!(
+                f,
+                "Error creating locked accounts report file: {}",
+                path.display()
+            ),
+            Self::LockedAccountsWrite { path, .. } => write!(
+                f,
+                "Error writing to locked accounts report file: {}",
+                path.display()
+            ),
+            Self::FixedFormatLayoutRead { path, .. } => write!(
+                f,
+                "Error reading fixed-width export layout file: {}",
+                path.display()
+            ),
+            Self::FixedFormatLayoutParse { path, .. } => write!(
+                f,
+                "Error parsing fixed-width export layout file: {}",
+                path.display()
+            ),
+            Self::FixedFormatFileCreate { path, .. } => write!(
+                f,
+                "Error creating fixed-width export file: {}",
+                path.display()
+            ),
+            Self::FixedFormatWrite { path, .. } => write!(
+                f,
+                "Error writing to fixed-width export file: {}",
+                path.display()
+            ),
+            Self::FixedFormatFieldOverflow {
+                client,
+                column,
+                value,
+                width,
+            } => write!(
+                f,
+                "Client {client}'s `{column}` value `{value}` is wider than its field of {width} characters."
+            ),
+            Self::DisputesFileOpen { path, .. } => {
+                write!(f, "Error opening dispute ledger file: {}", path.display())
+            }
+            Self::DisputesWrite { path, .. } => {
+                write!(f, "Error writing to dispute ledger file: {}", path.display())
+            }
+            Self::DisputesRead { path, .. } => {
+                write!(f, "Error reading dispute ledger file: {}", path.display())
+            }
+            Self::DisputesEntryInvalid { path, line, .. } => write!(
+                f,
+                "Invalid entry on line {line} of dispute ledger file: {}",
+                path.display()
+            ),
+            Self::OutputPromote { path, .. } => {
+                write!(f, "Error promoting output file: {}", path.display())
+            }
+            Self::RunSuccessMarkerWrite { path, .. } => {
+                write!(f, "Error writing run success marker: {}", path.display())
+            }
+            Self::InputRead(_) => write!(f, "Error reading from stdin."),
+            Self::OutputJsonWrite(_) => write!(f, "Error writing accounts as JSON."),
+            Self::OutputFlush(_) => write!(f, "Error flushing output."),
+            #[cfg(feature = "tui")]
+            Self::DashboardTerminal(_) => write!(f, "Error showing the dashboard."),
+            #[cfg(feature = "otel")]
+            Self::TraceExporterBuild(_) => write!(f, "Error building the OTLP span exporter."),
+            Self::VerifyFailed { discrepancy_count } => write!(
+                f,
+                "Accounts verification failed with {discrepancy_count} discrepancies."
+            ),
+            Self::ValidateFailed { issue_count } => {
+                write!(
+                    f,
+                    "Transactions validation failed with {issue_count} issues."
+                )
+            }
+            Self::DiffFound { diff_count } => {
+                write!(f, "Accounts files have {diff_count} differences.")
+            }
+            Self::ReconcileFailed { diff_count } => write!(
+                f,
+                "Processed accounts have {diff_count} differences from the expected balances."
+            ),
+            Self::Interrupted => write!(
+                f,
+                "Processing was interrupted, accounts only include transactions read before it was stopped."
+            ),
+            Self::ClientNotFound { client } => {
+                write!(f, "Client {client} has no transactions.")
+            }
+            Self::TransactionNotFound { client, tx } => {
+                write!(f, "Client {client} has no deposit with transaction ID {tx}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Process(error) => error.source(),
+            Self::ConfigRead { error, .. } => Some(error),
+            Self::ConfigParse { error, .. } => Some(error),
+            Self::OutputFileCreate { error, .. } => Some(error),
+            Self::LedgerOutputDirCreate { error, .. } => Some(error),
+            Self::LedgerNameInvalid { .. } => None,
+            Self::JournalFileCreate { error, .. } => Some(error),
+            Self::JournalWrite { error, .. } => Some(error),
+            Self::JournalRead { error, .. } => Some(error),
+            Self::JournalChainBroken { .. } | Self::JournalRootMismatch { .. } => None,
+            Self::RejectionsFileCreate { error, .. } => Some(error),
+            Self::RejectionsWrite { error, .. } => Some(error),
+            Self::EventsFileCreate { error, .. } => Some(error),
+            Self::EventsWrite { error, .. } => Some(error),
+            Self::AuditTrailFileCreate { error, .. } => Some(error),
+            Self::AuditTrailWrite { error, .. } => Some(error),
+            Self::LockedAccountsFileCreate { error, .. } => Some(error),
+            Self::LockedAccountsWrite { error, .. } => Some(error),
+            Self::FixedFormatLayoutRead { error, .. } => Some(error),
+            Self::FixedFormatLayoutParse { error, .. } => Some(error),
+            Self::FixedFormatFileCreate { error, .. } => Some(error),
+            Self::FixedFormatWrite { error, .. } => Some(error),
+            Self::FixedFormatFieldOverflow { .. } => None,
+            Self::DisputesFileOpen { error, .. } => Some(error),
+            Self::DisputesWrite { error, .. } => Some(error),
+            Self::DisputesRead { error, .. } => Some(error),
+            Self::DisputesEntryInvalid { error, .. } => Some(error),
+            Self::OutputPromote { error, .. } => Some(error),
+            Self::RunSuccessMarkerWrite { error, .. } => Some(error),
+            Self::InputRead(error) => Some(error),
+            Self::OutputJsonWrite(error) => Some(error),
+            Self::OutputFlush(error) => Some(error),
+            #[cfg(feature = "tui")]
+            Self::DashboardTerminal(error) => Some(error),
+            #[cfg(feature = "otel")]
+            Self::TraceExporterBuild(error) => Some(error),
+            Self::VerifyFailed { .. } => None,
+            Self::ValidateFailed { .. } => None,
+            Self::DiffFound { .. } => None,
+            Self::ReconcileFailed { .. } => None,
+            Self::Interrupted => None,
+            Self::ClientNotFound { .. } | Self::TransactionNotFound { .. } => None,
+        }
+    }
+}
+
+impl From<transact::Error> for CliError {
+    fn from(error: transact::Error) -> Self {
+        Self::Process(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use transact::{
+        model::{ClientId, TxId},
+        TxError,
+    };
+
+    use super::CliError;
+
+    #[test]
+    fn exit_code_distinguishes_failure_classes() {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+
+        assert_eq!(
+            CliError::EXIT_CODE_INPUT_PARSE,
+            CliError::from(transact::Error::DepositAmountNotProvided { client, tx }).exit_code()
+        );
+        assert_eq!(
+            CliError::EXIT_CODE_IO,
+            CliError::from(transact::Error::TransactCsvOpen {
+                path: PathBuf::from("transactions.csv"),
+                error: std::io::Error::from(std::io::ErrorKind::NotFound),
+            })
+            .exit_code()
+        );
+        assert_eq!(
+            CliError::EXIT_CODE_TRANSACTION_REJECTED,
+            CliError::from(transact::Error::TransactionRejected(
+                TxError::AccountLocked { client, tx }
+            ))
+            .exit_code()
+        );
+        assert_eq!(
+            CliError::EXIT_CODE_FAILURE,
+            CliError::ClientNotFound { client }.exit_code()
+        );
+        assert_eq!(
+            CliError::EXIT_CODE_FAILURE,
+            CliError::TransactionNotFound { client, tx }.exit_code()
+        );
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    io::{self, Stderr},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        execute,
+        terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+    },
+    layout::{Constraint, Layout},
+    widgets::{Block, Paragraph, Row, Table},
+    Frame, Terminal,
+};
+use transact::CancellationToken;
+
+use crate::cli::DashboardObserver;
+
+/// Terminal dashboard of processing progress.
+///
+/// The dashboard is drawn on stderr in a separate thread, so it does not
+/// interleave with accounts written to stdout. Pressing `q` or `Ctrl+C`
+/// cancels processing, as the terminal does not send `SIGINT` while the
+/// dashboard is shown.
+#[derive(Debug)]
+pub struct Dashboard {
+    /// Set to stop drawing the dashboard.
+    stop: Arc<AtomicBool>,
+    /// Thread drawing the dashboard.
+    join_handle: JoinHandle<io::Result<()>>,
+}
+
+/// Progress at the previous draw, to calculate the current throughput.
+#[derive(Clone, Copy, Debug)]
+struct Progress {
+    /// When the progress was recorded.
+    instant: Instant,
+    /// Number of transactions processed.
+    processed: u64,
+}
+
+impl Dashboard {
+    /// Interval between draws.
+    const DRAW_INTERVAL: Duration = Duration::from_millis(250);
+    /// Number of locked accounts to show.
+    const LOCKED_ACCOUNTS_SHOWN: usize = 10;
+
+    /// Shows the dashboard until [`Dashboard::finish`] is called.
+    ///
+    /// # Parameters
+    ///
+    /// * `dashboard_observer`: Observer passed to processing, to read progress
+    ///   from.
+    /// * `cancellation_token`: Token to cancel processing with when the
+    ///   operator quits.
+    pub fn spawn(
+        dashboard_observer: Arc<DashboardObserver>,
+        cancellation_token: CancellationToken,
+    ) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stderr(), EnterAlternateScreen)?;
+        let terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let join_handle = std::thread::spawn({
+            let stop = Arc::clone(&stop);
+            move || {
+                Self::draw_until_stopped(terminal, &dashboard_observer, &cancellation_token, &stop)
+            }
+        });
+
+        Ok(Self { stop, join_handle })
+    }
+
+    /// Stops drawing the dashboard, and restores the terminal.
+    pub fn finish(self) -> io::Result<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        let draw_result = self
+            .join_handle
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("Dashboard thread panicked.")));
+
+        let restore_result = terminal::disable_raw_mode()
+            .and_then(|()| execute!(io::stderr(), LeaveAlternateScreen));
+        draw_result.and(restore_result)
+    }
+
+    /// Draws the dashboard at each interval, and handles key presses.
+    fn draw_until_stopped(
+        mut terminal: Terminal<CrosstermBackend<Stderr>>,
+        dashboard_observer: &DashboardObserver,
+        cancellation_token: &CancellationToken,
+        stop: &AtomicBool,
+    ) -> io::Result<()> {
+        let start = Instant::now();
+        let mut progress_previous = Progress {
+            instant: start,
+            processed: 0,
+        };
+
+        while !stop.load(Ordering::Relaxed) {
+            let progress = Progress {
+                instant: Instant::now(),
+                processed: Self::processed(dashboard_observer),
+            };
+            terminal.draw(|frame| {
+                Self::render(
+                    frame,
+                    dashboard_observer,
+                    start,
+                    progress_previous,
+                    progress,
+                    cancellation_token.is_cancelled(),
+                )
+            })?;
+            progress_previous = progress;
+
+            if event::poll(Self::DRAW_INTERVAL)? {
+                if let Event::Key(key_event) = event::read()? {
+                    let quit = key_event.kind == KeyEventKind::Press
+                        && (key_event.code == KeyCode::Char('q')
+                            || (key_event.code == KeyCode::Char('c')
+                                && key_event.modifiers.contains(KeyModifiers::CONTROL)));
+                    if quit {
+                        cancellation_token.cancel();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renders the dashboard.
+    fn render(
+        frame: &mut Frame<'_>,
+        dashboard_observer: &DashboardObserver,
+        start: Instant,
+        progress_previous: Progress,
+        progress: Progress,
+        cancelled: bool,
+    ) {
+        let elapsed = progress.instant.duration_since(start);
+        let rejections = dashboard_observer.rejections();
+        let rejected = rejections.values().sum::<u64>();
+        let throughput_overall = Self::throughput(progress.processed, elapsed);
+        let throughput_current = Self::throughput(
+            progress.processed - progress_previous.processed,
+            progress.instant.duration_since(progress_previous.instant),
+        );
+        let status = if cancelled {
+            "Stopping, applying the transactions already read."
+        } else {
+            "Processing. Press `q` to stop."
+        };
+
+        let progress_lines = [
+            format!("elapsed: {:.1}s", elapsed.as_secs_f64()),
+            format!("processed: {}", progress.processed),
+            format!("applied: {}", dashboard_observer.applied()),
+            format!("rejected: {rejected}"),
+            format!(
+                "throughput: {throughput_current} rows/s (average {throughput_overall} rows/s)"
+            ),
+            format!(
+                "block store: {} blocks, {} transactions",
+                dashboard_observer.blocks(),
+                dashboard_observer.block_transactions()
+            ),
+            status.to_string(),
+        ];
+
+        let [progress_area, tables_area] = Layout::vertical([
+            Constraint::Length(progress_lines.len() as u16 + 2),
+            Constraint::Fill(1),
+        ])
+        .areas(frame.area());
+        let [rejections_area, locked_accounts_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(tables_area);
+
+        frame.render_widget(
+            Paragraph::new(progress_lines.join("\n")).block(Block::bordered().title(" transact ")),
+            progress_area,
+        );
+        frame.render_widget(
+            Table::new(
+                rejections
+                    .into_iter()
+                    .map(|(code, count)| Row::new([code.to_string(), count.to_string()])),
+                [Constraint::Fill(1), Constraint::Length(12)],
+            )
+            .header(Row::new(["code", "count"]))
+            .block(Block::bordered().title(" Rejections ")),
+            rejections_area,
+        );
+        frame.render_widget(
+            Table::new(
+                dashboard_observer
+                    .top_locked_accounts(Self::LOCKED_ACCOUNTS_SHOWN)
+                    .into_iter()
+                    .map(|(client, total)| Row::new([client.to_string(), total.to_string()])),
+                [Constraint::Length(8), Constraint::Fill(1)],
+            )
+            .header(Row::new(["client", "total"]))
+            .block(Block::bordered().title(format!(
+                " Locked accounts ({}) ",
+                dashboard_observer.locked_account_count()
+            ))),
+            locked_accounts_area,
+        );
+    }
+
+    /// Returns the number of transactions processed.
+    fn processed(dashboard_observer: &DashboardObserver) -> u64 {
+        dashboard_observer.applied() + dashboard_observer.rejections().values().sum::<u64>()
+    }
+
+    /// Returns the number of rows processed per second.
+    fn throughput(rows: u64, duration: Duration) -> u64 {
+        let seconds = duration.as_secs_f64();
+        if seconds > 0.0 {
+            (rows as f64 / seconds) as u64
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use ratatui::{backend::TestBackend, Terminal};
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{Account, ClientId, Deposit, Transaction, TxId, Withdrawal},
+        ProcessObserver, TxError,
+    };
+
+    use super::{Dashboard, Progress};
+    use crate::cli::DashboardObserver;
+
+    #[test]
+    fn render_shows_progress_rejections_and_locked_accounts()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(7);
+        let dashboard_observer = DashboardObserver::new();
+        let withdrawal = Transaction::from(Withdrawal::new(client, TxId::new(2), dec!(9.0)));
+        dashboard_observer.on_transaction_applied(
+            &Transaction::from(Deposit::new(client, TxId::new(1), dec!(2.0))),
+            &Account::try_new(client, dec!(2.0), dec!(0.0), true, Default::default())
+                .expect("Test data invalid."),
+        );
+        dashboard_observer.on_transaction_rejected(
+            &withdrawal,
+            &TxError::AccountLocked {
+                client,
+                tx: TxId::new(2),
+            },
+        );
+        let mut terminal = Terminal::new(TestBackend::new(100, 20))?;
+        let start = Instant::now();
+
+        terminal.draw(|frame| {
+            Dashboard::render(
+                frame,
+                &dashboard_observer,
+                start,
+                Progress {
+                    instant: start,
+                    processed: 0,
+                },
+                Progress {
+                    instant: start + Duration::from_secs(1),
+                    processed: 2,
+                },
+                false,
+            )
+        })?;
+
+        let content = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect::<String>();
+        assert!(content.contains("processed: 2"));
+        assert!(content.contains("throughput: 2 rows/s"));
+        assert!(content.contains("ACCOUNT_LOCKED"));
+        assert!(content.contains("Locked accounts (1)"));
+        Ok(())
+    }
+}
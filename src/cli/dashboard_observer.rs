@@ -0,0 +1,158 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use rust_decimal::Decimal;
+use transact::{
+    model::{Account, ClientId, Transaction},
+    ProcessObserver, TxError,
+};
+
+/// Collects processing progress to show in the [`Dashboard`].
+///
+/// [`Dashboard`]: crate::cli::Dashboard
+#[derive(Debug, Default)]
+pub struct DashboardObserver {
+    /// Number of transactions applied.
+    applied: AtomicU64,
+    /// Number of transactions rejected, by error code.
+    rejections: Mutex<BTreeMap<&'static str, u64>>,
+    /// Total funds of each locked account.
+    locked_accounts: Mutex<HashMap<ClientId, Decimal>>,
+    /// Number of blocks persisted to the block store.
+    blocks: AtomicU64,
+    /// Number of transactions in persisted blocks.
+    block_transactions: AtomicU64,
+}
+
+impl DashboardObserver {
+    /// Returns a new `DashboardObserver`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of transactions applied.
+    pub fn applied(&self) -> u64 {
+        self.applied.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of transactions rejected, by error code.
+    pub fn rejections(&self) -> BTreeMap<&'static str, u64> {
+        self.rejections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Returns up to `count` locked accounts with the most total funds, in
+    /// descending order of funds.
+    pub fn top_locked_accounts(&self, count: usize) -> Vec<(ClientId, Decimal)> {
+        let mut locked_accounts = self
+            .locked_accounts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(client, total)| (*client, *total))
+            .collect::<Vec<_>>();
+        locked_accounts.sort_by(|(_, total_a), (_, total_b)| total_b.cmp(total_a));
+        locked_accounts.truncate(count);
+        locked_accounts
+    }
+
+    /// Returns the number of locked accounts.
+    pub fn locked_account_count(&self) -> usize {
+        self.locked_accounts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Returns the number of blocks persisted to the block store.
+    pub fn blocks(&self) -> u64 {
+        self.blocks.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of transactions in persisted blocks.
+    pub fn block_transactions(&self) -> u64 {
+        self.block_transactions.load(Ordering::Relaxed)
+    }
+}
+
+impl ProcessObserver for DashboardObserver {
+    fn on_transaction_applied(&self, _transaction: &Transaction, account: &Account) {
+        self.applied.fetch_add(1, Ordering::Relaxed);
+        if account.locked() {
+            // Locked accounts may still have funds held or released by
+            // disputes, so the total is kept current.
+            self.locked_accounts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(account.client(), account.total());
+        }
+    }
+
+    fn on_transaction_rejected(&self, _transaction: &Transaction, tx_error: &TxError) {
+        *self
+            .rejections
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(tx_error.code())
+            .or_default() += 1;
+    }
+
+    fn on_block_persisted(&self, transactions: &[Transaction]) {
+        self.blocks.fetch_add(1, Ordering::Relaxed);
+        self.block_transactions
+            .fetch_add(transactions.len() as u64, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{Chargeback, ClientId, Deposit, Dispute, Transaction, TxId, Withdrawal},
+        process_transactions, MemTxStore, ProcessOptions,
+    };
+
+    use super::DashboardObserver;
+
+    #[tokio::test]
+    async fn collects_rejections_and_locked_accounts() -> Result<(), Box<dyn std::error::Error>> {
+        let client_1 = ClientId::new(1);
+        let client_2 = ClientId::new(2);
+        let transactions = [
+            Transaction::from(Deposit::new(client_1, TxId::new(1), dec!(3.0))),
+            Transaction::from(Deposit::new(client_1, TxId::new(2), dec!(5.0))),
+            Transaction::from(Deposit::new(client_2, TxId::new(3), dec!(1.0))),
+            Transaction::from(Dispute::new(client_1, TxId::new(1))),
+            Transaction::from(Chargeback::new(client_1, TxId::new(1))),
+            Transaction::from(Withdrawal::new(client_2, TxId::new(4), dec!(2.0))),
+        ];
+        let dashboard_observer = std::sync::Arc::new(DashboardObserver::new());
+
+        process_transactions(
+            futures::stream::iter(transactions.map(Ok)),
+            &MemTxStore::new(),
+            ProcessOptions::new().with_observer(dashboard_observer.clone()),
+        )
+        .await?;
+
+        assert_eq!(5, dashboard_observer.applied());
+        assert_eq!(
+            Some(&1),
+            dashboard_observer
+                .rejections()
+                .get("WITHDRAWAL_INSUFFICIENT_AVAILABLE")
+        );
+        assert_eq!(
+            vec![(client_1, dec!(5.0))],
+            dashboard_observer.top_locked_accounts(10)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use rust_decimal::Decimal;
+use transact::model::{Account, Accounts};
+
+use crate::cli::{AccountDiff, CliError, DiffArgs};
+
+/// Compares two accounts files.
+#[derive(Debug)]
+pub struct DiffCmd;
+
+impl DiffCmd {
+    /// Runs the `diff` command.
+    ///
+    /// Each difference is printed to stdout, and an error is returned if
+    /// there are any.
+    pub fn run(diff_args: DiffArgs) -> Result<(), CliError> {
+        let DiffArgs {
+            accounts_a,
+            accounts_b,
+            tolerance,
+        } = diff_args;
+
+        let accounts_a = transact::blocking::read_accounts(&accounts_a)?;
+        let accounts_b = transact::blocking::read_accounts(&accounts_b)?;
+
+        let account_diffs = Self::diffs(&accounts_a, &accounts_b, tolerance);
+        account_diffs
+            .iter()
+            .for_each(|account_diff| println!("{account_diff}"));
+
+        if account_diffs.is_empty() {
+            Ok(())
+        } else {
+            Err(CliError::DiffFound {
+                diff_count: account_diffs.len(),
+            })
+        }
+    }
+
+    /// Returns the differences between two sets of accounts, ordered by
+    /// client.
+    ///
+    /// Amounts are only considered different when they differ by more than
+    /// `tolerance`.
+    pub fn diffs(
+        accounts_a: &Accounts,
+        accounts_b: &Accounts,
+        tolerance: Decimal,
+    ) -> Vec<AccountDiff> {
+        let mut clients = accounts_a
+            .keys()
+            .chain(accounts_b.keys())
+            .copied()
+            .collect::<Vec<_>>();
+        clients.sort_unstable();
+        clients.dedup();
+
+        clients
+            .into_iter()
+            .flat_map(
+                |client| match (accounts_a.get(client), accounts_b.get(client)) {
+                    (Some(account_a), Some(account_b)) => {
+                        Self::account_diffs(account_a, account_b, tolerance)
+                    }
+                    (Some(_), None) => vec![AccountDiff::OnlyInA { client }],
+                    (None, Some(_)) => vec![AccountDiff::OnlyInB { client }],
+                    (None, None) => Vec::new(),
+                },
+            )
+            .collect()
+    }
+
+    /// Returns the differences between a client's accounts.
+    fn account_diffs(
+        account_a: &Account,
+        account_b: &Account,
+        tolerance: Decimal,
+    ) -> Vec<AccountDiff> {
+        let client = account_a.client();
+        let mut account_diffs = [
+            ("available", account_a.available(), account_b.available()),
+            ("held", account_a.held(), account_b.held()),
+            ("total", account_a.total(), account_b.total()),
+        ]
+        .into_iter()
+        .filter(|(_field, amount_a, amount_b)| {
+            amount_b
+                .checked_sub(*amount_a)
+                .is_none_or(|difference| difference.abs() > tolerance)
+        })
+        .map(|(field, amount_a, amount_b)| AccountDiff::AmountDiff {
+            client,
+            field,
+            amount_a,
+            amount_b,
+        })
+        .collect::<Vec<_>>();
+
+        if account_a.locked() != account_b.locked() {
+            account_diffs.push(AccountDiff::LockedDiff {
+                client,
+                locked_a: account_a.locked(),
+                locked_b: account_b.locked(),
+            });
+        }
+
+        account_diffs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rust_decimal_macros::dec;
+    use transact::model::{Account, Accounts, ClientId};
+
+    use super::DiffCmd;
+    use crate::cli::AccountDiff;
+
+    #[test]
+    fn diffs_ignores_differences_within_tolerance() {
+        let client = ClientId::new(1);
+        let mut accounts_a = Accounts::new();
+        accounts_a.insert(
+            client,
+            Account::try_new(client, dec!(1.0000), dec!(0.0), false, HashSet::new())
+                .expect("Test data invalid."),
+        );
+        let mut accounts_b = Accounts::new();
+        accounts_b.insert(
+            client,
+            Account::try_new(client, dec!(1.0001), dec!(0.0), false, HashSet::new())
+                .expect("Test data invalid."),
+        );
+
+        assert!(DiffCmd::diffs(&accounts_a, &accounts_b, dec!(0.0001)).is_empty());
+        assert_eq!(
+            vec![
+                AccountDiff::AmountDiff {
+                    client,
+                    field: "available",
+                    amount_a: dec!(1.0000),
+                    amount_b: dec!(1.0001),
+                },
+                AccountDiff::AmountDiff {
+                    client,
+                    field: "total",
+                    amount_a: dec!(1.0000),
+                    amount_b: dec!(1.0001),
+                },
+            ],
+            DiffCmd::diffs(&accounts_a, &accounts_b, dec!(0.0))
+        );
+    }
+
+    #[test]
+    fn diffs_reports_locked_and_missing_accounts() {
+        let client_one = ClientId::new(1);
+        let client_two = ClientId::new(2);
+        let client_three = ClientId::new(3);
+        let mut accounts_a = Accounts::new();
+        accounts_a.insert(client_one, Account::empty(client_one));
+        accounts_a.insert(client_two, Account::empty(client_two));
+        let mut accounts_b = Accounts::new();
+        accounts_b.insert(
+            client_one,
+            Account::try_new(client_one, dec!(0.0), dec!(0.0), true, HashSet::new())
+                .expect("Test data invalid."),
+        );
+        accounts_b.insert(client_three, Account::empty(client_three));
+
+        assert_eq!(
+            vec![
+                AccountDiff::LockedDiff {
+                    client: client_one,
+                    locked_a: false,
+                    locked_b: true,
+                },
+                AccountDiff::OnlyInA { client: client_two },
+                AccountDiff::OnlyInB {
+                    client: client_three
+                },
+            ],
+            DiffCmd::diffs(&accounts_a, &accounts_b, dec!(0.0))
+        );
+    }
+}
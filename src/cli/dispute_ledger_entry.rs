@@ -0,0 +1,86 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use transact::model::{AccountEvent, ClientId, TxId};
+
+/// Line in the dispute ledger.
+///
+/// Serializes as a JSON object, e.g.
+/// `{"event":"opened","client":1,"tx":2,"amount":"5.0"}`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DisputeLedgerEntry {
+    /// What happened to the dispute.
+    pub event: DisputeEvent,
+    /// Client ID.
+    pub client: ClientId,
+    /// ID of the disputed deposit.
+    pub tx: TxId,
+    /// Amount held, released, or reversed.
+    pub amount: Decimal,
+}
+
+/// What happened to a dispute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeEvent {
+    /// Dispute was opened, and the deposit's amount held.
+    Opened,
+    /// Dispute was resolved, and the held amount released.
+    Resolved,
+    /// Dispute was charged back, and the held amount reversed.
+    ChargedBack,
+}
+
+impl DisputeLedgerEntry {
+    /// CSV header for entries written with [`fmt::Display`].
+    pub const CSV_HEADER: &'static str = "event,client,tx,amount";
+
+    /// Returns the ledger entry for an account event, if it changes a dispute.
+    pub fn from_account_event(account_event: &AccountEvent) -> Option<Self> {
+        let (event, client, tx, amount) = match *account_event {
+            AccountEvent::FundsHeld { client, tx, amount } => {
+                (DisputeEvent::Opened, client, tx, amount)
+            }
+            AccountEvent::FundsReleased { client, tx, amount } => {
+                (DisputeEvent::Resolved, client, tx, amount)
+            }
+            AccountEvent::FundsReversed { client, tx, amount } => {
+                (DisputeEvent::ChargedBack, client, tx, amount)
+            }
+            AccountEvent::FundsDeposited { .. }
+            | AccountEvent::FundsWithdrawn { .. }
+            | AccountEvent::AccountLocked { .. } => return None,
+        };
+
+        Some(Self {
+            event,
+            client,
+            tx,
+            amount,
+        })
+    }
+}
+
+/// Writes the entry as a CSV row, e.g. `opened,1,2,5.0`.
+impl fmt::Display for DisputeLedgerEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            event,
+            client,
+            tx,
+            amount,
+        } = self;
+        write!(f, "{event},{client},{tx},{amount}")
+    }
+}
+
+impl fmt::Display for DisputeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Opened => write!(f, "opened"),
+            Self::Resolved => write!(f, "resolved"),
+            Self::ChargedBack => write!(f, "charged_back"),
+        }
+    }
+}
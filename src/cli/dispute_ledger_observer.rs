@@ -0,0 +1,123 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use rust_decimal::Decimal;
+use transact::{
+    model::{Account, AccountEvent, ClientId, Transaction},
+    ProcessObserver,
+};
+
+use crate::cli::{CliError, DisputeLedgerEntry, ReportWriter};
+
+/// Appends each applied dispute, resolve, and chargeback to a dispute ledger.
+///
+/// Each line is a JSON [`DisputeLedgerEntry`]. The ledger is appended to, so
+/// it keeps the dispute history of every run that writes to it.
+#[derive(Debug)]
+pub struct DisputeLedgerObserver {
+    /// Path to the dispute ledger file.
+    path: PathBuf,
+    /// Writer to the dispute ledger file.
+    report_writer: ReportWriter,
+    /// Held funds of each account after its last applied transaction.
+    held_by_client: Mutex<HashMap<ClientId, Decimal>>,
+}
+
+impl DisputeLedgerObserver {
+    /// Returns a new `DisputeLedgerObserver` appending to the file at `path`.
+    ///
+    /// The file is created if it does not exist.
+    pub fn try_new(path: PathBuf) -> Result<Self, CliError> {
+        match ReportWriter::try_append(&path) {
+            Ok(report_writer) => Ok(Self {
+                path,
+                report_writer,
+                held_by_client: Mutex::new(HashMap::new()),
+            }),
+            Err(error) => Err(CliError::DisputesFileOpen { path, error }),
+        }
+    }
+
+    /// Flushes the dispute ledger, returning the first error writing to it.
+    pub fn finish(&self) -> Result<(), CliError> {
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::DisputesWrite {
+                path: self.path.clone(),
+                error,
+            })
+    }
+}
+
+impl ProcessObserver for DisputeLedgerObserver {
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        let held_before = self
+            .held_by_client
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(account.client(), account.held())
+            .unwrap_or_default();
+
+        AccountEvent::from_applied(transaction, held_before, account)
+            .iter()
+            .filter_map(DisputeLedgerEntry::from_account_event)
+            // Serializing these types to a string does not fail.
+            .filter_map(|dispute_ledger_entry| serde_json::to_string(&dispute_ledger_entry).ok())
+            .for_each(|entry| self.report_writer.write_line(&entry));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::stream;
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{Chargeback, ClientId, Deposit, Dispute, Transaction, TxId},
+        MemTxStore, ProcessOptions,
+    };
+
+    use super::DisputeLedgerObserver;
+
+    #[tokio::test]
+    async fn appends_dispute_events_across_runs() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+        let ledger_dir = tempfile::tempdir()?;
+        let ledger_path = ledger_dir.path().join("disputes.jsonl");
+
+        for transactions in [
+            vec![
+                Transaction::from(Deposit::new(client, tx, dec!(3.0))),
+                Transaction::from(Dispute::new(client, tx)),
+            ],
+            vec![
+                Transaction::from(Deposit::new(client, tx, dec!(3.0))),
+                Transaction::from(Dispute::new(client, tx)),
+                Transaction::from(Chargeback::new(client, tx)),
+            ],
+        ] {
+            let dispute_ledger_observer =
+                Arc::new(DisputeLedgerObserver::try_new(ledger_path.clone())?);
+            transact::process_transactions(
+                stream::iter(transactions.into_iter().map(Ok)),
+                &MemTxStore::new(),
+                ProcessOptions::new().with_observer(dispute_ledger_observer.clone()),
+            )
+            .await?;
+            dispute_ledger_observer.finish()?;
+        }
+
+        assert_eq!(
+            "{\"event\":\"opened\",\"client\":1,\"tx\":1,\"amount\":\"3\"}\n\
+             {\"event\":\"opened\",\"client\":1,\"tx\":1,\"amount\":\"3\"}\n\
+             {\"event\":\"charged_back\",\"client\":1,\"tx\":1,\"amount\":\"3\"}\n",
+            std::fs::read_to_string(&ledger_path)?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+};
+
+use transact::model::ClientId;
+
+use crate::cli::{CliError, DisputeLedgerEntry, DisputesArgs};
+
+/// Prints the dispute history from a dispute ledger.
+#[derive(Debug)]
+pub struct DisputesCmd;
+
+impl DisputesCmd {
+    /// Runs the `disputes` command.
+    ///
+    /// Entries are printed as CSV in the order they were recorded, optionally
+    /// only for one client.
+    pub fn run(disputes_args: DisputesArgs) -> Result<(), CliError> {
+        let DisputesArgs { ledger, client } = disputes_args;
+        let client = client.map(ClientId::new);
+        let disputes_read = |error| CliError::DisputesRead {
+            path: ledger.clone(),
+            error,
+        };
+
+        let reader = BufReader::new(File::open(&ledger).map_err(disputes_read)?);
+        let mut out_stream = io::stdout().lock();
+        writeln!(out_stream, "{}", DisputeLedgerEntry::CSV_HEADER)
+            .map_err(CliError::OutputFlush)?;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(disputes_read)?;
+            let dispute_ledger_entry =
+                serde_json::from_str::<DisputeLedgerEntry>(&line).map_err(|error| {
+                    CliError::DisputesEntryInvalid {
+                        path: ledger.clone(),
+                        line: index + 1,
+                        error,
+                    }
+                })?;
+
+            if client.is_none_or(|client| client == dispute_ledger_entry.client) {
+                writeln!(out_stream, "{dispute_ledger_entry}").map_err(CliError::OutputFlush)?;
+            }
+        }
+
+        out_stream.flush().map_err(CliError::OutputFlush)
+    }
+}
@@ -0,0 +1,45 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+use transact::model::{Account, ClientId};
+
+/// Account row in extended output, with the rejections attributed to the
+/// account.
+///
+/// This is the accounts output schema with two more columns:
+///
+/// * `rejected`: Number of the account's transactions that were rejected.
+/// * `last_rejection`: Code of the last rejection, empty if none.
+#[derive(Debug, Serialize)]
+pub struct ExtendedAccount<'a> {
+    /// Client ID.
+    client: ClientId,
+    /// Available funds.
+    #[serde(with = "rust_decimal::serde::float")]
+    available: Decimal,
+    /// Held funds.
+    #[serde(with = "rust_decimal::serde::float")]
+    held: Decimal,
+    /// Total funds.
+    #[serde(with = "rust_decimal::serde::float")]
+    total: Decimal,
+    /// Whether the account is locked.
+    locked: bool,
+    /// Number of the account's transactions that were rejected.
+    rejected: u64,
+    /// Code of the last rejection.
+    last_rejection: Option<&'a str>,
+}
+
+impl<'a> From<&'a Account> for ExtendedAccount<'a> {
+    fn from(account: &'a Account) -> Self {
+        Self {
+            client: account.client(),
+            available: account.available(),
+            held: account.held(),
+            total: account.total(),
+            locked: account.locked(),
+            rejected: account.rejections().count(),
+            last_rejection: account.rejections().last_code(),
+        }
+    }
+}
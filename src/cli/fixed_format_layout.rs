@@ -0,0 +1,315 @@
+use std::{fmt, num::NonZeroUsize, path::Path};
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use transact::model::ClientId;
+
+use crate::cli::CliError;
+
+/// Layout of each record in a fixed-width export, read from a TOML file.
+///
+/// Each record is one line, made of the `fields` in order. Every field is
+/// padded to its `width`, and a value wider than its field is an error rather
+/// than being truncated.
+///
+/// ```toml
+/// [[fields]]
+/// column = "text"
+/// value = "D"
+/// width = 1
+///
+/// [[fields]]
+/// column = "client"
+/// width = 5
+/// pad = "0"
+///
+/// [[fields]]
+/// column = "total"
+/// width = 12
+/// scale = 2
+/// ```
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixedFormatLayout {
+    /// Fields of each record, in order.
+    pub fields: Vec<FixedFormatField>,
+}
+
+/// Field in a fixed-width record.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixedFormatField {
+    /// Value written in the field.
+    pub column: FixedFormatColumn,
+    /// Number of characters in the field.
+    pub width: NonZeroUsize,
+    /// Side of the field to write the value on. Defaults to `right` for
+    /// numbers, and `left` otherwise.
+    pub align: Option<FixedFormatAlign>,
+    /// Character to fill the rest of the field with. Defaults to a space.
+    pub pad: Option<char>,
+    /// Number of decimal places to write amounts with. Defaults to the
+    /// amount's own scale.
+    pub scale: Option<u32>,
+    /// Value of a `text` field.
+    pub value: Option<String>,
+}
+
+/// Value written in a fixed-width field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixedFormatColumn {
+    /// Field's `value`, e.g. a record type code.
+    Text,
+    /// Client ID.
+    Client,
+    /// Available funds.
+    Available,
+    /// Held funds.
+    Held,
+    /// Total funds.
+    Total,
+    /// `Y` if the account is locked, `N` otherwise.
+    Locked,
+    /// Number of disputes opened.
+    DisputesOpened,
+    /// Number of disputes resolved.
+    DisputesResolved,
+    /// Number of disputes charged back.
+    Chargebacks,
+    /// Amount reversed by chargebacks.
+    AmountChargedBack,
+}
+
+/// Side of a fixed-width field to write the value on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixedFormatAlign {
+    /// Value is written at the start of the field.
+    Left,
+    /// Value is written at the end of the field.
+    Right,
+}
+
+/// Values of one client's record in a fixed-width export.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedFormatRecord {
+    /// Client ID.
+    pub client: ClientId,
+    /// Available funds.
+    pub available: Decimal,
+    /// Held funds.
+    pub held: Decimal,
+    /// Total funds.
+    pub total: Decimal,
+    /// Whether the account is locked.
+    pub locked: bool,
+    /// Number of disputes opened.
+    pub disputes_opened: u64,
+    /// Number of disputes resolved.
+    pub disputes_resolved: u64,
+    /// Number of disputes charged back.
+    pub chargebacks: u64,
+    /// Amount reversed by chargebacks.
+    pub amount_charged_back: Decimal,
+}
+
+impl FixedFormatRecord {
+    /// Returns a `FixedFormatRecord` for a client with no funds or dispute
+    /// activity.
+    pub fn new(client: ClientId) -> Self {
+        Self {
+            client,
+            available: Decimal::ZERO,
+            held: Decimal::ZERO,
+            total: Decimal::ZERO,
+            locked: false,
+            disputes_opened: 0,
+            disputes_resolved: 0,
+            chargebacks: 0,
+            amount_charged_back: Decimal::ZERO,
+        }
+    }
+}
+
+impl FixedFormatLayout {
+    /// Reads the layout from a TOML file.
+    pub fn read(path: &Path) -> Result<Self, CliError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|error| CliError::FixedFormatLayoutRead {
+                path: path.to_path_buf(),
+                error,
+            })?;
+
+        toml::from_str(&content).map_err(|error| CliError::FixedFormatLayoutParse {
+            path: path.to_path_buf(),
+            error,
+        })
+    }
+
+    /// Returns the line for a record, without a trailing newline.
+    pub fn render(&self, record: &FixedFormatRecord) -> Result<String, CliError> {
+        self.fields
+            .iter()
+            .try_fold(String::new(), |mut line, field| {
+                field.render(record, &mut line)?;
+                Ok(line)
+            })
+    }
+}
+
+impl FixedFormatField {
+    /// Appends the padded value of this field for a record to `line`.
+    fn render(&self, record: &FixedFormatRecord, line: &mut String) -> Result<(), CliError> {
+        let amount = |amount: Decimal| match self.scale {
+            Some(scale) => format!("{:.*}", scale as usize, amount.round_dp(scale)),
+            None => amount.to_string(),
+        };
+        let value = match self.column {
+            FixedFormatColumn::Text => self.value.clone().unwrap_or_default(),
+            FixedFormatColumn::Client => record.client.to_string(),
+            FixedFormatColumn::Available => amount(record.available),
+            FixedFormatColumn::Held => amount(record.held),
+            FixedFormatColumn::Total => amount(record.total),
+            FixedFormatColumn::Locked => String::from(if record.locked { "Y" } else { "N" }),
+            FixedFormatColumn::DisputesOpened => record.disputes_opened.to_string(),
+            FixedFormatColumn::DisputesResolved => record.disputes_resolved.to_string(),
+            FixedFormatColumn::Chargebacks => record.chargebacks.to_string(),
+            FixedFormatColumn::AmountChargedBack => amount(record.amount_charged_back),
+        };
+
+        let width = self.width.get();
+        let value_width = value.chars().count();
+        if value_width > width {
+            return Err(CliError::FixedFormatFieldOverflow {
+                client: record.client,
+                column: self.column,
+                value,
+                width,
+            });
+        }
+
+        let pad = self.pad.unwrap_or(' ');
+        let padding = std::iter::repeat_n(pad, width - value_width);
+        match self.align.unwrap_or_else(|| self.column.align_default()) {
+            FixedFormatAlign::Left => {
+                line.push_str(&value);
+                line.extend(padding);
+            }
+            FixedFormatAlign::Right => {
+                // Zero padding goes between the sign and the digits, so that
+                // `-1.5` is written as `-001.5` and not `00-1.5`.
+                let digits = match value.strip_prefix('-') {
+                    Some(digits) if pad == '0' => {
+                        line.push('-');
+                        digits
+                    }
+                    _ => value.as_str(),
+                };
+                line.extend(padding);
+                line.push_str(digits);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FixedFormatColumn {
+    /// Returns the alignment used when a field does not specify one.
+    fn align_default(self) -> FixedFormatAlign {
+        match self {
+            Self::Text | Self::Locked => FixedFormatAlign::Left,
+            Self::Client
+            | Self::Available
+            | Self::Held
+            | Self::Total
+            | Self::DisputesOpened
+            | Self::DisputesResolved
+            | Self::Chargebacks
+            | Self::AmountChargedBack => FixedFormatAlign::Right,
+        }
+    }
+}
+
+/// Writes the column name used in the layout file, e.g. `disputes_opened`.
+impl fmt::Display for FixedFormatColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Text => "text",
+            Self::Client => "client",
+            Self::Available => "available",
+            Self::Held => "held",
+            Self::Total => "total",
+            Self::Locked => "locked",
+            Self::DisputesOpened => "disputes_opened",
+            Self::DisputesResolved => "disputes_resolved",
+            Self::Chargebacks => "chargebacks",
+            Self::AmountChargedBack => "amount_charged_back",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use transact::model::ClientId;
+
+    use super::{FixedFormatLayout, FixedFormatRecord};
+    use crate::cli::CliError;
+
+    #[test]
+    fn renders_fields_padded_to_width() -> Result<(), Box<dyn std::error::Error>> {
+        let layout = toml::from_str::<FixedFormatLayout>(
+            "[[fields]]\n\
+             column = \"text\"\n\
+             value = \"D\"\n\
+             width = 2\n\
+             [[fields]]\n\
+             column = \"client\"\n\
+             width = 5\n\
+             pad = \"0\"\n\
+             [[fields]]\n\
+             column = \"total\"\n\
+             width = 8\n\
+             scale = 2\n\
+             pad = \"0\"\n\
+             [[fields]]\n\
+             column = \"locked\"\n\
+             width = 1\n\
+             [[fields]]\n\
+             column = \"chargebacks\"\n\
+             width = 3\n\
+             align = \"left\"\n",
+        )?;
+        let record = FixedFormatRecord {
+            total: dec!(-1.5),
+            locked: true,
+            chargebacks: 1,
+            ..FixedFormatRecord::new(ClientId::new(42))
+        };
+
+        assert_eq!("D 00042-0001.50Y1  ", layout.render(&record)?);
+        Ok(())
+    }
+
+    #[test]
+    fn render_fails_when_value_is_wider_than_field() -> Result<(), Box<dyn std::error::Error>> {
+        let layout = toml::from_str::<FixedFormatLayout>(
+            "[[fields]]\n\
+             column = \"available\"\n\
+             width = 3\n",
+        )?;
+        let record = FixedFormatRecord {
+            available: dec!(1000),
+            ..FixedFormatRecord::new(ClientId::new(1))
+        };
+
+        assert!(matches!(
+            layout.render(&record),
+            Err(CliError::FixedFormatFieldOverflow { width: 3, .. })
+        ));
+        Ok(())
+    }
+}
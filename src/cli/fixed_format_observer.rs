@@ -0,0 +1,171 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use transact::{
+    model::{Account, AccountEvent, ClientId, Transaction},
+    ProcessObserver,
+};
+
+use crate::cli::{CliError, FixedFormatLayout, FixedFormatRecord, ReportWriter};
+
+/// Writes final balances and dispute activity in a fixed-width format.
+///
+/// Each line is one client's [`FixedFormatRecord`], laid out by a
+/// [`FixedFormatLayout`] and ordered by client. Only clients with applied
+/// transactions in the run are written.
+///
+/// The export is written by [`FixedFormatObserver::finish`], at the end of the
+/// run.
+#[derive(Debug)]
+pub struct FixedFormatObserver {
+    /// Path to the fixed-width export file.
+    path: PathBuf,
+    /// Layout of each line.
+    layout: FixedFormatLayout,
+    /// Writer to the fixed-width export file.
+    report_writer: ReportWriter,
+    /// Account and dispute activity of each client.
+    records: Mutex<HashMap<ClientId, FixedFormatRecord>>,
+}
+
+impl FixedFormatObserver {
+    /// Returns a new `FixedFormatObserver` writing to the file at `path`.
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn try_new(path: PathBuf, layout: FixedFormatLayout) -> Result<Self, CliError> {
+        match ReportWriter::try_new(&path) {
+            Ok(report_writer) => Ok(Self {
+                path,
+                layout,
+                report_writer,
+                records: Mutex::new(HashMap::new()),
+            }),
+            Err(error) => Err(CliError::FixedFormatFileCreate { path, error }),
+        }
+    }
+
+    /// Writes the fixed-width export, returning the first error laying out a
+    /// record or writing to the file.
+    pub fn finish(&self) -> Result<(), CliError> {
+        let mut records = self
+            .records
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain()
+            .map(|(_client, record)| record)
+            .collect::<Vec<_>>();
+        records.sort_unstable_by_key(|record| record.client);
+
+        records.iter().try_for_each(|record| {
+            let line = self.layout.render(record)?;
+            self.report_writer.write_line(&line);
+            Ok::<_, CliError>(())
+        })?;
+
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::FixedFormatWrite {
+                path: self.path.clone(),
+                error,
+            })
+    }
+}
+
+impl ProcessObserver for FixedFormatObserver {
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        let mut records = self.records.lock().unwrap_or_else(PoisonError::into_inner);
+        let record = records
+            .entry(account.client())
+            .or_insert_with(|| FixedFormatRecord::new(account.client()));
+        let held_before = record.held;
+
+        AccountEvent::from_applied(transaction, held_before, account)
+            .iter()
+            .for_each(|account_event| match *account_event {
+                AccountEvent::FundsHeld { .. } => record.disputes_opened += 1,
+                AccountEvent::FundsReleased { .. } => record.disputes_resolved += 1,
+                AccountEvent::FundsReversed { amount, .. } => {
+                    record.chargebacks += 1;
+                    record.amount_charged_back = record.amount_charged_back.saturating_add(amount);
+                }
+                AccountEvent::FundsDeposited { .. }
+                | AccountEvent::FundsWithdrawn { .. }
+                | AccountEvent::AccountLocked { .. } => {}
+            });
+        record.available = account.available();
+        record.held = account.held();
+        record.total = account.total();
+        record.locked = account.locked();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::stream;
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{Chargeback, ClientId, Deposit, Dispute, Transaction, TxId},
+        MemTxStore, ProcessOptions,
+    };
+
+    use super::FixedFormatObserver;
+    use crate::cli::FixedFormatLayout;
+
+    #[tokio::test]
+    async fn writes_records_by_client() -> Result<(), Box<dyn std::error::Error>> {
+        let client_one = ClientId::new(1);
+        let client_two = ClientId::new(2);
+        let export_dir = tempfile::tempdir()?;
+        let export_path = export_dir.path().join("export.txt");
+        let layout = toml::from_str::<FixedFormatLayout>(
+            "[[fields]]\n\
+             column = \"client\"\n\
+             width = 3\n\
+             pad = \"0\"\n\
+             [[fields]]\n\
+             column = \"total\"\n\
+             width = 7\n\
+             scale = 2\n\
+             [[fields]]\n\
+             column = \"disputes_opened\"\n\
+             width = 2\n\
+             [[fields]]\n\
+             column = \"amount_charged_back\"\n\
+             width = 7\n\
+             scale = 2\n",
+        )?;
+        let fixed_format_observer =
+            Arc::new(FixedFormatObserver::try_new(export_path.clone(), layout)?);
+
+        transact::process_transactions(
+            stream::iter(
+                [
+                    Transaction::from(Deposit::new(client_two, TxId::new(1), dec!(5.0))),
+                    Transaction::from(Deposit::new(client_two, TxId::new(2), dec!(2.0))),
+                    Transaction::from(Deposit::new(client_one, TxId::new(3), dec!(1.5))),
+                    Transaction::from(Dispute::new(client_two, TxId::new(1))),
+                    Transaction::from(Dispute::new(client_two, TxId::new(2))),
+                    Transaction::from(Chargeback::new(client_two, TxId::new(1))),
+                ]
+                .into_iter()
+                .map(Ok),
+            ),
+            &MemTxStore::new(),
+            ProcessOptions::new().with_observer(fixed_format_observer.clone()),
+        )
+        .await?;
+        fixed_format_observer.finish()?;
+
+        assert_eq!(
+            "001   1.50 0   0.00\n\
+             002   2.00 2   5.00\n",
+            std::fs::read_to_string(&export_path)?
+        );
+        Ok(())
+    }
+}
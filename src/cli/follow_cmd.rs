@@ -0,0 +1,56 @@
+use futures::{StreamExt, TryStreamExt};
+use transact::{csv::TransactCsv, model::AccountSummary, Error, Processor};
+
+use crate::cli::{runtime, shutdown_signal, CliError};
+
+/// Processes transactions from stdin as they arrive, writing each updated
+/// account to stdout.
+#[derive(Debug)]
+pub struct FollowCmd;
+
+impl FollowCmd {
+    /// Runs the `follow` command.
+    ///
+    /// After each applied transaction, the client's account is written as a
+    /// CSV row and stdout is flushed, so the latest row for a client is its
+    /// current state. Rejected transactions and malformed records are skipped,
+    /// and are logged with `-v`.
+    ///
+    /// This runs until stdin is closed, or a shutdown signal is received.
+    pub fn run() -> Result<(), CliError> {
+        runtime()?.block_on(async {
+            let mut processor = Processor::try_new()?;
+            let mut transactions = TransactCsv::stream_reader(tokio::io::stdin())
+                .into_stream()
+                .take_until(shutdown_signal())
+                .boxed_local();
+            let mut writer = TransactCsv::csv_writer(tokio::io::stdout());
+
+            while let Some(transaction) = transactions.next().await {
+                let transaction = match transaction {
+                    Ok(transaction) => transaction,
+                    Err(error) if error.is_record_error() => {
+                        tracing::info!(code = error.code(), %error, "Record skipped.");
+                        continue;
+                    }
+                    Err(error) => return Err(error),
+                };
+                let client = transaction.client();
+
+                if processor.apply(transaction).await?.is_ok() {
+                    if let Some(account) = processor.accounts().get(client) {
+                        writer
+                            .serialize(AccountSummary::from(account))
+                            .await
+                            .map_err(Error::OutputWrite)?;
+                        writer.flush().await.map_err(Error::OutputFlush)?;
+                    }
+                }
+            }
+
+            Ok::<_, Error>(())
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+/// SHA-256 hash chain over the rows of an audit journal.
+///
+/// Each row's hash covers the previous row's hash and the row, so altering,
+/// removing, or reordering a row changes the hash of every row after it, and
+/// the final hash -- the root. Keeping the root apart from the journal lets an
+/// auditor check that the journal was not altered after the run.
+#[derive(Clone, Debug, Default)]
+pub struct JournalChain {
+    /// Hash of the rows appended so far, all zeros before the first row.
+    hash: [u8; 32],
+}
+
+impl JournalChain {
+    /// Returns a new `JournalChain` with no rows.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a row to the chain, and returns the hex encoded hash of the
+    /// chain including the row.
+    pub fn append(&mut self, row: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.hash);
+        hasher.update(row.as_bytes());
+        self.hash = hasher.finalize().into();
+
+        self.root()
+    }
+
+    /// Returns the hex encoded hash of the rows appended so far.
+    pub fn root(&self) -> String {
+        self.hash
+            .iter()
+            .fold(String::with_capacity(64), |mut root, byte| {
+                // Writing to a `String` does not fail.
+                let _ = write!(root, "{byte:02x}");
+                root
+            })
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+
+use transact::{
+    model::{Account, Transaction},
+    ProcessObserver,
+};
+
+use crate::cli::{CliError, JournalChain, ReportWriter};
+
+/// Writes each applied transaction to an audit journal.
+///
+/// The journal is a transactions CSV file that only contains the applied
+/// transactions, in the order they were applied. Processing it reproduces the
+/// same accounts, which is what `transact replay` does.
+///
+/// Each row ends with a `hash` column, chaining it to the rows before it, see
+/// [`JournalChain`]. The final hash is written to the root file when the
+/// journal is finished, and `transact journal-verify` checks the journal
+/// against it.
+#[derive(Debug)]
+pub struct JournalObserver {
+    /// Path to the journal file.
+    path: PathBuf,
+    /// Path to the file to write the journal's root hash to.
+    root_path: PathBuf,
+    /// Writer to the journal file.
+    report_writer: ReportWriter,
+    /// Hash chain over the rows written so far.
+    journal_chain: Mutex<JournalChain>,
+}
+
+impl JournalObserver {
+    /// Header row of a journal without hashes, such as one saved from the
+    /// REPL.
+    pub const HEADER: &'static str = "type,client,tx,amount";
+    /// Header row of a journal with a hash chain.
+    pub const HEADER_CHAINED: &'static str = "type,client,tx,amount,hash";
+
+    /// Returns a new `JournalObserver` writing to the file at `path`, and its
+    /// root hash to `root_path`.
+    ///
+    /// The files are created, or truncated if they exist.
+    pub fn try_new(path: PathBuf, root_path: PathBuf) -> Result<Self, CliError> {
+        let report_writer = match ReportWriter::try_new(&path) {
+            Ok(report_writer) => report_writer,
+            Err(error) => return Err(CliError::JournalFileCreate { path, error }),
+        };
+        report_writer.write_line(Self::HEADER_CHAINED);
+
+        Ok(Self {
+            path,
+            root_path,
+            report_writer,
+            journal_chain: Mutex::new(JournalChain::new()),
+        })
+    }
+
+    /// Returns the path of the root hash file for a journal, which is the
+    /// journal's path with `.root` appended.
+    pub fn root_path(path: &Path) -> PathBuf {
+        let mut root_path = OsString::from(path);
+        root_path.push(".root");
+        PathBuf::from(root_path)
+    }
+
+    /// Returns the journal row for an applied transaction.
+    pub fn entry(transaction: &Transaction) -> String {
+        let client = transaction.client();
+        let tx = transaction.tx();
+        match transaction {
+            Transaction::Deposit(deposit) => format!("deposit,{client},{tx},{}", deposit.amount()),
+            Transaction::Withdrawal(withdrawal) => {
+                format!("withdrawal,{client},{tx},{}", withdrawal.amount())
+            }
+            Transaction::Dispute(_) => format!("dispute,{client},{tx},"),
+            Transaction::Resolve(_) => format!("resolve,{client},{tx},"),
+            Transaction::Chargeback(_) => format!("chargeback,{client},{tx},"),
+        }
+    }
+
+    /// Flushes the journal and writes its root hash, returning the first
+    /// error writing to them.
+    pub fn finish(&self) -> Result<(), CliError> {
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::JournalWrite {
+                path: self.path.clone(),
+                error,
+            })?;
+
+        let root = self
+            .journal_chain
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .root();
+        std::fs::write(&self.root_path, format!("{root}\n")).map_err(|error| {
+            CliError::JournalWrite {
+                path: self.root_path.clone(),
+                error,
+            }
+        })
+    }
+}
+
+impl ProcessObserver for JournalObserver {
+    fn on_transaction_applied(&self, transaction: &Transaction, _account: &Account) {
+        let entry = Self::entry(transaction);
+
+        // The lock is held while writing, so rows are written in chain order.
+        let mut journal_chain = self
+            .journal_chain
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let hash = journal_chain.append(&entry);
+        self.report_writer.write_line(&format!("{entry},{hash}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::stream;
+    use rust_decimal_macros::dec;
+    use transact::{
+        csv::TransactCsv,
+        model::{ClientId, Deposit, Dispute, Transaction, TxId, Withdrawal},
+        ErrorPolicy, MemTxStore, ProcessOptions,
+    };
+
+    use super::JournalObserver;
+    use crate::cli::{CliError, JournalVerifyCmd};
+
+    #[tokio::test]
+    async fn journal_replays_to_same_accounts() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let transactions = stream::iter([
+            Ok(Transaction::from(Deposit::new(
+                client,
+                TxId::new(1),
+                dec!(3.0),
+            ))),
+            Ok(Transaction::from(Withdrawal::new(
+                client,
+                TxId::new(2),
+                dec!(5.0),
+            ))),
+            Ok(Transaction::from(Deposit::new(
+                client,
+                TxId::new(3),
+                dec!(1.5),
+            ))),
+            Ok(Transaction::from(Dispute::new(client, TxId::new(1)))),
+        ]);
+        let journal_dir = tempfile::tempdir()?;
+        let journal_path = journal_dir.path().join("journal.csv");
+        let journal_observer = Arc::new(JournalObserver::try_new(
+            journal_path.clone(),
+            JournalObserver::root_path(&journal_path),
+        )?);
+
+        let summary = transact::process_transactions(
+            transactions,
+            &MemTxStore::new(),
+            ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Collect)
+                .with_observer(journal_observer.clone()),
+        )
+        .await?;
+        journal_observer.finish()?;
+
+        let replay_summary = transact::process_transactions(
+            TransactCsv::stream(&journal_path).await?,
+            &MemTxStore::new(),
+            ProcessOptions::new().with_error_policy(ErrorPolicy::Strict),
+        )
+        .await?;
+
+        assert_eq!(1, summary.rejections().len());
+        assert_eq!(
+            summary.accounts().get(client),
+            replay_summary.accounts().get(client)
+        );
+        let journal = std::fs::read_to_string(&journal_path)?;
+        let rows = journal
+            .lines()
+            .filter_map(|line| line.rsplit_once(',').map(|(row, _hash)| row))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "type,client,tx,amount",
+                "deposit,1,1,3",
+                "deposit,1,3,1.5",
+                "dispute,1,1,"
+            ],
+            rows
+        );
+        assert_eq!(
+            std::fs::read_to_string(JournalObserver::root_path(&journal_path))?.trim(),
+            JournalVerifyCmd::verify(&journal_path)?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn journal_verify_detects_altered_row() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let journal_dir = tempfile::tempdir()?;
+        let journal_path = journal_dir.path().join("journal.csv");
+        let journal_observer = Arc::new(JournalObserver::try_new(
+            journal_path.clone(),
+            JournalObserver::root_path(&journal_path),
+        )?);
+
+        transact::process_transactions(
+            stream::iter([
+                Ok(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(3.0),
+                ))),
+                Ok(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(2),
+                    dec!(4.0),
+                ))),
+            ]),
+            &MemTxStore::new(),
+            ProcessOptions::new().with_observer(journal_observer.clone()),
+        )
+        .await?;
+        journal_observer.finish()?;
+        let journal = std::fs::read_to_string(&journal_path)?;
+        std::fs::write(
+            &journal_path,
+            journal.replace("deposit,1,1,3", "deposit,1,1,30.0"),
+        )?;
+
+        assert!(matches!(
+            JournalVerifyCmd::verify(&journal_path),
+            Err(CliError::JournalChainBroken { line: 2, .. })
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use crate::cli::{CliError, JournalChain, JournalObserver, JournalVerifyArgs};
+
+/// Checks an audit journal against its root hash.
+#[derive(Debug)]
+pub struct JournalVerifyCmd;
+
+impl JournalVerifyCmd {
+    /// Runs the `journal-verify` command.
+    ///
+    /// The root hash is printed when the journal is unaltered, and an error is
+    /// returned otherwise.
+    pub fn run(journal_verify_args: JournalVerifyArgs) -> Result<(), CliError> {
+        let JournalVerifyArgs { journal, root } = journal_verify_args;
+        let root_expected = match root {
+            Some(root) => root,
+            None => {
+                let root_path = JournalObserver::root_path(&journal);
+                std::fs::read_to_string(&root_path)
+                    .map_err(|error| CliError::JournalRead {
+                        path: root_path,
+                        error,
+                    })?
+                    .trim()
+                    .to_string()
+            }
+        };
+
+        let root_actual = Self::verify(&journal)?;
+        if root_actual.eq_ignore_ascii_case(&root_expected) {
+            println!("{root_actual}");
+            Ok(())
+        } else {
+            Err(CliError::JournalRootMismatch {
+                expected: root_expected,
+                actual: root_actual,
+            })
+        }
+    }
+
+    /// Checks the hash of each row in the journal, and returns the root hash.
+    pub fn verify(journal: &Path) -> Result<String, CliError> {
+        let journal_read = |error| CliError::JournalRead {
+            path: journal.to_path_buf(),
+            error,
+        };
+        let chain_broken = |line| CliError::JournalChainBroken {
+            path: journal.to_path_buf(),
+            line,
+        };
+
+        let reader = BufReader::new(File::open(journal).map_err(journal_read)?);
+        let mut journal_chain = JournalChain::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(journal_read)?;
+            let line_number = index + 1;
+            if line_number == 1 {
+                if line != JournalObserver::HEADER_CHAINED {
+                    return Err(chain_broken(line_number));
+                }
+                continue;
+            }
+
+            let (entry, hash) = line
+                .rsplit_once(',')
+                .ok_or_else(|| chain_broken(line_number))?;
+            if journal_chain.append(entry) != hash {
+                return Err(chain_broken(line_number));
+            }
+        }
+
+        Ok(journal_chain.root())
+    }
+}
@@ -0,0 +1,38 @@
+use std::{
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use transact::{model::Transaction, LatencySummary, ProcessObserver};
+
+/// Records processing latencies by transaction type, to print at the end of a
+/// run.
+#[derive(Debug, Default)]
+pub struct LatencySummaryObserver {
+    /// Processing latencies by transaction type.
+    latency_summary: Mutex<LatencySummary>,
+}
+
+impl LatencySummaryObserver {
+    /// Returns a new `LatencySummaryObserver`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latencies of transactions processed so far.
+    pub fn latency_summary(&self) -> LatencySummary {
+        self.latency_summary
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl ProcessObserver for LatencySummaryObserver {
+    fn on_transaction_latency(&self, transaction: &Transaction, latency: Duration) {
+        self.latency_summary
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(transaction, latency);
+    }
+}
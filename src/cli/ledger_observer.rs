@@ -0,0 +1,31 @@
+use transact::{
+    model::{Account, Transaction},
+    ProcessObserver,
+};
+
+/// Prints each applied transaction and the resulting account state.
+#[derive(Debug)]
+pub struct LedgerObserver;
+
+impl ProcessObserver for LedgerObserver {
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        let tx = transaction.tx();
+        let entry = match transaction {
+            Transaction::Deposit(deposit) => format!("deposit {tx} {}", deposit.amount()),
+            Transaction::Withdrawal(withdrawal) => {
+                format!("withdrawal {tx} {}", withdrawal.amount())
+            }
+            Transaction::Dispute(_) => format!("dispute {tx}"),
+            Transaction::Resolve(_) => format!("resolve {tx}"),
+            Transaction::Chargeback(_) => format!("chargeback {tx}"),
+        };
+
+        println!(
+            "{entry}: available {}, held {}, total {}, locked {}",
+            account.available(),
+            account.held(),
+            account.total(),
+            account.locked()
+        );
+    }
+}
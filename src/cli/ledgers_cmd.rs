@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, path::Path};
+
+use transact::{ErrorPolicy, ProcessOptions, ProcessSummary};
+
+use crate::cli::{runtime, CliError, LedgersArgs, OutputFormat, ReplayCmd};
+
+/// Processes transactions of several ledgers, and writes each ledger's
+/// accounts to its own file.
+#[derive(Debug)]
+pub struct LedgersCmd;
+
+impl LedgersCmd {
+    /// Runs the `ledgers` command.
+    ///
+    /// All ledger names are checked before any file is written, so an invalid
+    /// name does not leave some ledgers' files written.
+    pub fn run(ledgers_args: LedgersArgs) -> Result<(), CliError> {
+        let LedgersArgs {
+            transactions,
+            output_dir,
+            format,
+            block_size,
+            block_store_dir,
+            strict,
+        } = ledgers_args;
+
+        let mut options = ProcessOptions::new();
+        if let Some(block_size) = block_size {
+            options = options.with_block_size(block_size);
+        }
+        if let Some(block_store_dir) = block_store_dir {
+            options = options.with_block_store_dir(block_store_dir);
+        }
+        if strict {
+            options = options.with_error_policy(ErrorPolicy::Strict);
+        }
+
+        let ledger_summaries =
+            transact::blocking::process_ledgers_to_accounts(&transactions, options)?;
+        Self::ledgers_write(&ledger_summaries, &output_dir, format)
+    }
+
+    /// Writes each ledger's accounts to `<ledger>.csv` or `<ledger>.json` in
+    /// `output_dir`.
+    pub fn ledgers_write(
+        ledger_summaries: &BTreeMap<String, ProcessSummary>,
+        output_dir: &Path,
+        format: OutputFormat,
+    ) -> Result<(), CliError> {
+        if let Some(ledger) = ledger_summaries
+            .keys()
+            .find(|ledger| !Self::is_file_name_safe(ledger))
+        {
+            return Err(CliError::LedgerNameInvalid {
+                ledger: ledger.clone(),
+            });
+        }
+        std::fs::create_dir_all(output_dir).map_err(|error| CliError::LedgerOutputDirCreate {
+            path: output_dir.to_path_buf(),
+            error,
+        })?;
+
+        let extension = match format {
+            OutputFormat::Csv => "csv",
+            OutputFormat::Json => "json",
+        };
+        runtime()?.block_on(async {
+            for (ledger, process_summary) in ledger_summaries {
+                let output = output_dir.join(format!("{ledger}.{extension}"));
+                ReplayCmd::accounts_write(process_summary.accounts(), format, Some(&output))
+                    .await?;
+                tracing::debug!(
+                    ledger,
+                    accounts = process_summary.accounts().len(),
+                    "Ledger accounts written."
+                );
+            }
+            Ok(())
+        })
+    }
+
+    /// Returns whether the ledger name can be used as a file name on common
+    /// platforms.
+    fn is_file_name_safe(ledger: &str) -> bool {
+        !ledger.is_empty()
+            && !ledger.starts_with('.')
+            && ledger
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use transact::{ProcessOptions, LEDGER_DEFAULT};
+
+    use super::LedgersCmd;
+    use crate::cli::{CliError, OutputFormat};
+
+    #[test]
+    fn ledgers_write_writes_file_per_ledger() -> Result<(), Box<dyn std::error::Error>> {
+        let ledgers_dir = tempfile::tempdir()?;
+        let transactions_path = ledgers_dir.path().join("transactions.csv");
+        let output_dir = ledgers_dir.path().join("accounts");
+        std::fs::write(
+            &transactions_path,
+            "type,client,tx,amount,tenant\n\
+             deposit,1,1,3.0,acme\n\
+             deposit,1,1,2.0,\n",
+        )?;
+
+        let ledger_summaries = transact::blocking::process_ledgers_to_accounts(
+            &transactions_path,
+            ProcessOptions::new(),
+        )?;
+        LedgersCmd::ledgers_write(&ledger_summaries, &output_dir, OutputFormat::Csv)?;
+
+        assert_eq!(
+            "client,available,held,total,locked\n1,3.0,0.0,3.0,false\n",
+            std::fs::read_to_string(output_dir.join("acme.csv"))?
+        );
+        assert_eq!(
+            "client,available,held,total,locked\n1,2.0,0.0,2.0,false\n",
+            std::fs::read_to_string(output_dir.join(format!("{LEDGER_DEFAULT}.csv")))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ledgers_write_rejects_unsafe_ledger_name() -> Result<(), Box<dyn std::error::Error>> {
+        let ledgers_dir = tempfile::tempdir()?;
+        let ledger_summaries = BTreeMap::from([(
+            String::from("../acme"),
+            transact::ProcessSummary::new(Default::default(), Vec::new(), Vec::new(), false),
+        )]);
+
+        let result =
+            LedgersCmd::ledgers_write(&ledger_summaries, ledgers_dir.path(), OutputFormat::Csv);
+
+        assert!(matches!(
+            result,
+            Err(CliError::LedgerNameInvalid { ledger }) if ledger == "../acme"
+        ));
+        assert!(std::fs::read_dir(ledgers_dir.path())?.next().is_none());
+        Ok(())
+    }
+}
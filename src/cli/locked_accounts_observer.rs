@@ -0,0 +1,187 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use rust_decimal::Decimal;
+use transact::{
+    model::{Account, ClientId, Transaction, TxId},
+    ProcessObserver,
+};
+
+use crate::cli::{CliError, ReportWriter};
+
+/// Writes a report of each account locked by a chargeback.
+///
+/// The report is CSV with the columns in
+/// [`LockedAccountsObserver::CSV_HEADER`], one row per locked account ordered
+/// by client:
+///
+/// * `client`: Client ID.
+/// * `tx`: ID of the deposit that was charged back.
+/// * `amount`: Amount reversed by the chargeback.
+/// * `available`, `held`, `total`: The account after the chargeback. Locked
+///   accounts reject further transactions, so this is also the final state.
+///
+/// The report is written by [`LockedAccountsObserver::finish`], at the end of
+/// the run.
+#[derive(Debug)]
+pub struct LockedAccountsObserver {
+    /// Path to the locked accounts report file.
+    path: PathBuf,
+    /// Writer to the locked accounts report file.
+    report_writer: ReportWriter,
+    /// Held funds of each account after its last applied transaction.
+    held_by_client: Mutex<HashMap<ClientId, Decimal>>,
+    /// Rows of accounts locked so far.
+    locked_accounts: Mutex<Vec<LockedAccountEntry>>,
+}
+
+/// Row in the locked accounts report.
+#[derive(Debug)]
+struct LockedAccountEntry {
+    /// Client ID.
+    client: ClientId,
+    /// ID of the deposit that was charged back.
+    tx: TxId,
+    /// Amount reversed by the chargeback.
+    amount: Decimal,
+    /// Available funds after the chargeback.
+    available: Decimal,
+    /// Held funds after the chargeback.
+    held: Decimal,
+    /// Total funds after the chargeback.
+    total: Decimal,
+}
+
+impl LockedAccountsObserver {
+    /// CSV header of the locked accounts report.
+    pub const CSV_HEADER: &'static str = "client,tx,amount,available,held,total";
+
+    /// Returns a new `LockedAccountsObserver` writing to the file at `path`.
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn try_new(path: PathBuf) -> Result<Self, CliError> {
+        match ReportWriter::try_new(&path) {
+            Ok(report_writer) => Ok(Self {
+                path,
+                report_writer,
+                held_by_client: Mutex::new(HashMap::new()),
+                locked_accounts: Mutex::new(Vec::new()),
+            }),
+            Err(error) => Err(CliError::LockedAccountsFileCreate { path, error }),
+        }
+    }
+
+    /// Writes the locked accounts report, returning the first error writing
+    /// to it.
+    pub fn finish(&self) -> Result<(), CliError> {
+        let mut locked_accounts = self
+            .locked_accounts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        locked_accounts.sort_unstable_by_key(|locked_account_entry| locked_account_entry.client);
+
+        self.report_writer.write_line(Self::CSV_HEADER);
+        locked_accounts.drain(..).for_each(|locked_account_entry| {
+            let LockedAccountEntry {
+                client,
+                tx,
+                amount,
+                available,
+                held,
+                total,
+            } = locked_account_entry;
+            self.report_writer.write_line(&format!(
+                "{client},{tx},{amount},{available},{held},{total}"
+            ));
+        });
+
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::LockedAccountsWrite {
+                path: self.path.clone(),
+                error,
+            })
+    }
+}
+
+impl ProcessObserver for LockedAccountsObserver {
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        let held_before = self
+            .held_by_client
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(account.client(), account.held())
+            .unwrap_or_default();
+
+        if let Transaction::Chargeback(chargeback) = transaction {
+            let locked_account_entry = LockedAccountEntry {
+                client: chargeback.client(),
+                tx: chargeback.tx(),
+                amount: held_before - account.held(),
+                available: account.available(),
+                held: account.held(),
+                total: account.total(),
+            };
+            self.locked_accounts
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(locked_account_entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use futures::stream;
+    use rust_decimal_macros::dec;
+    use transact::{
+        model::{Chargeback, ClientId, Deposit, Dispute, Transaction, TxId},
+        MemTxStore, ProcessOptions,
+    };
+
+    use super::LockedAccountsObserver;
+
+    #[tokio::test]
+    async fn writes_locked_accounts_by_client() -> Result<(), Box<dyn std::error::Error>> {
+        let client_one = ClientId::new(1);
+        let client_two = ClientId::new(2);
+        let report_dir = tempfile::tempdir()?;
+        let report_path = report_dir.path().join("locked.csv");
+        let locked_accounts_observer =
+            Arc::new(LockedAccountsObserver::try_new(report_path.clone())?);
+
+        transact::process_transactions(
+            stream::iter(
+                [
+                    Transaction::from(Deposit::new(client_two, TxId::new(1), dec!(5.0))),
+                    Transaction::from(Deposit::new(client_two, TxId::new(2), dec!(2.0))),
+                    Transaction::from(Deposit::new(client_one, TxId::new(3), dec!(1.5))),
+                    Transaction::from(Dispute::new(client_two, TxId::new(1))),
+                    Transaction::from(Dispute::new(client_two, TxId::new(2))),
+                    Transaction::from(Chargeback::new(client_two, TxId::new(1))),
+                    Transaction::from(Dispute::new(client_one, TxId::new(3))),
+                    Transaction::from(Chargeback::new(client_one, TxId::new(3))),
+                ]
+                .into_iter()
+                .map(Ok),
+            ),
+            &MemTxStore::new(),
+            ProcessOptions::new().with_observer(locked_accounts_observer.clone()),
+        )
+        .await?;
+        locked_accounts_observer.finish()?;
+
+        assert_eq!(
+            "client,tx,amount,available,held,total\n\
+             1,3,1.5,0,0,0\n\
+             2,1,5,0,2,2\n",
+            std::fs::read_to_string(&report_path)?
+        );
+        Ok(())
+    }
+}
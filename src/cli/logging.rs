@@ -0,0 +1,91 @@
+use std::io::IsTerminal;
+
+#[cfg(feature = "otel")]
+use opentelemetry::trace::TracerProvider;
+#[cfg(feature = "otel")]
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[cfg(feature = "otel")]
+use crate::cli::TraceExport;
+use crate::cli::{CliError, LogFormat};
+
+/// Environment variable with log filter directives, used when `-v` is not
+/// passed.
+///
+/// e.g. `TRANSACT_LOG=transact=debug`
+const LOG_ENV: &str = "TRANSACT_LOG";
+
+/// Filter for spans exported over OTLP, regardless of the log filter.
+///
+/// This includes the per-chunk and per-request spans at `info`, and the
+/// per-lookup spans at `debug`.
+#[cfg(feature = "otel")]
+const TRACE_EXPORT_FILTER: &str = "transact=debug";
+
+/// Keeps logging and span export running until it is shut down.
+#[derive(Debug, Default)]
+pub struct LoggingGuard {
+    /// Exports spans over OTLP.
+    #[cfg(feature = "otel")]
+    tracer_provider: Option<SdkTracerProvider>,
+}
+
+impl LoggingGuard {
+    /// Exports buffered spans, and stops exporting.
+    pub fn shutdown(self) {
+        #[cfg(feature = "otel")]
+        if let Some(tracer_provider) = self.tracer_provider {
+            if let Err(error) = tracer_provider.shutdown() {
+                eprintln!("Error exporting spans: {error}");
+            }
+        }
+    }
+}
+
+/// Installs the global subscriber that writes logs to stderr.
+///
+/// With the `otel` feature, spans are also exported over OTLP when
+/// `trace_export` is passed.
+///
+/// # Parameters
+///
+/// * `verbose`: Number of times `-v` was passed.
+/// * `log_format`: Format to write logs in.
+/// * `trace_export`: Exports spans over OTLP, see [`TraceExport::from_env`].
+pub fn init_logging(
+    verbose: u8,
+    log_format: LogFormat,
+    #[cfg(feature = "otel")] trace_export: Option<TraceExport>,
+) -> Result<LoggingGuard, CliError> {
+    let env_filter = match verbose {
+        0 => EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new("warn")),
+        1 => EnvFilter::new("warn,transact=info"),
+        2 => EnvFilter::new("warn,transact=debug"),
+        _ => EnvFilter::new("warn,transact=trace"),
+    };
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .with_ansi(std::io::stderr().is_terminal())
+        .with_writer(std::io::stderr);
+    let fmt_layer = match log_format {
+        LogFormat::Text => fmt_layer.boxed(),
+        LogFormat::Json => fmt_layer.json().boxed(),
+    };
+    let subscriber = tracing_subscriber::registry().with(fmt_layer.with_filter(env_filter));
+
+    #[cfg(feature = "otel")]
+    if let Some(trace_export) = trace_export {
+        let tracer_provider = trace_export.tracer_provider()?;
+        let otel_layer = tracing_opentelemetry::layer()
+            .with_tracer(tracer_provider.tracer("transact"))
+            .with_filter(EnvFilter::new(TRACE_EXPORT_FILTER));
+        subscriber.with(otel_layer).init();
+
+        return Ok(LoggingGuard {
+            tracer_provider: Some(tracer_provider),
+        });
+    }
+
+    subscriber.init();
+    Ok(LoggingGuard::default())
+}
@@ -0,0 +1,187 @@
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use serde::Serialize;
+use transact::{
+    model::{Account, Transaction},
+    ProcessObserver, TxError,
+};
+
+use crate::cli::CliError;
+
+/// Name of the marker file written next to the accounts output when a run
+/// succeeds.
+pub const RUN_SUCCESS: &str = "RUN_SUCCESS";
+
+/// Stages output files, and promotes them to their paths only when the run
+/// succeeds.
+///
+/// Each output file is written to a `.partial` file next to its path. When the
+/// run succeeds, each file is synced and renamed to its path, then the
+/// [`RUN_SUCCESS`] marker is written with a summary of the run. When the run
+/// fails, the `.partial` files are left for inspection, and no output file is
+/// replaced, so downstream jobs never read a truncated output.
+///
+/// This also observes the run to count applied and rejected transactions for
+/// the marker.
+#[derive(Debug)]
+pub struct OutputCommit {
+    /// Path to the run success marker, if there is an accounts output file.
+    marker_path: Option<PathBuf>,
+    /// Paths of the staged output files.
+    paths: Vec<PathBuf>,
+    /// Number of transactions applied.
+    applied: AtomicU64,
+    /// Number of transactions rejected.
+    rejected: AtomicU64,
+}
+
+/// Content of the run success marker.
+#[derive(Debug, Serialize)]
+struct RunSuccess<'p> {
+    /// Number of transactions applied.
+    applied: u64,
+    /// Number of transactions rejected.
+    rejected: u64,
+    /// Paths of the promoted output files.
+    outputs: Vec<&'p Path>,
+}
+
+impl OutputCommit {
+    /// Returns a new `OutputCommit`.
+    ///
+    /// The run success marker is written in the directory of `output`, and
+    /// any marker from a previous run is removed, so that it is only present
+    /// when this run succeeds. No marker is written when `output` is `None`.
+    pub fn try_new(output: Option<&Path>) -> Result<Self, CliError> {
+        let marker_path = output.map(|output| {
+            output
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(RUN_SUCCESS)
+        });
+        if let Some(marker_path) = marker_path.as_deref() {
+            match std::fs::remove_file(marker_path) {
+                Ok(()) => {}
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    return Err(CliError::RunSuccessMarkerWrite {
+                        path: marker_path.to_path_buf(),
+                        error,
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            marker_path,
+            paths: Vec::new(),
+            applied: AtomicU64::new(0),
+            rejected: AtomicU64::new(0),
+        })
+    }
+
+    /// Stages an output file, returning the path to write its content to until
+    /// it is promoted.
+    pub fn stage(&mut self, path: PathBuf) -> PathBuf {
+        let path_partial = Self::path_partial(&path);
+        self.paths.push(path);
+        path_partial
+    }
+
+    /// Promotes each staged output file to its path, then writes the run
+    /// success marker.
+    ///
+    /// Staged files must be flushed before this is called.
+    pub fn promote(&self) -> Result<(), CliError> {
+        self.paths.iter().try_for_each(|path| {
+            let path_partial = Self::path_partial(path);
+            File::open(&path_partial)
+                .and_then(|file| file.sync_all())
+                .and_then(|()| std::fs::rename(&path_partial, path))
+                .map_err(|error| CliError::OutputPromote {
+                    path: path.clone(),
+                    error,
+                })
+        })?;
+
+        let Some(marker_path) = self.marker_path.as_deref() else {
+            return Ok(());
+        };
+        let run_success = RunSuccess {
+            applied: self.applied.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            outputs: self.paths.iter().map(PathBuf::as_path).collect(),
+        };
+        let marker_write = |error| CliError::RunSuccessMarkerWrite {
+            path: marker_path.to_path_buf(),
+            error,
+        };
+        let mut content = serde_json::to_vec(&run_success)
+            .map_err(|error| marker_write(io::Error::from(error)))?;
+        content.push(b'\n');
+        let marker_path_partial = Self::path_partial(marker_path);
+        std::fs::write(&marker_path_partial, content)
+            .and_then(|()| std::fs::rename(&marker_path_partial, marker_path))
+            .map_err(marker_write)
+    }
+
+    fn path_partial(path: &Path) -> PathBuf {
+        let mut path_partial = path.as_os_str().to_owned();
+        path_partial.push(".partial");
+        PathBuf::from(path_partial)
+    }
+}
+
+impl ProcessObserver for OutputCommit {
+    fn on_transaction_applied(&self, _transaction: &Transaction, _account: &Account) {
+        self.applied.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_transaction_rejected(&self, _transaction: &Transaction, _tx_error: &TxError) {
+        self.rejected.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutputCommit, RUN_SUCCESS};
+
+    #[test]
+    fn promote_renames_staged_files_and_writes_marker() -> Result<(), Box<dyn std::error::Error>> {
+        let output_dir = tempfile::tempdir()?;
+        let accounts_path = output_dir.path().join("accounts.csv");
+        let marker_path = output_dir.path().join(RUN_SUCCESS);
+        std::fs::write(&marker_path, "stale")?;
+
+        let mut output_commit = OutputCommit::try_new(Some(&accounts_path))?;
+        let accounts_path_partial = output_commit.stage(accounts_path.clone());
+        std::fs::write(
+            &accounts_path_partial,
+            "client,available,held,total,locked\n",
+        )?;
+
+        assert!(!marker_path.exists());
+        assert!(!accounts_path.exists());
+
+        output_commit.promote()?;
+
+        assert!(!accounts_path_partial.exists());
+        assert_eq!(
+            "client,available,held,total,locked\n",
+            std::fs::read_to_string(&accounts_path)?
+        );
+        assert_eq!(
+            format!(
+                "{{\"applied\":0,\"rejected\":0,\"outputs\":[{}]}}\n",
+                serde_json::to_string(&accounts_path)?
+            ),
+            std::fs::read_to_string(&marker_path)?
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,384 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use transact::{
+    csv::TransactCsv,
+    model::{Account, AccountSummary},
+    CancellationToken, ChargebackPolicy, Error, ErrorPolicy, LoggingNotifier, MemoryBudget,
+    ProcessObserver, ProcessOptions,
+};
+
+use crate::cli::{
+    cancel_on_shutdown_signal, runtime, AccountEventObserver, AuditTrailObserver, CliError,
+    DisputeLedgerObserver, ExtendedAccount, FixedFormatLayout, FixedFormatObserver,
+    JournalObserver, LatencySummaryObserver, LockedAccountsObserver, OutputCommit, OutputFormat,
+    ProcessArgs, ProcessConfig, RejectionObserver, RejectionSummaryObserver,
+};
+#[cfg(feature = "tui")]
+use crate::cli::{Dashboard, DashboardObserver};
+
+/// Processes transactions and writes the final state of client accounts.
+#[derive(Debug)]
+pub struct ProcessCmd;
+
+impl ProcessCmd {
+    /// Runs the `process` command.
+    pub fn run(process_args: ProcessArgs) -> Result<(), CliError> {
+        let ProcessArgs {
+            input,
+            output,
+            format,
+            extended,
+            block_size,
+            block_store_dir,
+            account_cache_capacity,
+            partitions,
+            persist_concurrency,
+            lookup_concurrency,
+            channel_capacity,
+            memory_budget,
+            strict,
+            park_early_disputes,
+            implicit_disputes,
+            sort_by_tx,
+            blocking_parse,
+            #[cfg(feature = "simd-csv")]
+            simd_parse,
+            require_increasing_tx,
+            deterministic,
+            config,
+            journal,
+            rejections,
+            rejection_summary,
+            latency_summary,
+            notify,
+            events,
+            audit_trail,
+            locked_accounts,
+            fixed_format,
+            fixed_format_layout,
+            disputes,
+            snapshot,
+            #[cfg(feature = "tui")]
+            tui,
+        } = process_args;
+        let process_config = config
+            .as_deref()
+            .map(ProcessConfig::read)
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut options = ProcessOptions::new();
+        if let Some(block_size) = block_size.or(process_config.block_size) {
+            options = options.with_block_size(block_size);
+        }
+        if let Some(block_store_dir) = block_store_dir {
+            options = options.with_block_store_dir(block_store_dir);
+        }
+        if let Some(account_cache_capacity) = account_cache_capacity {
+            options = options.with_account_cache_capacity(account_cache_capacity);
+        }
+        if let Some(partitions) = partitions {
+            options = options.with_partitions(partitions);
+        }
+        if let Some(persist_concurrency) = persist_concurrency {
+            options = options.with_persist_concurrency(persist_concurrency);
+        }
+        if let Some(lookup_concurrency) = lookup_concurrency {
+            options = options.with_lookup_concurrency(lookup_concurrency);
+        }
+        if let Some(channel_capacity) = channel_capacity {
+            options = options.with_channel_capacity(channel_capacity);
+        }
+        if let Some(memory_budget) = memory_budget {
+            options = options.with_memory_budget(MemoryBudget::new(memory_budget));
+        }
+        let error_policy = if strict {
+            Some(ErrorPolicy::Strict)
+        } else {
+            process_config.error_policy
+        };
+        if let Some(error_policy) = error_policy {
+            options = options.with_error_policy(error_policy);
+        }
+        let chargeback_policy = if implicit_disputes {
+            Some(ChargebackPolicy::ImplicitDispute)
+        } else {
+            process_config.chargeback_policy
+        };
+        if let Some(chargeback_policy) = chargeback_policy {
+            options = options.with_chargeback_policy(chargeback_policy);
+        }
+        options = options
+            .with_park_early_disputes(park_early_disputes)
+            .with_sort_by_tx(sort_by_tx)
+            .with_blocking_parse(blocking_parse)
+            .with_require_increasing_tx(require_increasing_tx)
+            .with_deterministic_output(deterministic);
+        #[cfg(feature = "simd-csv")]
+        {
+            options = options.with_simd_parse(simd_parse);
+        }
+        let format = format.or(process_config.format).unwrap_or_default();
+        // Output files are staged, and only replace existing files when the run
+        // succeeds.
+        let mut output_commit = OutputCommit::try_new(output.as_deref())?;
+        let output = output.map(|path| output_commit.stage(path));
+        let journal_observer = journal
+            .map(|path| {
+                let root_path = output_commit.stage(JournalObserver::root_path(&path));
+                JournalObserver::try_new(output_commit.stage(path), root_path)
+            })
+            .transpose()?
+            .map(Arc::new);
+        let rejection_observer = rejections
+            .map(|path| {
+                RejectionObserver::try_new(output_commit.stage(path))
+                    .map(|rejection_observer| rejection_observer.with_sorted(deterministic))
+            })
+            .transpose()?
+            .map(Arc::new);
+        let rejection_summary_observer =
+            rejection_summary.then(|| Arc::new(RejectionSummaryObserver::new()));
+        let latency_summary_observer =
+            latency_summary.then(|| Arc::new(LatencySummaryObserver::new()));
+        let account_event_observer = events
+            .map(|path| AccountEventObserver::try_new(output_commit.stage(path)))
+            .transpose()?
+            .map(Arc::new);
+        let audit_trail_observer = audit_trail
+            .map(|path| AuditTrailObserver::try_new(output_commit.stage(path)))
+            .transpose()?
+            .map(Arc::new);
+        let locked_accounts_observer = locked_accounts
+            .map(|path| LockedAccountsObserver::try_new(output_commit.stage(path)))
+            .transpose()?
+            .map(Arc::new);
+        let fixed_format_observer = fixed_format
+            .zip(fixed_format_layout)
+            .map(|(path, layout_path)| {
+                let layout = FixedFormatLayout::read(&layout_path)?;
+                FixedFormatObserver::try_new(output_commit.stage(path), layout)
+            })
+            .transpose()?
+            .map(Arc::new);
+        let dispute_ledger_observer = disputes
+            .map(DisputeLedgerObserver::try_new)
+            .transpose()?
+            .map(Arc::new);
+        let output_commit = Arc::new(output_commit);
+        #[cfg(feature = "tui")]
+        let dashboard_observer = tui.then(|| Arc::new(DashboardObserver::new()));
+        #[allow(unused_mut)]
+        let mut observers =
+            std::iter::once(Arc::clone(&output_commit) as Arc<dyn ProcessObserver>)
+                .chain(journal_observer.iter().map(|journal_observer| {
+                    Arc::clone(journal_observer) as Arc<dyn ProcessObserver>
+                }))
+                .chain(rejection_observer.iter().map(|rejection_observer| {
+                    Arc::clone(rejection_observer) as Arc<dyn ProcessObserver>
+                }))
+                .chain(
+                    rejection_summary_observer
+                        .iter()
+                        .map(|rejection_summary_observer| {
+                            Arc::clone(rejection_summary_observer) as Arc<dyn ProcessObserver>
+                        }),
+                )
+                .chain(
+                    latency_summary_observer
+                        .iter()
+                        .map(|latency_summary_observer| {
+                            Arc::clone(latency_summary_observer) as Arc<dyn ProcessObserver>
+                        }),
+                )
+                .chain(account_event_observer.iter().map(|account_event_observer| {
+                    Arc::clone(account_event_observer) as Arc<dyn ProcessObserver>
+                }))
+                .chain(audit_trail_observer.iter().map(|audit_trail_observer| {
+                    Arc::clone(audit_trail_observer) as Arc<dyn ProcessObserver>
+                }))
+                .chain(
+                    locked_accounts_observer
+                        .iter()
+                        .map(|locked_accounts_observer| {
+                            Arc::clone(locked_accounts_observer) as Arc<dyn ProcessObserver>
+                        }),
+                )
+                .chain(fixed_format_observer.iter().map(|fixed_format_observer| {
+                    Arc::clone(fixed_format_observer) as Arc<dyn ProcessObserver>
+                }))
+                .chain(
+                    dispute_ledger_observer
+                        .iter()
+                        .map(|dispute_ledger_observer| {
+                            Arc::clone(dispute_ledger_observer) as Arc<dyn ProcessObserver>
+                        }),
+                )
+                .collect::<Vec<_>>();
+        #[cfg(feature = "tui")]
+        observers.extend(
+            dashboard_observer.iter().map(|dashboard_observer| {
+                Arc::clone(dashboard_observer) as Arc<dyn ProcessObserver>
+            }),
+        );
+        options = options.with_observer(Arc::new(observers));
+        if notify {
+            options = options.with_notifier(Arc::new(LoggingNotifier));
+        }
+
+        let cancellation_token = CancellationToken::new();
+        options = options.with_cancellation_token(cancellation_token.clone());
+        cancel_on_shutdown_signal(cancellation_token.clone());
+        #[cfg(feature = "tui")]
+        let dashboard = dashboard_observer
+            .map(|dashboard_observer| {
+                Dashboard::spawn(dashboard_observer, cancellation_token.clone())
+                    .map_err(CliError::DashboardTerminal)
+            })
+            .transpose()?;
+
+        let mut out_stream: Box<dyn Write> = match output {
+            Some(path) => {
+                let file = File::create(&path)
+                    .map_err(|error| CliError::OutputFileCreate { path, error })?;
+                Box::new(BufWriter::new(file))
+            }
+            None => Box::new(io::stdout().lock()),
+        };
+
+        let process_result = Self::process(
+            &input,
+            snapshot.as_deref(),
+            format,
+            extended,
+            options,
+            &mut out_stream,
+        );
+        #[cfg(feature = "tui")]
+        if let Some(dashboard) = dashboard {
+            dashboard.finish().map_err(CliError::DashboardTerminal)?;
+        }
+
+        // Reports are flushed even if processing fails, so that a strict run
+        // records the rejection that stopped it in the staged report.
+        if let Some(rejection_observer) = rejection_observer {
+            rejection_observer.finish()?;
+        }
+        if let Some(journal_observer) = journal_observer {
+            journal_observer.finish()?;
+        }
+        if let Some(account_event_observer) = account_event_observer {
+            account_event_observer.finish()?;
+        }
+        if let Some(audit_trail_observer) = audit_trail_observer {
+            audit_trail_observer.finish()?;
+        }
+        if let Some(locked_accounts_observer) = locked_accounts_observer {
+            locked_accounts_observer.finish()?;
+        }
+        if let Some(fixed_format_observer) = fixed_format_observer {
+            fixed_format_observer.finish()?;
+        }
+        if let Some(dispute_ledger_observer) = dispute_ledger_observer {
+            dispute_ledger_observer.finish()?;
+        }
+        // Printed even if processing fails, as a strict run stops at the first
+        // rejection.
+        if let Some(rejection_summary_observer) = rejection_summary_observer {
+            eprint!("{}", rejection_summary_observer.rejection_summary());
+        }
+        if let Some(latency_summary_observer) = latency_summary_observer {
+            eprint!("{}", latency_summary_observer.latency_summary());
+        }
+        process_result?;
+        out_stream.flush().map_err(CliError::OutputFlush)?;
+        drop(out_stream);
+
+        if cancellation_token.is_cancelled() {
+            Err(CliError::Interrupted)
+        } else {
+            output_commit.promote()
+        }
+    }
+
+    /// Processes transactions and writes the accounts in the given format.
+    ///
+    /// When `snapshot` is given, transactions are applied on top of it, and it
+    /// is replaced with the updated state. When `extended` is `true`, accounts
+    /// are written with their rejections. Accounts are written in order of
+    /// client ID when the options ask for deterministic output.
+    fn process(
+        input: &Path,
+        snapshot: Option<&Path>,
+        format: OutputFormat,
+        extended: bool,
+        options: ProcessOptions,
+        mut out_stream: &mut dyn Write,
+    ) -> Result<(), CliError> {
+        let deterministic_output = options.deterministic_output();
+        if format == OutputFormat::Csv && !extended {
+            match snapshot {
+                Some(snapshot) => transact::blocking::process_incremental(
+                    snapshot,
+                    input,
+                    &mut out_stream,
+                    options,
+                )?,
+                None => transact::blocking::process(input, &mut out_stream, options)?,
+            }
+            return Ok(());
+        }
+
+        let summary = match snapshot {
+            Some(snapshot) => {
+                transact::blocking::process_incremental_to_accounts(snapshot, input, options)?
+            }
+            None => transact::blocking::process_to_accounts(input, options)?,
+        };
+        let accounts: Box<dyn Iterator<Item = &Account>> = if deterministic_output {
+            Box::new(summary.accounts().iter_sorted())
+        } else {
+            Box::new(summary.accounts().values())
+        };
+        match format {
+            OutputFormat::Csv => {
+                // `extended` is `true`, as other CSV output is streamed above.
+                let content = runtime()?.block_on(async {
+                    let mut content = Vec::new();
+                    let mut writer = TransactCsv::csv_writer(&mut content);
+                    for account in accounts {
+                        writer
+                            .serialize(ExtendedAccount::from(account))
+                            .await
+                            .map_err(Error::OutputWrite)?;
+                    }
+                    writer.flush().await.map_err(Error::OutputFlush)?;
+                    drop(writer);
+
+                    Ok::<_, Error>(content)
+                })?;
+                out_stream
+                    .write_all(&content)
+                    .map_err(CliError::OutputFlush)?;
+            }
+            OutputFormat::Json => {
+                if extended {
+                    let accounts = accounts.map(ExtendedAccount::from).collect::<Vec<_>>();
+                    serde_json::to_writer(&mut out_stream, &accounts)
+                } else {
+                    let accounts = accounts.map(AccountSummary::from).collect::<Vec<_>>();
+                    serde_json::to_writer(&mut out_stream, &accounts)
+                }
+                .map_err(CliError::OutputJsonWrite)?;
+                writeln!(out_stream).map_err(CliError::OutputFlush)?;
+            }
+        }
+
+        Ok(())
+    }
+}
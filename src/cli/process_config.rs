@@ -0,0 +1,86 @@
+use std::{num::NonZeroUsize, path::Path};
+
+use serde::Deserialize;
+use transact::{ChargebackPolicy, ErrorPolicy};
+
+use crate::cli::{CliError, OutputFormat};
+
+/// Options for the `process` command, read from a TOML file.
+///
+/// Options passed on the command line or through environment variables take
+/// precedence over those in the file.
+///
+/// ```toml
+/// block_size = 50000
+/// error_policy = "strict"
+/// chargeback_policy = "implicit_dispute"
+/// format = "json"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProcessConfig {
+    /// Number of transactions to persist per block.
+    pub block_size: Option<NonZeroUsize>,
+    /// How to handle rejected transactions and malformed records.
+    pub error_policy: Option<ErrorPolicy>,
+    /// How to handle chargebacks of transactions that are not in dispute.
+    pub chargeback_policy: Option<ChargebackPolicy>,
+    /// Format to write accounts in.
+    pub format: Option<OutputFormat>,
+}
+
+impl ProcessConfig {
+    /// Reads the configuration from a TOML file.
+    ///
+    /// Unknown keys are rejected, so that misspelt options are not silently
+    /// ignored.
+    pub fn read(path: &Path) -> Result<Self, CliError> {
+        let content = std::fs::read_to_string(path).map_err(|error| CliError::ConfigRead {
+            path: path.to_path_buf(),
+            error,
+        })?;
+
+        toml::from_str(&content).map_err(|error| CliError::ConfigParse {
+            path: path.to_path_buf(),
+            error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use transact::{ChargebackPolicy, ErrorPolicy};
+
+    use super::ProcessConfig;
+    use crate::cli::OutputFormat;
+
+    #[test]
+    fn deserializes_all_options() -> Result<(), Box<dyn std::error::Error>> {
+        let process_config = toml::from_str::<ProcessConfig>(
+            "block_size = 50000\n\
+             error_policy = \"collect\"\n\
+             chargeback_policy = \"implicit_dispute\"\n\
+             format = \"json\"\n",
+        )?;
+
+        assert_eq!(
+            ProcessConfig {
+                block_size: NonZeroUsize::new(50000),
+                error_policy: Some(ErrorPolicy::Collect),
+                chargeback_policy: Some(ChargebackPolicy::ImplicitDispute),
+                format: Some(OutputFormat::Json),
+            },
+            process_config
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unknown_options() {
+        let result = toml::from_str::<ProcessConfig>("fee_schedule = \"flat\"\n");
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use transact::{
+    csv::{TransactCsv, TxRecord},
+    model::{AccountSummary, ClientId, TxId},
+    Error, MemTxStore, ProcessOptions, TxStore,
+};
+
+use crate::cli::{runtime, CliError, LedgerObserver, QueryArgs};
+
+/// Processes transactions for a single client, and prints its account or one
+/// of its deposits.
+#[derive(Debug)]
+pub struct QueryCmd;
+
+impl QueryCmd {
+    /// Runs the `query` command.
+    ///
+    /// Only the client's transactions are processed and stored, so this is
+    /// faster than processing the whole file. Disputes that reference another
+    /// client's transaction are rejected as not found instead of as a client
+    /// mismatch, which leaves the account unchanged either way.
+    ///
+    /// When `--tx` is given, the original deposit is looked up in the
+    /// transaction store after processing, and printed instead of the account.
+    pub fn run(query_args: QueryArgs) -> Result<(), CliError> {
+        let QueryArgs {
+            transactions,
+            client,
+            tx,
+            ledger,
+        } = query_args;
+        let client = ClientId::new(client);
+        let tx = tx.map(TxId::new);
+
+        let mut options = ProcessOptions::new();
+        if ledger {
+            options = options.with_observer(Arc::new(LedgerObserver));
+        }
+
+        runtime()?.block_on(async {
+            let transactions =
+                TransactCsv::stream(&transactions)
+                    .await?
+                    .try_filter(|transaction| {
+                        let is_client = transaction.client() == client;
+                        async move { is_client }
+                    });
+            let tx_store = MemTxStore::new();
+            let summary = transact::process_transactions(transactions, &tx_store, options).await?;
+
+            let account = summary
+                .accounts()
+                .get(client)
+                .ok_or(CliError::ClientNotFound { client })?;
+            let mut writer = TransactCsv::csv_writer(tokio::io::stdout());
+            match tx {
+                Some(tx) => {
+                    let deposit = tx_store
+                        .find_transaction(tx)
+                        .await?
+                        .ok_or(CliError::TransactionNotFound { client, tx })?;
+                    writer
+                        .serialize(TxRecord::from(deposit))
+                        .await
+                        .map_err(Error::OutputWrite)?;
+                }
+                None => {
+                    writer
+                        .serialize(AccountSummary::from(account))
+                        .await
+                        .map_err(Error::OutputWrite)?;
+                }
+            }
+            writer.flush().await.map_err(Error::OutputFlush)?;
+
+            Ok::<_, CliError>(())
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+use rust_decimal::Decimal;
+use transact::ProcessOptions;
+
+use crate::cli::{AccountDiff, CliError, DiffCmd, ReconcileArgs};
+
+/// Processes transactions and compares the accounts with expected balances.
+#[derive(Debug)]
+pub struct ReconcileCmd;
+
+impl ReconcileCmd {
+    /// Runs the `reconcile` command.
+    ///
+    /// Each difference is printed to stdout, and an error is returned if
+    /// there are any.
+    pub fn run(reconcile_args: ReconcileArgs) -> Result<(), CliError> {
+        let ReconcileArgs {
+            transactions,
+            expected_balances,
+            tolerance,
+        } = reconcile_args;
+
+        let account_diffs = Self::reconcile(&transactions, &expected_balances, tolerance)?;
+        account_diffs
+            .iter()
+            .for_each(|account_diff| println!("{}", Self::describe(account_diff)));
+
+        if account_diffs.is_empty() {
+            Ok(())
+        } else {
+            Err(CliError::ReconcileFailed {
+                diff_count: account_diffs.len(),
+            })
+        }
+    }
+
+    /// Returns the differences between the expected balances and the accounts
+    /// processed from the transactions, ordered by client.
+    ///
+    /// In each [`AccountDiff`], the expected balances are the first accounts,
+    /// and the processed accounts the second. Amounts are only considered
+    /// different when they differ by more than `tolerance`.
+    pub fn reconcile(
+        transactions: &Path,
+        expected_balances: &Path,
+        tolerance: Decimal,
+    ) -> Result<Vec<AccountDiff>, CliError> {
+        let accounts_expected = transact::blocking::read_accounts(expected_balances)?;
+        let (accounts_processed, _rejections, _record_errors) =
+            transact::blocking::process_to_accounts(transactions, ProcessOptions::new())?
+                .into_inner();
+
+        Ok(DiffCmd::diffs(
+            &accounts_expected,
+            &accounts_processed,
+            tolerance,
+        ))
+    }
+
+    /// Returns a line describing a difference in terms of expected and
+    /// processed accounts.
+    fn describe(account_diff: &AccountDiff) -> String {
+        match account_diff {
+            AccountDiff::AmountDiff {
+                client,
+                field,
+                amount_a,
+                amount_b,
+            } => match amount_b.checked_sub(*amount_a) {
+                Some(difference) => format!(
+                    "client {client}: {field} expected {amount_a}, processed {amount_b} ({difference:+})"
+                ),
+                None => {
+                    format!("client {client}: {field} expected {amount_a}, processed {amount_b}")
+                }
+            },
+            AccountDiff::LockedDiff {
+                client,
+                locked_a,
+                locked_b,
+            } => format!("client {client}: locked expected {locked_a}, processed {locked_b}"),
+            AccountDiff::OnlyInA { client } => {
+                format!("client {client}: expected, but has no transactions")
+            }
+            AccountDiff::OnlyInB { client } => {
+                format!("client {client}: not in expected balances")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use transact::model::ClientId;
+
+    use super::ReconcileCmd;
+    use crate::cli::AccountDiff;
+
+    #[test]
+    fn reconcile_reports_differences_beyond_tolerance() -> Result<(), Box<dyn std::error::Error>> {
+        let reconcile_dir = tempfile::tempdir()?;
+        let transactions_path = reconcile_dir.path().join("transactions.csv");
+        let expected_balances_path = reconcile_dir.path().join("expected_balances.csv");
+        std::fs::write(
+            &transactions_path,
+            "type,client,tx,amount\n\
+             deposit,1,1,3.0\n\
+             deposit,2,2,2.0\n",
+        )?;
+        std::fs::write(
+            &expected_balances_path,
+            "client,available,held,total,locked\n\
+             1,3.001,0.0,3.001,false\n\
+             3,1.0,0.0,1.0,false\n",
+        )?;
+
+        let account_diffs =
+            ReconcileCmd::reconcile(&transactions_path, &expected_balances_path, dec!(0.01))?;
+
+        assert_eq!(
+            vec![
+                AccountDiff::OnlyInB {
+                    client: ClientId::new(2)
+                },
+                AccountDiff::OnlyInA {
+                    client: ClientId::new(3)
+                },
+            ],
+            account_diffs
+        );
+        assert_eq!(
+            vec![
+                "client 2: not in expected balances",
+                "client 3: expected, but has no transactions",
+            ],
+            account_diffs
+                .iter()
+                .map(ReconcileCmd::describe)
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn describe_amount_diff_includes_difference() {
+        let account_diff = AccountDiff::AmountDiff {
+            client: ClientId::new(1),
+            field: "available",
+            amount_a: dec!(3.0),
+            amount_b: dec!(2.5),
+        };
+
+        assert_eq!(
+            "client 1: available expected 3.0, processed 2.5 (-0.5)",
+            ReconcileCmd::describe(&account_diff)
+        );
+    }
+}
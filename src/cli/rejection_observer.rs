@@ -0,0 +1,110 @@
+use std::{
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+};
+
+use serde::Serialize;
+use transact::{
+    csv::TxRecord,
+    model::{Transaction, TxId},
+    ProcessObserver, TxError,
+};
+
+use crate::cli::{CliError, ReportWriter};
+
+/// Writes each rejected transaction to a rejection report.
+///
+/// Each line is a JSON object with the transaction's `type`, `client`, `tx`,
+/// and `amount`, and the rejection `code` and `message`.
+///
+/// Lines are written as transactions are rejected, or ordered by transaction
+/// ID when the report is [sorted](RejectionObserver::with_sorted).
+#[derive(Debug)]
+pub struct RejectionObserver {
+    /// Path to the rejection report file.
+    path: PathBuf,
+    /// Writer to the rejection report file.
+    report_writer: ReportWriter,
+    /// Lines held until the report is finished, with their transaction IDs,
+    /// when the report is sorted.
+    sorted_entries: Option<Mutex<Vec<(TxId, String)>>>,
+}
+
+/// Line in the rejection report.
+#[derive(Debug, Serialize)]
+struct RejectionEntry<'e> {
+    /// The rejected transaction.
+    #[serde(flatten)]
+    tx_record: TxRecord,
+    /// The reason the transaction was rejected.
+    #[serde(flatten)]
+    tx_error: &'e TxError,
+}
+
+impl RejectionObserver {
+    /// Returns a new `RejectionObserver` writing to the file at `path`.
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn try_new(path: PathBuf) -> Result<Self, CliError> {
+        match ReportWriter::try_new(&path) {
+            Ok(report_writer) => Ok(Self {
+                path,
+                report_writer,
+                sorted_entries: None,
+            }),
+            Err(error) => Err(CliError::RejectionsFileCreate { path, error }),
+        }
+    }
+
+    /// Sets whether to write the report ordered by transaction ID when it is
+    /// finished, instead of as transactions are rejected.
+    ///
+    /// This keeps the report the same across runs however processing is
+    /// partitioned.
+    pub fn with_sorted(mut self, sorted: bool) -> Self {
+        self.sorted_entries = sorted.then(|| Mutex::new(Vec::new()));
+        self
+    }
+
+    /// Flushes the rejection report, returning the first error writing to it.
+    pub fn finish(&self) -> Result<(), CliError> {
+        if let Some(sorted_entries) = self.sorted_entries.as_ref() {
+            let mut sorted_entries = std::mem::take(
+                &mut *sorted_entries
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            );
+            sorted_entries.sort_unstable();
+            sorted_entries
+                .iter()
+                .for_each(|(_tx, entry)| self.report_writer.write_line(entry));
+        }
+
+        self.report_writer
+            .finish()
+            .map_err(|error| CliError::RejectionsWrite {
+                path: self.path.clone(),
+                error,
+            })
+    }
+}
+
+impl ProcessObserver for RejectionObserver {
+    fn on_transaction_rejected(&self, transaction: &Transaction, tx_error: &TxError) {
+        let rejection_entry = RejectionEntry {
+            tx_record: TxRecord::from(transaction.clone()),
+            tx_error,
+        };
+
+        // Serializing these types to a string does not fail.
+        if let Ok(entry) = serde_json::to_string(&rejection_entry) {
+            match self.sorted_entries.as_ref() {
+                Some(sorted_entries) => sorted_entries
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push((tx_error.tx(), entry)),
+                None => self.report_writer.write_line(&entry),
+            }
+        }
+    }
+}
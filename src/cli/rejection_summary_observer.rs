@@ -0,0 +1,34 @@
+use std::sync::{Mutex, PoisonError};
+
+use transact::{model::Transaction, ProcessObserver, RejectionSummary, TxError};
+
+/// Counts rejected transactions by error code, to print at the end of a run.
+#[derive(Debug, Default)]
+pub struct RejectionSummaryObserver {
+    /// Counts and amounts of rejected transactions by error code.
+    rejection_summary: Mutex<RejectionSummary>,
+}
+
+impl RejectionSummaryObserver {
+    /// Returns a new `RejectionSummaryObserver`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counts and amounts of transactions rejected so far.
+    pub fn rejection_summary(&self) -> RejectionSummary {
+        self.rejection_summary
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl ProcessObserver for RejectionSummaryObserver {
+    fn on_transaction_rejected(&self, transaction: &Transaction, tx_error: &TxError) {
+        self.rejection_summary
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(transaction, tx_error);
+    }
+}
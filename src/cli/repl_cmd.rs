@@ -0,0 +1,157 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use futures::TryStreamExt;
+use transact::{
+    csv::TransactCsv,
+    model::{Account, AccountSummary, Transaction},
+    Error, MemTxStore, Processor,
+};
+
+use crate::cli::{runtime, CliError, JournalObserver, ReplCommand};
+
+/// Applies transactions entered one at a time, printing the result of each.
+#[derive(Debug)]
+pub struct ReplCmd;
+
+/// State of the `repl`.
+#[derive(Debug)]
+struct ReplState {
+    /// Processes entered transactions.
+    processor: Processor<MemTxStore>,
+    /// Transactions that have been applied, to save as a journal.
+    applied: Vec<Transaction>,
+}
+
+impl ReplState {
+    /// Returns a new `ReplState` with no accounts.
+    fn new() -> Self {
+        Self {
+            processor: Processor::new(MemTxStore::new()),
+            applied: Vec::new(),
+        }
+    }
+}
+
+impl ReplCmd {
+    /// Runs the `repl` command.
+    ///
+    /// Commands are read from stdin until `quit` or the end of input. Errors
+    /// in individual commands are printed, and do not stop the `repl`.
+    pub fn run() -> Result<(), CliError> {
+        let runtime = runtime()?;
+        let interactive = io::stdin().is_terminal();
+        let mut repl_state = ReplState::new();
+
+        if interactive {
+            println!("Enter `help` for the available commands.");
+        }
+        let mut lines = io::stdin().lock().lines();
+        loop {
+            if interactive {
+                print!("> ");
+                io::stdout().flush().map_err(CliError::OutputFlush)?;
+            }
+            let Some(line) = lines.next() else {
+                break;
+            };
+            let line = line.map_err(CliError::InputRead)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let repl_command = match line.parse::<ReplCommand>() {
+                Ok(repl_command) => repl_command,
+                Err(message) => {
+                    println!("{message}");
+                    continue;
+                }
+            };
+            match repl_command {
+                ReplCommand::Quit => break,
+                repl_command => {
+                    if let Err(error) =
+                        runtime.block_on(Self::execute(&mut repl_state, repl_command))
+                    {
+                        println!("{error}");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Executes a command, printing its result.
+    async fn execute(repl_state: &mut ReplState, repl_command: ReplCommand) -> Result<(), Error> {
+        match repl_command {
+            ReplCommand::Apply(transaction) => {
+                let client = transaction.client();
+                match repl_state.processor.apply(transaction.clone()).await? {
+                    Ok(()) => {
+                        repl_state.applied.push(transaction);
+                        if let Some(account) = repl_state.processor.accounts().get(client) {
+                            Self::print_account(account);
+                        }
+                    }
+                    Err(tx_error) => println!("rejected ({}): {tx_error}", tx_error.code()),
+                }
+            }
+            ReplCommand::Account(client) => match repl_state.processor.accounts().get(client) {
+                Some(account) => Self::print_account(account),
+                None => println!("Client {client} has no transactions."),
+            },
+            ReplCommand::Accounts => {
+                repl_state
+                    .processor
+                    .accounts()
+                    .iter_sorted()
+                    .for_each(Self::print_account);
+            }
+            ReplCommand::Save(path) => {
+                let journal = std::iter::once(JournalObserver::HEADER.to_string())
+                    .chain(repl_state.applied.iter().map(JournalObserver::entry))
+                    .map(|entry| entry + "\n")
+                    .collect::<String>();
+                match std::fs::write(&path, journal) {
+                    Ok(()) => println!(
+                        "Saved {} transactions to {}.",
+                        repl_state.applied.len(),
+                        path.display()
+                    ),
+                    Err(error) => println!("Error saving to {}: {error}", path.display()),
+                }
+            }
+            ReplCommand::Load(path) => {
+                let mut repl_state_loaded = ReplState::new();
+                let mut rejected = 0;
+                let mut transactions = Box::pin(TransactCsv::stream(&path).await?.into_stream());
+                while let Some(transaction) = transactions.try_next().await? {
+                    match repl_state_loaded
+                        .processor
+                        .apply(transaction.clone())
+                        .await?
+                    {
+                        Ok(()) => repl_state_loaded.applied.push(transaction),
+                        Err(_) => rejected += 1,
+                    }
+                }
+
+                println!(
+                    "Loaded {} transactions from {}, {rejected} rejected.",
+                    repl_state_loaded.applied.len(),
+                    path.display()
+                );
+                *repl_state = repl_state_loaded;
+            }
+            ReplCommand::Help => println!("{}", ReplCommand::HELP),
+            ReplCommand::Quit => {}
+        }
+
+        Ok(())
+    }
+
+    /// Prints an account on a single line.
+    fn print_account(account: &Account) {
+        println!("{}", AccountSummary::from(account));
+    }
+}
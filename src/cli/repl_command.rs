@@ -0,0 +1,145 @@
+use std::{path::PathBuf, str::FromStr};
+
+use rust_decimal::Decimal;
+use transact::{
+    csv::TxType,
+    model::{Chargeback, ClientId, Deposit, Dispute, Resolve, Transaction, TxId, Withdrawal},
+};
+
+/// Command entered in the `repl`.
+#[derive(Clone, Debug)]
+pub enum ReplCommand {
+    /// Applies a transaction.
+    Apply(Transaction),
+    /// Prints a client's account.
+    Account(ClientId),
+    /// Prints all accounts.
+    Accounts,
+    /// Saves the applied transactions to a journal file.
+    Save(PathBuf),
+    /// Replaces the state with the transactions in a transactions file.
+    Load(PathBuf),
+    /// Prints the available commands.
+    Help,
+    /// Exits the `repl`.
+    Quit,
+}
+
+impl ReplCommand {
+    /// Description of the available commands.
+    pub const HELP: &'static str = "\
+deposit <client> <tx> <amount>     Deposits into the client's account.
+withdrawal <client> <tx> <amount>  Withdraws from the client's account.
+dispute <client> <tx>              Holds the funds of a deposit.
+resolve <client> <tx>              Releases the funds held by a dispute.
+chargeback <client> <tx>           Reverses a disputed deposit, and locks the account.
+account <client>                   Prints the client's account.
+accounts                           Prints all accounts.
+save <path>                        Saves the applied transactions as a journal.
+load <path>                        Replaces the state with the transactions in a file.
+help                               Prints this message.
+quit                               Exits.";
+}
+
+impl FromStr for ReplCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut words = s.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let args = words.collect::<Vec<_>>();
+
+        let client = |arg: &str| {
+            arg.parse::<u16>()
+                .map(ClientId::new)
+                .map_err(|_| format!("Invalid client ID `{arg}`, expected 0 to 65535."))
+        };
+        let tx = |arg: &str| {
+            arg.parse::<u32>()
+                .map(TxId::new)
+                .map_err(|_| format!("Invalid transaction ID `{arg}`."))
+        };
+        let amount = |arg: &str| {
+            arg.parse::<Decimal>()
+                .map_err(|_| format!("Invalid amount `{arg}`."))
+        };
+        let usage = || format!("Invalid arguments for `{command}`, enter `help` for usage.");
+
+        if let Ok(tx_type) = command.parse::<TxType>() {
+            let transaction = match (tx_type, args.as_slice()) {
+                (TxType::Deposit, [c, t, a]) => {
+                    Transaction::from(Deposit::new(client(c)?, tx(t)?, amount(a)?))
+                }
+                (TxType::Withdrawal, [c, t, a]) => {
+                    Transaction::from(Withdrawal::new(client(c)?, tx(t)?, amount(a)?))
+                }
+                (TxType::Dispute, [c, t]) => Transaction::from(Dispute::new(client(c)?, tx(t)?)),
+                (TxType::Resolve, [c, t]) => Transaction::from(Resolve::new(client(c)?, tx(t)?)),
+                (TxType::Chargeback, [c, t]) => {
+                    Transaction::from(Chargeback::new(client(c)?, tx(t)?))
+                }
+                _ => return Err(usage()),
+            };
+            return Ok(Self::Apply(transaction));
+        }
+
+        match (command, args.as_slice()) {
+            ("account", [c]) => Ok(Self::Account(client(c)?)),
+            ("accounts", []) => Ok(Self::Accounts),
+            ("save", [path]) => Ok(Self::Save(PathBuf::from(path))),
+            ("load", [path]) => Ok(Self::Load(PathBuf::from(path))),
+            ("help", []) => Ok(Self::Help),
+            ("quit" | "exit", []) => Ok(Self::Quit),
+            ("account" | "accounts" | "save" | "load" | "help" | "quit" | "exit", _) => {
+                Err(usage())
+            }
+            _ => Err(format!(
+                "Unknown command `{command}`, enter `help` for usage."
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use transact::model::{ClientId, Transaction, TxId};
+
+    use super::ReplCommand;
+
+    #[test]
+    fn parses_transactions() -> Result<(), Box<dyn std::error::Error>> {
+        let deposit = "deposit 1 100 50.0".parse::<ReplCommand>()?;
+        let dispute = " dispute  1 100 ".parse::<ReplCommand>()?;
+
+        assert!(matches!(
+            deposit,
+            ReplCommand::Apply(Transaction::Deposit(deposit))
+            if deposit.client() == ClientId::new(1)
+                && deposit.tx() == TxId::new(100)
+                && deposit.amount() == dec!(50.0)
+        ));
+        assert!(matches!(
+            dispute,
+            ReplCommand::Apply(Transaction::Dispute(dispute))
+            if dispute.client() == ClientId::new(1) && dispute.tx() == TxId::new(100)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert_eq!(
+            Some("Invalid client ID `-1`, expected 0 to 65535.".to_string()),
+            "deposit -1 100 50.0".parse::<ReplCommand>().err()
+        );
+        assert_eq!(
+            Some("Invalid arguments for `dispute`, enter `help` for usage.".to_string()),
+            "dispute 1".parse::<ReplCommand>().err()
+        );
+        assert_eq!(
+            Some("Unknown command `deposits`, enter `help` for usage.".to_string()),
+            "deposits 1 100 50.0".parse::<ReplCommand>().err()
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use futures::{StreamExt, TryStreamExt};
+use tokio::io::AsyncWriteExt;
+use transact::{
+    csv::TransactCsv,
+    model::{AccountSummary, Accounts},
+    CancellationToken, Checkpointer, Error, TxBlockStore,
+};
+
+use crate::cli::{
+    cancel_on_shutdown_signal, runtime, CliError, OutputCommit, OutputFormat, ProcessArgs,
+    ProcessCmd, ReplayArgs,
+};
+
+/// Reconstructs accounts from an audit journal.
+#[derive(Debug)]
+pub struct ReplayCmd;
+
+impl ReplayCmd {
+    /// Runs the `replay` command.
+    ///
+    /// The journal only contains applied transactions, so it is processed
+    /// strictly -- a rejected transaction means the journal was altered or
+    /// truncated, and the accounts would not match the original run.
+    pub fn run(replay_args: ReplayArgs) -> Result<(), CliError> {
+        let ReplayArgs {
+            journal,
+            output,
+            format,
+            checkpoint_dir,
+            checkpoint_interval,
+            checkpoint_retain,
+        } = replay_args;
+
+        if let Some(checkpoint_dir) = checkpoint_dir {
+            let checkpointer = Checkpointer::new(checkpoint_dir, checkpoint_interval)
+                .with_retain(checkpoint_retain);
+            return Self::run_checkpointed(&journal, output, format, checkpointer);
+        }
+
+        let process_args = ProcessArgs {
+            output,
+            format: Some(format),
+            strict: true,
+            ..ProcessArgs::new(journal)
+        };
+
+        ProcessCmd::run(process_args)
+    }
+
+    /// Replays the journal, resuming from the latest checkpoint and writing
+    /// checkpoints as transactions are applied.
+    ///
+    /// When interrupted, a checkpoint is written before exiting, so the next
+    /// run continues from where this one stopped.
+    fn run_checkpointed(
+        journal: &Path,
+        output: Option<PathBuf>,
+        format: OutputFormat,
+        checkpointer: Checkpointer,
+    ) -> Result<(), CliError> {
+        let mut output_commit = OutputCommit::try_new(output.as_deref())?;
+        let output = output.map(|path| output_commit.stage(path));
+        let cancellation_token = CancellationToken::new();
+        cancel_on_shutdown_signal(cancellation_token.clone());
+
+        let accounts = runtime()?.block_on(async {
+            let mut processor = checkpointer.restore(TxBlockStore::try_new()?).await?;
+            let position = usize::try_from(processor.position()).unwrap_or(usize::MAX);
+            if position != 0 {
+                tracing::info!(position, "Resuming from checkpoint.");
+            }
+            let mut transactions = TransactCsv::stream(journal)
+                .await?
+                .into_stream()
+                .skip(position)
+                .take_until(cancellation_token.cancelled())
+                .boxed_local();
+
+            while let Some(transaction) = transactions.next().await {
+                processor
+                    .apply(transaction?)
+                    .await?
+                    .map_err(Error::TransactionRejected)?;
+                checkpointer.checkpoint_if_due(&mut processor).await?;
+            }
+
+            if cancellation_token.is_cancelled() {
+                checkpointer.checkpoint(&mut processor).await?;
+                return Err(CliError::Interrupted);
+            }
+
+            let accounts = processor.into_accounts();
+            Self::accounts_write(&accounts, format, output.as_deref()).await?;
+
+            Ok::<_, CliError>(accounts)
+        })?;
+        tracing::debug!(accounts = accounts.len(), "Journal replayed.");
+
+        output_commit.promote()
+    }
+
+    /// Writes accounts to the output file, or stdout.
+    pub async fn accounts_write(
+        accounts: &Accounts,
+        format: OutputFormat,
+        output: Option<&Path>,
+    ) -> Result<(), CliError> {
+        let mut out_stream: Box<dyn tokio::io::AsyncWrite + Unpin> = match output {
+            Some(path) => Box::new(tokio::fs::File::create(path).await.map_err(|error| {
+                CliError::OutputFileCreate {
+                    path: path.to_path_buf(),
+                    error,
+                }
+            })?),
+            None => Box::new(tokio::io::stdout()),
+        };
+
+        match format {
+            OutputFormat::Csv => {
+                let mut writer = TransactCsv::csv_writer(out_stream);
+                for account in accounts.values() {
+                    writer
+                        .serialize(AccountSummary::from(account))
+                        .await
+                        .map_err(Error::OutputWrite)?;
+                }
+                writer.flush().await.map_err(Error::OutputFlush)?;
+            }
+            OutputFormat::Json => {
+                let accounts = accounts
+                    .values()
+                    .map(AccountSummary::from)
+                    .collect::<Vec<AccountSummary>>();
+                let mut content =
+                    serde_json::to_vec(&accounts).map_err(CliError::OutputJsonWrite)?;
+                content.push(b'\n');
+                out_stream
+                    .write_all(&content)
+                    .await
+                    .map_err(CliError::OutputFlush)?;
+                out_stream.flush().await.map_err(CliError::OutputFlush)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Mutex, PoisonError},
+};
+
+/// Writes lines to a report file from [`ProcessObserver`] methods.
+///
+/// Observer methods cannot return errors, so the first error writing to the
+/// file is kept, and returned by [`ReportWriter::finish`]. Lines after that
+/// error are not written.
+///
+/// [`ProcessObserver`]: transact::ProcessObserver
+#[derive(Debug)]
+pub struct ReportWriter(Mutex<ReportWriterState>);
+
+/// Writer to the report file, and the first error writing to it.
+#[derive(Debug)]
+struct ReportWriterState {
+    /// Writer to the report file.
+    writer: BufWriter<File>,
+    /// First error writing to the report file.
+    error: Option<io::Error>,
+}
+
+impl ReportWriter {
+    /// Returns a new `ReportWriter` writing to the file at `path`.
+    ///
+    /// The file is created, or truncated if it exists.
+    pub fn try_new(path: &Path) -> io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+
+        Ok(Self(Mutex::new(ReportWriterState {
+            writer,
+            error: None,
+        })))
+    }
+
+    /// Returns a new `ReportWriter` appending to the file at `path`.
+    ///
+    /// The file is created if it does not exist.
+    pub fn try_append(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self(Mutex::new(ReportWriterState {
+            writer: BufWriter::new(file),
+            error: None,
+        })))
+    }
+
+    /// Writes a line to the report file.
+    pub fn write_line(&self, line: &str) {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.error.is_none() {
+            state.error = writeln!(state.writer, "{line}").err();
+        }
+    }
+
+    /// Flushes the report file, returning the first error writing to it.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match state.error.take() {
+            Some(error) => Err(error),
+            None => state.writer.flush(),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use transact::Error;
+
+use crate::cli::CliError;
+
+/// Returns a runtime to drive a command on the current thread.
+pub fn runtime() -> Result<tokio::runtime::Runtime, CliError> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(Error::RuntimeCreate)
+        .map_err(CliError::from)
+}
@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use transact::{server::ServerOptions, MemTxStore, Processor, RateLimit};
+
+use crate::cli::{runtime, shutdown_signal, CliError, ServeArgs};
+
+/// Serves HTTP endpoints to ingest transactions and query accounts.
+#[derive(Debug)]
+pub struct ServeCmd;
+
+impl ServeCmd {
+    /// Runs the `serve` command.
+    ///
+    /// The server stops on `SIGINT` or `SIGTERM`, after in-progress requests
+    /// are finished or `--drain-timeout` passes. When `--snapshot` is passed,
+    /// the server restores from the snapshot file if it exists, and writes a
+    /// snapshot before exiting.
+    pub fn run(serve_args: ServeArgs) -> Result<(), CliError> {
+        let ServeArgs {
+            listen,
+            snapshot,
+            snapshot_interval,
+            max_records_per_sec,
+            max_bytes_per_sec,
+            drain_timeout,
+        } = serve_args;
+        let mut rate_limit = RateLimit::new();
+        if let Some(max_records_per_sec) = max_records_per_sec {
+            rate_limit = rate_limit.with_records_per_sec(max_records_per_sec);
+        }
+        if let Some(max_bytes_per_sec) = max_bytes_per_sec {
+            rate_limit = rate_limit.with_bytes_per_sec(max_bytes_per_sec);
+        }
+
+        runtime()?.block_on(async {
+            let (processor, options) = match snapshot {
+                Some(snapshot) => {
+                    let processor = if snapshot.is_file() {
+                        Processor::restore(MemTxStore::new(), &snapshot).await?
+                    } else {
+                        Processor::new(MemTxStore::new())
+                    };
+                    let options = ServerOptions::new()
+                        .with_snapshot(snapshot, Duration::from_secs(snapshot_interval.get()));
+                    (processor, options)
+                }
+                None => (Processor::new(MemTxStore::new()), ServerOptions::new()),
+            };
+            let options = options
+                .with_rate_limit(rate_limit)
+                .with_drain_timeout(Duration::from_secs(drain_timeout));
+            eprintln!("Listening on {listen}");
+            transact::server::serve(listen, processor, options, shutdown_signal()).await
+        })?;
+
+        Ok(())
+    }
+}
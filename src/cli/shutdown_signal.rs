@@ -0,0 +1,61 @@
+use std::future;
+
+use transact::CancellationToken;
+
+use crate::cli::runtime;
+
+/// Completes when the process receives `SIGINT` (Ctrl-C), or `SIGTERM` on
+/// Unix.
+///
+/// If the signal handlers cannot be installed, this never completes, and the
+/// process is stopped by the default signal behaviour.
+pub async fn shutdown_signal() {
+    let sigint = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let sigterm = async {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let sigterm = future::pending::<()>();
+
+    tokio::select! {
+        () = sigint => {}
+        () = sigterm => {}
+    }
+}
+
+/// Cancels `cancellation_token` when the process receives a shutdown signal.
+///
+/// Signals are listened for on a separate thread, so this may be used with the
+/// blocking API. A second signal exits the process immediately with
+/// [`CliError::EXIT_CODE_INTERRUPTED`].
+///
+/// [`CliError::EXIT_CODE_INTERRUPTED`]: crate::cli::CliError::EXIT_CODE_INTERRUPTED
+pub fn cancel_on_shutdown_signal(cancellation_token: CancellationToken) {
+    let Ok(runtime) = runtime() else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        runtime.block_on(async {
+            shutdown_signal().await;
+            eprintln!("Stopping after transactions that have been read. Signal again to exit now.");
+            cancellation_token.cancel();
+
+            shutdown_signal().await;
+            std::process::exit(crate::cli::CliError::EXIT_CODE_INTERRUPTED.into());
+        });
+    });
+}
@@ -0,0 +1,144 @@
+use std::path::Path;
+
+use futures::TryStreamExt;
+use transact::{
+    csv::TransactCsv,
+    model::{Account, Accounts, Transaction},
+    Error, MemTxStore, Processor, TxStore,
+};
+
+use crate::cli::{runtime, CliError, DiffCmd, JournalObserver, SimulateArgs};
+
+/// Applies hypothetical transactions to an accounts snapshot, and reports the
+/// outcome without writing any files.
+#[derive(Debug)]
+pub struct SimulateCmd;
+
+impl SimulateCmd {
+    /// Runs the `simulate` command.
+    ///
+    /// Whether each transaction would be accepted or rejected is printed,
+    /// followed by the changes to each client's account.
+    pub fn run(simulate_args: SimulateArgs) -> Result<(), CliError> {
+        let SimulateArgs {
+            accounts,
+            transactions,
+            deposits,
+        } = simulate_args;
+
+        // Accounts are read separately to compare against, as the simulation
+        // takes ownership of the accounts it updates.
+        let mut accounts_before = transact::blocking::read_accounts(&accounts)?;
+        let (accounts_after, accepted, rejected) = runtime()?.block_on(Self::simulate(
+            transact::blocking::read_accounts(&accounts)?,
+            &transactions,
+            deposits.as_deref(),
+        ))?;
+
+        println!("accepted: {accepted}");
+        println!("rejected: {rejected}");
+
+        // Clients that are new in the simulation are compared against empty
+        // accounts, so their balances are shown as changes from zero.
+        accounts_after.keys().for_each(|client| {
+            accounts_before
+                .entry(*client)
+                .or_insert_with(|| Account::empty(*client));
+        });
+        DiffCmd::diffs(
+            &accounts_before,
+            &accounts_after,
+            rust_decimal::Decimal::ZERO,
+        )
+        .iter()
+        .for_each(|account_diff| println!("{account_diff}"));
+
+        Ok(())
+    }
+
+    /// Applies the hypothetical transactions to the accounts, printing each
+    /// outcome.
+    ///
+    /// Returns the resulting accounts, and the number of accepted and
+    /// rejected transactions.
+    async fn simulate(
+        accounts: Accounts,
+        transactions: &Path,
+        deposits: Option<&Path>,
+    ) -> Result<(Accounts, usize, usize), Error> {
+        let tx_store = MemTxStore::new();
+        if let Some(deposits) = deposits {
+            let deposits = TransactCsv::stream(deposits)
+                .await?
+                .try_filter(|transaction| {
+                    futures::future::ready(matches!(transaction, Transaction::Deposit(_)))
+                })
+                .try_collect::<Vec<_>>()
+                .await?;
+            tx_store.persist_block(&deposits).await?;
+        }
+
+        let mut processor = Processor::with_accounts(tx_store, accounts);
+        let mut accepted = 0;
+        let mut rejected = 0;
+        let mut transactions = Box::pin(TransactCsv::stream(transactions).await?.into_stream());
+        while let Some(transaction) = transactions.try_next().await? {
+            let entry = JournalObserver::entry(&transaction);
+            match processor.apply(transaction).await? {
+                Ok(()) => {
+                    accepted += 1;
+                    println!("accepted: {entry}");
+                }
+                Err(tx_error) => {
+                    rejected += 1;
+                    println!("rejected: {entry}: {}: {tx_error}", tx_error.code());
+                }
+            }
+        }
+
+        Ok((processor.into_accounts(), accepted, rejected))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rust_decimal_macros::dec;
+    use transact::model::{Account, Accounts, ClientId, TxId};
+
+    use super::SimulateCmd;
+
+    #[tokio::test]
+    async fn simulate_charges_back_deposit_from_deposits_file()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let dir = tempfile::tempdir()?;
+        let deposits = dir.path().join("deposits.csv");
+        let transactions = dir.path().join("transactions.csv");
+        std::fs::write(&deposits, "type, client, tx, amount\ndeposit, 1, 1, 3.0\n")?;
+        std::fs::write(
+            &transactions,
+            "type, client, tx, amount\n\
+             dispute, 1, 1,\n\
+             chargeback, 1, 1,\n\
+             withdrawal, 1, 2, 1.0\n",
+        )?;
+        let mut accounts = Accounts::new();
+        accounts.insert(
+            client,
+            Account::try_new(client, dec!(5.0), dec!(0.0), false, HashSet::new())
+                .expect("Test data invalid."),
+        );
+
+        let (accounts, accepted, rejected) =
+            SimulateCmd::simulate(accounts, &transactions, Some(&deposits)).await?;
+
+        let account_expected = Account::try_new(client, dec!(2.0), dec!(0.0), true, HashSet::new())
+            .expect("Test data invalid.")
+            .with_charged_back_txs(HashSet::from([TxId::new(1)]));
+        assert_eq!(Some(&account_expected), accounts.get(client));
+        assert_eq!((2, 1), (accepted, rejected));
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use futures::{StreamExt, TryStreamExt};
+use transact::{csv::TransactCsv, Error};
+
+use crate::cli::{runtime, CliError, StatsArgs, TxStats};
+
+/// Reports statistics of a transactions file without applying it.
+#[derive(Debug)]
+pub struct StatsCmd;
+
+impl StatsCmd {
+    /// Runs the `stats` command.
+    pub fn run(stats_args: StatsArgs) -> Result<(), CliError> {
+        let StatsArgs { transactions } = stats_args;
+
+        let tx_stats = runtime()?.block_on(async {
+            TransactCsv::stream(&transactions)
+                .await?
+                .into_stream()
+                .map(Result::<_, Error>::Ok)
+                .try_fold(TxStats::new(), |mut tx_stats, transaction| async move {
+                    match transaction {
+                        Ok(transaction) => tx_stats.record(&transaction),
+                        Err(error) if error.is_record_error() => tx_stats.record_malformed(),
+                        Err(error) => return Err(error),
+                    }
+                    Ok(tx_stats)
+                })
+                .await
+        })?;
+
+        print!("{}", tx_stats.report());
+
+        Ok(())
+    }
+}
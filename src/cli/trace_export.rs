@@ -0,0 +1,91 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::SpanExporter;
+use opentelemetry_sdk::{trace::SdkTracerProvider, Resource};
+
+use crate::cli::CliError;
+
+/// Environment variables with the OTLP endpoint to export spans to.
+///
+/// Spans are only exported when one of these is set.
+const OTLP_ENDPOINT_ENVS: [&str; 2] = [
+    "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+];
+
+/// Exports tracing spans over OTLP, with the run's resource attributes.
+///
+/// Spans are sent over HTTP with protobuf encoding. The endpoint, headers,
+/// and timeout are read from the standard `OTEL_EXPORTER_OTLP_*` environment
+/// variables.
+#[derive(Debug)]
+pub struct TraceExport {
+    /// ID of the run, exported as `transact.run_id`.
+    run_id: String,
+    /// File the run reads, exported as `transact.input_file`.
+    input_file: Option<PathBuf>,
+}
+
+impl TraceExport {
+    /// Service name of exported spans.
+    const SERVICE_NAME: &'static str = "transact";
+
+    /// Returns a `TraceExport` if an OTLP endpoint is set in the
+    /// environment.
+    ///
+    /// # Parameters
+    ///
+    /// * `run_id`: ID of the run. Defaults to one derived from the start time
+    ///   and process ID.
+    /// * `input_file`: File the run reads, if any.
+    pub fn from_env(run_id: Option<String>, input_file: Option<&Path>) -> Option<Self> {
+        let endpoint_set = OTLP_ENDPOINT_ENVS
+            .iter()
+            .any(|env| std::env::var_os(env).is_some_and(|endpoint| !endpoint.is_empty()));
+        if !endpoint_set {
+            return None;
+        }
+
+        let run_id = run_id.unwrap_or_else(|| {
+            let start_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_millis())
+                .unwrap_or_default();
+            format!("{start_ms:x}-{:x}", std::process::id())
+        });
+
+        Some(Self {
+            run_id,
+            input_file: input_file.map(Path::to_path_buf),
+        })
+    }
+
+    /// Returns the tracer provider that exports spans in batches.
+    ///
+    /// The provider must be shut down before the application exits, so that
+    /// buffered spans are exported.
+    pub fn tracer_provider(self) -> Result<SdkTracerProvider, CliError> {
+        let Self { run_id, input_file } = self;
+
+        let span_exporter = SpanExporter::builder()
+            .with_http()
+            .build()
+            .map_err(CliError::TraceExporterBuild)?;
+        let resource = Resource::builder()
+            .with_service_name(Self::SERVICE_NAME)
+            .with_attribute(KeyValue::new("transact.run_id", run_id))
+            .with_attributes(input_file.map(|input_file| {
+                KeyValue::new("transact.input_file", input_file.display().to_string())
+            }))
+            .build();
+
+        Ok(SdkTracerProvider::builder()
+            .with_batch_exporter(span_exporter)
+            .with_resource(resource)
+            .build())
+    }
+}
@@ -0,0 +1,197 @@
+use std::{collections::HashSet, fmt};
+
+use rust_decimal::Decimal;
+use transact::model::{ClientId, Transaction, TxId};
+
+/// Summary statistics of a transactions file.
+///
+/// Amounts are kept to compute exact percentiles, so memory grows with the
+/// number of deposits and withdrawals.
+#[derive(Debug, Default)]
+pub struct TxStats {
+    /// Number of deposit transactions.
+    deposits: u64,
+    /// Number of withdrawal transactions.
+    withdrawals: u64,
+    /// Number of dispute transactions.
+    disputes: u64,
+    /// Number of resolve transactions.
+    resolves: u64,
+    /// Number of chargeback transactions.
+    chargebacks: u64,
+    /// Number of records that could not be parsed.
+    malformed: u64,
+    /// Clients that have at least one transaction.
+    clients: HashSet<ClientId>,
+    /// Lowest and highest transaction IDs.
+    tx_range: Option<(TxId, TxId)>,
+    /// Amounts of deposits and withdrawals.
+    amounts: Vec<Decimal>,
+}
+
+impl TxStats {
+    /// Returns a new `TxStats` with no transactions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a transaction.
+    pub fn record(&mut self, transaction: &Transaction) {
+        match transaction {
+            Transaction::Deposit(deposit) => {
+                self.deposits += 1;
+                self.amounts.push(deposit.amount());
+            }
+            Transaction::Withdrawal(withdrawal) => {
+                self.withdrawals += 1;
+                self.amounts.push(withdrawal.amount());
+            }
+            Transaction::Dispute(_) => self.disputes += 1,
+            Transaction::Resolve(_) => self.resolves += 1,
+            Transaction::Chargeback(_) => self.chargebacks += 1,
+        }
+
+        self.clients.insert(transaction.client());
+
+        let tx = transaction.tx();
+        self.tx_range = Some(match self.tx_range {
+            Some((tx_min, tx_max)) => (tx_min.min(tx), tx_max.max(tx)),
+            None => (tx, tx),
+        });
+    }
+
+    /// Records a record that could not be parsed.
+    pub fn record_malformed(&mut self) {
+        self.malformed += 1;
+    }
+
+    /// Returns the number of transactions, excluding malformed records.
+    pub fn transaction_count(&self) -> u64 {
+        self.deposits + self.withdrawals + self.disputes + self.resolves + self.chargebacks
+    }
+
+    /// Returns the amount at the given percentile, using the nearest-rank
+    /// method.
+    ///
+    /// Returns `None` if there are no deposits or withdrawals.
+    ///
+    /// # Parameters
+    ///
+    /// * `percentile`: Percentile between `0` and `100`.
+    pub fn amount_percentile(&mut self, percentile: u8) -> Option<Decimal> {
+        if self.amounts.is_empty() {
+            return None;
+        }
+
+        self.amounts.sort_unstable();
+        let len = self.amounts.len();
+        let rank = (usize::from(percentile.min(100)) * len).div_ceil(100);
+        self.amounts.get(rank.saturating_sub(1)).copied()
+    }
+
+    /// Returns a report of these statistics.
+    pub fn report(mut self) -> TxStatsReport {
+        let amount_percentiles = [0, 50, 90, 99, 100]
+            .into_iter()
+            .filter_map(|percentile| {
+                self.amount_percentile(percentile)
+                    .map(|amount| (percentile, amount))
+            })
+            .collect();
+
+        TxStatsReport {
+            stats: self,
+            amount_percentiles,
+        }
+    }
+}
+
+/// Displayable report of [`TxStats`].
+#[derive(Debug)]
+pub struct TxStatsReport {
+    /// Statistics to report.
+    stats: TxStats,
+    /// Amounts at each reported percentile.
+    amount_percentiles: Vec<(u8, Decimal)>,
+}
+
+impl fmt::Display for TxStatsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            stats,
+            amount_percentiles,
+        } = self;
+
+        writeln!(f, "transactions: {}", stats.transaction_count())?;
+        writeln!(f, "  deposit: {}", stats.deposits)?;
+        writeln!(f, "  withdrawal: {}", stats.withdrawals)?;
+        writeln!(f, "  dispute: {}", stats.disputes)?;
+        writeln!(f, "  resolve: {}", stats.resolves)?;
+        writeln!(f, "  chargeback: {}", stats.chargebacks)?;
+        writeln!(f, "malformed records: {}", stats.malformed)?;
+        writeln!(f, "distinct clients: {}", stats.clients.len())?;
+        match stats.tx_range {
+            Some((tx_min, tx_max)) => writeln!(f, "tx id range: {tx_min} to {tx_max}")?,
+            None => writeln!(f, "tx id range: none")?,
+        }
+        writeln!(f, "amounts (deposits and withdrawals):")?;
+        if amount_percentiles.is_empty() {
+            writeln!(f, "  none")?;
+        }
+        amount_percentiles
+            .iter()
+            .try_for_each(|(percentile, amount)| match percentile {
+                0 => writeln!(f, "  min: {amount}"),
+                100 => writeln!(f, "  max: {amount}"),
+                _ => writeln!(f, "  p{percentile}: {amount}"),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use transact::model::{ClientId, Deposit, Dispute, Transaction, TxId, Withdrawal};
+
+    use super::TxStats;
+
+    #[test]
+    fn record_counts_types_clients_and_tx_range() {
+        let mut tx_stats = TxStats::new();
+
+        [
+            Transaction::from(Deposit::new(ClientId::new(1), TxId::new(5), dec!(1.0))),
+            Transaction::from(Withdrawal::new(ClientId::new(2), TxId::new(3), dec!(2.0))),
+            Transaction::from(Dispute::new(ClientId::new(1), TxId::new(5))),
+        ]
+        .iter()
+        .for_each(|transaction| tx_stats.record(transaction));
+        tx_stats.record_malformed();
+
+        assert_eq!(3, tx_stats.transaction_count());
+        assert_eq!(1, tx_stats.deposits);
+        assert_eq!(1, tx_stats.withdrawals);
+        assert_eq!(1, tx_stats.disputes);
+        assert_eq!(1, tx_stats.malformed);
+        assert_eq!(2, tx_stats.clients.len());
+        assert_eq!(Some((TxId::new(3), TxId::new(5))), tx_stats.tx_range);
+    }
+
+    #[test]
+    fn amount_percentile_uses_nearest_rank() {
+        let mut tx_stats = TxStats::new();
+        (1..=10).for_each(|n| {
+            tx_stats.record(&Transaction::from(Deposit::new(
+                ClientId::new(1),
+                TxId::new(n),
+                rust_decimal::Decimal::from(n),
+            )))
+        });
+
+        assert_eq!(Some(dec!(1)), tx_stats.amount_percentile(0));
+        assert_eq!(Some(dec!(5)), tx_stats.amount_percentile(50));
+        assert_eq!(Some(dec!(9)), tx_stats.amount_percentile(90));
+        assert_eq!(Some(dec!(10)), tx_stats.amount_percentile(99));
+        assert_eq!(Some(dec!(10)), tx_stats.amount_percentile(100));
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+use futures::{StreamExt, TryStreamExt};
+use transact::{csv::TransactCsv, model::Transaction, Error};
+
+use crate::cli::{runtime, CliError, ValidateArgs, ValidationIssue};
+
+/// Checks that a transactions file can be processed, without processing it.
+#[derive(Debug)]
+pub struct ValidateCmd;
+
+impl ValidateCmd {
+    /// Maximum number of decimal places in an amount.
+    pub const AMOUNT_SCALE_MAX: u32 = 4;
+
+    /// Columns expected in the header row.
+    const HEADER_COLUMNS: [&'static str; 4] = ["type", "client", "tx", "amount"];
+
+    /// Runs the `validate` command.
+    ///
+    /// Records are parsed and checked, but no transactions are stored or
+    /// applied. Each issue is printed to stdout, and an error is returned if
+    /// there are any.
+    pub fn run(validate_args: ValidateArgs) -> Result<(), CliError> {
+        let ValidateArgs { transactions } = validate_args;
+
+        if let Some(issue) = Self::header_issue(&transactions)? {
+            println!("{issue}");
+            return Err(CliError::ValidateFailed { issue_count: 1 });
+        }
+
+        let (record_count, issue_count) = runtime()?.block_on(async {
+            TransactCsv::stream(&transactions)
+                .await?
+                .into_stream()
+                .map(Result::<_, Error>::Ok)
+                .try_fold(
+                    (0u64, 0usize),
+                    |(record_count, issue_count), transaction| async move {
+                        let record = record_count + 1;
+                        let issues = match transaction {
+                            Ok(transaction) => Self::transaction_issues(record, &transaction),
+                            Err(error) if error.is_record_error() => {
+                                let message = match std::error::Error::source(&error) {
+                                    Some(source) => format!("{error} {source}"),
+                                    None => error.to_string(),
+                                };
+                                vec![ValidationIssue::RecordMalformed { record, message }]
+                            }
+                            Err(error) => return Err(error),
+                        };
+                        issues.iter().for_each(|issue| println!("{issue}"));
+
+                        Ok((record, issue_count + issues.len()))
+                    },
+                )
+                .await
+        })?;
+
+        if issue_count == 0 {
+            println!("{record_count} records are valid.");
+            Ok(())
+        } else {
+            Err(CliError::ValidateFailed { issue_count })
+        }
+    }
+
+    /// Returns an issue if the header row does not name the expected columns.
+    fn header_issue(path: &Path) -> Result<Option<ValidationIssue>, Error> {
+        let file = File::open(path).map_err(|error| Error::TransactCsvOpen {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        let mut header = String::new();
+        BufReader::new(file)
+            .read_line(&mut header)
+            .map_err(|error| Error::TransactCsvOpen {
+                path: path.to_path_buf(),
+                error,
+            })?;
+        let header = header.trim_end_matches(['\r', '\n']);
+
+        let columns_valid = header
+            .split(',')
+            .map(str::trim)
+            .eq(Self::HEADER_COLUMNS.iter().copied());
+        if columns_valid {
+            Ok(None)
+        } else {
+            Ok(Some(ValidationIssue::HeaderInvalid {
+                header: header.to_string(),
+            }))
+        }
+    }
+
+    /// Returns the issues with a parsed transaction.
+    ///
+    /// # Parameters
+    ///
+    /// * `record`: Number of the record, starting from `1` after the header.
+    /// * `transaction`: The parsed transaction.
+    pub fn transaction_issues(record: u64, transaction: &Transaction) -> Vec<ValidationIssue> {
+        let Some(amount) = transaction.amount() else {
+            return Vec::new();
+        };
+        let tx = transaction.tx();
+
+        let mut issues = Vec::new();
+        if amount.is_sign_negative() {
+            issues.push(ValidationIssue::AmountNegative { record, tx, amount });
+        }
+        if amount.scale() > Self::AMOUNT_SCALE_MAX {
+            issues.push(ValidationIssue::AmountPrecisionExceeded { record, tx, amount });
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use transact::model::{ClientId, Deposit, Dispute, Transaction, TxId, Withdrawal};
+
+    use super::ValidateCmd;
+    use crate::cli::ValidationIssue;
+
+    #[test]
+    fn transaction_issues_is_empty_for_valid_transactions() {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+
+        assert!(ValidateCmd::transaction_issues(
+            1,
+            &Transaction::from(Deposit::new(client, tx, dec!(1.2340)))
+        )
+        .is_empty());
+        assert!(
+            ValidateCmd::transaction_issues(2, &Transaction::from(Dispute::new(client, tx)))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn transaction_issues_reports_negative_and_imprecise_amounts() {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+
+        let issues = ValidateCmd::transaction_issues(
+            3,
+            &Transaction::from(Withdrawal::new(client, tx, dec!(-1.23456))),
+        );
+
+        assert_eq!(
+            vec![
+                ValidationIssue::AmountNegative {
+                    record: 3,
+                    tx,
+                    amount: dec!(-1.23456),
+                },
+                ValidationIssue::AmountPrecisionExceeded {
+                    record: 3,
+                    tx,
+                    amount: dec!(-1.23456),
+                },
+            ],
+            issues
+        );
+    }
+}
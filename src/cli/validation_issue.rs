@@ -0,0 +1,64 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+use transact::model::TxId;
+
+use crate::cli::ValidateCmd;
+
+/// Problem found when validating a transactions file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// Header row does not name the expected columns.
+    HeaderInvalid {
+        /// Header row in the transactions file.
+        header: String,
+    },
+    /// Record could not be parsed as a transaction.
+    RecordMalformed {
+        /// Number of the record, starting from `1` after the header.
+        record: u64,
+        /// Description of the problem.
+        message: String,
+    },
+    /// Amount is negative.
+    AmountNegative {
+        /// Number of the record, starting from `1` after the header.
+        record: u64,
+        /// Transaction ID.
+        tx: TxId,
+        /// Amount of the transaction.
+        amount: Decimal,
+    },
+    /// Amount has more decimal places than supported.
+    AmountPrecisionExceeded {
+        /// Number of the record, starting from `1` after the header.
+        record: u64,
+        /// Transaction ID.
+        tx: TxId,
+        /// Amount of the transaction.
+        amount: Decimal,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HeaderInvalid { header } => write!(
+                f,
+                "header: expected `type, client, tx, amount`, found `{header}`."
+            ),
+            Self::RecordMalformed { record, message } => {
+                write!(f, "record {record}: {message}")
+            }
+            Self::AmountNegative { record, tx, amount } => write!(
+                f,
+                "record {record}: transaction {tx} amount {amount} is negative."
+            ),
+            Self::AmountPrecisionExceeded { record, tx, amount } => write!(
+                f,
+                "record {record}: transaction {tx} amount {amount} has more than {} decimal places.",
+                ValidateCmd::AMOUNT_SCALE_MAX
+            ),
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use rust_decimal::Decimal;
+use transact::{
+    model::{Account, Accounts},
+    ProcessOptions,
+};
+
+use crate::cli::{AccountDiscrepancy, CliError, VerifyArgs};
+
+/// Verifies an accounts file against the transactions it was derived from.
+#[derive(Debug)]
+pub struct VerifyCmd;
+
+impl VerifyCmd {
+    /// Runs the `verify` command.
+    ///
+    /// Each discrepancy is printed to stdout, and an error is returned if
+    /// there are any.
+    pub fn run(verify_args: VerifyArgs) -> Result<(), CliError> {
+        let VerifyArgs {
+            accounts,
+            transactions,
+        } = verify_args;
+
+        let accounts_actual = transact::blocking::read_accounts(&accounts)?;
+        let (accounts_expected, _rejections, _record_errors) =
+            transact::blocking::process_to_accounts(&transactions, ProcessOptions::new())?
+                .into_inner();
+
+        let discrepancies = Self::discrepancies(&accounts_expected, &accounts_actual);
+        discrepancies
+            .iter()
+            .for_each(|discrepancy| println!("{discrepancy}"));
+
+        if discrepancies.is_empty() {
+            Ok(())
+        } else {
+            Err(CliError::VerifyFailed {
+                discrepancy_count: discrepancies.len(),
+            })
+        }
+    }
+
+    /// Returns the discrepancies between the derived and actual accounts,
+    /// ordered by client.
+    pub fn discrepancies(
+        accounts_expected: &Accounts,
+        accounts_actual: &Accounts,
+    ) -> Vec<AccountDiscrepancy> {
+        let mut clients = accounts_expected
+            .keys()
+            .chain(accounts_actual.keys())
+            .copied()
+            .collect::<Vec<_>>();
+        clients.sort_unstable();
+        clients.dedup();
+
+        clients
+            .into_iter()
+            .flat_map(
+                |client| match (accounts_expected.get(client), accounts_actual.get(client)) {
+                    (Some(account_expected), Some(account_actual)) => {
+                        Self::account_discrepancies(account_expected, account_actual)
+                    }
+                    (Some(_), None) => vec![AccountDiscrepancy::AccountMissing { client }],
+                    (None, Some(account_actual)) => {
+                        let mut discrepancies = Self::invariant_discrepancies(account_actual);
+                        if account_actual.locked() {
+                            discrepancies
+                                .push(AccountDiscrepancy::LockedWithoutChargeback { client });
+                        }
+                        discrepancies.push(AccountDiscrepancy::AccountUnexpected { client });
+                        discrepancies
+                    }
+                    (None, None) => Vec::new(),
+                },
+            )
+            .collect()
+    }
+
+    /// Returns discrepancies between an account in the accounts file and the
+    /// account derived from the transactions.
+    fn account_discrepancies(
+        account_expected: &Account,
+        account_actual: &Account,
+    ) -> Vec<AccountDiscrepancy> {
+        let client = account_actual.client();
+        let mut discrepancies = Self::invariant_discrepancies(account_actual);
+
+        [
+            (
+                "available",
+                account_expected.available(),
+                account_actual.available(),
+            ),
+            ("held", account_expected.held(), account_actual.held()),
+            ("total", account_expected.total(), account_actual.total()),
+        ]
+        .into_iter()
+        .filter(|(_field, expected, actual)| expected != actual)
+        .for_each(|(field, expected, actual)| {
+            discrepancies.push(AccountDiscrepancy::AmountMismatch {
+                client,
+                field,
+                expected,
+                actual,
+            })
+        });
+
+        // Accounts are only locked by a chargeback.
+        match (account_expected.locked(), account_actual.locked()) {
+            (false, true) => {
+                discrepancies.push(AccountDiscrepancy::LockedWithoutChargeback { client })
+            }
+            (true, false) => discrepancies.push(AccountDiscrepancy::ChargebackNotLocked { client }),
+            (false, false) | (true, true) => {}
+        }
+
+        discrepancies
+    }
+
+    /// Returns discrepancies where an account's values are inconsistent with
+    /// each other.
+    fn invariant_discrepancies(account: &Account) -> Vec<AccountDiscrepancy> {
+        let client = account.client();
+        let mut discrepancies = Vec::new();
+
+        if account.available().checked_add(account.held()) != Some(account.total()) {
+            discrepancies.push(AccountDiscrepancy::TotalMismatch {
+                client,
+                available: account.available(),
+                held: account.held(),
+                total: account.total(),
+            });
+        }
+        if account.held() < Decimal::ZERO {
+            discrepancies.push(AccountDiscrepancy::HeldNegative {
+                client,
+                held: account.held(),
+            });
+        }
+
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rust_decimal_macros::dec;
+    use transact::model::{Account, Accounts, ClientId};
+
+    use super::VerifyCmd;
+    use crate::cli::AccountDiscrepancy;
+
+    #[test]
+    fn discrepancies_is_empty_when_accounts_match() {
+        let client = ClientId::new(1);
+        let account = || {
+            Account::try_new(client, dec!(1.0), dec!(2.0), false, HashSet::new())
+                .expect("Test data invalid.")
+        };
+        let mut accounts_expected = Accounts::new();
+        accounts_expected.insert(client, account());
+        let mut accounts_actual = Accounts::new();
+        accounts_actual.insert(client, account());
+
+        let discrepancies = VerifyCmd::discrepancies(&accounts_expected, &accounts_actual);
+
+        assert!(discrepancies.is_empty());
+    }
+
+    #[test]
+    fn discrepancies_reports_mismatched_and_missing_accounts() {
+        let client_one = ClientId::new(1);
+        let client_two = ClientId::new(2);
+        let mut accounts_expected = Accounts::new();
+        accounts_expected.insert(
+            client_one,
+            Account::try_new(client_one, dec!(1.0), dec!(0.0), true, HashSet::new())
+                .expect("Test data invalid."),
+        );
+        accounts_expected.insert(client_two, Account::empty(client_two));
+        let mut accounts_actual = Accounts::new();
+        accounts_actual.insert(
+            client_one,
+            Account::try_new(client_one, dec!(2.0), dec!(0.0), false, HashSet::new())
+                .expect("Test data invalid."),
+        );
+
+        let discrepancies = VerifyCmd::discrepancies(&accounts_expected, &accounts_actual);
+
+        assert_eq!(
+            vec![
+                AccountDiscrepancy::AmountMismatch {
+                    client: client_one,
+                    field: "available",
+                    expected: dec!(1.0),
+                    actual: dec!(2.0),
+                },
+                AccountDiscrepancy::AmountMismatch {
+                    client: client_one,
+                    field: "total",
+                    expected: dec!(1.0),
+                    actual: dec!(2.0),
+                },
+                AccountDiscrepancy::ChargebackNotLocked { client: client_one },
+                AccountDiscrepancy::AccountMissing { client: client_two },
+            ],
+            discrepancies
+        );
+    }
+}
@@ -1,7 +1,23 @@
+//! Types to read and write transactions CSV content.
+//!
+//! These apply the same parsing and serialization rules used by
+//! [`process_reader`], so tools that pre-process or generate transactions
+//! files stay consistent with this crate.
+//!
+//! [`process_reader`]: crate::process_reader
+
 pub use self::{
+    account_record::AccountRecord,
+    into_transaction::IntoTransaction,
     transact_csv::TransactCsv,
-    tx_record::{TxRecord, TxType},
+    tx_record::{TxRecord, TxType, TxTypeParseError},
 };
 
+mod account_record;
+mod into_transaction;
+#[cfg(feature = "simd-csv")]
+mod simd_records;
 mod transact_csv;
+mod tx_byte_fields;
+mod tx_byte_records;
 mod tx_record;
@@ -0,0 +1,110 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Account, AccountSummary, ClientId};
+
+/// Represents an account record.
+///
+/// This is the accounts output schema, decoupled from [`Account`] so that
+/// the account may hold more state without changing the output. Disputed
+/// and charged back transactions, and rejections, are not included.
+///
+/// # Examples
+///
+/// ```rust
+/// use transact::{
+///     csv::AccountRecord,
+///     model::{Account, ClientId},
+/// };
+///
+/// let account = Account::empty(ClientId::new(1));
+/// let account_record = AccountRecord::from(&account);
+///
+/// assert_eq!(ClientId::new(1), account_record.client());
+/// assert!(!account_record.locked());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountRecord {
+    /// Client ID.
+    client: ClientId,
+    /// Available funds.
+    #[serde(with = "rust_decimal::serde::float")]
+    available: Decimal,
+    /// Held funds.
+    #[serde(with = "rust_decimal::serde::float")]
+    held: Decimal,
+    /// Total funds.
+    #[serde(with = "rust_decimal::serde::float")]
+    total: Decimal,
+    /// Whether the account is locked.
+    locked: bool,
+}
+
+impl AccountRecord {
+    /// Returns the client ID.
+    pub fn client(&self) -> ClientId {
+        self.client
+    }
+
+    /// Returns the available funds.
+    pub fn available(&self) -> Decimal {
+        self.available
+    }
+
+    /// Returns the held funds.
+    pub fn held(&self) -> Decimal {
+        self.held
+    }
+
+    /// Returns the total funds.
+    pub fn total(&self) -> Decimal {
+        self.total
+    }
+
+    /// Returns whether the account is locked.
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl From<&Account> for AccountRecord {
+    fn from(account: &Account) -> Self {
+        Self {
+            client: account.client(),
+            available: account.available(),
+            held: account.held(),
+            total: account.total(),
+            locked: account.locked(),
+        }
+    }
+}
+
+impl From<AccountSummary> for AccountRecord {
+    fn from(account_summary: AccountSummary) -> Self {
+        Self {
+            client: account_summary.client(),
+            available: account_summary.available(),
+            held: account_summary.held(),
+            total: account_summary.total(),
+            locked: account_summary.locked(),
+        }
+    }
+}
+
+/// Converts a record read from accounts output.
+///
+/// The total is kept as read, even if it is not the sum of the available and
+/// held funds, so such discrepancies may be found.
+impl From<AccountRecord> for Account {
+    fn from(account_record: AccountRecord) -> Self {
+        let AccountRecord {
+            client,
+            available,
+            held,
+            total,
+            locked,
+        } = account_record;
+
+        Account::from_balances(client, available, held, total, locked)
+    }
+}
@@ -0,0 +1,26 @@
+use crate::{csv::TxRecord, model::Transaction, Error};
+
+/// Converts a record read from transactions CSV content into a
+/// [`Transaction`].
+///
+/// Implement this for a record type with a different shape to [`TxRecord`],
+/// such as extra fields or different transaction type spellings, to read it
+/// with [`process_records`] or [`TransactCsv::records_stream_reader`].
+///
+/// Records that cannot be converted should return an error whose
+/// [`Error::is_record_error`] is `true`, such as
+/// [`Error::DepositAmountNotProvided`], so they are handled according to the
+/// error policy instead of stopping processing.
+///
+/// [`process_records`]: crate::process_records
+/// [`TransactCsv::records_stream_reader`]: crate::csv::TransactCsv::records_stream_reader
+pub trait IntoTransaction {
+    /// Returns the transaction this record represents.
+    fn into_transaction(self) -> Result<Transaction, Error>;
+}
+
+impl IntoTransaction for TxRecord {
+    fn into_transaction(self) -> Result<Transaction, Error> {
+        Transaction::try_from(self)
+    }
+}
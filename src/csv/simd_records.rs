@@ -0,0 +1,211 @@
+use std::io::{self, BufRead, Cursor, Read};
+
+use crate::{
+    csv::{tx_byte_fields::TxByteFields, TransactCsv, TxRecord},
+    model::Transaction,
+    Error,
+};
+
+/// Header of transactions CSV content whose rows are parsed by hand.
+const HEADER: &[u8] = b"type,client,tx,amount\n";
+
+/// Transactions parsed from transactions CSV content, splitting fields with
+/// SIMD accelerated byte search.
+pub(crate) enum SimdRecords<R> {
+    /// Rows are parsed by hand, as the header is `type,client,tx,amount`.
+    Fast {
+        /// Reader of the rows after the header.
+        reader: R,
+        /// Buffer for the current row.
+        row: Vec<u8>,
+    },
+    /// Rows are parsed with `csv`, as the header has another layout.
+    Flexible(csv::DeserializeRecordsIntoIter<io::Chain<Cursor<Vec<u8>>, R>, TxRecord>),
+    /// Reading the header failed.
+    HeaderError(Option<Error>),
+}
+
+impl<R> SimdRecords<R>
+where
+    R: BufRead,
+{
+    /// Returns the transactions in the given transactions CSV content.
+    pub(crate) fn new(mut reader: R) -> Self {
+        let mut header = Vec::new();
+        if let Err(error) = reader.read_until(b'\n', &mut header) {
+            return Self::HeaderError(Some(Error::TransactionDeserializeBlocking(error.into())));
+        }
+
+        let header_is_simple = header
+            .split(|byte| *byte == b',')
+            .map(<[u8]>::trim_ascii)
+            .eq(HEADER.split(|byte| *byte == b',').map(<[u8]>::trim_ascii));
+        if header_is_simple {
+            Self::Fast {
+                reader,
+                row: Vec::new(),
+            }
+        } else {
+            let records = TransactCsv::blocking_reader(Cursor::new(header).chain(reader))
+                .into_deserialize::<TxRecord>();
+            Self::Flexible(records)
+        }
+    }
+
+    /// Returns the transaction in a row, or `None` if the row is not simple.
+    ///
+    /// Simple rows have three or four unquoted fields, with a known
+    /// transaction type and fields that parse.
+    fn parse_row_fast(row: &[u8]) -> Option<Result<Transaction, Error>> {
+        if memchr::memchr(b'"', row).is_some() {
+            return None;
+        }
+
+        let mut fields = [&b""[..]; 4];
+        let mut field_count = 0;
+        let mut start = 0;
+        for end in memchr::memchr_iter(b',', row).chain(std::iter::once(row.len())) {
+            *fields.get_mut(field_count)? = row[start..end].trim_ascii();
+            field_count += 1;
+            start = end + 1;
+        }
+        if field_count < 3 {
+            return None;
+        }
+
+        let [r#type, client, tx, amount] = fields;
+        TxByteFields {
+            r#type,
+            client,
+            tx,
+            amount,
+        }
+        .parse()
+    }
+
+    /// Returns the transaction in a row that is not simple, parsed the same
+    /// way as [`TransactCsv::stream_blocking`].
+    fn parse_row_flexible(row: &[u8]) -> Option<Result<Transaction, Error>> {
+        TransactCsv::blocking_reader(HEADER.chain(row))
+            .into_deserialize::<TxRecord>()
+            .next()
+            .map(|tx_record| {
+                tx_record
+                    .map_err(Error::TransactionDeserializeBlocking)
+                    .and_then(Transaction::try_from)
+            })
+    }
+}
+
+impl<R> Iterator for SimdRecords<R>
+where
+    R: BufRead,
+{
+    type Item = Result<Transaction, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Fast { reader, row } => loop {
+                row.clear();
+                match reader.read_until(b'\n', row) {
+                    Ok(0) => return None,
+                    Ok(_) => {}
+                    Err(error) => {
+                        return Some(Err(Error::TransactionDeserializeBlocking(error.into())));
+                    }
+                }
+
+                let row = row.trim_ascii_end();
+                if row.is_empty() {
+                    // Empty rows are skipped, the same as by `csv`.
+                    continue;
+                }
+                if let Some(transaction) =
+                    Self::parse_row_fast(row).or_else(|| Self::parse_row_flexible(row))
+                {
+                    return Some(transaction);
+                }
+            },
+            Self::Flexible(records) => records.next().map(|tx_record| {
+                tx_record
+                    .map_err(Error::TransactionDeserializeBlocking)
+                    .and_then(Transaction::try_from)
+            }),
+            Self::HeaderError(error) => error.take().map(Err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use rust_decimal_macros::dec;
+
+    use super::SimdRecords;
+    use crate::{
+        csv::TxRecord,
+        model::{ClientId, Deposit, Dispute, Transaction, TxId},
+        Error,
+    };
+
+    #[test]
+    fn parses_simple_rows_and_falls_back_for_irregular_rows() {
+        let client = ClientId::new(1);
+        let content = "type, client, tx, amount\r\n\
+             deposit, 1, 1, 2.0\r\n\
+             \r\n\
+             \"deposit\", 1, 2, 1.5\r\n\
+             dispute, 1, 1\r\n\
+             deposit, 1, 3,\r\n\
+             refund, 1, 4, 1.0\r\n";
+
+        let records = SimdRecords::new(Cursor::new(content)).collect::<Vec<_>>();
+
+        assert_eq!(5, records.len());
+        assert_eq!(
+            vec![
+                TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(2.0)
+                ))),
+                TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(2),
+                    dec!(1.5)
+                ))),
+                TxRecord::from(Transaction::from(Dispute::new(client, TxId::new(1)))),
+            ],
+            records
+                .iter()
+                .filter_map(|record| record.as_ref().ok().cloned().map(TxRecord::from))
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            &records[3..],
+            [
+                Err(Error::DepositAmountNotProvided { tx, .. }),
+                Err(Error::TransactionDeserializeBlocking(_)),
+            ] if *tx == TxId::new(3)
+        ));
+    }
+
+    #[test]
+    fn parses_other_header_layouts_flexibly() -> Result<(), Box<dyn std::error::Error>> {
+        let content = "client, type, amount, tx\n\
+             1, deposit, 2.0, 1\n";
+
+        let records = SimdRecords::new(Cursor::new(content)).collect::<Result<Vec<_>, _>>()?;
+
+        assert_eq!(
+            vec![TxRecord::from(Transaction::from(Deposit::new(
+                ClientId::new(1),
+                TxId::new(1),
+                dec!(2.0)
+            )))],
+            records.into_iter().map(TxRecord::from).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
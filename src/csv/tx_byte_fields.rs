@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::{
+    model::{Chargeback, ClientId, Deposit, Dispute, Resolve, Transaction, TxId, Withdrawal},
+    Error,
+};
+
+/// Trimmed fields of a transactions CSV row, borrowed from the bytes read.
+///
+/// Parsing these directly avoids deserializing a [`TxRecord`] through
+/// `serde`.
+///
+/// [`TxRecord`]: crate::csv::TxRecord
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TxByteFields<'b> {
+    /// Type of transaction.
+    pub(crate) r#type: &'b [u8],
+    /// Client ID.
+    pub(crate) client: &'b [u8],
+    /// Transaction ID.
+    pub(crate) tx: &'b [u8],
+    /// Amount of the transaction, empty if not applicable.
+    pub(crate) amount: &'b [u8],
+}
+
+impl TxByteFields<'_> {
+    /// Returns the transaction in these fields, or `None` if a field does not
+    /// parse.
+    ///
+    /// Callers fall back to deserializing a [`TxRecord`] when `None` is
+    /// returned, so that errors are reported the same way.
+    ///
+    /// [`TxRecord`]: crate::csv::TxRecord
+    pub(crate) fn parse(self) -> Option<Result<Transaction, Error>> {
+        let Self {
+            r#type,
+            client,
+            tx,
+            amount,
+        } = self;
+        let client = ClientId::new(Self::parse_field(client)?);
+        let tx = TxId::new(Self::parse_field(tx)?);
+        let amount = if amount.is_empty() {
+            None
+        } else {
+            Some(Self::parse_field::<Decimal>(amount)?)
+        };
+
+        let transaction = match r#type {
+            b"deposit" => match amount {
+                Some(amount) => Ok(Transaction::from(Deposit::new(client, tx, amount))),
+                None => Err(Error::DepositAmountNotProvided { client, tx }),
+            },
+            b"withdrawal" => match amount {
+                Some(amount) => Ok(Transaction::from(Withdrawal::new(client, tx, amount))),
+                None => Err(Error::WithdrawalAmountNotProvided { client, tx }),
+            },
+            b"dispute" => Ok(Transaction::from(Dispute::new(client, tx))),
+            b"resolve" => Ok(Transaction::from(Resolve::new(client, tx))),
+            b"chargeback" => Ok(Transaction::from(Chargeback::new(client, tx))),
+            _ => return None,
+        };
+
+        Some(transaction)
+    }
+
+    /// Returns the value of a field, or `None` if it does not parse.
+    fn parse_field<T>(field: &[u8]) -> Option<T>
+    where
+        T: FromStr,
+    {
+        std::str::from_utf8(field).ok()?.parse().ok()
+    }
+}
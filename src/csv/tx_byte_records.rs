@@ -0,0 +1,144 @@
+use csv_async::{AsyncReader, ByteRecord};
+
+use crate::{
+    csv::{tx_byte_fields::TxByteFields, TxRecord},
+    io::AsyncRead,
+    model::Transaction,
+    Error,
+};
+
+/// Reads transactions from transactions CSV content as [`ByteRecord`]s.
+///
+/// One record buffer is reused for every row, and fields are parsed from the
+/// bytes read. Rows whose fields do not parse are deserialized as a
+/// [`TxRecord`] instead, so errors are reported the same as when reading
+/// [`TxRecord`]s.
+#[derive(Debug)]
+pub(crate) struct TxByteRecords<R> {
+    /// Reader of transactions CSV content.
+    reader: AsyncReader<R>,
+    /// Header row, read before the first record.
+    headers: Option<ByteRecord>,
+    /// Indices of the `type`, `client`, `tx`, and `amount` columns, if the
+    /// header has all of them.
+    columns: Option<[usize; 4]>,
+    /// Buffer for the current row.
+    record: ByteRecord,
+}
+
+impl<R> TxByteRecords<R>
+where
+    R: AsyncRead + Unpin + Send,
+{
+    /// Returns a new `TxByteRecords` that reads from the given reader.
+    pub(crate) fn new(reader: AsyncReader<R>) -> Self {
+        Self {
+            reader,
+            headers: None,
+            columns: None,
+            record: ByteRecord::new(),
+        }
+    }
+
+    /// Returns the next transaction, or `None` at the end of the content.
+    pub(crate) async fn next_transaction(&mut self) -> Option<Result<Transaction, Error>> {
+        if self.headers.is_none() {
+            let headers = match self.reader.byte_headers().await {
+                Ok(headers) => headers.clone(),
+                Err(error) => {
+                    self.headers = Some(ByteRecord::new());
+                    return Some(Err(Error::TransactionDeserialize(error)));
+                }
+            };
+            self.columns = Self::columns(&headers);
+            self.headers = Some(headers);
+        }
+
+        match self.reader.read_byte_record(&mut self.record).await {
+            Ok(true) => Some(self.parse_record()),
+            Ok(false) => None,
+            Err(error) => Some(Err(Error::TransactionDeserialize(error))),
+        }
+    }
+
+    /// Returns the transaction in the current record.
+    fn parse_record(&self) -> Result<Transaction, Error> {
+        let tx_byte_fields = self.columns.map(|[r#type, client, tx, amount]| {
+            let field = |index| self.record.get(index).unwrap_or_default();
+            TxByteFields {
+                r#type: field(r#type),
+                client: field(client),
+                tx: field(tx),
+                amount: field(amount),
+            }
+        });
+
+        tx_byte_fields
+            .and_then(TxByteFields::parse)
+            .unwrap_or_else(|| {
+                self.record
+                    .deserialize::<TxRecord>(self.headers.as_ref())
+                    .map_err(Error::TransactionDeserialize)
+                    .and_then(Transaction::try_from)
+            })
+    }
+
+    /// Returns the indices of the `type`, `client`, `tx`, and `amount`
+    /// columns, if the header has all of them.
+    fn columns(headers: &ByteRecord) -> Option<[usize; 4]> {
+        let column = |name: &[u8]| headers.iter().position(|header| header == name);
+        Some([
+            column(b"type")?,
+            column(b"client")?,
+            column(b"tx")?,
+            column(b"amount")?,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{StreamExt, TryStreamExt};
+    use rust_decimal_macros::dec;
+
+    use crate::{
+        csv::{TransactCsv, TxRecord},
+        model::{ClientId, Deposit, Dispute, Transaction, TxId},
+        Error,
+    };
+
+    #[tokio::test]
+    async fn reads_columns_in_header_order_and_falls_back_for_invalid_rows()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let content = "client, tx, type, amount\n\
+             1, 1, deposit, 2.0\n\
+             1, 1, dispute\n\
+             1, 2, refund, 1.0\n";
+
+        let records = TransactCsv::stream_reader(content.as_bytes())
+            .into_stream()
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(
+            vec![
+                TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(2.0)
+                ))),
+                TxRecord::from(Transaction::from(Dispute::new(client, TxId::new(1)))),
+            ],
+            records
+                .iter()
+                .filter_map(|record| record.as_ref().ok().cloned().map(TxRecord::from))
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            records.last(),
+            Some(Err(Error::TransactionDeserialize(error))) if !error.is_io_error()
+        ));
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
@@ -8,17 +10,83 @@ use crate::{
 
 /// Represents a transaction record.
 ///
-/// In contrast to the types in `crate::model`, this is specifically
-#[derive(Debug, Deserialize, Serialize)]
+/// In contrast to the types in `crate::model`, this is specifically the shape
+/// of a row in a transactions CSV file, where `amount` is only present for
+/// deposits and withdrawals.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct TxRecord {
+    /// Type of transaction.
     r#type: TxType,
+    /// Client ID.
     client: ClientId,
+    /// Transaction ID.
     tx: TxId,
+    /// Amount of the transaction, if applicable.
     amount: Option<Decimal>,
 }
 
+impl TxRecord {
+    /// Returns a deposit record.
+    pub fn deposit(client: ClientId, tx: TxId, amount: Decimal) -> Self {
+        Self::new(TxType::Deposit, client, tx, Some(amount))
+    }
+
+    /// Returns a withdrawal record.
+    pub fn withdrawal(client: ClientId, tx: TxId, amount: Decimal) -> Self {
+        Self::new(TxType::Withdrawal, client, tx, Some(amount))
+    }
+
+    /// Returns a dispute record.
+    pub fn dispute(client: ClientId, tx: TxId) -> Self {
+        Self::new(TxType::Dispute, client, tx, None)
+    }
+
+    /// Returns a resolve record.
+    pub fn resolve(client: ClientId, tx: TxId) -> Self {
+        Self::new(TxType::Resolve, client, tx, None)
+    }
+
+    /// Returns a chargeback record.
+    pub fn chargeback(client: ClientId, tx: TxId) -> Self {
+        Self::new(TxType::Chargeback, client, tx, None)
+    }
+
+    /// Returns a record with the given fields.
+    ///
+    /// The fields are not checked, so a deposit or withdrawal without an
+    /// amount is only rejected when converted into a [`Transaction`].
+    pub fn new(r#type: TxType, client: ClientId, tx: TxId, amount: Option<Decimal>) -> Self {
+        Self {
+            r#type,
+            client,
+            tx,
+            amount,
+        }
+    }
+
+    /// Returns the type of transaction.
+    pub fn r#type(&self) -> TxType {
+        self.r#type
+    }
+
+    /// Returns the client ID.
+    pub fn client(&self) -> ClientId {
+        self.client
+    }
+
+    /// Returns the transaction ID.
+    pub fn tx(&self) -> TxId {
+        self.tx
+    }
+
+    /// Returns the amount of the transaction, if applicable.
+    pub fn amount(&self) -> Option<Decimal> {
+        self.amount
+    }
+}
+
 /// Types of transactions.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TxType {
     /// Credit to the client's asset account.
@@ -34,6 +102,88 @@ pub enum TxType {
     Chargeback,
 }
 
+impl TxType {
+    /// Returns the name of the type, as written in transactions CSV content,
+    /// e.g. `deposit`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Deposit => "deposit",
+            Self::Withdrawal => "withdrawal",
+            Self::Dispute => "dispute",
+            Self::Resolve => "resolve",
+            Self::Chargeback => "chargeback",
+        }
+    }
+}
+
+impl fmt::Display for TxType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parses the name of the type, as written in transactions CSV content.
+///
+/// # Examples
+///
+/// ```rust
+/// use transact::csv::TxType;
+///
+/// assert_eq!(TxType::Deposit, "deposit".parse::<TxType>()?);
+/// assert!("Deposit".parse::<TxType>().is_err());
+/// # Ok::<(), transact::csv::TxTypeParseError>(())
+/// ```
+impl FromStr for TxType {
+    type Err = TxTypeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "deposit" => Ok(Self::Deposit),
+            "withdrawal" => Ok(Self::Withdrawal),
+            "dispute" => Ok(Self::Dispute),
+            "resolve" => Ok(Self::Resolve),
+            "chargeback" => Ok(Self::Chargeback),
+            _ => Err(TxTypeParseError {
+                value: s.to_string(),
+            }),
+        }
+    }
+}
+
+impl TryFrom<&str> for TxType {
+    type Error = TxTypeParseError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Error when a string is not the name of a [`TxType`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxTypeParseError {
+    /// String that was parsed.
+    value: String,
+}
+
+impl TxTypeParseError {
+    /// Returns the string that was parsed.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+impl fmt::Display for TxTypeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unknown transaction type `{}`, expected one of `deposit`, `withdrawal`, `dispute`, `resolve`, or `chargeback`.",
+            self.value
+        )
+    }
+}
+
+impl std::error::Error for TxTypeParseError {}
+
 impl TryFrom<TxRecord> for Transaction {
     type Error = Error;
 
@@ -128,3 +278,51 @@ impl From<Chargeback> for TxRecord {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::{TxRecord, TxType};
+    use crate::model::{ClientId, Transaction, TxId};
+
+    #[test]
+    fn tx_type_parses_names_it_displays() {
+        [
+            TxType::Deposit,
+            TxType::Withdrawal,
+            TxType::Dispute,
+            TxType::Resolve,
+            TxType::Chargeback,
+        ]
+        .into_iter()
+        .for_each(|tx_type| {
+            assert_eq!(Ok(tx_type), tx_type.to_string().parse::<TxType>());
+            assert_eq!(Ok(tx_type), TxType::try_from(tx_type.as_str()));
+        });
+
+        let error = "refund"
+            .parse::<TxType>()
+            .expect_err("Expected parse to fail.");
+        assert_eq!("refund", error.value());
+    }
+
+    #[test]
+    fn constructors_convert_into_transactions() -> Result<(), Box<dyn std::error::Error>> {
+        let deposit =
+            Transaction::try_from(TxRecord::deposit(ClientId::new(1), TxId::new(2), dec!(1.5)))?;
+        let chargeback =
+            Transaction::try_from(TxRecord::chargeback(ClientId::new(1), TxId::new(2)))?;
+
+        assert_eq!("deposit client 1 tx 2 amount 1.5", deposit.to_string());
+        assert_eq!("chargeback client 1 tx 2", chargeback.to_string());
+        assert!(Transaction::try_from(TxRecord::new(
+            TxType::Withdrawal,
+            ClientId::new(1),
+            TxId::new(3),
+            None
+        ))
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+/// Offsets of transactions applied from each source partition.
+///
+/// Network sources that deliver transactions at least once, such as message
+/// queues or retried HTTP requests, may deliver a transaction more than once.
+/// Each delivery is identified by its source partition and offset, and a
+/// delivery whose offset was already applied is a duplicate.
+///
+/// Offsets within a partition must be applied in increasing order, as only
+/// the next offset to apply is kept for each partition.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DedupIndex {
+    /// Next offset to apply, by partition.
+    next_offsets: HashMap<String, u64>,
+}
+
+impl DedupIndex {
+    /// Returns an empty `DedupIndex`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the offset was already applied for the partition.
+    pub fn contains(&self, partition: &str, offset: u64) -> bool {
+        self.next_offset(partition)
+            .is_some_and(|next_offset| offset < next_offset)
+    }
+
+    /// Records that the offset was applied for the partition.
+    pub fn insert(&mut self, partition: &str, offset: u64) {
+        let next_offset = offset.saturating_add(1);
+        match self.next_offsets.get_mut(partition) {
+            Some(next_offset_existing) => {
+                *next_offset_existing = (*next_offset_existing).max(next_offset);
+            }
+            None => {
+                self.next_offsets.insert(partition.to_string(), next_offset);
+            }
+        }
+    }
+
+    /// Returns the next offset to apply for the partition, if any offset was
+    /// applied.
+    pub fn next_offset(&self, partition: &str) -> Option<u64> {
+        self.next_offsets.get(partition).copied()
+    }
+
+    /// Returns the partitions and their next offsets to apply.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.next_offsets
+            .iter()
+            .map(|(partition, next_offset)| (partition.as_str(), *next_offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DedupIndex;
+
+    #[test]
+    fn contains_offsets_up_to_the_highest_inserted() {
+        let mut dedup_index = DedupIndex::new();
+
+        dedup_index.insert("a", 4);
+        dedup_index.insert("a", 2);
+
+        assert!(dedup_index.contains("a", 0));
+        assert!(dedup_index.contains("a", 4));
+        assert!(!dedup_index.contains("a", 5));
+        assert!(!dedup_index.contains("b", 0));
+        assert_eq!(Some(5), dedup_index.next_offset("a"));
+    }
+}
@@ -1,10 +1,17 @@
 use std::{
     ffi::OsString,
     fmt,
+    net::SocketAddr,
     path::{Path, PathBuf},
+    time::Duration,
 };
 
-use crate::model::{ClientId, TxId};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{
+    model::{ClientId, TxId},
+    TxError,
+};
 
 /// Errors relating to running the application / corrupt data.
 #[derive(Debug)]
@@ -33,6 +40,50 @@ pub enum Error {
     },
     /// Error writing transaction to a block file.
     BlockTxWrite(csv_async::Error),
+    /// Error creating directory to store sorted run files.
+    SortDirCreate(std::io::Error),
+    /// Error creating a sorted run file.
+    SortRunCreate(std::io::Error),
+    /// Error writing transaction to a sorted run file.
+    SortRunWrite(csv_async::Error),
+    /// Error flushing output stream for a sorted run file.
+    SortRunFlush(std::io::Error),
+    /// Error creating directory to store ledger files.
+    LedgerDirCreate(std::io::Error),
+    /// Error creating a ledger file.
+    LedgerFileCreate(std::io::Error),
+    /// Error writing transaction to a ledger file.
+    LedgerFileWrite(csv_async::Error),
+    /// Error flushing output stream for a ledger file.
+    LedgerFileFlush(std::io::Error),
+    /// Error creating directory to store account page files.
+    AccountPageDirCreate(std::io::Error),
+    /// Error reading an account page file.
+    AccountPageRead {
+        /// Path to the page file or directory.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error writing an account page file.
+    AccountPageWrite {
+        /// Path to the page file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Timed out looking up a transaction in the block store.
+    BlockStoreLookupTimeout {
+        /// Transaction ID being looked up.
+        tx: TxId,
+        /// Maximum duration to wait for the lookup.
+        timeout: Duration,
+    },
+    /// Timed out processing all transactions.
+    RunTimeout {
+        /// Maximum duration to wait for processing.
+        timeout: Duration,
+    },
     /// Error opening transactions CSV.
     TransactCsvOpen {
         /// Path to the CSV.
@@ -42,6 +93,29 @@ pub enum Error {
     },
     /// Error deserializing a transaction.
     TransactionDeserialize(csv_async::Error),
+    /// Error deserializing a transaction on a blocking thread.
+    #[cfg(feature = "fs")]
+    TransactionDeserializeBlocking(csv::Error),
+    /// Error reading change events.
+    #[cfg(feature = "cdc")]
+    ChangeEventRead(std::io::Error),
+    /// Error deserializing a change event.
+    #[cfg(feature = "cdc")]
+    ChangeEventDeserialize {
+        /// Line number of the change event, starting from 1.
+        line: u64,
+        /// Underlying JSON error.
+        error: serde_json::Error,
+    },
+    /// Error opening accounts CSV.
+    AccountsCsvOpen {
+        /// Path to the CSV.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error deserializing an account.
+    AccountDeserialize(csv_async::Error),
     /// Deposit amount not provided in transaction record.
     DepositAmountNotProvided {
         /// Client ID.
@@ -56,10 +130,294 @@ pub enum Error {
         /// Transaction ID.
         tx: TxId,
     },
+    /// Deposit or withdrawal ID is not greater than the previous one, when
+    /// transaction IDs are required to be increasing.
+    TxIdNotIncreasing {
+        /// Client ID.
+        client: ClientId,
+        /// Transaction ID.
+        tx: TxId,
+        /// ID of the previous deposit or withdrawal.
+        tx_previous: TxId,
+    },
+    /// Transaction rejected when using [`ErrorPolicy::Strict`].
+    ///
+    /// [`ErrorPolicy::Strict`]: crate::ErrorPolicy::Strict
+    TransactionRejected(TxError),
+    /// Error creating the runtime for the blocking API.
+    RuntimeCreate(std::io::Error),
+    /// Error binding the server to its listen address.
+    ServerBind {
+        /// Address to listen on.
+        addr: SocketAddr,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error while serving requests.
+    ServerServe(std::io::Error),
+    /// Timed out finishing in-progress requests after shutdown.
+    ServerDrainTimeout {
+        /// Maximum duration to wait for requests to finish.
+        timeout: Duration,
+    },
+    /// Error connecting to Redis.
+    #[cfg(feature = "redis")]
+    RedisConnect(redis::RedisError),
+    /// Error creating the Redis Stream consumer group.
+    #[cfg(feature = "redis")]
+    RedisGroupCreate(redis::RedisError),
+    /// Error reading entries from a Redis Stream.
+    #[cfg(feature = "redis")]
+    RedisRead(redis::RedisError),
+    /// Error acknowledging an applied Redis Stream entry.
+    #[cfg(feature = "redis")]
+    RedisAck(redis::RedisError),
+    /// Error connecting to NATS.
+    #[cfg(feature = "nats")]
+    NatsConnect(async_nats::ConnectError),
+    /// Error getting the NATS JetStream stream.
+    #[cfg(feature = "nats")]
+    NatsStreamGet(async_nats::jetstream::context::GetStreamError),
+    /// Error creating the NATS JetStream consumer.
+    #[cfg(feature = "nats")]
+    NatsConsumerCreate(async_nats::jetstream::stream::ConsumerError),
+    /// Error subscribing to messages of the NATS JetStream consumer.
+    #[cfg(feature = "nats")]
+    NatsSubscribe(async_nats::jetstream::consumer::StreamError),
+    /// Error reading a message from the NATS JetStream consumer.
+    #[cfg(feature = "nats")]
+    NatsRead(async_nats::jetstream::consumer::pull::MessagesError),
+    /// Error reading the stream sequence of a NATS JetStream message.
+    #[cfg(feature = "nats")]
+    NatsMessageInfo(async_nats::Error),
+    /// Error acknowledging an applied NATS JetStream message.
+    #[cfg(feature = "nats")]
+    NatsAck(async_nats::Error),
+    /// Error connecting to the AMQP broker.
+    #[cfg(feature = "amqp")]
+    AmqpConnect(lapin::Error),
+    /// Error starting to consume from the AMQP queue.
+    #[cfg(feature = "amqp")]
+    AmqpConsume(lapin::Error),
+    /// Error receiving a delivery from the AMQP queue.
+    #[cfg(feature = "amqp")]
+    AmqpRead(lapin::Error),
+    /// Error acknowledging or rejecting an AMQP delivery.
+    #[cfg(feature = "amqp")]
+    AmqpAck(lapin::Error),
+    /// Error closing the AMQP channel or connection.
+    #[cfg(feature = "amqp")]
+    AmqpClose(lapin::Error),
     /// Error writing output.
     OutputWrite(csv_async::Error),
     /// Error flushing output stream.
     OutputFlush(std::io::Error),
+    /// Error writing a processor snapshot.
+    SnapshotWrite {
+        /// Path to the snapshot file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error reading a processor snapshot.
+    SnapshotRead {
+        /// Path to the snapshot file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Record in a processor snapshot is not valid.
+    SnapshotRecordInvalid {
+        /// Path to the snapshot file.
+        path: PathBuf,
+        /// Line number of the record, starting from 1.
+        line: usize,
+        /// Underlying error, with kind [`std::io::ErrorKind::InvalidData`].
+        error: std::io::Error,
+    },
+    /// Error creating the checkpoint directory.
+    CheckpointDirCreate {
+        /// Path to the checkpoint directory.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error reading the checkpoint directory to find checkpoints.
+    CheckpointDirRead {
+        /// Path to the checkpoint directory.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+    /// Error removing an old checkpoint.
+    CheckpointPrune {
+        /// Path to the checkpoint file.
+        path: PathBuf,
+        /// Underlying IO error.
+        error: std::io::Error,
+    },
+}
+
+impl Error {
+    /// Returns a stable code for this error, e.g. `"TRANSACT_CSV_OPEN"`.
+    ///
+    /// Codes do not change when the error message wording changes, so they may
+    /// be used to identify the error in reports and responses.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BlockStoreDirCreate(_) => "BLOCK_STORE_DIR_CREATE",
+            Self::BlockStoreDirRead(_) => "BLOCK_STORE_DIR_READ",
+            Self::BlockFileCreate(_) => "BLOCK_FILE_CREATE",
+            Self::BlockFileFlush(_) => "BLOCK_FILE_FLUSH",
+            Self::BlockFileRename { .. } => "BLOCK_FILE_RENAME",
+            Self::BlockFileNameInvalid { .. } => "BLOCK_FILE_NAME_INVALID",
+            Self::BlockTxWrite(_) => "BLOCK_TX_WRITE",
+            Self::SortDirCreate(_) => "SORT_DIR_CREATE",
+            Self::SortRunCreate(_) => "SORT_RUN_CREATE",
+            Self::SortRunWrite(_) => "SORT_RUN_WRITE",
+            Self::SortRunFlush(_) => "SORT_RUN_FLUSH",
+            Self::LedgerDirCreate(_) => "LEDGER_DIR_CREATE",
+            Self::LedgerFileCreate(_) => "LEDGER_FILE_CREATE",
+            Self::LedgerFileWrite(_) => "LEDGER_FILE_WRITE",
+            Self::LedgerFileFlush(_) => "LEDGER_FILE_FLUSH",
+            Self::AccountPageDirCreate(_) => "ACCOUNT_PAGE_DIR_CREATE",
+            Self::AccountPageRead { .. } => "ACCOUNT_PAGE_READ",
+            Self::AccountPageWrite { .. } => "ACCOUNT_PAGE_WRITE",
+            Self::BlockStoreLookupTimeout { .. } => "BLOCK_STORE_LOOKUP_TIMEOUT",
+            Self::RunTimeout { .. } => "RUN_TIMEOUT",
+            Self::TransactCsvOpen { .. } => "TRANSACT_CSV_OPEN",
+            Self::TransactionDeserialize(_) => "TRANSACTION_DESERIALIZE",
+            #[cfg(feature = "fs")]
+            Self::TransactionDeserializeBlocking(_) => "TRANSACTION_DESERIALIZE",
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventRead(_) => "CHANGE_EVENT_READ",
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventDeserialize { .. } => "CHANGE_EVENT_DESERIALIZE",
+            Self::AccountsCsvOpen { .. } => "ACCOUNTS_CSV_OPEN",
+            Self::AccountDeserialize(_) => "ACCOUNT_DESERIALIZE",
+            Self::DepositAmountNotProvided { .. } => "DEPOSIT_AMOUNT_NOT_PROVIDED",
+            Self::WithdrawalAmountNotProvided { .. } => "WITHDRAWAL_AMOUNT_NOT_PROVIDED",
+            Self::TxIdNotIncreasing { .. } => "TX_ID_NOT_INCREASING",
+            Self::TransactionRejected(_) => "TRANSACTION_REJECTED",
+            Self::RuntimeCreate(_) => "RUNTIME_CREATE",
+            Self::ServerBind { .. } => "SERVER_BIND",
+            Self::ServerServe(_) => "SERVER_SERVE",
+            Self::ServerDrainTimeout { .. } => "SERVER_DRAIN_TIMEOUT",
+            #[cfg(feature = "redis")]
+            Self::RedisConnect(_) => "REDIS_CONNECT",
+            #[cfg(feature = "redis")]
+            Self::RedisGroupCreate(_) => "REDIS_GROUP_CREATE",
+            #[cfg(feature = "redis")]
+            Self::RedisRead(_) => "REDIS_READ",
+            #[cfg(feature = "redis")]
+            Self::RedisAck(_) => "REDIS_ACK",
+            #[cfg(feature = "nats")]
+            Self::NatsConnect(_) => "NATS_CONNECT",
+            #[cfg(feature = "nats")]
+            Self::NatsStreamGet(_) => "NATS_STREAM_GET",
+            #[cfg(feature = "nats")]
+            Self::NatsConsumerCreate(_) => "NATS_CONSUMER_CREATE",
+            #[cfg(feature = "nats")]
+            Self::NatsSubscribe(_) => "NATS_SUBSCRIBE",
+            #[cfg(feature = "nats")]
+            Self::NatsRead(_) => "NATS_READ",
+            #[cfg(feature = "nats")]
+            Self::NatsMessageInfo(_) => "NATS_MESSAGE_INFO",
+            #[cfg(feature = "nats")]
+            Self::NatsAck(_) => "NATS_ACK",
+            #[cfg(feature = "amqp")]
+            Self::AmqpConnect(_) => "AMQP_CONNECT",
+            #[cfg(feature = "amqp")]
+            Self::AmqpConsume(_) => "AMQP_CONSUME",
+            #[cfg(feature = "amqp")]
+            Self::AmqpRead(_) => "AMQP_READ",
+            #[cfg(feature = "amqp")]
+            Self::AmqpAck(_) => "AMQP_ACK",
+            #[cfg(feature = "amqp")]
+            Self::AmqpClose(_) => "AMQP_CLOSE",
+            Self::OutputWrite(_) => "OUTPUT_WRITE",
+            Self::OutputFlush(_) => "OUTPUT_FLUSH",
+            Self::SnapshotWrite { .. } => "SNAPSHOT_WRITE",
+            Self::SnapshotRead { .. } => "SNAPSHOT_READ",
+            Self::SnapshotRecordInvalid { .. } => "SNAPSHOT_RECORD_INVALID",
+            Self::CheckpointDirCreate { .. } => "CHECKPOINT_DIR_CREATE",
+            Self::CheckpointDirRead { .. } => "CHECKPOINT_DIR_READ",
+            Self::CheckpointPrune { .. } => "CHECKPOINT_PRUNE",
+        }
+    }
+
+    /// Returns whether this error is due to a single malformed record.
+    ///
+    /// Processing may continue with the next record after these errors.
+    pub fn is_record_error(&self) -> bool {
+        match self {
+            Self::TransactionDeserialize(error) => !error.is_io_error(),
+            #[cfg(feature = "fs")]
+            Self::TransactionDeserializeBlocking(error) => !error.is_io_error(),
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventRead(_) => false,
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventDeserialize { .. } => true,
+            Self::DepositAmountNotProvided { .. }
+            | Self::WithdrawalAmountNotProvided { .. }
+            | Self::TxIdNotIncreasing { .. } => true,
+            Self::BlockStoreDirCreate(_)
+            | Self::BlockStoreDirRead(_)
+            | Self::BlockFileCreate(_)
+            | Self::BlockFileFlush(_)
+            | Self::BlockFileRename { .. }
+            | Self::BlockFileNameInvalid { .. }
+            | Self::BlockTxWrite(_)
+            | Self::SortDirCreate(_)
+            | Self::SortRunCreate(_)
+            | Self::SortRunWrite(_)
+            | Self::SortRunFlush(_)
+            | Self::LedgerDirCreate(_)
+            | Self::LedgerFileCreate(_)
+            | Self::LedgerFileWrite(_)
+            | Self::LedgerFileFlush(_)
+            | Self::AccountPageDirCreate(_)
+            | Self::AccountPageRead { .. }
+            | Self::AccountPageWrite { .. }
+            | Self::BlockStoreLookupTimeout { .. }
+            | Self::RunTimeout { .. }
+            | Self::TransactCsvOpen { .. }
+            | Self::AccountsCsvOpen { .. }
+            | Self::AccountDeserialize(_)
+            | Self::TransactionRejected(_)
+            | Self::RuntimeCreate(_)
+            | Self::ServerBind { .. }
+            | Self::ServerServe(_)
+            | Self::ServerDrainTimeout { .. }
+            | Self::OutputWrite(_)
+            | Self::OutputFlush(_)
+            | Self::SnapshotWrite { .. }
+            | Self::SnapshotRead { .. }
+            | Self::SnapshotRecordInvalid { .. }
+            | Self::CheckpointDirCreate { .. }
+            | Self::CheckpointDirRead { .. }
+            | Self::CheckpointPrune { .. } => false,
+            #[cfg(feature = "redis")]
+            Self::RedisConnect(_)
+            | Self::RedisGroupCreate(_)
+            | Self::RedisRead(_)
+            | Self::RedisAck(_) => false,
+            #[cfg(feature = "nats")]
+            Self::NatsConnect(_)
+            | Self::NatsStreamGet(_)
+            | Self::NatsConsumerCreate(_)
+            | Self::NatsSubscribe(_)
+            | Self::NatsRead(_)
+            | Self::NatsMessageInfo(_)
+            | Self::NatsAck(_) => false,
+            #[cfg(feature = "amqp")]
+            Self::AmqpConnect(_)
+            | Self::AmqpConsume(_)
+            | Self::AmqpRead(_)
+            | Self::AmqpAck(_)
+            | Self::AmqpClose(_) => false,
+        }
+    }
 }
 
 impl fmt::Display for Error {
@@ -84,10 +442,56 @@ impl fmt::Display for Error {
                 Path::new(file_name).display()
             ),
             Self::BlockTxWrite(_) => write!(f, "Error writing transaction to a block file."),
+            Self::SortDirCreate(_) => {
+                write!(f, "Error creating directory to store sorted run files.")
+            }
+            Self::SortRunCreate(_) => write!(f, "Error creating sorted run file."),
+            Self::SortRunWrite(_) => write!(f, "Error writing transaction to a sorted run file."),
+            Self::SortRunFlush(_) => {
+                write!(f, "Error flushing output stream for a sorted run file.")
+            }
+            Self::LedgerDirCreate(_) => {
+                write!(f, "Error creating directory to store ledger files.")
+            }
+            Self::LedgerFileCreate(_) => write!(f, "Error creating ledger file."),
+            Self::LedgerFileWrite(_) => write!(f, "Error writing transaction to a ledger file."),
+            Self::LedgerFileFlush(_) => {
+                write!(f, "Error flushing output stream for a ledger file.")
+            }
+            Self::AccountPageDirCreate(_) => {
+                write!(f, "Error creating directory to store account page files.")
+            }
+            Self::AccountPageRead { path, .. } => {
+                write!(f, "Error reading account page file: {}", path.display())
+            }
+            Self::AccountPageWrite { path, .. } => {
+                write!(f, "Error writing account page file: {}", path.display())
+            }
+            Self::BlockStoreLookupTimeout { tx, timeout } => write!(
+                f,
+                "Timed out after {timeout:?} looking up transaction {tx} in the block store."
+            ),
+            Self::RunTimeout { timeout } => {
+                write!(f, "Timed out after {timeout:?} processing transactions.")
+            }
             Self::TransactCsvOpen { path, .. } => {
                 write!(f, "Error opening transactions CSV: {}", path.display())
             }
             Self::TransactionDeserialize(_) => write!(f, "Error deserializing a transaction."),
+            #[cfg(feature = "fs")]
+            Self::TransactionDeserializeBlocking(_) => {
+                write!(f, "Error deserializing a transaction.")
+            }
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventRead(_) => write!(f, "Error reading change events."),
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventDeserialize { line, .. } => {
+                write!(f, "Error deserializing the change event on line {line}.")
+            }
+            Self::AccountsCsvOpen { path, .. } => {
+                write!(f, "Error opening accounts CSV: {}", path.display())
+            }
+            Self::AccountDeserialize(_) => write!(f, "Error deserializing an account."),
             Self::DepositAmountNotProvided { client, tx } => write!(
                 f,
                 "Deposit amount not provided in transaction record for client {client}, transaction {tx}."
@@ -96,8 +500,80 @@ impl fmt::Display for Error {
                 f,
                 "Withdrawal amount not provided in transaction record for client {client}, transaction {tx}."
             ),
+            Self::TxIdNotIncreasing {
+                client,
+                tx,
+                tx_previous,
+            } => write!(
+                f,
+                "Transaction {tx} for client {client} is not after the previous transaction {tx_previous}."
+            ),
+            Self::TransactionRejected(_) => write!(f, "Transaction rejected."),
+            Self::RuntimeCreate(_) => write!(f, "Error creating the runtime for the blocking API."),
+            Self::ServerBind { addr, .. } => write!(f, "Error binding server to {addr}."),
+            Self::ServerServe(_) => write!(f, "Error while serving requests."),
+            Self::ServerDrainTimeout { timeout } => write!(
+                f,
+                "Timed out after {timeout:?} finishing requests on shutdown."
+            ),
+            #[cfg(feature = "redis")]
+            Self::RedisConnect(_) => write!(f, "Error connecting to Redis."),
+            #[cfg(feature = "redis")]
+            Self::RedisGroupCreate(_) => {
+                write!(f, "Error creating the Redis Stream consumer group.")
+            }
+            #[cfg(feature = "redis")]
+            Self::RedisRead(_) => write!(f, "Error reading entries from the Redis Stream."),
+            #[cfg(feature = "redis")]
+            Self::RedisAck(_) => write!(f, "Error acknowledging a Redis Stream entry."),
+            #[cfg(feature = "nats")]
+            Self::NatsConnect(_) => write!(f, "Error connecting to NATS."),
+            #[cfg(feature = "nats")]
+            Self::NatsStreamGet(_) => write!(f, "Error getting the NATS JetStream stream."),
+            #[cfg(feature = "nats")]
+            Self::NatsConsumerCreate(_) => write!(f, "Error creating the NATS JetStream consumer."),
+            #[cfg(feature = "nats")]
+            Self::NatsSubscribe(_) => write!(f, "Error subscribing to NATS JetStream messages."),
+            #[cfg(feature = "nats")]
+            Self::NatsRead(_) => write!(f, "Error reading a NATS JetStream message."),
+            #[cfg(feature = "nats")]
+            Self::NatsMessageInfo(_) => {
+                write!(f, "Error reading the stream sequence of a NATS JetStream message.")
+            }
+            #[cfg(feature = "nats")]
+            Self::NatsAck(_) => write!(f, "Error acknowledging a NATS JetStream message."),
+            #[cfg(feature = "amqp")]
+            Self::AmqpConnect(_) => write!(f, "Error connecting to the AMQP broker."),
+            #[cfg(feature = "amqp")]
+            Self::AmqpConsume(_) => write!(f, "Error starting to consume from the AMQP queue."),
+            #[cfg(feature = "amqp")]
+            Self::AmqpRead(_) => write!(f, "Error receiving an AMQP delivery."),
+            #[cfg(feature = "amqp")]
+            Self::AmqpAck(_) => write!(f, "Error acknowledging or rejecting an AMQP delivery."),
+            #[cfg(feature = "amqp")]
+            Self::AmqpClose(_) => write!(f, "Error closing the AMQP channel or connection."),
             Self::OutputWrite(_) => write!(f, "Error writing output"),
             Self::OutputFlush(_) => write!(f, "Error flushing output stream"),
+            Self::SnapshotWrite { path, .. } => {
+                write!(f, "Error writing processor snapshot: {}", path.display())
+            }
+            Self::SnapshotRead { path, .. } => {
+                write!(f, "Error reading processor snapshot: {}", path.display())
+            }
+            Self::SnapshotRecordInvalid { path, line, .. } => write!(
+                f,
+                "Invalid record on line {line} of processor snapshot: {}",
+                path.display()
+            ),
+            Self::CheckpointDirCreate { path, .. } => {
+                write!(f, "Error creating checkpoint directory: {}", path.display())
+            }
+            Self::CheckpointDirRead { path, .. } => {
+                write!(f, "Error reading checkpoint directory: {}", path.display())
+            }
+            Self::CheckpointPrune { path, .. } => {
+                write!(f, "Error removing old checkpoint: {}", path.display())
+            }
         }
     }
 }
@@ -112,12 +588,95 @@ impl std::error::Error for Error {
             Self::BlockFileRename { error, .. } => Some(error),
             Self::BlockFileNameInvalid { .. } => None,
             Self::BlockTxWrite(error) => Some(error),
+            Self::SortDirCreate(error) => Some(error),
+            Self::SortRunCreate(error) => Some(error),
+            Self::SortRunWrite(error) => Some(error),
+            Self::SortRunFlush(error) => Some(error),
+            Self::LedgerDirCreate(error) => Some(error),
+            Self::LedgerFileCreate(error) => Some(error),
+            Self::LedgerFileWrite(error) => Some(error),
+            Self::LedgerFileFlush(error) => Some(error),
+            Self::AccountPageDirCreate(error) => Some(error),
+            Self::AccountPageRead { error, .. } => Some(error),
+            Self::AccountPageWrite { error, .. } => Some(error),
+            Self::BlockStoreLookupTimeout { .. } => None,
+            Self::RunTimeout { .. } => None,
             Self::TransactCsvOpen { error, .. } => Some(error),
             Self::TransactionDeserialize(error) => Some(error),
+            #[cfg(feature = "fs")]
+            Self::TransactionDeserializeBlocking(error) => Some(error),
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventRead(error) => Some(error),
+            #[cfg(feature = "cdc")]
+            Self::ChangeEventDeserialize { error, .. } => Some(error),
+            Self::AccountsCsvOpen { error, .. } => Some(error),
+            Self::AccountDeserialize(error) => Some(error),
             Self::DepositAmountNotProvided { .. } => None,
             Self::WithdrawalAmountNotProvided { .. } => None,
+            Self::TxIdNotIncreasing { .. } => None,
+            Self::TransactionRejected(tx_error) => Some(tx_error),
+            Self::RuntimeCreate(error) => Some(error),
+            Self::ServerBind { error, .. } => Some(error),
+            Self::ServerServe(error) => Some(error),
+            Self::ServerDrainTimeout { .. } => None,
+            #[cfg(feature = "redis")]
+            Self::RedisConnect(error)
+            | Self::RedisGroupCreate(error)
+            | Self::RedisRead(error)
+            | Self::RedisAck(error) => Some(error),
+            #[cfg(feature = "nats")]
+            Self::NatsConnect(error) => Some(error),
+            #[cfg(feature = "nats")]
+            Self::NatsStreamGet(error) => Some(error),
+            #[cfg(feature = "nats")]
+            Self::NatsConsumerCreate(error) => Some(error),
+            #[cfg(feature = "nats")]
+            Self::NatsSubscribe(error) => Some(error),
+            #[cfg(feature = "nats")]
+            Self::NatsRead(error) => Some(error),
+            #[cfg(feature = "nats")]
+            Self::NatsMessageInfo(error) | Self::NatsAck(error) => Some(error.as_ref()),
+            #[cfg(feature = "amqp")]
+            Self::AmqpConnect(error)
+            | Self::AmqpConsume(error)
+            | Self::AmqpRead(error)
+            | Self::AmqpAck(error)
+            | Self::AmqpClose(error) => Some(error),
             Self::OutputWrite(error) => Some(error),
             Self::OutputFlush(error) => Some(error),
+            Self::SnapshotWrite { error, .. } => Some(error),
+            Self::SnapshotRead { error, .. } => Some(error),
+            Self::SnapshotRecordInvalid { error, .. } => Some(error),
+            Self::CheckpointDirCreate { error, .. } => Some(error),
+            Self::CheckpointDirRead { error, .. } => Some(error),
+            Self::CheckpointPrune { error, .. } => Some(error),
+        }
+    }
+}
+
+/// Serializes as the error `code` and `message`.
+///
+/// [`Error::TransactionRejected`] also includes the rejected transaction's
+/// error as `rejection`.
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::TransactionRejected(tx_error) => {
+                let mut state = serializer.serialize_struct("Error", 3)?;
+                state.serialize_field("code", self.code())?;
+                state.serialize_field("message", &self.to_string())?;
+                state.serialize_field("rejection", tx_error)?;
+                state.end()
+            }
+            _ => {
+                let mut state = serializer.serialize_struct("Error", 2)?;
+                state.serialize_field("code", self.code())?;
+                state.serialize_field("message", &self.to_string())?;
+                state.end()
+            }
         }
     }
 }
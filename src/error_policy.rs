@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// How to handle rejected transactions and malformed records.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ErrorPolicy {
+    /// Abort processing on the first rejected transaction or malformed record.
+    Strict,
+    /// Skip rejected transactions and malformed records, and continue
+    /// processing.
+    #[default]
+    Lenient,
+    /// Continue processing, and return all rejected transactions and malformed
+    /// records to the caller.
+    Collect,
+}
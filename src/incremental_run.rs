@@ -0,0 +1,106 @@
+use std::{cell::Cell, path::Path};
+
+use futures::TryStreamExt;
+
+use crate::{
+    processor_snapshot::SnapshotState, DedupIndex, Error, ProcessOptions, ProcessSummary,
+    TxBlockStore, TxStore,
+};
+
+/// Result of processing a transactions file on top of a snapshot, which may be
+/// saved as the next snapshot.
+#[derive(Debug)]
+pub(crate) struct IncrementalRun {
+    /// Result of processing the transactions file.
+    summary: ProcessSummary,
+    /// Deposits from the snapshot and the transactions file.
+    tx_block_store: TxBlockStore,
+    /// Number of transactions applied across all runs.
+    position: u64,
+    /// Offsets applied from each source partition, carried over from the
+    /// snapshot.
+    dedup_index: DedupIndex,
+}
+
+impl IncrementalRun {
+    /// Processes the transactions file at `path` on top of the state in
+    /// `snapshot`, or on top of no accounts if `snapshot` does not exist.
+    pub(crate) async fn process(
+        snapshot: &Path,
+        path: &Path,
+        options: ProcessOptions,
+    ) -> Result<Self, Error> {
+        let snapshot_exists =
+            tokio::fs::try_exists(snapshot)
+                .await
+                .map_err(|error| Error::SnapshotRead {
+                    path: snapshot.to_path_buf(),
+                    error,
+                })?;
+        let SnapshotState {
+            position,
+            dedup_index,
+            accounts,
+            deposits,
+        } = if snapshot_exists {
+            SnapshotState::read(snapshot).await?
+        } else {
+            SnapshotState::default()
+        };
+
+        let tx_block_store = match options.block_store_dir() {
+            Some(block_store_dir) => TxBlockStore::try_new_in(block_store_dir)?,
+            None => TxBlockStore::try_new()?,
+        };
+        for deposits_block in deposits.chunks(options.block_size().get()) {
+            tx_block_store.persist_block(deposits_block).await?;
+        }
+
+        let transactions_read = &Cell::new(0u64);
+        let transactions = crate::read_transactions(path, &options)
+            .await?
+            .inspect_ok(|_transaction| transactions_read.set(transactions_read.get() + 1));
+        let transactions = crate::sort_by_tx_if_enabled(transactions, &options).await?;
+        let summary =
+            crate::process_transactions_from(transactions, &tx_block_store, accounts, &options)
+                .await?;
+
+        Ok(Self {
+            summary,
+            tx_block_store,
+            position: position + transactions_read.get(),
+            dedup_index,
+        })
+    }
+
+    /// Returns the result of processing the transactions file.
+    pub(crate) fn summary(&self) -> &ProcessSummary {
+        &self.summary
+    }
+
+    /// Writes the accounts and deposits to `snapshot`, unless processing was
+    /// cancelled.
+    ///
+    /// A cancelled run did not apply the whole transactions file, so the
+    /// previous snapshot is kept for the file to be processed again.
+    pub(crate) async fn save(&self, snapshot: &Path) -> Result<(), Error> {
+        if self.summary.is_cancelled() {
+            return Ok(());
+        }
+
+        SnapshotState::write(
+            snapshot,
+            self.position,
+            &self.dedup_index,
+            self.summary.accounts(),
+            self.tx_block_store.deposits().await?,
+        )
+        .await
+    }
+
+    /// Returns the result of processing the transactions file, consuming this
+    /// run.
+    pub(crate) fn into_summary(self) -> ProcessSummary {
+        self.summary
+    }
+}
@@ -0,0 +1,68 @@
+use serde::Serialize;
+
+use crate::{Error, TxError};
+
+/// Result of ingesting transactions into a [`Processor`].
+///
+/// [`Processor`]: crate::Processor
+#[derive(Debug, Default, Serialize)]
+pub struct IngestSummary {
+    /// Number of transactions applied.
+    applied: usize,
+    /// Number of transactions skipped as their offsets were already applied.
+    duplicates: usize,
+    /// Transactions that were rejected, in the order they were processed.
+    rejections: Vec<TxError>,
+    /// Records that could not be parsed, in the order they were read.
+    record_errors: Vec<Error>,
+}
+
+impl IngestSummary {
+    /// Returns the number of transactions applied.
+    pub fn applied(&self) -> usize {
+        self.applied
+    }
+
+    /// Returns the number of transactions skipped as their offsets were
+    /// already applied.
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+
+    /// Returns the transactions that were rejected.
+    pub fn rejections(&self) -> &[TxError] {
+        &self.rejections
+    }
+
+    /// Returns the records that could not be parsed.
+    pub fn record_errors(&self) -> &[Error] {
+        &self.record_errors
+    }
+
+    /// Counts the result of applying a transaction, where `None` is a
+    /// duplicate delivery.
+    #[cfg(any(
+        feature = "server",
+        feature = "redis",
+        feature = "nats",
+        feature = "amqp"
+    ))]
+    pub(crate) fn record_tx_result(&mut self, tx_result: Option<Result<(), TxError>>) {
+        match tx_result {
+            Some(Ok(())) => self.applied += 1,
+            Some(Err(tx_error)) => self.rejections.push(tx_error),
+            None => self.duplicates += 1,
+        }
+    }
+
+    /// Records a malformed record.
+    #[cfg(any(
+        feature = "server",
+        feature = "redis",
+        feature = "nats",
+        feature = "amqp"
+    ))]
+    pub(crate) fn record_error(&mut self, error: Error) {
+        self.record_errors.push(error);
+    }
+}
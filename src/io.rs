@@ -0,0 +1,10 @@
+//! Async IO traits used for input and output.
+//!
+//! These are the [`tokio`] traits when the `tokio` feature is enabled, and the
+//! [`futures::io`] traits otherwise, so the crate may be used with other async
+//! runtimes.
+
+#[cfg(not(feature = "tokio"))]
+pub use futures::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "tokio")]
+pub use tokio::io::{AsyncRead, AsyncWrite};
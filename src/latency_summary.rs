@@ -0,0 +1,253 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::model::Transaction;
+
+/// Number of buckets in a [`LatencyHistogram`], one for each power of two
+/// nanoseconds that a `u64` holds, and one for zero.
+const BUCKET_COUNT: usize = 65;
+
+/// Distributions of transaction processing latency, grouped by transaction
+/// type.
+///
+/// Latency is the time to apply a transaction to its account, including
+/// looking up the disputed transaction in the transaction store for disputes,
+/// resolves, and chargebacks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LatencySummary {
+    /// Latency histogram for each transaction type.
+    by_type: BTreeMap<&'static str, LatencyHistogram>,
+}
+
+/// Distribution of processing latencies, in power of two buckets.
+///
+/// Percentiles are the upper bound of the bucket they fall in, so they are at
+/// most twice the actual latency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// Number of latencies in each bucket.
+    ///
+    /// Bucket `0` holds latencies of zero nanoseconds, and bucket `i` holds
+    /// latencies of at least `2^(i-1)` and less than `2^i` nanoseconds.
+    buckets: [u64; BUCKET_COUNT],
+    /// Number of recorded latencies.
+    count: u64,
+    /// Sum of the recorded latencies.
+    total: Duration,
+    /// Longest recorded latency.
+    max: Duration,
+}
+
+impl LatencySummary {
+    /// Returns an empty `LatencySummary`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the time taken to process a transaction.
+    pub fn record(&mut self, transaction: &Transaction, latency: Duration) {
+        self.by_type
+            .entry(transaction.type_name())
+            .or_default()
+            .record(latency);
+    }
+
+    /// Adds the latencies of `other`, e.g. from another partition.
+    pub fn merge(&mut self, other: &LatencySummary) {
+        other.iter().for_each(|(tx_type, latency_histogram_other)| {
+            self.by_type
+                .entry(tx_type)
+                .or_default()
+                .merge(latency_histogram_other);
+        });
+    }
+
+    /// Returns the latency histogram for a transaction type, e.g. `dispute`.
+    pub fn get(&self, tx_type: &str) -> Option<&LatencyHistogram> {
+        self.by_type.get(tx_type)
+    }
+
+    /// Returns an iterator over each transaction type and its latencies,
+    /// ordered by type.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &LatencyHistogram)> {
+        self.by_type
+            .iter()
+            .map(|(tx_type, latency_histogram)| (*tx_type, latency_histogram))
+    }
+
+    /// Returns whether no latencies were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.by_type.is_empty()
+    }
+
+    /// Returns the instant to measure a latency from, or `None` if the target
+    /// has no clock.
+    pub(crate) fn start() -> Option<Instant> {
+        // `Instant::now` panics on `wasm32-unknown-unknown`.
+        if cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+            None
+        } else {
+            Some(Instant::now())
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// Returns an empty `LatencyHistogram`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a latency.
+    pub fn record(&mut self, latency: Duration) {
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        let bucket = (u64::BITS - nanos.leading_zeros()) as usize;
+        self.buckets[bucket] += 1;
+        self.count += 1;
+        self.total = self.total.saturating_add(latency);
+        self.max = self.max.max(latency);
+    }
+
+    /// Adds the latencies of `other`.
+    pub fn merge(&mut self, other: &LatencyHistogram) {
+        self.buckets
+            .iter_mut()
+            .zip(other.buckets.iter())
+            .for_each(|(count, count_other)| *count += count_other);
+        self.count += other.count;
+        self.total = self.total.saturating_add(other.total);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Returns the number of recorded latencies.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the mean latency, or zero if none were recorded.
+    pub fn mean(&self) -> Duration {
+        let mean_nanos = self
+            .total
+            .as_nanos()
+            .checked_div(u128::from(self.count))
+            .unwrap_or_default();
+        Duration::from_nanos(u64::try_from(mean_nanos).unwrap_or(u64::MAX))
+    }
+
+    /// Returns the longest recorded latency.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Returns the latency that `percentile` percent of recorded latencies
+    /// are at or below, or zero if none were recorded.
+    ///
+    /// This is the upper bound of the bucket the percentile falls in, capped
+    /// at the longest recorded latency. `percentile` is capped at `100`.
+    pub fn percentile(&self, percentile: u8) -> Duration {
+        let percentile = u64::from(percentile.min(100));
+        let rank = (self.count * percentile).div_ceil(100).max(1);
+        let mut count_cumulative = 0;
+        let bucket = self.buckets.iter().position(|count| {
+            count_cumulative += count;
+            count_cumulative >= rank
+        });
+
+        match bucket {
+            Some(0) => Duration::ZERO,
+            Some(bucket) => {
+                let upper_nanos = 1u64.checked_shl(bucket as u32).unwrap_or(u64::MAX);
+                Duration::from_nanos(upper_nanos).min(self.max)
+            }
+            None => Duration::ZERO,
+        }
+    }
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: [0; BUCKET_COUNT],
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+/// Writes one line per transaction type, e.g.
+///
+/// ```text
+/// dispute: 1024 transactions, mean 3.1µs, p50 2.048µs, p99 16.384µs, max 40.2µs
+/// ```
+impl fmt::Display for LatencySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|(tx_type, latency_histogram)| {
+            writeln!(
+                f,
+                "{tx_type}: {count} transactions, mean {mean:?}, p50 {p50:?}, p99 {p99:?}, max {max:?}",
+                count = latency_histogram.count(),
+                mean = latency_histogram.mean(),
+                p50 = latency_histogram.percentile(50),
+                p99 = latency_histogram.percentile(99),
+                max = latency_histogram.max(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_decimal_macros::dec;
+
+    use super::{LatencyHistogram, LatencySummary};
+    use crate::model::{ClientId, Deposit, Dispute, Transaction, TxId};
+
+    #[test]
+    fn percentile_returns_upper_bound_of_bucket() {
+        let mut latency_histogram = LatencyHistogram::new();
+        (0..99).for_each(|_| latency_histogram.record(Duration::from_nanos(1000)));
+        latency_histogram.record(Duration::from_nanos(5000));
+
+        assert_eq!(100, latency_histogram.count());
+        assert_eq!(Duration::from_nanos(1040), latency_histogram.mean());
+        assert_eq!(Duration::from_nanos(1024), latency_histogram.percentile(50));
+        assert_eq!(Duration::from_nanos(1024), latency_histogram.percentile(99));
+        assert_eq!(
+            Duration::from_nanos(5000),
+            latency_histogram.percentile(100)
+        );
+        assert_eq!(Duration::ZERO, LatencyHistogram::new().percentile(50));
+    }
+
+    #[test]
+    fn merge_adds_latencies_by_transaction_type() {
+        let client = ClientId::new(1);
+        let deposit = Transaction::from(Deposit::new(client, TxId::new(1), dec!(1.0)));
+        let dispute = Transaction::from(Dispute::new(client, TxId::new(1)));
+        let mut latency_summary = LatencySummary::new();
+        latency_summary.record(&deposit, Duration::from_nanos(100));
+        let mut latency_summary_other = LatencySummary::new();
+        latency_summary_other.record(&deposit, Duration::from_nanos(300));
+        latency_summary_other.record(&dispute, Duration::from_micros(20));
+
+        latency_summary.merge(&latency_summary_other);
+
+        let deposits = latency_summary.get("deposit").map(LatencyHistogram::count);
+        let disputes = latency_summary.get("dispute").map(LatencyHistogram::max);
+        assert_eq!(Some(2), deposits);
+        assert_eq!(Some(Duration::from_micros(20)), disputes);
+        assert_eq!(
+            vec!["deposit", "dispute"],
+            latency_summary
+                .iter()
+                .map(|(tx_type, _)| tx_type)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    pin::pin,
+};
+
+use csv_async::AsyncSerializer;
+use futures::{
+    stream::{self, BoxStream},
+    Stream, StreamExt, TryStreamExt,
+};
+use tempfile::TempDir;
+use tokio::fs::File;
+
+use crate::{csv::TxRecord, model::Transaction, Error, TransactCsv};
+
+/// Transactions of each ledger in their own file in a temporary directory.
+///
+/// Ledgers are independent, so each ledger's transactions are processed on
+/// their own, with their own accounts and transaction store. Transactions are
+/// kept in input order within each ledger.
+#[derive(Debug)]
+pub(crate) struct LedgerSplit {
+    /// Directory holding the ledger files.
+    temp_dir: TempDir,
+    /// Index of each ledger's file, and its malformed records in input order.
+    ledgers: BTreeMap<String, (usize, Vec<Error>)>,
+}
+
+impl LedgerSplit {
+    /// Reads all rows and writes each ledger's transactions to its file.
+    ///
+    /// Malformed records are kept to be streamed before the ledger's
+    /// transactions, and any other error stops reading. One file per ledger
+    /// is open while reading.
+    ///
+    /// # Parameters
+    ///
+    /// * `rows`: Stream of the ledger and transaction of each row.
+    /// * `dir`: Directory to create the ledger directory in, defaults to the
+    ///   system temporary directory.
+    pub(crate) async fn write<T>(rows: T, dir: Option<&Path>) -> Result<Self, Error>
+    where
+        T: Stream<Item = (String, Result<Transaction, Error>)>,
+    {
+        let temp_dir = match dir {
+            Some(dir) => tempfile::tempdir_in(dir),
+            None => tempfile::tempdir(),
+        }
+        .map_err(Error::LedgerDirCreate)?;
+        let mut ledgers = BTreeMap::<String, (usize, Vec<Error>)>::new();
+        let mut ledger_writers = HashMap::<usize, AsyncSerializer<File>>::new();
+
+        let mut rows = pin!(rows);
+        while let Some((ledger, transaction)) = rows.next().await {
+            let ledger_count = ledgers.len();
+            let (ledger_index, record_errors) = ledgers
+                .entry(ledger)
+                .or_insert_with(|| (ledger_count, Vec::new()));
+            match transaction {
+                Ok(transaction) => {
+                    if !ledger_writers.contains_key(ledger_index) {
+                        let ledger_file = File::create(Self::ledger_path(&temp_dir, *ledger_index))
+                            .await
+                            .map_err(Error::LedgerFileCreate)?;
+                        ledger_writers.insert(*ledger_index, TransactCsv::csv_writer(ledger_file));
+                    }
+                    let ledger_writer = ledger_writers
+                        .get_mut(ledger_index)
+                        .expect("Ledger writer inserted above.");
+                    ledger_writer
+                        .serialize(TxRecord::from(transaction))
+                        .await
+                        .map_err(Error::LedgerFileWrite)?;
+                }
+                Err(error) if error.is_record_error() => record_errors.push(error),
+                Err(error) => return Err(error),
+            }
+        }
+        for ledger_writer in ledger_writers.values_mut() {
+            ledger_writer
+                .flush()
+                .await
+                .map_err(Error::LedgerFileFlush)?;
+        }
+
+        Ok(Self { temp_dir, ledgers })
+    }
+
+    /// Returns each ledger's name and stream of transactions, in order of
+    /// ledger name.
+    ///
+    /// Each stream has the ledger's malformed records, followed by its
+    /// transactions in input order. The ledger files are removed when the
+    /// returned directory is dropped, so it must outlive the streams.
+    pub(crate) async fn into_streams(
+        self,
+    ) -> Result<
+        (
+            TempDir,
+            Vec<(String, BoxStream<'static, Result<Transaction, Error>>)>,
+        ),
+        Error,
+    > {
+        let Self { temp_dir, ledgers } = self;
+
+        let mut ledger_streams = Vec::with_capacity(ledgers.len());
+        for (ledger, (ledger_index, record_errors)) in ledgers {
+            let ledger_path = Self::ledger_path(&temp_dir, ledger_index);
+            // A ledger whose rows were all malformed has no file.
+            let transactions = if ledger_path.is_file() {
+                TransactCsv::stream(&ledger_path)
+                    .await?
+                    .into_stream()
+                    .left_stream()
+            } else {
+                stream::empty().right_stream()
+            };
+            let ledger_stream =
+                stream::iter(record_errors.into_iter().map(Err)).chain(transactions);
+            ledger_streams.push((ledger, ledger_stream.boxed()));
+        }
+
+        Ok((temp_dir, ledger_streams))
+    }
+
+    /// Returns the path of the file for the ledger at `ledger_index`.
+    fn ledger_path(temp_dir: &TempDir, ledger_index: usize) -> PathBuf {
+        temp_dir.path().join(format!("{ledger_index}.csv"))
+    }
+}
@@ -5,31 +5,120 @@
 
 // API
 
+#[cfg(feature = "amqp")]
+pub mod amqp_source;
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+#[cfg(feature = "fs")]
+pub mod blocking;
+#[cfg(feature = "cdc")]
+pub mod cdc;
+pub mod csv;
+pub mod io;
 pub mod model;
-pub use crate::error::Error;
+#[cfg(feature = "nats")]
+pub mod nats_source;
+#[cfg(feature = "metrics")]
+pub mod process_metrics;
+#[cfg(feature = "redis")]
+pub mod redis_source;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+#[cfg(feature = "tracing")]
+pub use crate::notifier::LoggingNotifier;
+#[cfg(any(
+    feature = "server",
+    feature = "redis",
+    feature = "nats",
+    feature = "amqp"
+))]
+pub use crate::rate_limit::RateLimit;
+#[cfg(feature = "fs")]
+pub use crate::{
+    account_page_store::AccountPageStore, checkpointer::Checkpointer, tx_block_store::TxBlockStore,
+};
+pub use crate::{
+    account_store::AccountStore, chargeback_policy::ChargebackPolicy, dedup_index::DedupIndex,
+    error::Error, error_policy::ErrorPolicy, ingest_summary::IngestSummary,
+    latency_summary::LatencyHistogram, latency_summary::LatencySummary, mem_tx_store::MemTxStore,
+    memory_budget::MemoryBudget, notifier::NoopNotifier, notifier::Notifier,
+    process_observer::ProcessObserver, process_options::ProcessOptions,
+    process_summary::ProcessSummary, processor::Processor, rejection_summary::RejectionCount,
+    rejection_summary::RejectionSummary, tx_error::TxError, tx_store::TxStore,
+};
+pub use tokio_util::sync::CancellationToken;
 
 // impl
 
-use std::path::Path;
+use std::{cell::RefCell, iter, pin::pin};
+#[cfg(feature = "fs")]
+use std::{collections::BTreeMap, path::Path};
 
 use futures::{
-    stream::{self, TryChunksError, TryStreamExt},
-    StreamExt,
+    channel::mpsc,
+    future::{self, Either},
+    stream::{self, Stream, TryChunksError, TryStream, TryStreamExt},
+    SinkExt, StreamExt,
 };
+use serde::de::DeserializeOwned;
 
 use crate::{
-    csv::TransactCsv,
-    model::{Account, Accounts},
-    tx_block_store::TxBlockStore,
-    tx_error::TxError,
+    csv::{IntoTransaction, TransactCsv, TxRecord},
+    io::{AsyncRead, AsyncWrite},
+    model::{Account, AccountSummary, Accounts, ClientId, Transaction},
+    process_run::ProcessRun,
     tx_processor::TxProcessor,
 };
 
-mod csv;
+#[cfg(feature = "fs")]
+use crate::{
+    incremental_run::IncrementalRun,
+    ledger_split::LedgerSplit,
+    tx_sort::{SortedRuns, SORT_RUN_SIZE},
+};
+
+#[cfg(feature = "fs")]
+mod account_page_store;
+mod account_store;
+mod chargeback_policy;
+#[cfg(feature = "fs")]
+mod checkpointer;
+mod dedup_index;
 mod error;
+mod error_policy;
+#[cfg(feature = "fs")]
+mod incremental_run;
+mod ingest_summary;
+mod latency_summary;
+#[cfg(feature = "fs")]
+mod ledger_split;
+mod mem_tx_store;
+mod memory_budget;
+mod notifier;
+mod process_observer;
+mod process_options;
+mod process_run;
+mod process_summary;
+mod processor;
+#[cfg(feature = "fs")]
+mod processor_snapshot;
+#[cfg(any(
+    feature = "server",
+    feature = "redis",
+    feature = "nats",
+    feature = "amqp"
+))]
+mod rate_limit;
+mod rejection_summary;
+#[cfg(feature = "fs")]
 mod tx_block_store;
 mod tx_error;
 mod tx_processor;
+#[cfg(feature = "fs")]
+mod tx_sort;
+mod tx_store;
 
 /// Number of transactions to store per transaction file.
 ///
@@ -37,75 +126,688 @@ mod tx_processor;
 /// disputed transactions.
 const TX_BLOCK_SIZE: usize = 10000;
 
+/// Ledger of transactions without a `ledger` or `tenant` value.
+pub const LEDGER_DEFAULT: &str = "default";
+
 /// Processes transactions and outputs them to the given stream.
-pub async fn process<W>(path: &Path, out_stream: W) -> Result<(), Error>
-where
-    W: tokio::io::AsyncWrite + Unpin,
-{
-    let tx_block_store = &TxBlockStore::try_new()?;
-    let tx_processor = &TxProcessor::new(tx_block_store);
-    let transactions = TransactCsv::stream(path).await?;
-    let accounts = transactions
-        .try_chunks(TX_BLOCK_SIZE)
-        .and_then(|transactions| async move {
-            tx_block_store
-                .persist_block(&transactions)
-                .await
-                .map_err(|e| TryChunksError(transactions.clone(), e))?;
-
-            let stream = stream::iter(transactions.into_iter())
-                .map(Result::<_, Error>::Ok)
-                .map_err(|e| TryChunksError(Vec::new(), e));
-
-            Ok(stream)
+#[cfg(feature = "fs")]
+pub async fn process<W>(path: &Path, out_stream: W, options: ProcessOptions) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    let deterministic_output = options.deterministic_output();
+    let (accounts, _rejections, _record_errors) =
+        process_to_accounts(path, options).await?.into_inner();
+
+    write_accounts(&accounts, out_stream, deterministic_output).await
+}
+
+/// Processes transactions and returns the final account state.
+///
+/// Rejected transactions and malformed records are handled according to the
+/// [`ErrorPolicy`] in `options`, and do not change any account.
+#[cfg(feature = "fs")]
+pub async fn process_to_accounts(
+    path: &Path,
+    options: ProcessOptions,
+) -> Result<ProcessSummary, Error> {
+    let tx_block_store = match options.block_store_dir() {
+        Some(block_store_dir) => TxBlockStore::try_new_in(block_store_dir)?,
+        None => TxBlockStore::try_new()?,
+    };
+    let transactions = read_transactions(path, &options).await?;
+    let transactions = sort_by_tx_if_enabled(transactions, &options).await?;
+
+    process_transactions(transactions, &tx_block_store, options).await
+}
+
+/// Processes transactions of several ledgers, and returns the final account
+/// state of each ledger.
+///
+/// The ledger of each transaction is read from the `ledger` or `tenant`
+/// column. Transactions without a ledger are in the [`LEDGER_DEFAULT`] ledger.
+/// Each ledger is processed on its own, with its own accounts and transaction
+/// store, so the same client and transaction IDs may be used in different
+/// ledgers.
+///
+/// Transactions are split into a file per ledger before processing, in the
+/// block store directory.
+#[cfg(feature = "fs")]
+pub async fn process_ledgers_to_accounts(
+    path: &Path,
+    options: ProcessOptions,
+) -> Result<BTreeMap<String, ProcessSummary>, Error> {
+    let rows = TransactCsv::stream_by_ledger(path).await?;
+    let ledger_split = LedgerSplit::write(rows, options.block_store_dir()).await?;
+    let (_ledger_dir, ledger_streams) = ledger_split.into_streams().await?;
+
+    let mut ledger_summaries = BTreeMap::new();
+    for (ledger, transactions) in ledger_streams {
+        let tx_block_store = match options.block_store_dir() {
+            Some(block_store_dir) => TxBlockStore::try_new_in(block_store_dir)?,
+            None => TxBlockStore::try_new()?,
+        };
+        let transactions = sort_by_tx_if_enabled(transactions, &options).await?;
+        let process_summary =
+            process_transactions(transactions, &tx_block_store, options.clone()).await?;
+
+        ledger_summaries.insert(ledger, process_summary);
+    }
+
+    Ok(ledger_summaries)
+}
+
+/// Returns the transactions in the file at `path`, parsed with the parser
+/// chosen in `options`.
+#[cfg(feature = "fs")]
+async fn read_transactions(
+    path: &Path,
+    options: &ProcessOptions,
+) -> Result<stream::BoxStream<'static, Result<Transaction, Error>>, Error> {
+    let options = &options.within_memory_budget();
+    #[cfg(feature = "simd-csv")]
+    if options.simd_parse() {
+        let transactions = TransactCsv::stream_simd(path, options.channel_capacity()).await?;
+        return Ok(transactions.into_stream().boxed());
+    }
+
+    if options.blocking_parse() {
+        let transactions = TransactCsv::stream_blocking(path, options.channel_capacity()).await?;
+        Ok(transactions.into_stream().boxed())
+    } else {
+        let transactions = TransactCsv::stream(path).await?;
+        Ok(transactions.into_stream().boxed())
+    }
+}
+
+/// Returns the transactions sorted by transaction ID when
+/// [`ProcessOptions::with_sort_by_tx`] is set, or unchanged otherwise.
+///
+/// Sorting reads all transactions before returning, spilling sorted runs to
+/// files in the block store directory.
+#[cfg(feature = "fs")]
+async fn sort_by_tx_if_enabled<T>(
+    transactions: T,
+    options: &ProcessOptions,
+) -> Result<impl TryStream<Ok = Transaction, Error = Error>, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+{
+    if !options.sort_by_tx() {
+        return Ok(Either::Left(transactions.into_stream()));
+    }
+
+    let sorted_runs =
+        SortedRuns::write(transactions, SORT_RUN_SIZE, options.block_store_dir()).await?;
+    let transactions = sorted_runs.into_stream().await?;
+
+    Ok(Either::Right(transactions.into_stream()))
+}
+
+/// Processes a new transactions file on top of the state in a snapshot, and
+/// outputs the accounts to the given stream.
+///
+/// The snapshot holds the accounts, including open disputes, and the deposits
+/// that may be disputed from previous runs, so only new transactions are
+/// processed. When `snapshot` does not exist, processing starts with no
+/// accounts. After the accounts are written, `snapshot` is replaced with the
+/// updated state, so the next run continues from this one.
+///
+/// The snapshot is not updated when processing fails or is cancelled, so the
+/// same transactions file may be processed again. Snapshots have the same
+/// format as [`Processor::snapshot`].
+#[cfg(feature = "fs")]
+pub async fn process_incremental<W>(
+    snapshot: &Path,
+    path: &Path,
+    out_stream: W,
+    options: ProcessOptions,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    let deterministic_output = options.deterministic_output();
+    let incremental_run = IncrementalRun::process(snapshot, path, options).await?;
+    write_accounts(
+        incremental_run.summary().accounts(),
+        out_stream,
+        deterministic_output,
+    )
+    .await?;
+
+    incremental_run.save(snapshot).await
+}
+
+/// Processes a new transactions file on top of the state in a snapshot, and
+/// returns the final account state.
+///
+/// `snapshot` is replaced with the updated state before returning, see
+/// [`process_incremental`].
+#[cfg(feature = "fs")]
+pub async fn process_incremental_to_accounts(
+    snapshot: &Path,
+    path: &Path,
+    options: ProcessOptions,
+) -> Result<ProcessSummary, Error> {
+    let incremental_run = IncrementalRun::process(snapshot, path, options).await?;
+    incremental_run.save(snapshot).await?;
+
+    Ok(incremental_run.into_summary())
+}
+
+/// Reads accounts from an accounts CSV file, such as the output of [`process`].
+#[cfg(feature = "fs")]
+pub async fn read_accounts(path: &Path) -> Result<Accounts, Error> {
+    TransactCsv::accounts_stream(path)
+        .await?
+        .map_ok(|account| (account.client(), account))
+        .try_collect()
+        .await
+}
+
+/// Processes transactions read from `in_stream` and outputs the accounts to
+/// `out_stream`.
+///
+/// Transactions are stored in memory, so this does not need a filesystem, and
+/// may be used with any async runtime.
+pub async fn process_reader<R, W>(
+    in_stream: R,
+    out_stream: W,
+    options: ProcessOptions,
+) -> Result<(), Error>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin,
+{
+    process_records::<TxRecord, R, W>(in_stream, out_stream, options).await
+}
+
+/// Processes transactions CSV content whose rows are deserialized as `Rec`
+/// from `in_stream`, and outputs the accounts to `out_stream`.
+///
+/// This is for input whose rows do not have the shape of [`TxRecord`], such
+/// as with extra fields or different transaction type spellings. Each row is
+/// converted with [`IntoTransaction`], and otherwise processed the same as
+/// with [`process_reader`].
+///
+/// [`TxRecord`]: crate::csv::TxRecord
+/// [`IntoTransaction`]: crate::csv::IntoTransaction
+pub async fn process_records<Rec, R, W>(
+    in_stream: R,
+    out_stream: W,
+    options: ProcessOptions,
+) -> Result<(), Error>
+where
+    Rec: DeserializeOwned + IntoTransaction,
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin,
+{
+    let tx_store = MemTxStore::new();
+    let transactions = TransactCsv::records_stream_reader::<Rec, R>(in_stream);
+    let deterministic_output = options.deterministic_output();
+    let (accounts, _rejections, _record_errors) =
+        process_transactions(transactions, &tx_store, options)
+            .await?
+            .into_inner();
+
+    write_accounts(&accounts, out_stream, deterministic_output).await
+}
+
+/// Processes transactions from Debezium change events read from `in_stream`,
+/// and outputs the accounts to `out_stream`.
+///
+/// Each line of `in_stream` is a change event, whose row image is
+/// deserialized as `Rec`, such as [`TxRecord`]. Only inserted rows are
+/// applied, see [`cdc::ChangeEvent::into_transaction`].
+///
+/// [`TxRecord`]: crate::csv::TxRecord
+#[cfg(feature = "cdc")]
+pub async fn process_change_events<Rec, R, W>(
+    in_stream: R,
+    out_stream: W,
+    options: ProcessOptions,
+) -> Result<(), Error>
+where
+    Rec: DeserializeOwned + IntoTransaction,
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin,
+{
+    let tx_store = MemTxStore::new();
+    let transactions = cdc::TransactCdc::stream_reader::<Rec, R>(in_stream);
+    let deterministic_output = options.deterministic_output();
+    let (accounts, _rejections, _record_errors) =
+        process_transactions(transactions, &tx_store, options)
+            .await?
+            .into_inner();
+
+    write_accounts(&accounts, out_stream, deterministic_output).await
+}
+
+/// Processes transactions received from `rx`, and outputs the accounts to
+/// `out_stream` once every sender is dropped.
+///
+/// This is for applications that produce transactions themselves, such as
+/// from their own decoders or network handlers. Senders wait while the
+/// channel is full, so transactions are not produced faster than they are
+/// processed.
+///
+/// Transactions are stored in memory, the same as with [`process_reader`].
+pub async fn process_from_channel<W>(
+    rx: mpsc::Receiver<Transaction>,
+    out_stream: W,
+    options: ProcessOptions,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    let tx_store = MemTxStore::new();
+    let transactions = rx.map(Result::<Transaction, Error>::Ok);
+    let deterministic_output = options.deterministic_output();
+    let (accounts, _rejections, _record_errors) =
+        process_transactions(transactions, &tx_store, options)
+            .await?
+            .into_inner();
+
+    write_accounts(&accounts, out_stream, deterministic_output).await
+}
+
+/// Processes a stream of transactions and returns the final account state.
+///
+/// Rejected transactions and malformed records are handled according to the
+/// [`ErrorPolicy`] in `options`, and do not change any account.
+///
+/// # Parameters
+///
+/// * `transactions`: Stream of transactions to process.
+/// * `tx_store`: Stores transactions to look up when processing disputes.
+/// * `options`: Options to control how transactions are processed.
+pub async fn process_transactions<T, S>(
+    transactions: T,
+    tx_store: &S,
+    options: ProcessOptions,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+{
+    process_transactions_from(transactions, tx_store, Accounts::new(), &options).await
+}
+
+/// Processes a stream of transactions on top of existing accounts.
+async fn process_transactions_from<T, S>(
+    transactions: T,
+    tx_store: &S,
+    accounts: Accounts,
+    options: &ProcessOptions,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+{
+    let result =
+        process_transactions_within_run_timeout(transactions, tx_store, accounts, options).await;
+    if let (Err(error), Some(notifier)) = (&result, options.notifier()) {
+        notifier.notify_run_failed(error);
+    }
+
+    result
+}
+
+/// Processes a stream of transactions, failing if the run timeout is exceeded.
+async fn process_transactions_within_run_timeout<T, S>(
+    transactions: T,
+    tx_store: &S,
+    accounts: Accounts,
+    options: &ProcessOptions,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+{
+    #[cfg(feature = "tokio")]
+    if let Some(timeout) = options.run_timeout() {
+        return tokio::time::timeout(
+            timeout,
+            process_transactions_until_done(transactions, tx_store, accounts, options),
+        )
+        .await
+        .map_err(|_| Error::RunTimeout { timeout })?;
+    }
+
+    process_transactions_until_done(transactions, tx_store, accounts, options).await
+}
+
+/// Processes a stream of transactions without a run timeout.
+async fn process_transactions_until_done<T, S>(
+    transactions: T,
+    tx_store: &S,
+    accounts: Accounts,
+    options: &ProcessOptions,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+{
+    let options = &options.within_memory_budget();
+    let transactions = if options.require_increasing_tx() {
+        Either::Left(require_increasing_tx(transactions))
+    } else {
+        Either::Right(transactions.into_stream())
+    };
+    let partitions = options.partitions().get();
+    let mut summary = if partitions > 1 {
+        process_transactions_partitioned(transactions, tx_store, accounts, options, partitions)
+            .await?
+    } else {
+        process_transactions_serially(transactions, tx_store, accounts, options).await?
+    };
+    if options.deterministic_output() {
+        summary.sort_rejections();
+    }
+
+    Ok(summary)
+}
+
+/// Returns the transactions, with each deposit or withdrawal whose ID is not
+/// greater than the previous deposit or withdrawal's replaced by
+/// [`Error::TxIdNotIncreasing`].
+fn require_increasing_tx<T>(transactions: T) -> impl Stream<Item = Result<Transaction, Error>>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+{
+    let mut tx_last = None;
+    transactions.into_stream().map(move |transaction| {
+        let transaction = transaction?;
+        if let Transaction::Deposit(_) | Transaction::Withdrawal(_) = transaction {
+            let tx = transaction.tx();
+            if let Some(tx_previous) = tx_last.filter(|tx_previous| tx <= *tx_previous) {
+                return Err(Error::TxIdNotIncreasing {
+                    client: transaction.client(),
+                    tx,
+                    tx_previous,
+                });
+            }
+            tx_last = Some(tx);
+        }
+        Ok(transaction)
+    })
+}
+
+/// Processes a stream of transactions in partitions by client.
+///
+/// Each client belongs to one partition, and each partition applies its
+/// transactions in input order, so every client's transactions are applied in
+/// input order. Partitions are processed concurrently, and their summaries
+/// are merged once the input is read.
+///
+/// Malformed records have no client, and are handled by the first partition.
+async fn process_transactions_partitioned<T, S>(
+    transactions: T,
+    tx_store: &S,
+    accounts: Accounts,
+    options: &ProcessOptions,
+    partitions: usize,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+{
+    let partition_of = |client: ClientId| usize::from(client.into_inner()) % partitions;
+    let mut partition_accounts = iter::repeat_with(Accounts::new)
+        .take(partitions)
+        .collect::<Vec<_>>();
+    for account in accounts.into_values() {
+        partition_accounts[partition_of(account.client())].insert(account.client(), account);
+    }
+    let (mut senders, receivers): (Vec<_>, Vec<_>) =
+        iter::repeat_with(|| mpsc::channel(options.channel_capacity().get()))
+            .take(partitions)
+            .unzip();
+
+    let cancellation_token = options.cancellation_token();
+    let dispatch = async move {
+        let mut transactions = pin!(transactions
+            .into_stream()
+            .take_until(cancelled(cancellation_token)));
+        while let Some(transaction) = transactions.next().await {
+            let partition = transaction
+                .as_ref()
+                .map_or(0, |transaction| partition_of(transaction.client()));
+            if senders[partition].send(transaction).await.is_err() {
+                // The partition stopped early, e.g. on a rejection with
+                // `ErrorPolicy::Strict`, and its error is returned.
+                break;
+            }
+        }
+        // Dropping the senders ends each partition's stream.
+        drop(senders);
+
+        Ok(())
+    };
+    let partition_runs =
+        receivers
+            .into_iter()
+            .zip(partition_accounts)
+            .map(|(receiver, accounts)| {
+                process_transactions_serially(receiver, tx_store, accounts, options)
+            });
+
+    let (summaries, ()) = future::try_join(future::try_join_all(partition_runs), dispatch).await?;
+
+    Ok(summaries.into_iter().fold(
+        ProcessSummary::new(Accounts::new(), Vec::new(), Vec::new(), false),
+        |mut summary, partition_summary| {
+            summary.merge(partition_summary);
+            summary
+        },
+    ))
+}
+
+/// Processes a stream of transactions in input order.
+async fn process_transactions_serially<T, S>(
+    transactions: T,
+    tx_store: &S,
+    accounts: Accounts,
+    options: &ProcessOptions,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+{
+    #[cfg(feature = "fs")]
+    if let Some(account_cache_capacity) = options.account_cache_capacity() {
+        let mut account_page_store = match options.block_store_dir() {
+            Some(block_store_dir) => {
+                AccountPageStore::try_new_in(block_store_dir, account_cache_capacity)?
+            }
+            None => AccountPageStore::try_new(account_cache_capacity)?,
+        };
+        for account in accounts.into_values() {
+            account_page_store.put_account(account).await?;
+        }
+
+        return process_transactions_with_account_store(
+            transactions,
+            tx_store,
+            account_page_store,
+            options,
+        )
+        .await;
+    }
+
+    process_transactions_with_account_store(transactions, tx_store, accounts, options).await
+}
+
+/// Processes a stream of transactions, storing accounts in `account_store`.
+async fn process_transactions_with_account_store<T, S, A>(
+    transactions: T,
+    tx_store: &S,
+    account_store: A,
+    options: &ProcessOptions,
+) -> Result<ProcessSummary, Error>
+where
+    T: TryStream<Ok = Transaction, Error = Error>,
+    S: TxStore,
+    A: AccountStore,
+{
+    let error_policy = options.error_policy();
+    let block_size = options.block_size().get();
+    let persist_concurrency = options.persist_concurrency().get();
+    let lookup_concurrency = options.lookup_concurrency().get();
+    let observer = options.observer();
+    let notifier = options.notifier();
+    let cancellation_token = options.cancellation_token();
+    let cancelled = cancelled(cancellation_token);
+    let tx_processor = TxProcessor::new(tx_store);
+    #[cfg(feature = "tokio")]
+    let tx_processor = tx_processor.with_lookup_timeout(options.lookup_timeout());
+    let tx_processor = &tx_processor;
+    let record_errors = &RefCell::new(Vec::new());
+    let process_run = ProcessRun::new(
+        tx_processor,
+        error_policy,
+        observer,
+        notifier,
+        account_store,
+    )
+    .with_park_early_disputes(options.park_early_disputes())
+    .with_chargeback_policy(options.chargeback_policy());
+    let process_run = transactions
+        .into_stream()
+        // Stop reading records when cancelled, and process those already read.
+        .take_until(cancelled)
+        .filter_map(|transaction| async move {
+            match transaction {
+                Err(error) if error.is_record_error() => {
+                    if let Some(observer) = observer {
+                        observer.on_record_error(&error);
+                    }
+                    match error_policy {
+                        ErrorPolicy::Strict => Some(Err(error)),
+                        ErrorPolicy::Lenient => None,
+                        ErrorPolicy::Collect => {
+                            record_errors.borrow_mut().push(error);
+                            None
+                        }
+                    }
+                }
+                transaction => Some(transaction),
+            }
         })
-        .try_flatten()
+        .try_chunks(block_size)
         // drop transactions when encountering an error
         .map_err(|TryChunksError(_transactions, e)| e)
-        .try_fold(Accounts::new(), |mut accounts, transaction| async move {
-            let account = accounts
-                .entry(transaction.client())
-                .or_insert_with(|| Account::empty(transaction.client()));
-
-            tx_processor
-                .process(account, transaction)
-                .await
-                .map(|tx_result| match tx_result {
-                    Ok(()) => (),
-                    Err(
-                        // Choose which transaction errors to ignore.
-                        // Errors not in this list will cause the application execution to fail.
-                        TxError::AccountLocked { .. }
-                        | TxError::DisputeClientMismatch { .. }
-                        | TxError::DisputeTxNotFound { .. }
-                        | TxError::DisputeInsufficientAvailable { .. }
-                        | TxError::DisputeHeldOverflow { .. }
-                        | TxError::ResolveClientMismatch { .. }
-                        | TxError::ResolveInsufficientHeld { .. }
-                        | TxError::ResolveAvailableOverflow { .. }
-                        | TxError::ResolveTxNotInDispute { .. }
-                        | TxError::ChargebackClientMismatch { .. }
-                        | TxError::ChargebackInsufficientHeld { .. }
-                        | TxError::ChargebackTxNotInDispute { .. }
-                        | TxError::DepositAmountNegative { .. }
-                        | TxError::DepositAvailableOverflow { .. }
-                        | TxError::DepositTotalOverflow { .. }
-                        | TxError::WithdrawalAmountNegative { .. }
-                        | TxError::WithdrawalInsufficientAvailable { .. },
-                    ) => (),
-                })?;
-
-            Ok(accounts)
+        .try_chunks(persist_concurrency)
+        .map_err(|TryChunksError(_blocks, e)| e)
+        .and_then(|blocks| async move {
+            // Every block in the window is persisted before any of its
+            // transactions are applied.
+            future::try_join_all(blocks.iter().map(|transactions| {
+                let persist_block = tx_store.persist_block(transactions);
+                #[cfg(feature = "tracing")]
+                let persist_block =
+                    tracing::Instrument::instrument(persist_block, chunk_span(transactions));
+                persist_block
+            }))
+            .await?;
+            for transactions in &blocks {
+                #[cfg(feature = "metrics")]
+                process_metrics::block_persisted(transactions);
+                #[cfg(feature = "tracing")]
+                tracing::debug!(transactions = transactions.len(), "Block persisted.");
+                if let Some(observer) = observer {
+                    observer.on_block_persisted(transactions);
+                }
+            }
+            if lookup_concurrency > 1 {
+                tx_processor
+                    .prefetch(blocks.iter().flatten(), lookup_concurrency)
+                    .await?;
+            }
+
+            Ok(blocks)
+        })
+        .try_fold(process_run, |mut process_run, blocks| async move {
+            process_run
+                .process_window(blocks.into_iter().flatten())
+                .await?;
+            Ok(process_run)
         })
         .await?;
+    let (account_store, rejections, rejection_summary, latency_summary) =
+        process_run.finish().await?;
+    let accounts = account_store.into_accounts().await?;
+
+    let cancelled = cancellation_token.is_some_and(CancellationToken::is_cancelled);
+
+    #[cfg(feature = "tracing")]
+    rejection_summary
+        .iter()
+        .for_each(|(code, rejection_count)| {
+            tracing::info!(
+                code,
+                count = rejection_count.count(),
+                amount = %rejection_count.amount(),
+                "Transactions rejected."
+            )
+        });
+
+    Ok(
+        ProcessSummary::new(accounts, rejections, record_errors.take(), cancelled)
+            .with_rejection_summary(rejection_summary)
+            .with_latency_summary(latency_summary),
+    )
+}
+
+/// Returns the span for persisting a chunk of transactions.
+#[cfg(feature = "tracing")]
+pub(crate) fn chunk_span(transactions: &[Transaction]) -> tracing::Span {
+    tracing::info_span!(
+        "chunk",
+        transactions = transactions.len(),
+        first_tx = transactions
+            .first()
+            .map(|transaction| transaction.tx().into_inner()),
+        last_tx = transactions
+            .last()
+            .map(|transaction| transaction.tx().into_inner()),
+    )
+}
+
+/// Completes when the token is cancelled, or never if there is no token.
+async fn cancelled(cancellation_token: Option<&CancellationToken>) {
+    match cancellation_token {
+        Some(cancellation_token) => cancellation_token.cancelled().await,
+        None => future::pending().await,
+    }
+}
 
-    let mut writer = stream::iter(accounts.into_values())
-        .map(Result::<Account, Error>::Ok)
+/// Writes accounts as CSV to the given stream.
+///
+/// When `deterministic_output` is `true`, accounts are written in order of
+/// client ID.
+async fn write_accounts<W>(
+    accounts: &Accounts,
+    out_stream: W,
+    deterministic_output: bool,
+) -> Result<(), Error>
+where
+    W: AsyncWrite + Unpin,
+{
+    #[cfg(feature = "metrics")]
+    let out_stream = process_metrics::MeteredIo::new(out_stream);
+    let accounts: Box<dyn Iterator<Item = &Account> + Send> = if deterministic_output {
+        Box::new(accounts.iter_sorted())
+    } else {
+        Box::new(accounts.values())
+    };
+    let mut writer = stream::iter(accounts)
+        .map(Result::<&Account, Error>::Ok)
         .try_fold(
             TransactCsv::csv_writer(out_stream),
             |mut writer, account| async move {
                 writer
-                    .serialize(account)
+                    .serialize(AccountSummary::from(account))
                     .await
                     .map_err(Error::OutputWrite)?;
 
@@ -118,3 +820,1396 @@ where
 
     Ok(())
 }
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use std::{
+        future::{self, Future},
+        io::Write,
+        num::NonZeroUsize,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use futures::{stream, SinkExt};
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+    use serde::Deserialize;
+
+    use super::{
+        process_from_channel, process_incremental, process_incremental_to_accounts,
+        process_ledgers_to_accounts, process_reader, process_records, process_to_accounts,
+        process_transactions, process_transactions_from,
+    };
+    use crate::{
+        csv::IntoTransaction,
+        model::{
+            Account, Accounts, Chargeback, ClientId, Deposit, Dispute, Resolve, Transaction, TxId,
+            Withdrawal,
+        },
+        test_util::AccountBuilder,
+        CancellationToken, ChargebackPolicy, Error, ErrorPolicy, MemTxStore, MemoryBudget,
+        Notifier, ProcessObserver, ProcessOptions, TxError, TxStore, LEDGER_DEFAULT,
+    };
+
+    /// Transaction store that never completes.
+    #[derive(Debug)]
+    struct PendingTxStore;
+
+    impl TxStore for PendingTxStore {
+        fn persist_block(
+            &self,
+            _transactions: &[Transaction],
+        ) -> impl Future<Output = Result<(), Error>> {
+            future::pending()
+        }
+
+        fn find_transaction(
+            &self,
+            _tx: TxId,
+        ) -> impl Future<Output = Result<Option<Transaction>, Error>> {
+            future::pending()
+        }
+
+        fn deposits(&self) -> impl Future<Output = Result<Vec<Transaction>, Error>> {
+            future::pending()
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl ProcessObserver for RecordingObserver {
+        fn on_transaction_applied(&self, transaction: &Transaction, _account: &Account) {
+            self.record(format!("applied {}", transaction.tx()));
+        }
+
+        fn on_transaction_rejected(&self, transaction: &Transaction, _tx_error: &TxError) {
+            self.record(format!("rejected {}", transaction.tx()));
+        }
+
+        fn on_account_locked(&self, account: &Account) {
+            self.record(format!("locked {}", account.client()));
+        }
+
+        fn on_record_error(&self, error: &Error) {
+            self.record(format!("record error {}", error.code()));
+        }
+
+        fn on_block_persisted(&self, transactions: &[Transaction]) {
+            self.record(format!("persisted {}", transactions.len()));
+        }
+    }
+
+    impl RecordingObserver {
+        fn record(&self, event: String) {
+            self.events.lock().expect("Lock poisoned.").push(event);
+        }
+    }
+
+    #[derive(Debug, Default)]
+    struct RecordingNotifier {
+        alerts: Mutex<Vec<String>>,
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify_account_locked(&self, account: &Account) {
+            self.record(format!("locked {}", account.client()));
+        }
+
+        fn notify_balance_negative(&self, account: &Account) {
+            self.record(format!("negative {}", account.client()));
+        }
+
+        fn notify_run_failed(&self, error: &Error) {
+            self.record(format!("failed {}", error.code()));
+        }
+    }
+
+    impl RecordingNotifier {
+        fn record(&self, alert: String) {
+            self.alerts.lock().expect("Lock poisoned.").push(alert);
+        }
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_returns_accounts_and_rejections()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             withdrawal, 1, 2, 3.0\n\
+             deposit, 2, 3, 1.5\n"
+        )?;
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_error_policy(ErrorPolicy::Collect),
+        )
+        .await?;
+
+        let client_one = ClientId::new(1);
+        let client_two = ClientId::new(2);
+        let accounts = summary.accounts();
+        assert_eq!(2, accounts.len());
+        assert_eq!(
+            Some(dec!(2.0)),
+            accounts.get(client_one).map(|account| account.available())
+        );
+        assert_eq!(
+            Some(dec!(1.5)),
+            accounts.get(client_two).map(|account| account.available())
+        );
+        assert_eq!(
+            &[TxError::WithdrawalInsufficientAvailable {
+                client: client_one,
+                tx: TxId::new(2),
+                available: dec!(2.0),
+                amount: dec!(3.0),
+            }],
+            summary.rejections()
+        );
+        assert_eq!(
+            Some((1, dec!(3.0))),
+            summary
+                .rejection_summary()
+                .get("WITHDRAWAL_INSUFFICIENT_AVAILABLE")
+                .map(|rejection_count| (rejection_count.count(), rejection_count.amount()))
+        );
+        assert_eq!(
+            vec![("deposit", 2), ("withdrawal", 1)],
+            summary
+                .latency_summary()
+                .iter()
+                .map(|(tx_type, latency_histogram)| (tx_type, latency_histogram.count()))
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_collects_malformed_records()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             deposit, 1, 2,\n\
+             deposit, 1, 3, 1.5\n"
+        )?;
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_error_policy(ErrorPolicy::Collect),
+        )
+        .await?;
+
+        let client = ClientId::new(1);
+        assert_eq!(
+            Some(dec!(3.5)),
+            summary
+                .accounts()
+                .get(client)
+                .map(|account| account.available())
+        );
+        assert!(matches!(
+            summary.record_errors(),
+            [Error::DepositAmountNotProvided { client, tx }]
+            if *client == ClientId::new(1) && *tx == TxId::new(2)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_parses_on_blocking_thread()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        writeln!(transactions_file, "type, client, tx, amount")?;
+        for tx in 1..=3000 {
+            writeln!(transactions_file, "deposit, 1, {tx}, 1.0")?;
+        }
+        writeln!(transactions_file, "deposit, 1, 3001,")?;
+        writeln!(transactions_file, "refund, 1, 3002, 1.0")?;
+        writeln!(transactions_file, "dispute, 1, 1,")?;
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Collect)
+                .with_blocking_parse(true),
+        )
+        .await?;
+
+        let account = summary.accounts().get(ClientId::new(1));
+        assert_eq!(Some(dec!(2999)), account.map(|account| account.available()));
+        assert_eq!(Some(dec!(1)), account.map(|account| account.held()));
+        assert!(matches!(
+            summary.record_errors(),
+            [
+                Error::DepositAmountNotProvided { tx, .. },
+                Error::TransactionDeserializeBlocking(_),
+            ] if *tx == TxId::new(3001)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_collects_tx_ids_not_increasing()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 2, 2.0\n\
+             deposit, 2, 1, 5.0\n\
+             dispute, 1, 2,\n\
+             withdrawal, 1, 3, 1.5\n"
+        )?;
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Collect)
+                .with_require_increasing_tx(true),
+        )
+        .await?;
+
+        assert!(summary.accounts().get(ClientId::new(2)).is_none());
+        assert!(matches!(
+            summary.record_errors(),
+            [Error::TxIdNotIncreasing { client, tx, tx_previous }]
+            if *client == ClientId::new(2)
+                && *tx == TxId::new(1)
+                && *tx_previous == TxId::new(2)
+        ));
+        assert!(matches!(
+            summary.rejections(),
+            [TxError::WithdrawalInsufficientAvailable { .. }]
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_strict_fails_on_rejection()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             withdrawal, 1, 1, 2.0\n"
+        )?;
+
+        let result = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_error_policy(ErrorPolicy::Strict),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::TransactionRejected(
+                TxError::WithdrawalInsufficientAvailable { .. }
+            ))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_sorts_by_tx() -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             withdrawal, 1, 3, 1.0\n\
+             dispute, 1, 1,\n\
+             deposit, 1, 2, 1.0\n\
+             deposit, 1, 4,\n\
+             deposit, 1, 1, 2.0\n"
+        )?;
+        let client = ClientId::new(1);
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new()
+                .with_block_size(NonZeroUsize::new(1).expect("Block size is non-zero."))
+                .with_error_policy(ErrorPolicy::Collect)
+                .with_sort_by_tx(true),
+        )
+        .await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(0.0)), account.map(|account| account.available()));
+        assert_eq!(Some(dec!(2.0)), account.map(|account| account.held()));
+        assert!(summary.rejections().is_empty());
+        assert!(matches!(
+            summary.record_errors(),
+            [Error::DepositAmountNotProvided { tx, .. }] if *tx == TxId::new(4)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_stores_blocks_in_block_store_dir()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             dispute, 1, 1,\n"
+        )?;
+        let block_store_dir = tempfile::tempdir()?;
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_block_store_dir(block_store_dir.path().to_path_buf()),
+        )
+        .await?;
+
+        assert_eq!(
+            Some(dec!(2.0)),
+            summary
+                .accounts()
+                .get(ClientId::new(1))
+                .map(|account| account.held())
+        );
+        // The block store directory is removed when processing is complete.
+        assert_eq!(0, std::fs::read_dir(block_store_dir.path())?.count());
+
+        let result = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_block_store_dir(block_store_dir.path().join("missing")),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::BlockStoreDirCreate(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_persists_and_looks_up_concurrently()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 5.0\n\
+             deposit, 2, 2, 3.0\n\
+             deposit, 1, 3, 1.0\n\
+             dispute, 1, 1,\n\
+             resolve, 1, 1,\n\
+             dispute, 2, 2,\n\
+             chargeback, 2, 2,\n\
+             dispute, 1, 9,\n"
+        )?;
+        let block_size = NonZeroUsize::new(2).expect("Test data invalid.");
+
+        let mut summaries = Vec::new();
+        for (persist_concurrency, lookup_concurrency) in [(1, 1), (3, 4)] {
+            let observer = Arc::new(RecordingObserver::default());
+            let (accounts, rejections, _record_errors) = process_to_accounts(
+                transactions_file.path(),
+                ProcessOptions::new()
+                    .with_block_size(block_size)
+                    .with_persist_concurrency(
+                        NonZeroUsize::new(persist_concurrency).expect("Test data invalid."),
+                    )
+                    .with_lookup_concurrency(
+                        NonZeroUsize::new(lookup_concurrency).expect("Test data invalid."),
+                    )
+                    .with_error_policy(ErrorPolicy::Collect)
+                    .with_observer(observer.clone()),
+            )
+            .await?
+            .into_inner();
+            let persisted = observer
+                .events
+                .lock()
+                .expect("Lock poisoned.")
+                .iter()
+                .filter(|event| event.starts_with("persisted"))
+                .cloned()
+                .collect::<Vec<_>>();
+            let mut accounts = accounts
+                .values()
+                .map(|account| {
+                    (
+                        account.client(),
+                        account.available(),
+                        account.held(),
+                        account.locked(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            accounts.sort_unstable_by_key(|(client, ..)| *client);
+            summaries.push((accounts, rejections, persisted));
+        }
+
+        let (accounts, rejections, persisted) = &summaries[0];
+        assert_eq!(
+            vec![
+                (ClientId::new(1), dec!(6.0), dec!(0), false),
+                (ClientId::new(2), dec!(0), dec!(0), true),
+            ],
+            *accounts
+        );
+        assert_eq!(
+            vec![TxError::DisputeTxNotFound { tx: TxId::new(9) }],
+            *rejections
+        );
+        assert_eq!(
+            vec!["persisted 2", "persisted 2", "persisted 2", "persisted 2"],
+            *persisted
+        );
+        assert_eq!(summaries[0], summaries[1]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_reduces_blocks_to_fit_memory_budget()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 5.0\n\
+             deposit, 2, 2, 3.0\n\
+             dispute, 1, 1,\n"
+        )?;
+        let observer = Arc::new(RecordingObserver::default());
+        let memory_budget = MemoryBudget::new(
+            NonZeroUsize::new(MemoryBudget::TRANSACTION_BYTES * 4).expect("Test data invalid."),
+        );
+
+        let (accounts, _rejections, _record_errors) = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new()
+                .with_memory_budget(memory_budget)
+                .with_observer(observer.clone()),
+        )
+        .await?
+        .into_inner();
+
+        assert_eq!(dec!(5.0), accounts[&ClientId::new(1)].held());
+        assert_eq!(dec!(3.0), accounts[&ClientId::new(2)].available());
+        assert_eq!(
+            vec!["persisted 1", "persisted 1", "persisted 1"],
+            observer
+                .events
+                .lock()
+                .expect("Lock poisoned.")
+                .iter()
+                .filter(|event| event.starts_with("persisted"))
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_ledgers_to_accounts_processes_each_ledger_separately()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount, ledger\n\
+             deposit, 1, 1, 5.0, b\n\
+             deposit, 1, 1, 3.0, a\n\
+             deposit, 2, 2, 1.0,\n\
+             dispute, 1, 1,, a\n\
+             refund, 1, 3, 1.0, b\n"
+        )?;
+
+        let ledger_summaries = process_ledgers_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_error_policy(ErrorPolicy::Collect),
+        )
+        .await?;
+
+        assert_eq!(
+            vec!["a", "b", LEDGER_DEFAULT],
+            ledger_summaries.keys().collect::<Vec<_>>()
+        );
+        let accounts_a = ledger_summaries["a"].accounts();
+        assert_eq!(dec!(0.0), accounts_a[&ClientId::new(1)].available());
+        assert_eq!(dec!(3.0), accounts_a[&ClientId::new(1)].held());
+        let accounts_b = ledger_summaries["b"].accounts();
+        assert_eq!(dec!(5.0), accounts_b[&ClientId::new(1)].available());
+        assert_eq!(1, ledger_summaries["b"].record_errors().len());
+        let accounts_default = ledger_summaries[LEDGER_DEFAULT].accounts();
+        assert_eq!(dec!(1.0), accounts_default[&ClientId::new(2)].available());
+        assert!(!accounts_default.contains_key(&ClientId::new(1)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_notifies_observer() -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             dispute, 1, 1,\n\
+             chargeback, 1, 1,\n\
+             deposit, 1, 2, 1.0\n\
+             deposit, 1, 3,\n"
+        )?;
+        let observer = Arc::new(RecordingObserver::default());
+
+        process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_observer(observer.clone()),
+        )
+        .await?;
+
+        assert_eq!(
+            vec![
+                "record error DEPOSIT_AMOUNT_NOT_PROVIDED",
+                "persisted 4",
+                "applied 1",
+                "applied 1",
+                "applied 1",
+                "locked 1",
+                "rejected 2",
+            ],
+            *observer.events.lock().expect("Lock poisoned.")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_notifies_locked_account_and_failed_run()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             deposit, 2, 2, 1.0\n\
+             dispute, 1, 1,\n\
+             chargeback, 1, 1,\n\
+             withdrawal, 2, 3, 5.0\n"
+        )?;
+        let notifier = Arc::new(RecordingNotifier::default());
+
+        let result = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Strict)
+                .with_notifier(notifier.clone()),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::TransactionRejected(_))));
+        assert_eq!(
+            vec!["locked 1", "failed TRANSACTION_REJECTED"],
+            *notifier.alerts.lock().expect("Lock poisoned.")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_reader_writes_accounts() -> Result<(), Box<dyn std::error::Error>> {
+        let transactions = "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             dispute, 1, 1,\n";
+        let mut output = Vec::new();
+
+        process_reader(
+            transactions.as_bytes(),
+            &mut output,
+            ProcessOptions::default(),
+        )
+        .await?;
+
+        assert_eq!(
+            "client,available,held,total,locked\n\
+             1,0.0,2.0,2.0,false\n",
+            String::from_utf8(output)?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_records_converts_custom_records() -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Deserialize)]
+        enum LedgerKind {
+            #[serde(rename = "CR")]
+            Credit,
+            #[serde(rename = "DR")]
+            Debit,
+        }
+
+        #[derive(Deserialize)]
+        struct LedgerRecord {
+            kind: LedgerKind,
+            account: ClientId,
+            reference: TxId,
+            value: Decimal,
+            #[allow(dead_code)]
+            channel: String,
+        }
+
+        impl IntoTransaction for LedgerRecord {
+            fn into_transaction(self) -> Result<Transaction, Error> {
+                let transaction = match self.kind {
+                    LedgerKind::Credit => {
+                        Transaction::from(Deposit::new(self.account, self.reference, self.value))
+                    }
+                    LedgerKind::Debit => {
+                        Transaction::from(Withdrawal::new(self.account, self.reference, self.value))
+                    }
+                };
+                Ok(transaction)
+            }
+        }
+
+        let transactions = "kind, account, reference, value, channel\n\
+             CR, 1, 1, 2.0, card\n\
+             DR, 1, 2, 0.5, atm\n";
+        let mut output = Vec::new();
+
+        process_records::<LedgerRecord, _, _>(
+            transactions.as_bytes(),
+            &mut output,
+            ProcessOptions::default(),
+        )
+        .await?;
+
+        assert_eq!(
+            "client,available,held,total,locked\n\
+             1,1.5,0.0,1.5,false\n",
+            String::from_utf8(output)?
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_from_channel_processes_sent_transactions()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let (mut tx, rx) = futures::channel::mpsc::channel(1);
+        let producer = tokio::spawn(async move {
+            for transaction in [
+                Transaction::from(Deposit::new(client, TxId::new(1), dec!(2.0))),
+                Transaction::from(Deposit::new(client, TxId::new(2), dec!(1.0))),
+                Transaction::from(Withdrawal::new(client, TxId::new(3), dec!(0.5))),
+                Transaction::from(Dispute::new(client, TxId::new(2))),
+            ] {
+                tx.send(transaction).await?;
+            }
+            Ok::<_, futures::channel::mpsc::SendError>(())
+        });
+        let mut output = Vec::new();
+
+        process_from_channel(rx, &mut output, ProcessOptions::default()).await?;
+        producer.await??;
+
+        assert_eq!(
+            "client,available,held,total,locked\n\
+             1,1.5,1.0,2.5,false\n",
+            String::from_utf8(output)?
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "gen")]
+    #[tokio::test]
+    async fn process_reader_deterministic_output_is_identical_across_partitions()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = gen::Scenario::builder()
+            .clients(100)
+            .rows(5_000)
+            .dispute_rate(0.2)
+            .chargeback_rate(0.3)
+            .seed(11)
+            .build()?;
+        let transactions = std::iter::once(String::from(gen::HEADER))
+            .chain(scenario.rows().map(|tx_row| tx_row.to_string()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut outputs = Vec::new();
+        for partitions in [1, 1, 2, 4, 7] {
+            let mut output = Vec::new();
+            process_reader(
+                transactions.as_bytes(),
+                &mut output,
+                ProcessOptions::new()
+                    .with_block_size(NonZeroUsize::new(64).expect("Test data invalid."))
+                    .with_partitions(NonZeroUsize::new(partitions).expect("Test data invalid."))
+                    .with_deterministic_output(true),
+            )
+            .await?;
+            outputs.push(String::from_utf8(output)?);
+        }
+
+        let clients = outputs[0]
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').next().unwrap_or_default().parse::<u16>())
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(clients.is_sorted());
+        assert_eq!(100, clients.len());
+        outputs
+            .iter()
+            .for_each(|output| assert_eq!(&outputs[0], output));
+        Ok(())
+    }
+
+    #[cfg(feature = "gen")]
+    #[tokio::test]
+    async fn process_transactions_deterministic_rejections_are_identical_across_partitions()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = gen::Scenario::builder()
+            .clients(100)
+            .rows(5_000)
+            .dispute_rate(0.2)
+            .chargeback_rate(0.3)
+            .seed(12)
+            .build()?;
+        let options = |partitions| {
+            ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Collect)
+                .with_partitions(NonZeroUsize::new(partitions).expect("Test data invalid."))
+                .with_deterministic_output(true)
+        };
+
+        let summary_serial = process_transactions(
+            stream::iter(scenario.rows().map(Transaction::try_from)),
+            &MemTxStore::new(),
+            options(1),
+        )
+        .await?;
+        let summary_partitioned = process_transactions(
+            stream::iter(scenario.rows().map(Transaction::try_from)),
+            &MemTxStore::new(),
+            options(4),
+        )
+        .await?;
+
+        assert!(!summary_serial.rejections().is_empty());
+        assert!(summary_serial.rejections().is_sorted_by_key(TxError::tx));
+        assert_eq!(
+            summary_serial.rejections(),
+            summary_partitioned.rejections()
+        );
+        assert_eq!(
+            summary_serial.rejection_summary(),
+            summary_partitioned.rejection_summary()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_incremental_applies_new_transactions_on_snapshot()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let snapshot_dir = tempfile::tempdir()?;
+        let snapshot = snapshot_dir.path().join("snapshot.jsonl");
+        let mut first_file = tempfile::NamedTempFile::new()?;
+        write!(
+            first_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n\
+             deposit, 1, 2, 1.5\n"
+        )?;
+        let mut second_file = tempfile::NamedTempFile::new()?;
+        write!(
+            second_file,
+            "type, client, tx, amount\n\
+             dispute, 1, 1,\n\
+             resolve, 1, 1,\n\
+             dispute, 1, 2,\n"
+        )?;
+
+        process_incremental_to_accounts(&snapshot, first_file.path(), ProcessOptions::new())
+            .await?;
+        let mut output = Vec::new();
+        process_incremental(
+            &snapshot,
+            second_file.path(),
+            &mut output,
+            ProcessOptions::new(),
+        )
+        .await?;
+
+        assert_eq!(
+            "client,available,held,total,locked\n\
+             1,2.0,1.5,3.5,false\n",
+            String::from_utf8(output)?
+        );
+        let summary = process_incremental_to_accounts(
+            &snapshot,
+            tempfile::NamedTempFile::new()?.path(),
+            ProcessOptions::new(),
+        )
+        .await?;
+        let account = summary.accounts().get(ClientId::new(1));
+        assert_eq!(Some(dec!(1.5)), account.map(|account| account.held()));
+        assert_eq!(Some(dec!(3.5)), account.map(|account| account.total()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_incremental_disputes_deposit_from_earlier_run()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let snapshot_dir = tempfile::tempdir()?;
+        let mut files = Vec::new();
+        for records in [
+            "deposit, 1, 1, 2.0\n",
+            "deposit, 1, 2, 1.5\n",
+            "dispute, 1, 1,\n\
+             chargeback, 1, 1,\n",
+        ] {
+            let mut file = tempfile::NamedTempFile::new()?;
+            write!(file, "type, client, tx, amount\n{records}")?;
+            files.push(file);
+        }
+
+        // Disputes of deposits from earlier runs are not parked until the end
+        // of the input.
+        for park_early_disputes in [false, true] {
+            let snapshot = snapshot_dir
+                .path()
+                .join(format!("snapshot_{park_early_disputes}.jsonl"));
+            let mut summary = None;
+            for file in &files {
+                summary = Some(
+                    process_incremental_to_accounts(
+                        &snapshot,
+                        file.path(),
+                        ProcessOptions::new()
+                            .with_error_policy(ErrorPolicy::Collect)
+                            .with_park_early_disputes(park_early_disputes),
+                    )
+                    .await?,
+                );
+            }
+
+            let summary = summary.expect("Files were processed.");
+            let account = summary.accounts().get(ClientId::new(1));
+            assert!(summary.rejections().is_empty());
+            assert_eq!(Some(dec!(1.5)), account.map(|account| account.total()));
+            assert_eq!(Some(true), account.map(|account| account.locked()));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "gen")]
+    #[tokio::test]
+    async fn process_transactions_generated_in_process_matches_csv()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = gen::Scenario::builder()
+            .clients(100)
+            .rows(2_000)
+            .dispute_rate(0.2)
+            .seed(7)
+            .build()?;
+        let transactions_csv = std::iter::once(gen::HEADER.to_string())
+            .chain(scenario.rows().map(|tx_row| tx_row.to_string()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (accounts_generated, _, _) = process_transactions(
+            stream::iter(scenario.rows().map(Transaction::try_from)),
+            &MemTxStore::new(),
+            ProcessOptions::new(),
+        )
+        .await?
+        .into_inner();
+        let (accounts_csv, _, _) = process_transactions(
+            crate::csv::TransactCsv::stream_reader(transactions_csv.as_bytes()),
+            &MemTxStore::new(),
+            ProcessOptions::new(),
+        )
+        .await?
+        .into_inner();
+
+        assert_eq!(100, accounts_generated.len());
+        assert_eq!(*accounts_csv, *accounts_generated);
+        Ok(())
+    }
+
+    #[cfg(feature = "gen")]
+    #[tokio::test]
+    async fn process_transactions_matches_generated_expected_accounts()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = gen::Scenario::builder()
+            .clients(50)
+            .rows(5_000)
+            .client_distribution(gen::ClientDistribution::Zipf { exponent: 1.1 })
+            .dispute_rate(0.2)
+            .chargeback_rate(0.3)
+            .seed(11)
+            .build()?;
+        let expected_accounts = gen::ExpectedAccounts::from_rows(scenario.rows());
+
+        let (accounts, _, _) = process_transactions(
+            stream::iter(scenario.rows().map(Transaction::try_from)),
+            &MemTxStore::new(),
+            ProcessOptions::new(),
+        )
+        .await?
+        .into_inner();
+
+        assert_eq!(expected_accounts.accounts().count(), accounts.len());
+        expected_accounts.accounts().for_each(|expected_account| {
+            let account = accounts
+                .get(ClientId::new(expected_account.client))
+                .expect("Expected account to be processed.");
+            let decimal = |amount: gen::Amount| {
+                rust_decimal::Decimal::from_i128_with_scale(i128::from(amount.units()), 4)
+            };
+            assert_eq!(
+                (
+                    decimal(expected_account.available),
+                    decimal(expected_account.held),
+                    expected_account.locked
+                ),
+                (account.available(), account.held(), account.locked()),
+                "client {}",
+                expected_account.client
+            );
+        });
+        Ok(())
+    }
+
+    #[cfg(feature = "gen")]
+    #[tokio::test]
+    async fn process_transactions_with_account_cache_matches_in_memory_accounts()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let scenario = gen::Scenario::builder()
+            .clients(2_000)
+            .rows(5_000)
+            .dispute_rate(0.2)
+            .chargeback_rate(0.3)
+            .seed(5)
+            .build()?;
+
+        let (accounts_in_memory, _, _) = process_transactions(
+            stream::iter(scenario.rows().map(Transaction::try_from)),
+            &MemTxStore::new(),
+            ProcessOptions::new(),
+        )
+        .await?
+        .into_inner();
+        let (accounts_paged, _, _) = process_transactions(
+            stream::iter(scenario.rows().map(Transaction::try_from)),
+            &MemTxStore::new(),
+            ProcessOptions::new()
+                .with_account_cache_capacity(NonZeroUsize::new(500).expect("Test data invalid.")),
+        )
+        .await?
+        .into_inner();
+
+        assert_eq!(*accounts_in_memory, *accounts_paged);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_partitioned_strict_fails_on_rejection()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let transactions = stream::iter(
+            (1..=100u32)
+                .map(|tx| {
+                    let client = ClientId::new(u16::try_from(tx % 4).expect("Test data invalid."));
+                    Transaction::from(Deposit::new(client, TxId::new(tx), dec!(1.0)))
+                })
+                .chain([Transaction::from(Withdrawal::new(
+                    ClientId::new(1),
+                    TxId::new(101),
+                    dec!(100.0),
+                ))])
+                .map(Ok),
+        );
+
+        let result = process_transactions(
+            transactions,
+            &MemTxStore::new(),
+            ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Strict)
+                .with_partitions(NonZeroUsize::new(3).expect("Test data invalid.")),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::TransactionRejected(
+                TxError::WithdrawalInsufficientAvailable { .. }
+            ))
+        ));
+        Ok(())
+    }
+
+    #[cfg(feature = "gen")]
+    #[tokio::test]
+    async fn process_transactions_partitioned_matches_serial_accounts()
+    -> Result<(), Box<dyn std::error::Error>> {
+        for seed in 0..8 {
+            let scenario = gen::Scenario::builder()
+                .clients(50)
+                .rows(5_000)
+                .dispute_rate(0.2)
+                .chargeback_rate(0.3)
+                .seed(seed)
+                .build()?;
+            let options = || {
+                ProcessOptions::new()
+                    .with_block_size(NonZeroUsize::new(64).expect("Test data invalid."))
+            };
+
+            let summary_serial = process_transactions(
+                stream::iter(scenario.rows().map(Transaction::try_from)),
+                &MemTxStore::new(),
+                options(),
+            )
+            .await?;
+            for partitions in [2, 3, 8] {
+                let summary_partitioned = process_transactions(
+                    stream::iter(scenario.rows().map(Transaction::try_from)),
+                    &MemTxStore::new(),
+                    options().with_partitions(
+                        NonZeroUsize::new(partitions).expect("Test data invalid."),
+                    ),
+                )
+                .await?;
+
+                assert_eq!(
+                    **summary_serial.accounts(),
+                    **summary_partitioned.accounts(),
+                    "seed {seed}, partitions {partitions}"
+                );
+                assert_eq!(
+                    summary_serial.rejection_summary().count(),
+                    summary_partitioned.rejection_summary().count(),
+                    "seed {seed}, partitions {partitions}"
+                );
+
+                // Partitions share the block store, and read each other's block
+                // files while they are written.
+                #[cfg(feature = "fs")]
+                {
+                    let summary_block_store = process_transactions(
+                        stream::iter(scenario.rows().map(Transaction::try_from)),
+                        &crate::TxBlockStore::try_new()?,
+                        options().with_partitions(
+                            NonZeroUsize::new(partitions).expect("Test data invalid."),
+                        ),
+                    )
+                    .await?;
+
+                    assert_eq!(
+                        **summary_serial.accounts(),
+                        **summary_block_store.accounts(),
+                        "seed {seed}, partitions {partitions}, block store"
+                    );
+                }
+            }
+
+            let expected_accounts = gen::ExpectedAccounts::from_rows(scenario.rows());
+            assert_eq!(
+                expected_accounts.accounts().count(),
+                summary_serial.accounts().len()
+            );
+        }
+        Ok(())
+    }
+
+    #[cfg(all(feature = "gen", feature = "tokio"))]
+    #[tokio::test]
+    async fn process_transactions_from_row_feed_channel() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let scenario = gen::Scenario::builder()
+            .clients(20)
+            .rows(1_000)
+            .seed(3)
+            .build()?;
+        let (mut receiver, join_handle) = gen::RowFeed::new(scenario.rows()).spawn(16);
+        let transactions = futures::StreamExt::map(
+            stream::poll_fn(move |cx| receiver.poll_recv(cx)),
+            Transaction::try_from,
+        );
+
+        let (accounts, _, _) =
+            process_transactions(transactions, &MemTxStore::new(), ProcessOptions::new())
+                .await?
+                .into_inner();
+
+        assert!(join_handle.await? >= 1_000);
+        assert_eq!(
+            gen::ExpectedAccounts::from_rows(scenario.rows())
+                .accounts()
+                .count(),
+            accounts.len()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_to_accounts_stops_when_cancelled() -> Result<(), Box<dyn std::error::Error>> {
+        let mut transactions_file = tempfile::NamedTempFile::new()?;
+        write!(
+            transactions_file,
+            "type, client, tx, amount\n\
+             deposit, 1, 1, 2.0\n"
+        )?;
+        let cancellation_token = CancellationToken::new();
+        cancellation_token.cancel();
+
+        let summary = process_to_accounts(
+            transactions_file.path(),
+            ProcessOptions::new().with_cancellation_token(cancellation_token),
+        )
+        .await?;
+
+        assert!(summary.is_cancelled());
+        assert!(summary.accounts().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_parks_disputes_until_deposit_is_applied()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let transactions = || {
+            stream::iter(
+                [
+                    Transaction::from(Dispute::new(client, TxId::new(1))),
+                    Transaction::from(Dispute::new(client, TxId::new(9))),
+                    Transaction::from(Deposit::new(client, TxId::new(1), dec!(3.0))),
+                    Transaction::from(Deposit::new(client, TxId::new(2), dec!(1.0))),
+                ]
+                .into_iter()
+                .map(Ok),
+            )
+        };
+        // The default block size persists each deposit before its dispute is
+        // processed, so the dispute must not be treated as seen.
+        let options = || ProcessOptions::new().with_error_policy(ErrorPolicy::Collect);
+
+        let summary = process_transactions(
+            transactions(),
+            &MemTxStore::new(),
+            options().with_park_early_disputes(true),
+        )
+        .await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(1.0)), account.map(|account| account.available()));
+        assert_eq!(Some(dec!(3.0)), account.map(|account| account.held()));
+        assert!(matches!(
+            summary.rejections(),
+            [TxError::DisputeTxNotFound { tx }] if *tx == TxId::new(9)
+        ));
+
+        let summary = process_transactions(transactions(), &MemTxStore::new(), options()).await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(4.0)), account.map(|account| account.available()));
+        assert_eq!(2, summary.rejections().len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_parks_resolve_and_chargeback_after_parked_dispute()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+        let process = |transaction: Transaction| async move {
+            process_transactions(
+                stream::iter(
+                    [
+                        Transaction::from(Dispute::new(client, tx)),
+                        transaction,
+                        Transaction::from(Deposit::new(client, tx, dec!(10.0))),
+                    ]
+                    .into_iter()
+                    .map(Ok),
+                ),
+                &MemTxStore::new(),
+                ProcessOptions::new()
+                    .with_error_policy(ErrorPolicy::Collect)
+                    .with_park_early_disputes(true),
+            )
+            .await
+        };
+
+        let summary = process(Transaction::from(Resolve::new(client, tx))).await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(10.0)), account.map(|account| account.available()));
+        assert_eq!(Some(dec!(0.0)), account.map(|account| account.held()));
+        assert!(summary.rejections().is_empty());
+
+        let summary = process(Transaction::from(Chargeback::new(client, tx))).await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(0.0)), account.map(|account| account.total()));
+        assert_eq!(Some(true), account.map(|account| account.locked()));
+        assert!(summary.rejections().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_does_not_park_dispute_of_stored_deposit()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+        let deposit = Transaction::from(Deposit::new(client, tx, dec!(10.0)));
+        let tx_store = MemTxStore::new();
+        tx_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
+        let mut accounts = Accounts::new();
+        accounts.insert(
+            client,
+            AccountBuilder::new(client)
+                .with_available(dec!(10.0))
+                .build(),
+        );
+
+        let summary = process_transactions_from(
+            stream::iter(
+                [
+                    Transaction::from(Dispute::new(client, tx)),
+                    Transaction::from(Withdrawal::new(client, TxId::new(2), dec!(10.0))),
+                    Transaction::from(Chargeback::new(client, tx)),
+                ]
+                .into_iter()
+                .map(Ok),
+            ),
+            &tx_store,
+            accounts,
+            &ProcessOptions::new()
+                .with_error_policy(ErrorPolicy::Collect)
+                .with_park_early_disputes(true),
+        )
+        .await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(0.0)), account.map(|account| account.total()));
+        assert_eq!(Some(true), account.map(|account| account.locked()));
+        assert!(matches!(
+            summary.rejections(),
+            [TxError::WithdrawalInsufficientAvailable { .. }]
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_applies_bare_chargeback_with_implicit_dispute()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let transactions = || {
+            stream::iter(
+                [
+                    Transaction::from(Deposit::new(client, TxId::new(1), dec!(3.0))),
+                    Transaction::from(Deposit::new(client, TxId::new(2), dec!(1.0))),
+                    Transaction::from(Chargeback::new(client, TxId::new(9))),
+                    Transaction::from(Chargeback::new(client, TxId::new(1))),
+                ]
+                .into_iter()
+                .map(Ok),
+            )
+        };
+        let options = || ProcessOptions::new().with_error_policy(ErrorPolicy::Collect);
+
+        let summary = process_transactions(
+            transactions(),
+            &MemTxStore::new(),
+            options().with_chargeback_policy(ChargebackPolicy::ImplicitDispute),
+        )
+        .await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(1.0)), account.map(|account| account.available()));
+        assert_eq!(Some(dec!(0.0)), account.map(|account| account.held()));
+        assert_eq!(Some(true), account.map(|account| account.locked()));
+        assert!(matches!(
+            summary.rejections(),
+            [TxError::DisputeTxNotFound { tx }] if *tx == TxId::new(9)
+        ));
+
+        let summary = process_transactions(transactions(), &MemTxStore::new(), options()).await?;
+
+        let account = summary.accounts().get(client);
+        assert_eq!(Some(dec!(4.0)), account.map(|account| account.available()));
+        assert_eq!(Some(false), account.map(|account| account.locked()));
+        assert!(matches!(
+            summary.rejections(),
+            [
+                TxError::ChargebackTxNotInDispute { .. },
+                TxError::ChargebackTxNotInDispute { .. }
+            ]
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_fails_when_run_times_out()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let timeout = Duration::from_millis(10);
+        let transactions = stream::iter([Ok(Transaction::from(Deposit::new(
+            ClientId::new(1),
+            TxId::new(1),
+            dec!(1.0),
+        )))]);
+
+        let result = process_transactions(
+            transactions,
+            &PendingTxStore,
+            ProcessOptions::new().with_run_timeout(timeout),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::RunTimeout { timeout: t }) if t == timeout));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn process_transactions_fails_when_lookup_times_out()
+    -> Result<(), Box<dyn std::error::Error>> {
+        #[derive(Debug)]
+        struct PendingLookupTxStore;
+
+        impl TxStore for PendingLookupTxStore {
+            async fn persist_block(&self, _transactions: &[Transaction]) -> Result<(), Error> {
+                Ok(())
+            }
+
+            fn find_transaction(
+                &self,
+                _tx: TxId,
+            ) -> impl Future<Output = Result<Option<Transaction>, Error>> {
+                future::pending()
+            }
+
+            async fn deposits(&self) -> Result<Vec<Transaction>, Error> {
+                Ok(Vec::new())
+            }
+        }
+
+        let timeout = Duration::from_millis(10);
+        let tx = TxId::new(1);
+        let transactions =
+            stream::iter([Ok(Transaction::from(Dispute::new(ClientId::new(1), tx)))]);
+
+        let result = process_transactions(
+            transactions,
+            &PendingLookupTxStore,
+            ProcessOptions::new().with_lookup_timeout(timeout),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::BlockStoreLookupTimeout { tx: t, timeout: d }) if t == tx && d == timeout
+        ));
+        Ok(())
+    }
+}
@@ -1,18 +1,88 @@
-use std::env;
+use clap::{error::ErrorKind, CommandFactory, Parser};
 
-use transact::Error;
+#[cfg(feature = "server")]
+use crate::cli::ServeCmd;
+#[cfg(feature = "otel")]
+use crate::cli::TraceExport;
+use crate::cli::{
+    init_logging, BenchCmd, CliArgs, CliCommand, CliError, DiffCmd, DisputesCmd, FollowCmd,
+    JournalVerifyCmd, LedgersCmd, ProcessArgs, ProcessCmd, QueryCmd, ReconcileCmd, ReplCmd,
+    ReplayCmd, SimulateCmd, StatsCmd, ValidateCmd, VerifyCmd,
+};
 
-fn main() -> Result<(), Error> {
-    // `nth(1)` skips executable path.
-    let file_path = env::args().nth(1);
-    if let Some(file_path) = file_path {
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .build()
-            .expect("Failed to initialize tokio runtime");
+mod cli;
 
-        rt.block_on(transact::process(file_path.as_ref(), tokio::io::stdout()))
-    } else {
-        eprintln!("This program expects the transactions file as the first argument.");
-        std::process::exit(1);
+fn main() {
+    let CliArgs {
+        command,
+        input,
+        verbose,
+        log_format,
+        #[cfg(feature = "otel")]
+        run_id,
+    } = CliArgs::parse();
+    let command = match (command, input) {
+        (Some(command), None) => command,
+        (None, Some(input)) => CliCommand::Process(Box::new(ProcessArgs::new(input))),
+        (Some(_), Some(_)) => CliArgs::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "The transactions file cannot be used with a command.",
+            )
+            .exit(),
+        (None, None) => CliArgs::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "Expected a command or the transactions file to process.",
+            )
+            .exit(),
+    };
+
+    #[cfg(feature = "otel")]
+    let trace_export = TraceExport::from_env(run_id, command.input_file());
+    let run_result = init_logging(
+        verbose,
+        log_format,
+        #[cfg(feature = "otel")]
+        trace_export,
+    )
+    .and_then(|logging_guard| {
+        let run_result = run(command);
+        logging_guard.shutdown();
+        run_result
+    });
+
+    if let Err(error) = run_result {
+        eprintln!("{error}");
+        let mut source = std::error::Error::source(&error);
+        while let Some(error) = source {
+            eprintln!("  caused by: {error}");
+            source = error.source();
+        }
+        std::process::exit(error.exit_code().into());
+    }
+}
+
+fn run(command: CliCommand) -> Result<(), CliError> {
+    match command {
+        CliCommand::Process(process_args) => ProcessCmd::run(*process_args),
+        CliCommand::Verify(verify_args) => VerifyCmd::run(verify_args),
+        CliCommand::Bench(bench_args) => BenchCmd::run(bench_args),
+        CliCommand::Follow => FollowCmd::run(),
+        CliCommand::Validate(validate_args) => ValidateCmd::run(validate_args),
+        CliCommand::Stats(stats_args) => StatsCmd::run(stats_args),
+        CliCommand::Diff(diff_args) => DiffCmd::run(diff_args),
+        CliCommand::Reconcile(reconcile_args) => ReconcileCmd::run(reconcile_args),
+        CliCommand::Ledgers(ledgers_args) => LedgersCmd::run(ledgers_args),
+        CliCommand::Query(query_args) => QueryCmd::run(query_args),
+        CliCommand::Disputes(disputes_args) => DisputesCmd::run(disputes_args),
+        CliCommand::Simulate(simulate_args) => SimulateCmd::run(simulate_args),
+        CliCommand::Repl => ReplCmd::run(),
+        CliCommand::Replay(replay_args) => ReplayCmd::run(replay_args),
+        CliCommand::JournalVerify(journal_verify_args) => {
+            JournalVerifyCmd::run(journal_verify_args)
+        }
+        #[cfg(feature = "server")]
+        CliCommand::Serve(serve_args) => ServeCmd::run(serve_args),
     }
 }
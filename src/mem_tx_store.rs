@@ -0,0 +1,140 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::{
+    model::{Transaction, TxId},
+    Error, TxStore,
+};
+
+/// Stores transactions in memory.
+///
+/// This does not need a filesystem, but memory usage grows with the number of
+/// deposits processed.
+#[derive(Debug, Default)]
+pub struct MemTxStore {
+    /// Deposits by transaction ID.
+    transactions: Mutex<HashMap<TxId, Transaction>>,
+}
+
+impl MemTxStore {
+    /// Returns a new empty `MemTxStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TxStore for MemTxStore {
+    async fn persist_block(&self, transactions: &[Transaction]) -> Result<(), Error> {
+        let mut stored_transactions = self
+            .transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        // Only persist deposits, as they're the only transactions that may be disputed.
+        transactions
+            .iter()
+            .filter(|transaction| matches!(transaction, Transaction::Deposit(_)))
+            .for_each(|transaction| {
+                // Keep the first transaction if the ID is duplicated.
+                stored_transactions
+                    .entry(transaction.tx())
+                    .or_insert_with(|| transaction.clone());
+            });
+
+        Ok(())
+    }
+
+    async fn find_transaction(&self, tx: TxId) -> Result<Option<Transaction>, Error> {
+        let transaction = self
+            .transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&tx)
+            .cloned();
+
+        Ok(transaction)
+    }
+
+    async fn contains(&self, tx: TxId) -> Result<bool, Error> {
+        let contains = self
+            .transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains_key(&tx);
+
+        Ok(contains)
+    }
+
+    async fn iter_range(&self, range: Range<TxId>) -> Result<Vec<Transaction>, Error> {
+        let mut deposits = self
+            .transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|(tx, _)| range.contains(tx))
+            .map(|(_, transaction)| transaction.clone())
+            .collect::<Vec<_>>();
+        deposits.sort_by_key(Transaction::tx);
+
+        Ok(deposits)
+    }
+
+    async fn deposits(&self) -> Result<Vec<Transaction>, Error> {
+        let deposits = self
+            .transactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect();
+
+        Ok(deposits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::MemTxStore;
+    use crate::{
+        model::{ClientId, TxId},
+        test_util::TransactionBuilder,
+        TxStore,
+    };
+
+    #[tokio::test]
+    async fn contains_and_iter_range_return_deposits() -> Result<(), Box<dyn std::error::Error>> {
+        let transactions = TransactionBuilder::new(ClientId::new(1))
+            .deposit(dec!(1.0))
+            .deposit(dec!(2.0))
+            .deposit(dec!(3.0))
+            .withdrawal(dec!(1.0))
+            .deposit(dec!(5.0))
+            .deposit(dec!(6.0))
+            .build();
+        let tx_store = MemTxStore::new();
+        tx_store.persist_block(&transactions[..4]).await?;
+        tx_store.persist_block(&transactions[4..]).await?;
+
+        assert!(tx_store.contains(TxId::new(5)).await?);
+        assert!(!tx_store.contains(TxId::new(4)).await?);
+        assert!(!tx_store.contains(TxId::new(7)).await?);
+        assert_eq!(
+            vec![
+                transactions[1].clone(),
+                transactions[2].clone(),
+                transactions[4].clone()
+            ],
+            tx_store.iter_range(TxId::new(2)..TxId::new(6)).await?
+        );
+        assert!(tx_store
+            .iter_range(TxId::new(7)..TxId::new(10))
+            .await?
+            .is_empty());
+        Ok(())
+    }
+}
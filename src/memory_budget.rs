@@ -0,0 +1,90 @@
+use std::{mem, num::NonZeroUsize};
+
+use crate::model::{Account, ClientId, Transaction};
+
+/// Approximate number of bytes that processing may hold in memory.
+///
+/// Half of the budget is for transactions buffered between reading,
+/// persisting, and applying them, and half is for accounts. When buffers
+/// would exceed their half, fewer transactions are read ahead, so reading
+/// waits for transactions to be applied. When accounts would exceed their
+/// half, accounts are stored in page files with the `fs` feature, keeping the
+/// most recently used ones in memory.
+///
+/// Sizes are estimated from the in-memory size of each transaction and
+/// account, and do not include transactions stored in memory for disputes,
+/// such as by a [`MemTxStore`].
+///
+/// [`MemTxStore`]: crate::MemTxStore
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryBudget {
+    /// Maximum number of bytes.
+    bytes: NonZeroUsize,
+}
+
+impl MemoryBudget {
+    /// Approximate number of bytes held for each buffered transaction.
+    pub const TRANSACTION_BYTES: usize = mem::size_of::<Transaction>();
+    /// Approximate number of bytes held for each account, including its map
+    /// entry and a few disputed transactions.
+    pub const ACCOUNT_BYTES: usize = mem::size_of::<(ClientId, Account)>() + 64;
+
+    /// Returns a `MemoryBudget` of the given number of bytes.
+    pub fn new(bytes: NonZeroUsize) -> Self {
+        Self { bytes }
+    }
+
+    /// Returns the maximum number of bytes.
+    pub fn bytes(&self) -> NonZeroUsize {
+        self.bytes
+    }
+
+    /// Returns the number of transactions each partition may buffer.
+    pub(crate) fn transaction_capacity(&self, partitions: NonZeroUsize) -> NonZeroUsize {
+        Self::capacity(self.bytes.get() / 2 / partitions, Self::TRANSACTION_BYTES)
+    }
+
+    /// Returns the number of accounts each partition may keep in memory.
+    #[cfg(feature = "fs")]
+    pub(crate) fn account_capacity(&self, partitions: NonZeroUsize) -> NonZeroUsize {
+        Self::capacity(self.bytes.get() / 2 / partitions, Self::ACCOUNT_BYTES)
+    }
+
+    /// Returns the number of items of `item_bytes` that fit in `bytes`, and at
+    /// least one.
+    fn capacity(bytes: usize, item_bytes: usize) -> NonZeroUsize {
+        NonZeroUsize::new(bytes / item_bytes.max(1)).unwrap_or(NonZeroUsize::MIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use super::MemoryBudget;
+
+    #[test]
+    fn transaction_capacity_is_shared_between_partitions() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let bytes = NonZeroUsize::new(MemoryBudget::TRANSACTION_BYTES * 1_000).ok_or("zero")?;
+        let memory_budget = MemoryBudget::new(bytes);
+
+        assert_eq!(
+            500,
+            memory_budget.transaction_capacity(NonZeroUsize::MIN).get()
+        );
+        assert_eq!(
+            125,
+            memory_budget
+                .transaction_capacity(NonZeroUsize::new(4).ok_or("zero")?)
+                .get()
+        );
+        assert_eq!(
+            1,
+            MemoryBudget::new(NonZeroUsize::MIN)
+                .transaction_capacity(NonZeroUsize::MIN)
+                .get()
+        );
+        Ok(())
+    }
+}
@@ -5,13 +5,21 @@
 
 pub use self::{
     account::{Account, TotalOverflow},
-    accounts::Accounts,
+    account_event::AccountEvent,
+    account_rejections::AccountRejections,
+    account_state::AccountState,
+    account_summary::AccountSummary,
+    accounts::{Accounts, AccountsMergeConflict},
     client_id::ClientId,
     transaction::{Chargeback, Deposit, Dispute, Resolve, Transaction, Withdrawal},
     tx_id::TxId,
 };
 
 mod account;
+mod account_event;
+mod account_rejections;
+mod account_state;
+mod account_summary;
 mod accounts;
 mod client_id;
 mod transaction;
@@ -1,30 +1,67 @@
 use std::collections::HashSet;
 
+use crate::model::{AccountRejections, ClientId, TxId};
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
-use serde::{Deserialize, Serialize};
-
-use crate::model::{ClientId, TxId};
 
 /// Error when `available` and `held` amounts will overflow when added together.
 #[derive(Debug)]
 pub struct TotalOverflow;
 
 /// Client account state.
-#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+///
+/// Balances are normalized like transaction amounts, so `1.50` is stored as
+/// `1.5` and zero has no trailing zeros. Reports that print balances next to
+/// transaction amounts are formatted consistently.
+///
+/// This is not serialized directly, so fields may be added without changing
+/// output. Use [`AccountRecord`] for the accounts output schema,
+/// [`AccountState`] to serialize all fields, or [`AccountSummary`] for amounts
+/// rounded for display.
+///
+/// Equality compares the account state, and ignores the
+/// [rejections](Account::rejections) annotation, so accounts are equal when
+/// they are reached through a different number of rejected transactions.
+///
+/// [`AccountRecord`]: crate::csv::AccountRecord
+/// [`AccountState`]: crate::model::AccountState
+/// [`AccountSummary`]: crate::model::AccountSummary
+#[derive(Debug)]
 pub struct Account {
     client: ClientId,
-    #[serde(with = "rust_decimal::serde::float")]
     available: Decimal,
-    #[serde(with = "rust_decimal::serde::float")]
     held: Decimal,
-    #[serde(with = "rust_decimal::serde::float")]
     total: Decimal,
     locked: bool,
-    #[serde(skip)]
     disputed_txs: HashSet<TxId>,
+    charged_back_txs: HashSet<TxId>,
+    rejections: AccountRejections,
+}
+
+impl PartialEq for Account {
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            client,
+            available,
+            held,
+            total,
+            locked,
+            disputed_txs,
+            charged_back_txs,
+            rejections: _,
+        } = self;
+
+        *client == other.client
+            && *available == other.available
+            && *held == other.held
+            && *total == other.total
+            && *locked == other.locked
+            && *disputed_txs == other.disputed_txs
+            && *charged_back_txs == other.charged_back_txs
+    }
 }
 
+impl Eq for Account {}
+
 impl Account {
     /// Returns a new `Account` with the provided values.
     pub fn try_new(
@@ -38,20 +75,46 @@ impl Account {
 
         Ok(Self {
             client,
-            available,
-            held,
-            total,
+            available: available.normalize(),
+            held: held.normalize(),
+            total: total.normalize(),
             locked,
             disputed_txs,
+            charged_back_txs: HashSet::new(),
+            rejections: AccountRejections::default(),
         })
     }
 
+    /// Returns a new `Account` with the provided balances, without checking
+    /// that `total` is the sum of `available` and `held`.
+    ///
+    /// This is used for accounts read from accounts output, which may be
+    /// checked for such discrepancies.
+    pub(crate) fn from_balances(
+        client: ClientId,
+        available: Decimal,
+        held: Decimal,
+        total: Decimal,
+        locked: bool,
+    ) -> Self {
+        Self {
+            client,
+            available: available.normalize(),
+            held: held.normalize(),
+            total: total.normalize(),
+            locked,
+            disputed_txs: HashSet::new(),
+            charged_back_txs: HashSet::new(),
+            rejections: AccountRejections::default(),
+        }
+    }
+
     /// Returns a new empty `Account`.
     pub fn empty(client: ClientId) -> Self {
         // Should be sensible defaults
-        let available = dec!(0.0);
-        let held = dec!(0.0);
-        let total = dec!(0.0);
+        let available = Decimal::ZERO;
+        let held = Decimal::ZERO;
+        let total = Decimal::ZERO;
         let locked = false;
         let disputed_txs = HashSet::new();
 
@@ -62,9 +125,45 @@ impl Account {
             total,
             locked,
             disputed_txs,
+            charged_back_txs: HashSet::new(),
+            rejections: AccountRejections::default(),
         }
     }
 
+    /// Sets the deposits that have been charged back.
+    pub fn with_charged_back_txs(mut self, charged_back_txs: HashSet<TxId>) -> Self {
+        self.charged_back_txs = charged_back_txs;
+        self
+    }
+
+    /// Sets the rejected transactions attributed to this account.
+    pub fn with_rejections(mut self, rejections: AccountRejections) -> Self {
+        self.rejections = rejections;
+        self
+    }
+
+    /// Sets the available and held funds, and the total as their sum.
+    ///
+    /// The account is unchanged if the total overflows.
+    pub(crate) fn try_set_balances(
+        &mut self,
+        available: Decimal,
+        held: Decimal,
+    ) -> Result<(), TotalOverflow> {
+        self.total = available
+            .checked_add(held)
+            .ok_or(TotalOverflow)?
+            .normalize();
+        self.available = available.normalize();
+        self.held = held.normalize();
+        Ok(())
+    }
+
+    /// Locks the account.
+    pub(crate) fn lock(&mut self) {
+        self.locked = true;
+    }
+
     /// Returns the account's client.
     pub fn client(&self) -> ClientId {
         self.client
@@ -94,4 +193,36 @@ impl Account {
     pub fn disputed_txs(&self) -> &HashSet<TxId> {
         &self.disputed_txs
     }
+
+    /// Returns a mutable reference to the open disputed transactions.
+    pub(crate) fn disputed_txs_mut(&mut self) -> &mut HashSet<TxId> {
+        &mut self.disputed_txs
+    }
+
+    /// Returns the deposits that have been charged back.
+    ///
+    /// Disputes of these transactions are rejected with
+    /// [`TxError::TxAlreadyChargedBack`].
+    ///
+    /// [`TxError::TxAlreadyChargedBack`]: crate::TxError::TxAlreadyChargedBack
+    pub fn charged_back_txs(&self) -> &HashSet<TxId> {
+        &self.charged_back_txs
+    }
+
+    /// Returns a mutable reference to the deposits that have been charged
+    /// back.
+    pub(crate) fn charged_back_txs_mut(&mut self) -> &mut HashSet<TxId> {
+        &mut self.charged_back_txs
+    }
+
+    /// Returns the rejected transactions attributed to this account.
+    pub fn rejections(&self) -> &AccountRejections {
+        &self.rejections
+    }
+
+    /// Returns a mutable reference to the rejected transactions attributed to
+    /// this account.
+    pub(crate) fn rejections_mut(&mut self) -> &mut AccountRejections {
+        &mut self.rejections
+    }
 }
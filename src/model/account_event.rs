@@ -0,0 +1,179 @@
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::model::{Account, ClientId, Transaction, TxId};
+
+/// Change to an account from an applied transaction.
+///
+/// Unlike transactions, events only describe changes that were applied, and
+/// carry the amount of every change, so replaying them reproduces the account
+/// balances without looking up disputed deposits.
+///
+/// Serializes as a JSON object with the event name in the `event` field, e.g.
+/// `{"event":"FundsHeld","client":1,"tx":2,"amount":"5.0"}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "event")]
+pub enum AccountEvent {
+    /// Funds were deposited into the available funds.
+    FundsDeposited {
+        /// Client ID.
+        client: ClientId,
+        /// Deposit transaction ID.
+        tx: TxId,
+        /// Amount deposited.
+        amount: Decimal,
+    },
+    /// Funds were withdrawn from the available funds.
+    FundsWithdrawn {
+        /// Client ID.
+        client: ClientId,
+        /// Withdrawal transaction ID.
+        tx: TxId,
+        /// Amount withdrawn.
+        amount: Decimal,
+    },
+    /// Available funds were held by a dispute.
+    FundsHeld {
+        /// Client ID.
+        client: ClientId,
+        /// Disputed deposit transaction ID.
+        tx: TxId,
+        /// Amount held.
+        amount: Decimal,
+    },
+    /// Held funds were released to the available funds by a resolve.
+    FundsReleased {
+        /// Client ID.
+        client: ClientId,
+        /// Disputed deposit transaction ID.
+        tx: TxId,
+        /// Amount released.
+        amount: Decimal,
+    },
+    /// Held funds were removed by a chargeback.
+    FundsReversed {
+        /// Client ID.
+        client: ClientId,
+        /// Disputed deposit transaction ID.
+        tx: TxId,
+        /// Amount removed.
+        amount: Decimal,
+    },
+    /// Account was locked by a chargeback.
+    AccountLocked {
+        /// Client ID.
+        client: ClientId,
+        /// Disputed deposit transaction ID.
+        tx: TxId,
+    },
+}
+
+impl AccountEvent {
+    /// Returns the events for a transaction that was applied to an account.
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction`: The transaction that was applied.
+    /// * `held_before`: The account's held funds before the transaction was
+    ///   applied, as disputes, resolves, and chargebacks do not carry their
+    ///   amounts.
+    /// * `account`: The account after the transaction was applied.
+    pub fn from_applied(
+        transaction: &Transaction,
+        held_before: Decimal,
+        account: &Account,
+    ) -> Vec<Self> {
+        let client = transaction.client();
+        let tx = transaction.tx();
+        let held_difference = (account.held() - held_before).abs();
+
+        match transaction {
+            Transaction::Deposit(deposit) => vec![Self::FundsDeposited {
+                client,
+                tx,
+                amount: deposit.amount(),
+            }],
+            Transaction::Withdrawal(withdrawal) => vec![Self::FundsWithdrawn {
+                client,
+                tx,
+                amount: withdrawal.amount(),
+            }],
+            Transaction::Dispute(_) => vec![Self::FundsHeld {
+                client,
+                tx,
+                amount: held_difference,
+            }],
+            Transaction::Resolve(_) => vec![Self::FundsReleased {
+                client,
+                tx,
+                amount: held_difference,
+            }],
+            Transaction::Chargeback(_) => vec![
+                Self::FundsReversed {
+                    client,
+                    tx,
+                    amount: held_difference,
+                },
+                Self::AccountLocked { client, tx },
+            ],
+        }
+    }
+
+    /// Returns the client whose account changed.
+    pub fn client(&self) -> ClientId {
+        match self {
+            Self::FundsDeposited { client, .. }
+            | Self::FundsWithdrawn { client, .. }
+            | Self::FundsHeld { client, .. }
+            | Self::FundsReleased { client, .. }
+            | Self::FundsReversed { client, .. }
+            | Self::AccountLocked { client, .. } => *client,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::AccountEvent;
+    use crate::{
+        model::{Chargeback, ClientId, Transaction, TxId},
+        test_util::AccountBuilder,
+    };
+
+    #[test]
+    fn from_applied_chargeback_reverses_held_funds_and_locks_account()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+        let account = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .with_held(dec!(0.5))
+            .with_locked(true)
+            .build();
+
+        let account_events = AccountEvent::from_applied(
+            &Transaction::from(Chargeback::new(client, tx)),
+            dec!(3.0),
+            &account,
+        );
+
+        assert_eq!(
+            vec![
+                AccountEvent::FundsReversed {
+                    client,
+                    tx,
+                    amount: dec!(2.5),
+                },
+                AccountEvent::AccountLocked { client, tx },
+            ],
+            account_events
+        );
+        assert_eq!(
+            r#"{"event":"FundsReversed","client":1,"tx":2,"amount":"2.5"}"#,
+            serde_json::to_string(&account_events[0])?
+        );
+        Ok(())
+    }
+}
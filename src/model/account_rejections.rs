@@ -0,0 +1,55 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+/// Rejected transactions attributed to an account.
+///
+/// This is an annotation for support and triage -- it does not affect the
+/// account's balances, and is not part of the accounts output schema.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountRejections {
+    /// Number of the account's transactions that were rejected.
+    count: u64,
+    /// Code of the last rejection, e.g. `"WITHDRAWAL_INSUFFICIENT_AVAILABLE"`.
+    last_code: Option<Cow<'static, str>>,
+}
+
+impl AccountRejections {
+    /// Returns `AccountRejections` with the given count and last rejection
+    /// code.
+    pub fn new(count: u64, last_code: Option<Cow<'static, str>>) -> Self {
+        Self { count, last_code }
+    }
+
+    /// Returns the number of the account's transactions that were rejected.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the code of the last rejection, if any transaction was
+    /// rejected.
+    pub fn last_code(&self) -> Option<&str> {
+        self.last_code.as_deref()
+    }
+
+    /// Returns whether no transactions were rejected.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Records a rejected transaction with the given rejection code.
+    pub fn record(&mut self, code: &'static str) {
+        self.count = self.count.saturating_add(1);
+        self.last_code = Some(Cow::Borrowed(code));
+    }
+
+    /// Adds the rejections from `other`, e.g. from another shard.
+    ///
+    /// The last rejection code is taken from `other` if it has one.
+    pub fn merge(&mut self, other: &AccountRejections) {
+        self.count = self.count.saturating_add(other.count);
+        if let Some(last_code) = other.last_code.as_ref() {
+            self.last_code = Some(last_code.clone());
+        }
+    }
+}
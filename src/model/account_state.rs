@@ -0,0 +1,166 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Account, AccountRejections, ClientId, TotalOverflow, TxId};
+
+/// Full state of an [`Account`], for serialization.
+///
+/// Unlike the accounts output schema, this includes the open disputed
+/// transactions, charged back transactions, and rejections, and amounts are
+/// serialized as strings so they round-trip exactly. Use this to persist
+/// accounts that are loaded to continue processing.
+///
+/// # Examples
+///
+/// ```rust
+/// use transact::model::{Account, AccountState, ClientId};
+///
+/// let account = Account::empty(ClientId::new(1));
+/// let account_state = AccountState::from(&account);
+///
+/// assert_eq!(account, Account::try_from(account_state)?);
+/// # Ok::<(), transact::model::TotalOverflow>(())
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct AccountState {
+    /// Client ID.
+    client: ClientId,
+    /// Available funds.
+    available: Decimal,
+    /// Funds held by open disputes.
+    held: Decimal,
+    /// Whether the account is locked.
+    locked: bool,
+    /// Deposits in dispute, ordered by transaction ID.
+    disputed_txs: Vec<TxId>,
+    /// Deposits that have been charged back, ordered by transaction ID.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    charged_back_txs: Vec<TxId>,
+    /// Rejected transactions attributed to the account.
+    #[serde(default, skip_serializing_if = "AccountRejections::is_empty")]
+    rejections: AccountRejections,
+}
+
+impl AccountState {
+    /// Returns the account's client.
+    pub fn client(&self) -> ClientId {
+        self.client
+    }
+
+    /// Returns the available funds in the account.
+    pub fn available(&self) -> Decimal {
+        self.available
+    }
+
+    /// Returns the held funds in the account.
+    pub fn held(&self) -> Decimal {
+        self.held
+    }
+
+    /// Returns whether the account is locked.
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Returns the deposits in dispute, ordered by transaction ID.
+    pub fn disputed_txs(&self) -> &[TxId] {
+        &self.disputed_txs
+    }
+
+    /// Returns the deposits that have been charged back, ordered by
+    /// transaction ID.
+    pub fn charged_back_txs(&self) -> &[TxId] {
+        &self.charged_back_txs
+    }
+
+    /// Returns the rejected transactions attributed to the account.
+    pub fn rejections(&self) -> &AccountRejections {
+        &self.rejections
+    }
+}
+
+impl From<&Account> for AccountState {
+    fn from(account: &Account) -> Self {
+        let mut disputed_txs = account.disputed_txs().iter().copied().collect::<Vec<_>>();
+        disputed_txs.sort();
+        let mut charged_back_txs = account
+            .charged_back_txs()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        charged_back_txs.sort();
+
+        Self {
+            client: account.client(),
+            available: account.available(),
+            held: account.held(),
+            locked: account.locked(),
+            disputed_txs,
+            charged_back_txs,
+            rejections: account.rejections().clone(),
+        }
+    }
+}
+
+impl TryFrom<AccountState> for Account {
+    type Error = TotalOverflow;
+
+    fn try_from(account_state: AccountState) -> Result<Self, TotalOverflow> {
+        let AccountState {
+            client,
+            available,
+            held,
+            locked,
+            disputed_txs,
+            charged_back_txs,
+            rejections,
+        } = account_state;
+
+        Account::try_new(
+            client,
+            available,
+            held,
+            locked,
+            disputed_txs.into_iter().collect(),
+        )
+        .map(|account| {
+            account
+                .with_charged_back_txs(charged_back_txs.into_iter().collect())
+                .with_rejections(rejections)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::AccountState;
+    use crate::{
+        model::{Account, ClientId, TxId},
+        test_util::AccountBuilder,
+    };
+
+    #[test]
+    fn round_trips_account_with_disputed_txs() -> Result<(), Box<dyn std::error::Error>> {
+        let account = AccountBuilder::new(ClientId::new(1))
+            .with_available(dec!(0.1))
+            .with_held(dec!(79228162514264337593543950.335))
+            .with_locked(true)
+            .with_disputed_txs([TxId::new(3), TxId::new(1)])
+            .build();
+
+        let json = serde_json::to_string(&AccountState::from(&account))?;
+        let account_state = serde_json::from_str::<AccountState>(&json)?;
+
+        assert_eq!(
+            r#"{"client":1,"available":"0.1","held":"79228162514264337593543950.335","locked":true,"disputed_txs":[1,3]}"#,
+            json
+        );
+        assert_eq!(
+            account,
+            Account::try_from(account_state).expect("Test data invalid.")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::{
+    csv::AccountRecord,
+    model::{Account, ClientId},
+};
+
+/// Human facing view of an [`Account`], with amounts rounded for display.
+///
+/// This serializes to the accounts output schema, as an [`AccountRecord`], and
+/// displays the account on a single line, e.g. `client 1: available 1.5, held
+/// 0, total 1.5, locked false`. Amounts are rounded to
+/// [`AccountSummary::AMOUNT_SCALE`] decimal places, using banker's rounding,
+/// without trailing zeros.
+///
+/// # Examples
+///
+/// ```rust
+/// use rust_decimal_macros::dec;
+/// use transact::model::{Account, AccountSummary, ClientId};
+///
+/// let account = Account::try_new(
+///     ClientId::new(1),
+///     dec!(1.23456),
+///     dec!(0.0),
+///     false,
+///     Default::default(),
+/// )?;
+/// let account_summary = AccountSummary::from(&account);
+///
+/// assert_eq!(dec!(1.2346), account_summary.available());
+/// assert_eq!(
+///     "client 1: available 1.2346, held 0, total 1.2346, locked false",
+///     account_summary.to_string()
+/// );
+/// # Ok::<(), transact::model::TotalOverflow>(())
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(into = "AccountRecord")]
+pub struct AccountSummary {
+    /// Client ID.
+    client: ClientId,
+    /// Available funds.
+    available: Decimal,
+    /// Held funds.
+    held: Decimal,
+    /// Total funds.
+    total: Decimal,
+    /// Whether the account is locked.
+    locked: bool,
+}
+
+impl AccountSummary {
+    /// Number of decimal places that amounts are rounded to.
+    pub const AMOUNT_SCALE: u32 = 4;
+
+    /// Returns the account's client.
+    pub fn client(&self) -> ClientId {
+        self.client
+    }
+
+    /// Returns the rounded available funds in the account.
+    pub fn available(&self) -> Decimal {
+        self.available
+    }
+
+    /// Returns the rounded held funds in the account.
+    pub fn held(&self) -> Decimal {
+        self.held
+    }
+
+    /// Returns the rounded total funds in the account.
+    pub fn total(&self) -> Decimal {
+        self.total
+    }
+
+    /// Returns whether the account is locked.
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+}
+
+impl From<&Account> for AccountSummary {
+    fn from(account: &Account) -> Self {
+        Self {
+            client: account.client(),
+            available: account.available().round_dp(Self::AMOUNT_SCALE).normalize(),
+            held: account.held().round_dp(Self::AMOUNT_SCALE).normalize(),
+            total: account.total().round_dp(Self::AMOUNT_SCALE).normalize(),
+            locked: account.locked(),
+        }
+    }
+}
+
+impl fmt::Display for AccountSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            client,
+            available,
+            held,
+            total,
+            locked,
+        } = self;
+        write!(
+            f,
+            "client {client}: available {available}, held {held}, total {total}, locked {locked}"
+        )
+    }
+}
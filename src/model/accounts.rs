@@ -1,18 +1,57 @@
 use std::{
-    collections::{hash_map::IntoValues, HashMap},
+    collections::{
+        hash_map::{Entry, IntoValues},
+        HashMap,
+    },
+    fmt,
     ops::{Deref, DerefMut},
 };
 
-use serde::{Deserialize, Serialize};
+use crate::model::{Account, ClientId, TxId};
 
-use crate::model::{Account, ClientId};
+/// Conflict found when merging two [`Accounts`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountsMergeConflict {
+    /// Summing the client's balances would overflow.
+    ///
+    /// The client's account is left unchanged.
+    BalanceOverflow {
+        /// Client ID.
+        client: ClientId,
+    },
+    /// Transaction is disputed in both accounts.
+    ///
+    /// This indicates both partial results processed the same transaction, so
+    /// its amount is held twice.
+    DisputedTxDuplicate {
+        /// Client ID.
+        client: ClientId,
+        /// Transaction ID that is disputed.
+        tx: TxId,
+    },
+}
+
+impl fmt::Display for AccountsMergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BalanceOverflow { client } => write!(
+                f,
+                "Summing balances would overflow when merging accounts: client {client}."
+            ),
+            Self::DisputedTxDuplicate { client, tx } => write!(
+                f,
+                "Transaction is disputed in both accounts being merged: client {client}, transaction {tx}."
+            ),
+        }
+    }
+}
 
 /// Working record of all accounts. `HashMap<ClientId, Account>` newtype.
 ///
 /// As long as we only hold up to `u16` accounts, the amount of working memory
 /// should be `2^16 * size_of::<Account>()` (27 bytes), which should be about
 /// 1.7 MB, plus any memory allocated while processing transactions.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug)]
 pub struct Accounts(HashMap<ClientId, Account>);
 
 impl Accounts {
@@ -21,6 +60,105 @@ impl Accounts {
         Self(HashMap::new())
     }
 
+    /// Merges `other` into these accounts.
+    ///
+    /// Balances are summed, disputed and charged back transactions are
+    /// unioned, and an account is locked if it is locked in either. This is
+    /// used to combine results that were processed separately, such as per
+    /// file or per shard.
+    ///
+    /// Returns the conflicts found while merging, which is empty when the
+    /// merge is clean.
+    pub fn merge(&mut self, other: Accounts) -> Vec<AccountsMergeConflict> {
+        other
+            .into_values()
+            .fold(Vec::new(), |mut conflicts, account_other| {
+                match self.0.entry(account_other.client()) {
+                    Entry::Occupied(mut entry) => {
+                        let account = entry.get_mut();
+                        let client = account.client();
+                        account_other
+                            .disputed_txs()
+                            .intersection(account.disputed_txs())
+                            .for_each(|tx| {
+                                conflicts.push(AccountsMergeConflict::DisputedTxDuplicate {
+                                    client,
+                                    tx: *tx,
+                                })
+                            });
+
+                        let account_merged = account
+                            .available()
+                            .checked_add(account_other.available())
+                            .zip(account.held().checked_add(account_other.held()))
+                            .and_then(|(available, held)| {
+                                let disputed_txs = account
+                                    .disputed_txs()
+                                    .union(account_other.disputed_txs())
+                                    .copied()
+                                    .collect();
+                                Account::try_new(
+                                    client,
+                                    available,
+                                    held,
+                                    account.locked() || account_other.locked(),
+                                    disputed_txs,
+                                )
+                                .ok()
+                            });
+
+                        match account_merged {
+                            Some(account_merged) => {
+                                let charged_back_txs = account
+                                    .charged_back_txs()
+                                    .union(account_other.charged_back_txs())
+                                    .copied()
+                                    .collect();
+                                let mut rejections = account.rejections().clone();
+                                rejections.merge(account_other.rejections());
+                                *account = account_merged
+                                    .with_charged_back_txs(charged_back_txs)
+                                    .with_rejections(rejections);
+                            }
+                            None => {
+                                conflicts.push(AccountsMergeConflict::BalanceOverflow { client })
+                            }
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(account_other);
+                    }
+                }
+
+                conflicts
+            })
+    }
+
+    /// Returns the client's account, if it has one.
+    pub fn get(&self, client: ClientId) -> Option<&Account> {
+        self.0.get(&client)
+    }
+
+    /// Returns an iterator of accounts in order of client ID.
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &Account> {
+        let mut accounts = self.0.values().collect::<Vec<&Account>>();
+        accounts.sort_unstable_by_key(|account| account.client());
+        accounts.into_iter()
+    }
+
+    /// Returns an iterator of locked accounts in order of client ID.
+    pub fn locked(&self) -> impl Iterator<Item = &Account> {
+        self.iter_sorted().filter(|account| account.locked())
+    }
+
+    /// Returns the number of accounts with available or held funds.
+    pub fn len_with_nonzero_balance(&self) -> usize {
+        self.0
+            .values()
+            .filter(|account| !account.available().is_zero() || !account.held().is_zero())
+            .count()
+    }
+
     /// Returns an iterator of accounts.
     pub fn into_values(self) -> IntoValues<ClientId, Account> {
         self.0.into_values()
@@ -53,3 +191,157 @@ impl Extend<(ClientId, Account)> for Accounts {
         self.0.extend(iter)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    use super::{Accounts, AccountsMergeConflict};
+    use crate::{
+        model::{Account, ClientId, TxId},
+        test_util::AccountBuilder,
+    };
+
+    #[test]
+    fn iter_sorted_and_locked_are_in_client_order() {
+        let mut accounts = Accounts::new();
+        accounts.extend([3, 1, 2].into_iter().map(|client| {
+            let client = ClientId::new(client);
+            let account = Account::try_new(
+                client,
+                Decimal::from(client.into_inner() - 1),
+                dec!(0.0),
+                client != ClientId::new(2),
+                HashSet::new(),
+            )
+            .expect("Test data invalid.");
+            (client, account)
+        }));
+
+        assert_eq!(
+            vec![1, 2, 3],
+            accounts
+                .iter_sorted()
+                .map(|account| account.client().into_inner())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![1, 3],
+            accounts
+                .locked()
+                .map(|account| account.client().into_inner())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, accounts.len_with_nonzero_balance());
+    }
+
+    #[test]
+    fn merge_sums_balances_unions_disputes_and_locks() {
+        let client = ClientId::new(1);
+        let tx_one = TxId::new(1);
+        let tx_two = TxId::new(2);
+        let mut accounts = Accounts::new();
+        accounts.insert(
+            client,
+            AccountBuilder::new(client)
+                .with_available(dec!(1.0))
+                .with_held(dec!(2.0))
+                .with_disputed_txs([tx_one])
+                .build(),
+        );
+        let mut accounts_other = Accounts::new();
+        accounts_other.insert(
+            client,
+            AccountBuilder::new(client)
+                .with_available(dec!(3.0))
+                .with_held(dec!(4.0))
+                .with_locked(true)
+                .with_disputed_txs([tx_two])
+                .build(),
+        );
+
+        let conflicts = accounts.merge(accounts_other);
+
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(4.0))
+            .with_held(dec!(6.0))
+            .with_locked(true)
+            .with_disputed_txs([tx_one, tx_two])
+            .build();
+        assert!(conflicts.is_empty());
+        assert_eq!(Some(&account_expected), accounts.get(client));
+    }
+
+    #[test]
+    fn merge_inserts_accounts_only_in_other() {
+        let client_one = ClientId::new(1);
+        let client_two = ClientId::new(2);
+        let mut accounts = Accounts::new();
+        accounts.insert(client_one, Account::empty(client_one));
+        let mut accounts_other = Accounts::new();
+        accounts_other.insert(client_two, Account::empty(client_two));
+
+        let conflicts = accounts.merge(accounts_other);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(Some(&Account::empty(client_one)), accounts.get(client_one));
+        assert_eq!(Some(&Account::empty(client_two)), accounts.get(client_two));
+    }
+
+    #[test]
+    fn merge_reports_duplicate_disputed_tx() {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+        let account = AccountBuilder::new(client)
+            .with_held(dec!(1.0))
+            .with_disputed_txs([tx])
+            .build();
+        let mut accounts = Accounts::new();
+        accounts.insert(client, account);
+        let mut accounts_other = Accounts::new();
+        accounts_other.insert(
+            client,
+            AccountBuilder::new(client)
+                .with_held(dec!(1.0))
+                .with_disputed_txs([tx])
+                .build(),
+        );
+
+        let conflicts = accounts.merge(accounts_other);
+
+        assert_eq!(
+            vec![AccountsMergeConflict::DisputedTxDuplicate { client, tx }],
+            conflicts
+        );
+    }
+
+    #[test]
+    fn merge_reports_balance_overflow_and_leaves_account_unchanged() {
+        let client = ClientId::new(1);
+        let account = Account::try_new(client, Decimal::MAX, dec!(0.0), false, HashSet::new())
+            .expect("Test data invalid.");
+        let mut accounts = Accounts::new();
+        accounts.insert(client, account);
+        let mut accounts_other = Accounts::new();
+        accounts_other.insert(
+            client,
+            AccountBuilder::new(client)
+                .with_available(dec!(1.0))
+                .build(),
+        );
+
+        let conflicts = accounts.merge(accounts_other);
+
+        let account_expected =
+            Account::try_new(client, Decimal::MAX, dec!(0.0), false, HashSet::new())
+                .expect("Test data invalid.");
+        assert_eq!(
+            vec![AccountsMergeConflict::BalanceOverflow { client }],
+            conflicts
+        );
+        assert_eq!(Some(&account_expected), accounts.get(client));
+    }
+}
@@ -1,10 +1,15 @@
+use std::fmt;
+
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-use crate::model::{ClientId, TxId};
+use crate::{
+    csv::TxType,
+    model::{ClientId, TxId},
+};
 
 /// Types of transactions.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Transaction {
     /// Credit to the client's asset account.
     Deposit(Deposit),
@@ -45,6 +50,49 @@ impl Transaction {
             Self::Chargeback(chargeback) => chargeback.tx(),
         }
     }
+
+    /// Returns this transaction's type.
+    pub fn kind(&self) -> TxType {
+        match self {
+            Self::Deposit(_) => TxType::Deposit,
+            Self::Withdrawal(_) => TxType::Withdrawal,
+            Self::Dispute(_) => TxType::Dispute,
+            Self::Resolve(_) => TxType::Resolve,
+            Self::Chargeback(_) => TxType::Chargeback,
+        }
+    }
+
+    /// Returns this transaction's amount, for deposits and withdrawals.
+    ///
+    /// Disputes, resolves, and chargebacks refer to the amount of the
+    /// transaction they dispute, so they have none.
+    pub fn amount(&self) -> Option<Decimal> {
+        match self {
+            Self::Deposit(deposit) => Some(deposit.amount()),
+            Self::Withdrawal(withdrawal) => Some(withdrawal.amount()),
+            Self::Dispute(_) | Self::Resolve(_) | Self::Chargeback(_) => None,
+        }
+    }
+
+    /// Returns the name of this transaction's type, as written in
+    /// transactions CSV content, e.g. `deposit`.
+    pub(crate) fn type_name(&self) -> &'static str {
+        self.kind().as_str()
+    }
+}
+
+/// Formats the transaction as its type, client, transaction ID, and amount,
+/// e.g. `deposit client 1 tx 2 amount 5.0`, or `dispute client 1 tx 2`.
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let type_name = self.type_name();
+        let client = self.client();
+        let tx = self.tx();
+        match self.amount() {
+            Some(amount) => write!(f, "{type_name} client {client} tx {tx} amount {amount}"),
+            None => write!(f, "{type_name} client {client} tx {tx}"),
+        }
+    }
 }
 
 impl From<Deposit> for Transaction {
@@ -77,8 +125,61 @@ impl From<Chargeback> for Transaction {
     }
 }
 
+/// Converts a row generated by [`gen`], so generated workloads can be
+/// processed without writing them to a file.
+///
+/// # Examples
+///
+/// ```rust
+/// use transact::model::Transaction;
+///
+/// let scenario = gen::Scenario::builder().clients(10).rows(100).build()?;
+/// let transactions = scenario
+///     .into_iter()
+///     .map(Transaction::try_from)
+///     .collect::<Result<Vec<_>, _>>()?;
+///
+/// assert!(transactions.len() >= 100);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gen")]
+impl TryFrom<gen::TxRow> for Transaction {
+    type Error = crate::Error;
+
+    fn try_from(tx_row: gen::TxRow) -> Result<Transaction, crate::Error> {
+        let gen::TxRow {
+            kind,
+            client,
+            tx,
+            amount,
+        } = tx_row;
+        let client = ClientId::new(client);
+        let tx = TxId::new(tx);
+        // Generated amounts are in ten-thousandths.
+        let amount =
+            amount.map(|amount| Decimal::from_i128_with_scale(i128::from(amount.units()), 4));
+
+        let transaction = match kind {
+            gen::TxKind::Deposit => {
+                let amount = amount.ok_or(crate::Error::DepositAmountNotProvided { client, tx })?;
+                Transaction::from(Deposit::new(client, tx, amount))
+            }
+            gen::TxKind::Withdrawal => {
+                let amount =
+                    amount.ok_or(crate::Error::WithdrawalAmountNotProvided { client, tx })?;
+                Transaction::from(Withdrawal::new(client, tx, amount))
+            }
+            gen::TxKind::Dispute => Transaction::from(Dispute::new(client, tx)),
+            gen::TxKind::Resolve => Transaction::from(Resolve::new(client, tx)),
+            gen::TxKind::Chargeback => Transaction::from(Chargeback::new(client, tx)),
+        };
+
+        Ok(transaction)
+    }
+}
+
 /// Credit to the client's asset account.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Deposit {
     client: ClientId,
     tx: TxId,
@@ -88,8 +189,15 @@ pub struct Deposit {
 
 impl Deposit {
     /// Returns a new `Deposit` transaction.
+    ///
+    /// The amount is normalized, so `-0.0` is zero and trailing zeros are
+    /// removed, e.g. `1.50` is stored as `1.5`.
     pub fn new(client: ClientId, tx: TxId, amount: Decimal) -> Self {
-        Self { client, tx, amount }
+        Self {
+            client,
+            tx,
+            amount: amount.normalize(),
+        }
     }
 
     /// Get the transaction's client.
@@ -109,7 +217,7 @@ impl Deposit {
 }
 
 /// Debit to the client's asset account.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Withdrawal {
     client: ClientId,
     tx: TxId,
@@ -119,8 +227,15 @@ pub struct Withdrawal {
 
 impl Withdrawal {
     /// Returns a new `Withdrawal` transaction.
+    ///
+    /// The amount is normalized, so `-0.0` is zero and trailing zeros are
+    /// removed, e.g. `1.50` is stored as `1.5`.
     pub fn new(client: ClientId, tx: TxId, amount: Decimal) -> Self {
-        Self { client, tx, amount }
+        Self {
+            client,
+            tx,
+            amount: amount.normalize(),
+        }
     }
 
     /// Get the transaction's client.
@@ -140,7 +255,7 @@ impl Withdrawal {
 }
 
 /// Client's claim that a transaction was erroneous and should be reversed.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Dispute {
     client: ClientId,
     tx: TxId,
@@ -164,7 +279,7 @@ impl Dispute {
 }
 
 /// Resolution to a dispute, releasing the associated held funds.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Resolve {
     client: ClientId,
     tx: TxId,
@@ -188,7 +303,7 @@ impl Resolve {
 }
 
 /// Final state of a dispute and represents the client reversing a transaction.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Chargeback {
     client: ClientId,
     tx: TxId,
@@ -210,3 +325,45 @@ impl Chargeback {
         self.tx
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::{Deposit, Dispute, Transaction, Withdrawal};
+    use crate::{
+        csv::TxType,
+        model::{ClientId, TxId},
+    };
+
+    #[test]
+    fn amount_and_kind_match_variant() {
+        let withdrawal =
+            Transaction::from(Withdrawal::new(ClientId::new(1), TxId::new(2), dec!(1.25)));
+        let dispute = Transaction::from(Dispute::new(ClientId::new(1), TxId::new(2)));
+
+        assert_eq!(Some(dec!(1.25)), withdrawal.amount());
+        assert_eq!(TxType::Withdrawal, withdrawal.kind());
+        assert_eq!(None, dispute.amount());
+        assert_eq!(TxType::Dispute, dispute.kind());
+    }
+
+    #[test]
+    fn display_includes_amount_when_present() {
+        let deposit = Transaction::from(Deposit::new(ClientId::new(1), TxId::new(2), dec!(5.50)));
+        let dispute = Transaction::from(Dispute::new(ClientId::new(1), TxId::new(2)));
+
+        assert_eq!("deposit client 1 tx 2 amount 5.5", deposit.to_string());
+        assert_eq!("dispute client 1 tx 2", dispute.to_string());
+    }
+
+    #[test]
+    fn eq_compares_normalized_amounts() {
+        let deposit_a = Transaction::from(Deposit::new(ClientId::new(1), TxId::new(2), dec!(5.50)));
+        let deposit_b = Transaction::from(Deposit::new(ClientId::new(1), TxId::new(2), dec!(5.5)));
+        let dispute = Transaction::from(Dispute::new(ClientId::new(1), TxId::new(2)));
+
+        assert_eq!(deposit_a, deposit_b);
+        assert_ne!(deposit_a, dispute);
+    }
+}
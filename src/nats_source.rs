@@ -0,0 +1,159 @@
+//! Applies transactions read from a NATS JetStream stream.
+//!
+//! Messages are read through a durable pull consumer, and each message is
+//! acknowledged once its transaction is applied to a [`Processor`]. Messages
+//! that were not acknowledged, such as when the application stopped while
+//! applying them, are delivered again by the server.
+//!
+//! Each message's payload is a transactions CSV row without a header.
+//!
+//! ```text
+//! nats pub transactions.orders "deposit, 1, 1, 2.0"
+//! nats pub transactions.orders "dispute, 1, 1"
+//! ```
+//!
+//! Transactions are applied with their stream sequence as the offset, so a
+//! message that is delivered again after it was applied is acknowledged
+//! without being applied twice.
+//!
+//! Reading may be limited to a [`RateLimit`], in which case messages wait in
+//! the stream until they may be applied.
+
+use std::{fmt, future::Future, pin::pin};
+
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy},
+    AckKind,
+};
+use futures::{
+    future::{self, Either},
+    StreamExt,
+};
+
+use crate::{
+    csv::TransactCsv, rate_limit::RateLimiter, Error, IngestSummary, Processor, RateLimit, TxStore,
+};
+
+/// Reads transactions from a NATS JetStream stream through a durable
+/// consumer.
+pub struct NatsSource {
+    /// Messages delivered to the consumer.
+    messages: pull::Stream,
+    /// Limits the rate that messages are applied.
+    rate_limiter: RateLimiter,
+}
+
+impl NatsSource {
+    /// Connects to NATS, and creates the durable consumer on the stream if it
+    /// does not exist.
+    ///
+    /// A new consumer reads the stream from its first message, and must
+    /// acknowledge each message explicitly.
+    ///
+    /// # Parameters
+    ///
+    /// * `url`: NATS server address, e.g. `nats://127.0.0.1:4222`.
+    /// * `stream`: Name of the JetStream stream to read messages from.
+    /// * `consumer`: Durable name of the consumer.
+    pub async fn connect(url: &str, stream: &str, consumer: String) -> Result<Self, Error> {
+        let client = async_nats::connect(url).await.map_err(Error::NatsConnect)?;
+        let consumer = jetstream::new(client)
+            .get_stream(stream)
+            .await
+            .map_err(Error::NatsStreamGet)?
+            .get_or_create_consumer(
+                &consumer,
+                pull::Config {
+                    durable_name: Some(consumer.clone()),
+                    ack_policy: AckPolicy::Explicit,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(Error::NatsConsumerCreate)?;
+        let messages = consumer.messages().await.map_err(Error::NatsSubscribe)?;
+
+        Ok(Self {
+            messages,
+            rate_limiter: RateLimiter::new(RateLimit::new()),
+        })
+    }
+
+    /// Limits the rate that messages are applied.
+    ///
+    /// A message that was received but not applied when `shutdown` completes
+    /// is negatively acknowledged, so it is delivered again.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limiter = RateLimiter::new(rate_limit);
+        self
+    }
+
+    /// Applies transactions from the stream until `shutdown` completes.
+    ///
+    /// Each message is acknowledged after its transaction is applied or
+    /// rejected, or if it is malformed. If applying a transaction fails, the
+    /// error is returned and the message is not acknowledged, so it is
+    /// delivered again.
+    ///
+    /// `shutdown` does not interrupt applying a transaction, so each message
+    /// is either applied and acknowledged, or negatively acknowledged.
+    ///
+    /// # Parameters
+    ///
+    /// * `processor`: Processor to apply transactions with.
+    /// * `shutdown`: Future that completes when reading should stop.
+    pub async fn run<S, F>(
+        &mut self,
+        processor: &mut Processor<S>,
+        shutdown: F,
+    ) -> Result<IngestSummary, Error>
+    where
+        S: TxStore,
+        F: Future<Output = ()>,
+    {
+        let mut shutdown = pin!(shutdown);
+        let mut ingest_summary = IngestSummary::default();
+
+        loop {
+            let message = match future::select(self.messages.next(), shutdown.as_mut()).await {
+                Either::Left((Some(message), _)) => message.map_err(Error::NatsRead)?,
+                Either::Left((None, _)) | Either::Right(((), _)) => return Ok(ingest_summary),
+            };
+            if !self
+                .rate_limiter
+                .acquire_or_shutdown(1, message.payload.len() as u64, shutdown.as_mut())
+                .await
+            {
+                message
+                    .ack_with(AckKind::Nak(None))
+                    .await
+                    .map_err(Error::NatsAck)?;
+                return Ok(ingest_summary);
+            }
+
+            match TransactCsv::row_transaction(&message.payload).await {
+                Some(Ok(transaction)) => {
+                    let info = message.info().map_err(Error::NatsMessageInfo)?;
+                    let tx_result = processor
+                        .apply_from(info.stream, info.stream_sequence, transaction)
+                        .await?;
+                    ingest_summary.record_tx_result(tx_result);
+                }
+                Some(Err(error)) if error.is_record_error() => {
+                    ingest_summary.record_error(error);
+                }
+                Some(Err(error)) => return Err(error),
+                None => {}
+            }
+
+            message.ack().await.map_err(Error::NatsAck)?;
+        }
+    }
+}
+
+impl fmt::Debug for NatsSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NatsSource").finish_non_exhaustive()
+    }
+}
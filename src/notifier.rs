@@ -0,0 +1,104 @@
+use std::{fmt, sync::Arc};
+
+use crate::{model::Account, Error};
+
+/// Sends alerts about accounts and runs that need attention.
+///
+/// This is the extension point for alerting integrations such as chat,
+/// email, or paging services, which embedders implement without this crate
+/// depending on them. All methods do nothing by default.
+///
+/// Notifiers are called from the processing loop, so implementations that
+/// send alerts over the network should queue them rather than wait for them
+/// to be sent.
+pub trait Notifier: fmt::Debug + Send + Sync {
+    /// Called when an account becomes locked by a chargeback.
+    ///
+    /// # Parameters
+    ///
+    /// * `account`: The account after it was locked.
+    fn notify_account_locked(&self, account: &Account) {
+        let _ = account;
+    }
+
+    /// Called when an account's available or total funds become negative.
+    ///
+    /// This is called once when the balance goes below zero, and not for
+    /// later transactions while it stays negative. Transaction rules keep
+    /// balances from going negative, so this signals an inconsistency, such
+    /// as an account restored with funds that do not add up.
+    ///
+    /// # Parameters
+    ///
+    /// * `account`: The account with the negative balance.
+    fn notify_balance_negative(&self, account: &Account) {
+        let _ = account;
+    }
+
+    /// Called when processing fails.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The error that stopped processing.
+    fn notify_run_failed(&self, error: &Error) {
+        let _ = error;
+    }
+}
+
+impl<T> Notifier for Arc<T>
+where
+    T: Notifier + ?Sized,
+{
+    fn notify_account_locked(&self, account: &Account) {
+        (**self).notify_account_locked(account);
+    }
+
+    fn notify_balance_negative(&self, account: &Account) {
+        (**self).notify_balance_negative(account);
+    }
+
+    fn notify_run_failed(&self, error: &Error) {
+        (**self).notify_run_failed(error);
+    }
+}
+
+/// Notifier that does nothing.
+///
+/// This is the behaviour when no notifier is set in [`ProcessOptions`].
+///
+/// [`ProcessOptions`]: crate::ProcessOptions
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopNotifier;
+
+impl Notifier for NoopNotifier {}
+
+/// Notifier that logs alerts through `tracing`.
+///
+/// Locked accounts and negative balances are logged as warnings. Failed runs
+/// are not logged, as the caller already has the error to report.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LoggingNotifier;
+
+#[cfg(feature = "tracing")]
+impl Notifier for LoggingNotifier {
+    fn notify_account_locked(&self, account: &Account) {
+        tracing::warn!(
+            client = %account.client(),
+            available = %account.available(),
+            held = %account.held(),
+            total = %account.total(),
+            "Account locked."
+        );
+    }
+
+    fn notify_balance_negative(&self, account: &Account) {
+        tracing::warn!(
+            client = %account.client(),
+            available = %account.available(),
+            held = %account.held(),
+            total = %account.total(),
+            "Account balance is negative."
+        );
+    }
+}
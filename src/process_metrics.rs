@@ -0,0 +1,247 @@
+//! Metrics emitted through the [`metrics`] facade.
+//!
+//! These are only recorded when the `metrics` feature is enabled. An exporter
+//! such as `metrics-exporter-prometheus` must be installed by the application
+//! to collect them.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use crate::{
+    io::{AsyncRead, AsyncWrite},
+    model::Transaction,
+    Error, TxError,
+};
+
+/// Counter of processed transactions.
+///
+/// Labelled with `type` (`deposit`, `withdrawal`, `dispute`, `resolve`,
+/// `chargeback`) and `outcome` (`applied`, `rejected`).
+pub const TRANSACTIONS_TOTAL: &str = "transact_transactions_total";
+/// Histogram of the seconds taken to process each transaction.
+///
+/// Labelled with `type` (`deposit`, `withdrawal`, `dispute`, `resolve`,
+/// `chargeback`).
+pub const TRANSACTION_DURATION_SECONDS: &str = "transact_transaction_duration_seconds";
+/// Counter of transaction lookups in the transaction store.
+///
+/// Labelled with `outcome` (`found`, `not_found`).
+pub const BLOCK_LOOKUPS_TOTAL: &str = "transact_block_lookups_total";
+/// Histogram of the number of transactions in each persisted block.
+pub const BLOCK_TRANSACTIONS: &str = "transact_block_transactions";
+/// Counter of bytes read from the transactions input.
+pub const BYTES_READ_TOTAL: &str = "transact_bytes_read_total";
+/// Counter of bytes written to the accounts output.
+pub const BYTES_WRITTEN_TOTAL: &str = "transact_bytes_written_total";
+
+/// Records that a transaction was processed.
+pub(crate) fn transaction_processed(tx_type: &'static str, tx_result: &Result<(), TxError>) {
+    let outcome = match tx_result {
+        Ok(()) => "applied",
+        Err(_) => "rejected",
+    };
+    metrics::counter!(TRANSACTIONS_TOTAL, "type" => tx_type, "outcome" => outcome).increment(1);
+}
+
+/// Records the time taken to process a transaction.
+pub(crate) fn transaction_latency(transaction: &Transaction, latency: Duration) {
+    metrics::histogram!(TRANSACTION_DURATION_SECONDS, "type" => transaction.type_name())
+        .record(latency.as_secs_f64());
+}
+
+/// Records a transaction lookup in the transaction store.
+pub(crate) fn block_lookup(lookup_result: &Result<Option<Transaction>, Error>) {
+    let outcome = match lookup_result {
+        Ok(Some(_)) => "found",
+        Ok(None) => "not_found",
+        Err(_) => return,
+    };
+    metrics::counter!(BLOCK_LOOKUPS_TOTAL, "outcome" => outcome).increment(1);
+}
+
+/// Records that a block of transactions was persisted.
+pub(crate) fn block_persisted(transactions: &[Transaction]) {
+    metrics::histogram!(BLOCK_TRANSACTIONS).record(transactions.len() as f64);
+}
+
+/// Wraps a reader or writer, counting the bytes read and written.
+#[derive(Debug)]
+pub(crate) struct MeteredIo<T> {
+    /// Underlying reader or writer.
+    inner: T,
+}
+
+impl<T> MeteredIo<T> {
+    /// Returns a new `MeteredIo`.
+    pub(crate) fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<T> AsyncRead for MeteredIo<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled_before = buf.filled().len();
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = poll {
+            let bytes_read = buf.filled().len() - filled_before;
+            metrics::counter!(BYTES_READ_TOTAL).increment(bytes_read as u64);
+        }
+        poll
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+impl<T> AsyncRead for MeteredIo<T>
+where
+    T: AsyncRead + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(bytes_read)) = poll {
+            metrics::counter!(BYTES_READ_TOTAL).increment(bytes_read as u64);
+        }
+        poll
+    }
+}
+
+impl<T> AsyncWrite for MeteredIo<T>
+where
+    T: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let poll = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(bytes_written)) = poll {
+            metrics::counter!(BYTES_WRITTEN_TOTAL).increment(bytes_written as u64);
+        }
+        poll
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    #[cfg(feature = "tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    #[cfg(not(feature = "tokio"))]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::block_on, stream};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use rust_decimal_macros::dec;
+
+    use super::{BLOCK_TRANSACTIONS, TRANSACTIONS_TOTAL, TRANSACTION_DURATION_SECONDS};
+    use crate::{
+        model::{ClientId, Deposit, Transaction, TxId, Withdrawal},
+        process_transactions, MemTxStore, ProcessOptions,
+    };
+
+    #[test]
+    fn records_transactions_by_type_and_outcome() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let transactions = stream::iter([
+            Ok(Transaction::from(Deposit::new(
+                client,
+                TxId::new(1),
+                dec!(1.0),
+            ))),
+            Ok(Transaction::from(Withdrawal::new(
+                client,
+                TxId::new(2),
+                dec!(2.0),
+            ))),
+        ]);
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        metrics::with_local_recorder(&recorder, || {
+            block_on(process_transactions(
+                transactions,
+                &MemTxStore::new(),
+                ProcessOptions::new(),
+            ))
+        })?;
+
+        let mut values = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(composite_key, _unit, _description, value)| {
+                let key = composite_key.key();
+                let labels = key
+                    .labels()
+                    .map(|label| format!("{}={}", label.key(), label.value()))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                (format!("{}{{{labels}}}", key.name()), value)
+            })
+            .collect::<Vec<_>>();
+        values.sort_by(|(name_a, _), (name_b, _)| name_a.cmp(name_b));
+        // Latencies vary between runs, so only the number recorded is compared.
+        let (latencies, values) = values
+            .into_iter()
+            .partition::<Vec<_>, _>(|(name, _)| name.starts_with(TRANSACTION_DURATION_SECONDS));
+
+        assert_eq!(
+            vec![
+                (format!("{TRANSACTION_DURATION_SECONDS}{{type=deposit}}"), 1),
+                (
+                    format!("{TRANSACTION_DURATION_SECONDS}{{type=withdrawal}}"),
+                    1
+                ),
+            ],
+            latencies
+                .into_iter()
+                .map(|(name, value)| match value {
+                    DebugValue::Histogram(latencies) => (name, latencies.len()),
+                    _ => (name, 0),
+                })
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                (
+                    format!("{BLOCK_TRANSACTIONS}{{}}"),
+                    DebugValue::Histogram(vec![2.0.into()])
+                ),
+                (
+                    format!("{TRANSACTIONS_TOTAL}{{type=deposit,outcome=applied}}"),
+                    DebugValue::Counter(1)
+                ),
+                (
+                    format!("{TRANSACTIONS_TOTAL}{{type=withdrawal,outcome=rejected}}"),
+                    DebugValue::Counter(1)
+                ),
+            ],
+            values
+        );
+        Ok(())
+    }
+}
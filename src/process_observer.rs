@@ -0,0 +1,192 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use crate::{
+    model::{Account, Transaction},
+    Error, TxError,
+};
+
+/// Receives events while transactions are processed.
+///
+/// All methods do nothing by default, so implementors only need to implement
+/// the events they are interested in. This allows metrics, alerting, or
+/// journaling to be added without changing the processing code.
+pub trait ProcessObserver: fmt::Debug + Send + Sync {
+    /// Called after a transaction is applied to an account.
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction`: The transaction that was applied.
+    /// * `account`: The account after the transaction was applied.
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        let _ = (transaction, account);
+    }
+
+    /// Called when a transaction is rejected.
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction`: The transaction that was rejected.
+    /// * `tx_error`: The reason the transaction was rejected.
+    fn on_transaction_rejected(&self, transaction: &Transaction, tx_error: &TxError) {
+        let _ = (transaction, tx_error);
+    }
+
+    /// Called after a transaction is applied or rejected, with the account
+    /// after processing.
+    ///
+    /// This is called after [`on_transaction_applied`] or
+    /// [`on_transaction_rejected`], for observers that record the outcome of
+    /// every transaction. A rejected transaction does not change the account.
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction`: The transaction that was processed.
+    /// * `tx_result`: `Ok` if the transaction was applied, or the reason it was
+    ///   rejected.
+    /// * `account`: The account after the transaction was processed.
+    ///
+    /// [`on_transaction_applied`]: Self::on_transaction_applied
+    /// [`on_transaction_rejected`]: Self::on_transaction_rejected
+    fn on_transaction_processed(
+        &self,
+        transaction: &Transaction,
+        tx_result: Result<(), &TxError>,
+        account: &Account,
+    ) {
+        let _ = (transaction, tx_result, account);
+    }
+
+    /// Called when a record cannot be read as a transaction.
+    ///
+    /// This is called for each malformed record regardless of the
+    /// [`ErrorPolicy`], before the policy is applied. Records are read ahead
+    /// of processing, so this may be called before earlier transactions are
+    /// processed.
+    ///
+    /// # Parameters
+    ///
+    /// * `error`: The record error, see [`Error::is_record_error`].
+    ///
+    /// [`ErrorPolicy`]: crate::ErrorPolicy
+    fn on_record_error(&self, error: &Error) {
+        let _ = error;
+    }
+
+    /// Called with the time taken to process a transaction, whether it was
+    /// applied or rejected.
+    ///
+    /// This is not called on targets without a clock, such as
+    /// `wasm32-unknown-unknown`.
+    ///
+    /// # Parameters
+    ///
+    /// * `transaction`: The transaction that was processed.
+    /// * `latency`: Time taken to process the transaction, including looking up
+    ///   the disputed transaction in the transaction store.
+    fn on_transaction_latency(&self, transaction: &Transaction, latency: Duration) {
+        let _ = (transaction, latency);
+    }
+
+    /// Called when an account becomes locked.
+    ///
+    /// # Parameters
+    ///
+    /// * `account`: The account after it was locked.
+    fn on_account_locked(&self, account: &Account) {
+        let _ = account;
+    }
+
+    /// Called after a block of transactions is persisted to the block store.
+    ///
+    /// # Parameters
+    ///
+    /// * `transactions`: The transactions in the block.
+    fn on_block_persisted(&self, transactions: &[Transaction]) {
+        let _ = transactions;
+    }
+}
+
+impl<T> ProcessObserver for Arc<T>
+where
+    T: ProcessObserver + ?Sized,
+{
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        (**self).on_transaction_applied(transaction, account);
+    }
+
+    fn on_transaction_rejected(&self, transaction: &Transaction, tx_error: &TxError) {
+        (**self).on_transaction_rejected(transaction, tx_error);
+    }
+
+    fn on_transaction_processed(
+        &self,
+        transaction: &Transaction,
+        tx_result: Result<(), &TxError>,
+        account: &Account,
+    ) {
+        (**self).on_transaction_processed(transaction, tx_result, account);
+    }
+
+    fn on_record_error(&self, error: &Error) {
+        (**self).on_record_error(error);
+    }
+
+    fn on_transaction_latency(&self, transaction: &Transaction, latency: Duration) {
+        (**self).on_transaction_latency(transaction, latency);
+    }
+
+    fn on_account_locked(&self, account: &Account) {
+        (**self).on_account_locked(account);
+    }
+
+    fn on_block_persisted(&self, transactions: &[Transaction]) {
+        (**self).on_block_persisted(transactions);
+    }
+}
+
+/// Notifies each observer in order.
+impl<T> ProcessObserver for Vec<T>
+where
+    T: ProcessObserver,
+{
+    fn on_transaction_applied(&self, transaction: &Transaction, account: &Account) {
+        self.iter()
+            .for_each(|observer| observer.on_transaction_applied(transaction, account));
+    }
+
+    fn on_transaction_rejected(&self, transaction: &Transaction, tx_error: &TxError) {
+        self.iter()
+            .for_each(|observer| observer.on_transaction_rejected(transaction, tx_error));
+    }
+
+    fn on_transaction_processed(
+        &self,
+        transaction: &Transaction,
+        tx_result: Result<(), &TxError>,
+        account: &Account,
+    ) {
+        self.iter().for_each(|observer| {
+            observer.on_transaction_processed(transaction, tx_result, account)
+        });
+    }
+
+    fn on_record_error(&self, error: &Error) {
+        self.iter()
+            .for_each(|observer| observer.on_record_error(error));
+    }
+
+    fn on_transaction_latency(&self, transaction: &Transaction, latency: Duration) {
+        self.iter()
+            .for_each(|observer| observer.on_transaction_latency(transaction, latency));
+    }
+
+    fn on_account_locked(&self, account: &Account) {
+        self.iter()
+            .for_each(|observer| observer.on_account_locked(account));
+    }
+
+    fn on_block_persisted(&self, transactions: &[Transaction]) {
+        self.iter()
+            .for_each(|observer| observer.on_block_persisted(transactions));
+    }
+}
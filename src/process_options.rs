@@ -0,0 +1,594 @@
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+use std::{borrow::Cow, num::NonZeroUsize, sync::Arc, thread};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    ChargebackPolicy, ErrorPolicy, MemoryBudget, Notifier, ProcessObserver, TX_BLOCK_SIZE,
+};
+
+/// Options to control how transactions are processed.
+#[derive(Clone, Debug)]
+pub struct ProcessOptions {
+    /// How to handle rejected transactions and malformed records.
+    error_policy: ErrorPolicy,
+    /// Number of transactions to persist per block.
+    block_size: NonZeroUsize,
+    /// Directory to create the block store directory in.
+    #[cfg(feature = "fs")]
+    block_store_dir: Option<PathBuf>,
+    /// Number of accounts to keep in memory, with the rest in page files.
+    #[cfg(feature = "fs")]
+    account_cache_capacity: Option<NonZeroUsize>,
+    /// Receives events while transactions are processed.
+    observer: Option<Arc<dyn ProcessObserver>>,
+    /// Sends alerts about accounts and runs that need attention.
+    notifier: Option<Arc<dyn Notifier>>,
+    /// Number of partitions to process transactions in concurrently.
+    partitions: NonZeroUsize,
+    /// Number of blocks to persist concurrently.
+    persist_concurrency: NonZeroUsize,
+    /// Number of transaction lookups to run concurrently.
+    lookup_concurrency: NonZeroUsize,
+    /// Number of transactions buffered between reading and applying them.
+    channel_capacity: Option<NonZeroUsize>,
+    /// Approximate number of bytes that processing may hold in memory.
+    memory_budget: Option<MemoryBudget>,
+    /// Whether output is identical for identical input, however the run is
+    /// partitioned.
+    deterministic_output: bool,
+    /// Whether to park disputes of deposits that have not been applied yet.
+    park_early_disputes: bool,
+    /// How to handle chargebacks of transactions that are not in dispute.
+    chargeback_policy: ChargebackPolicy,
+    /// Whether to sort transactions by transaction ID before applying them.
+    #[cfg(feature = "fs")]
+    sort_by_tx: bool,
+    /// Whether to parse transactions files on a blocking thread.
+    #[cfg(feature = "fs")]
+    blocking_parse: bool,
+    /// Whether to parse transactions files with SIMD accelerated field
+    /// splitting.
+    #[cfg(feature = "simd-csv")]
+    simd_parse: bool,
+    /// Whether deposit and withdrawal IDs must increase through the input.
+    require_increasing_tx: bool,
+    /// Token to stop processing early.
+    cancellation_token: Option<CancellationToken>,
+    /// Maximum duration to wait for processing all transactions.
+    #[cfg(feature = "tokio")]
+    run_timeout: Option<Duration>,
+    /// Maximum duration to wait for each transaction lookup.
+    #[cfg(feature = "tokio")]
+    lookup_timeout: Option<Duration>,
+}
+
+impl ProcessOptions {
+    /// Maximum default number of blocks to persist concurrently.
+    const PERSIST_CONCURRENCY_DEFAULT_MAX: usize = 4;
+
+    /// Returns a new `ProcessOptions` with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how to handle rejected transactions and malformed records.
+    pub fn with_error_policy(mut self, error_policy: ErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
+    /// Sets the number of transactions to persist per block.
+    ///
+    /// Larger blocks mean fewer block files, but more transactions are read
+    /// when looking up a disputed transaction. Defaults to `10_000`.
+    pub fn with_block_size(mut self, block_size: NonZeroUsize) -> Self {
+        self.block_size = block_size;
+        self
+    }
+
+    /// Sets the directory to create the block store directory in.
+    ///
+    /// Defaults to the system temporary directory. The block store directory
+    /// is removed when processing is complete.
+    #[cfg(feature = "fs")]
+    pub fn with_block_store_dir(mut self, block_store_dir: PathBuf) -> Self {
+        self.block_store_dir = Some(block_store_dir);
+        self
+    }
+
+    /// Sets the number of accounts to keep in memory while processing.
+    ///
+    /// Accounts are stored in page files in the block store directory, and the
+    /// most recently used pages of accounts are kept in memory. This bounds
+    /// memory use when there are many clients, at the cost of reading and
+    /// writing page files. The capacity is rounded up to whole pages of
+    /// `64` accounts.
+    ///
+    /// Defaults to keeping all accounts in memory. Accounts are still
+    /// returned in the [`ProcessSummary`] once processing is complete.
+    ///
+    /// [`ProcessSummary`]: crate::ProcessSummary
+    #[cfg(feature = "fs")]
+    pub fn with_account_cache_capacity(mut self, account_cache_capacity: NonZeroUsize) -> Self {
+        self.account_cache_capacity = Some(account_cache_capacity);
+        self
+    }
+
+    /// Sets the observer to receive events while transactions are processed.
+    pub fn with_observer(mut self, observer: Arc<dyn ProcessObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Sets the notifier to send alerts about locked accounts, negative
+    /// balances, and failed runs.
+    ///
+    /// Defaults to sending no alerts, like [`NoopNotifier`].
+    ///
+    /// [`NoopNotifier`]: crate::NoopNotifier
+    pub fn with_notifier(mut self, notifier: Arc<dyn Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Sets the number of partitions to process transactions in concurrently.
+    ///
+    /// Transactions are partitioned by client, and each partition applies its
+    /// transactions in input order, so every client's transactions are still
+    /// applied in input order. Final accounts are the same as processing with
+    /// one partition.
+    ///
+    /// Rejections in the [`ProcessSummary`] are grouped by partition instead
+    /// of being in input order. A dispute of another client's deposit is
+    /// rejected either way, but may be rejected with
+    /// [`TxError::DisputeTxNotFound`] instead of
+    /// [`TxError::DisputeClientMismatch`] if the other partition has not
+    /// stored the deposit yet. When accounts are stored in page files, each
+    /// partition keeps its own account cache.
+    ///
+    /// Defaults to `1`.
+    ///
+    /// [`ProcessSummary`]: crate::ProcessSummary
+    /// [`TxError::DisputeTxNotFound`]: crate::TxError::DisputeTxNotFound
+    /// [`TxError::DisputeClientMismatch`]: crate::TxError::DisputeClientMismatch
+    pub fn with_partitions(mut self, partitions: NonZeroUsize) -> Self {
+        self.partitions = partitions;
+        self
+    }
+
+    /// Sets the number of blocks to persist concurrently.
+    ///
+    /// Transactions are read in windows of this many blocks, and every block
+    /// in a window is persisted before its transactions are applied. A
+    /// dispute may find a deposit later in its window, the same as one later
+    /// in its block. Each partition persists its own blocks.
+    ///
+    /// Defaults to the available parallelism, up to `4`.
+    pub fn with_persist_concurrency(mut self, persist_concurrency: NonZeroUsize) -> Self {
+        self.persist_concurrency = persist_concurrency;
+        self
+    }
+
+    /// Sets the number of transaction lookups to run concurrently when
+    /// processing disputes, resolves, and chargebacks.
+    ///
+    /// Once a window of blocks is persisted, the transactions referred to by
+    /// its disputes, resolves, and chargebacks are looked up concurrently
+    /// before they are applied. With `1`, each transaction is looked up when
+    /// it is applied instead.
+    ///
+    /// Defaults to the available parallelism.
+    pub fn with_lookup_concurrency(mut self, lookup_concurrency: NonZeroUsize) -> Self {
+        self.lookup_concurrency = lookup_concurrency;
+        self
+    }
+
+    /// Sets the number of transactions buffered between reading and applying
+    /// them.
+    ///
+    /// This bounds the transactions waiting for each partition, and those
+    /// parsed ahead on a blocking thread. Defaults to the number of
+    /// transactions in a window of concurrently persisted blocks.
+    pub fn with_channel_capacity(mut self, channel_capacity: NonZeroUsize) -> Self {
+        self.channel_capacity = Some(channel_capacity);
+        self
+    }
+
+    /// Sets the approximate number of bytes that processing may hold in
+    /// memory.
+    ///
+    /// Blocks, concurrently persisted blocks, and buffered transactions are
+    /// reduced to fit half of the budget, and with the `fs` feature, the
+    /// [account cache capacity](Self::with_account_cache_capacity) is reduced
+    /// to fit the other half. Smaller settings are kept. See [`MemoryBudget`]
+    /// for how sizes are estimated. Defaults to no budget.
+    pub fn with_memory_budget(mut self, memory_budget: MemoryBudget) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+
+    /// Sets whether output is identical for identical input, across runs and
+    /// numbers of [partitions](Self::with_partitions).
+    ///
+    /// When set, accounts are written in order of client ID, and
+    /// [`ProcessSummary::rejections`] are ordered by transaction ID instead of
+    /// the order they were processed in. Amounts are always written in their
+    /// shortest form, e.g. `1.5` for both `1.5` and `1.50`.
+    ///
+    /// Observer events are still delivered in processing order, which is only
+    /// the same across runs with one partition. Defaults to `false`.
+    ///
+    /// [`ProcessSummary::rejections`]: crate::ProcessSummary::rejections
+    pub fn with_deterministic_output(mut self, deterministic_output: bool) -> Self {
+        self.deterministic_output = deterministic_output;
+        self
+    }
+
+    /// Sets whether to park disputes of deposits that have not been applied
+    /// yet, instead of rejecting them with [`TxError::DisputeTxNotFound`].
+    ///
+    /// This is for input merged from several sources, where a dispute may
+    /// come before the deposit it disputes. A parked dispute, and the resolves
+    /// and chargebacks of the same transaction after it, are processed in
+    /// order right after the deposit is applied. Transactions still parked at
+    /// the end of the input are processed then, and disputes are rejected if
+    /// their deposit never came. Disputes of deposits in the transaction
+    /// store from earlier runs are not parked. Defaults to `false`.
+    ///
+    /// [`TxError::DisputeTxNotFound`]: crate::TxError::DisputeTxNotFound
+    pub fn with_park_early_disputes(mut self, park_early_disputes: bool) -> Self {
+        self.park_early_disputes = park_early_disputes;
+        self
+    }
+
+    /// Sets how to handle chargebacks of transactions that are not in
+    /// dispute.
+    ///
+    /// Some processors send a chargeback without a preceding dispute. With
+    /// [`ChargebackPolicy::ImplicitDispute`], such a chargeback is applied as
+    /// a dispute followed by the chargeback, instead of being rejected.
+    /// Defaults to [`ChargebackPolicy::RequireDispute`].
+    pub fn with_chargeback_policy(mut self, chargeback_policy: ChargebackPolicy) -> Self {
+        self.chargeback_policy = chargeback_policy;
+        self
+    }
+
+    /// Sets whether to sort transactions by transaction ID before applying
+    /// them.
+    ///
+    /// This is for input whose producers cannot guarantee chronological
+    /// order, where transaction IDs are. All transactions are read and sorted
+    /// before any is applied, with sorted runs of transactions spilled to
+    /// files in the block store directory, so large files do not need to fit
+    /// in memory. Disputes, resolves, and chargebacks share the ID of the
+    /// transaction they refer to, and are applied after it, in input order.
+    /// Malformed records are handled before any transaction is applied.
+    ///
+    /// Only applies to transactions read from a file, such as with
+    /// [`process_to_accounts`]. Defaults to `false`.
+    ///
+    /// [`process_to_accounts`]: crate::process_to_accounts
+    #[cfg(feature = "fs")]
+    pub fn with_sort_by_tx(mut self, sort_by_tx: bool) -> Self {
+        self.sort_by_tx = sort_by_tx;
+        self
+    }
+
+    /// Sets whether to parse transactions files with the synchronous `csv`
+    /// crate on a blocking thread.
+    ///
+    /// Parsing with `csv_async` may take much of the run time when the file
+    /// is on a fast disk. This parses on a thread from the runtime's blocking
+    /// pool instead, while transactions are applied concurrently.
+    ///
+    /// Only applies to transactions read from a file, such as with
+    /// [`process_to_accounts`]. Defaults to `false`.
+    ///
+    /// [`process_to_accounts`]: crate::process_to_accounts
+    #[cfg(feature = "fs")]
+    pub fn with_blocking_parse(mut self, blocking_parse: bool) -> Self {
+        self.blocking_parse = blocking_parse;
+        self
+    }
+
+    /// Sets whether to parse transactions files with SIMD accelerated field
+    /// splitting on a blocking thread.
+    ///
+    /// This is for replays of very large files, where parsing dominates the
+    /// run time. Rows of files with a `type,client,tx,amount` header are
+    /// parsed by hand, and rows that are not simple, such as quoted fields,
+    /// are parsed as with [blocking parsing](Self::with_blocking_parse). This
+    /// takes precedence over blocking parsing.
+    ///
+    /// Only applies to transactions read from a file, such as with
+    /// [`process_to_accounts`]. Defaults to `false`.
+    ///
+    /// [`process_to_accounts`]: crate::process_to_accounts
+    #[cfg(feature = "simd-csv")]
+    pub fn with_simd_parse(mut self, simd_parse: bool) -> Self {
+        self.simd_parse = simd_parse;
+        self
+    }
+
+    /// Sets whether deposit and withdrawal IDs must be strictly increasing
+    /// through the input.
+    ///
+    /// This is for input whose producer guarantees transaction IDs are
+    /// assigned in order, to catch corrupted or misordered exports. A deposit
+    /// or withdrawal whose ID is not greater than the previous one is a
+    /// malformed record, [`Error::TxIdNotIncreasing`], and is handled
+    /// according to the [error policy](Self::with_error_policy). Disputes,
+    /// resolves, and chargebacks refer to earlier transactions, so are not
+    /// checked.
+    ///
+    /// The check applies to the order transactions are applied in, so after
+    /// sorting by transaction ID when that is set. Defaults to `false`.
+    ///
+    /// [`Error::TxIdNotIncreasing`]: crate::Error::TxIdNotIncreasing
+    pub fn with_require_increasing_tx(mut self, require_increasing_tx: bool) -> Self {
+        self.require_increasing_tx = require_increasing_tx;
+        self
+    }
+
+    /// Sets the token to stop processing early.
+    ///
+    /// When the token is cancelled, no further records are read, and the
+    /// transactions that have already been read are processed. The returned
+    /// [`ProcessSummary`] is marked as cancelled.
+    ///
+    /// [`ProcessSummary`]: crate::ProcessSummary
+    pub fn with_cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = Some(cancellation_token);
+        self
+    }
+
+    /// Sets the maximum duration to wait for processing all transactions.
+    ///
+    /// Processing fails with [`Error::RunTimeout`] when this is exceeded.
+    ///
+    /// [`Error::RunTimeout`]: crate::Error::RunTimeout
+    #[cfg(feature = "tokio")]
+    pub fn with_run_timeout(mut self, run_timeout: Duration) -> Self {
+        self.run_timeout = Some(run_timeout);
+        self
+    }
+
+    /// Sets the maximum duration to wait for each transaction lookup when
+    /// processing disputes.
+    ///
+    /// Processing fails with [`Error::BlockStoreLookupTimeout`] when this is
+    /// exceeded.
+    ///
+    /// [`Error::BlockStoreLookupTimeout`]: crate::Error::BlockStoreLookupTimeout
+    #[cfg(feature = "tokio")]
+    pub fn with_lookup_timeout(mut self, lookup_timeout: Duration) -> Self {
+        self.lookup_timeout = Some(lookup_timeout);
+        self
+    }
+
+    /// Returns how to handle rejected transactions and malformed records.
+    pub fn error_policy(&self) -> ErrorPolicy {
+        self.error_policy
+    }
+
+    /// Returns the number of transactions to persist per block.
+    pub fn block_size(&self) -> NonZeroUsize {
+        self.block_size
+    }
+
+    /// Returns the directory to create the block store directory in.
+    #[cfg(feature = "fs")]
+    pub fn block_store_dir(&self) -> Option<&Path> {
+        self.block_store_dir.as_deref()
+    }
+
+    /// Returns the number of accounts to keep in memory while processing, if
+    /// accounts are stored in page files.
+    #[cfg(feature = "fs")]
+    pub fn account_cache_capacity(&self) -> Option<NonZeroUsize> {
+        self.account_cache_capacity
+    }
+
+    /// Returns the observer to receive events while transactions are
+    /// processed.
+    pub fn observer(&self) -> Option<&dyn ProcessObserver> {
+        self.observer.as_deref()
+    }
+
+    /// Returns the notifier to send alerts to.
+    pub fn notifier(&self) -> Option<&dyn Notifier> {
+        self.notifier.as_deref()
+    }
+
+    /// Returns the number of partitions to process transactions in
+    /// concurrently.
+    pub fn partitions(&self) -> NonZeroUsize {
+        self.partitions
+    }
+
+    /// Returns the number of blocks to persist concurrently.
+    pub fn persist_concurrency(&self) -> NonZeroUsize {
+        self.persist_concurrency
+    }
+
+    /// Returns the number of transaction lookups to run concurrently.
+    pub fn lookup_concurrency(&self) -> NonZeroUsize {
+        self.lookup_concurrency
+    }
+
+    /// Returns the number of transactions buffered between reading and
+    /// applying them.
+    pub fn channel_capacity(&self) -> NonZeroUsize {
+        self.channel_capacity
+            .unwrap_or_else(|| self.block_size.saturating_mul(self.persist_concurrency))
+    }
+
+    /// Returns the approximate number of bytes that processing may hold in
+    /// memory.
+    pub fn memory_budget(&self) -> Option<MemoryBudget> {
+        self.memory_budget
+    }
+
+    /// Returns these options with buffers and caches reduced to fit the
+    /// memory budget, or these options if there is no budget.
+    pub(crate) fn within_memory_budget(&self) -> Cow<'_, Self> {
+        let Some(memory_budget) = self.memory_budget else {
+            return Cow::Borrowed(self);
+        };
+
+        // Half of each partition's transactions are for the blocks persisted
+        // together, and the rest are buffered ahead of them.
+        let transaction_capacity = memory_budget.transaction_capacity(self.partitions);
+        let window_capacity = transaction_capacity.get().div_ceil(2);
+        let block_size = self
+            .block_size
+            .min(NonZeroUsize::new(window_capacity).unwrap_or(NonZeroUsize::MIN));
+        let persist_concurrency = self
+            .persist_concurrency
+            .min(NonZeroUsize::new(window_capacity / block_size).unwrap_or(NonZeroUsize::MIN));
+        let window = block_size.saturating_mul(persist_concurrency);
+        let channel_capacity = self.channel_capacity().min(
+            NonZeroUsize::new(transaction_capacity.get().saturating_sub(window.get()))
+                .unwrap_or(NonZeroUsize::MIN),
+        );
+
+        let mut options = self.clone();
+        options.block_size = block_size;
+        options.persist_concurrency = persist_concurrency;
+        options.channel_capacity = Some(channel_capacity);
+        #[cfg(feature = "fs")]
+        {
+            let account_capacity = memory_budget.account_capacity(self.partitions);
+            options.account_cache_capacity = Some(
+                self.account_cache_capacity
+                    .map_or(account_capacity, |capacity| capacity.min(account_capacity)),
+            );
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            // The account cache capacity only exists with `fs`.
+            #[cfg(feature = "fs")]
+            let account_cache_capacity = options.account_cache_capacity();
+            #[cfg(not(feature = "fs"))]
+            let account_cache_capacity = None::<NonZeroUsize>;
+            tracing::debug!(
+                bytes = memory_budget.bytes().get(),
+                %block_size,
+                %persist_concurrency,
+                %channel_capacity,
+                ?account_cache_capacity,
+                "Memory budget applied."
+            );
+        }
+
+        Cow::Owned(options)
+    }
+
+    /// Returns whether output is identical for identical input, however the
+    /// run is partitioned.
+    pub fn deterministic_output(&self) -> bool {
+        self.deterministic_output
+    }
+
+    /// Returns whether to park disputes of deposits that have not been
+    /// applied yet.
+    pub fn park_early_disputes(&self) -> bool {
+        self.park_early_disputes
+    }
+
+    /// Returns how to handle chargebacks of transactions that are not in
+    /// dispute.
+    pub fn chargeback_policy(&self) -> ChargebackPolicy {
+        self.chargeback_policy
+    }
+
+    /// Returns whether to sort transactions by transaction ID before applying
+    /// them.
+    #[cfg(feature = "fs")]
+    pub fn sort_by_tx(&self) -> bool {
+        self.sort_by_tx
+    }
+
+    /// Returns whether to parse transactions files on a blocking thread.
+    #[cfg(feature = "fs")]
+    pub fn blocking_parse(&self) -> bool {
+        self.blocking_parse
+    }
+
+    /// Returns whether to parse transactions files with SIMD accelerated
+    /// field splitting.
+    #[cfg(feature = "simd-csv")]
+    pub fn simd_parse(&self) -> bool {
+        self.simd_parse
+    }
+
+    /// Returns whether deposit and withdrawal IDs must be strictly increasing
+    /// through the input.
+    pub fn require_increasing_tx(&self) -> bool {
+        self.require_increasing_tx
+    }
+
+    /// Returns the token to stop processing early.
+    pub fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.cancellation_token.as_ref()
+    }
+
+    /// Returns the maximum duration to wait for processing all transactions.
+    #[cfg(feature = "tokio")]
+    pub fn run_timeout(&self) -> Option<Duration> {
+        self.run_timeout
+    }
+
+    /// Returns the maximum duration to wait for each transaction lookup.
+    #[cfg(feature = "tokio")]
+    pub fn lookup_timeout(&self) -> Option<Duration> {
+        self.lookup_timeout
+    }
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        // Parallelism is unknown on some targets, such as WASM.
+        let parallelism = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        let persist_concurrency =
+            NonZeroUsize::new(parallelism.get().min(Self::PERSIST_CONCURRENCY_DEFAULT_MAX))
+                .unwrap_or(NonZeroUsize::MIN);
+
+        Self {
+            error_policy: ErrorPolicy::default(),
+            block_size: NonZeroUsize::new(TX_BLOCK_SIZE)
+                .expect("`TX_BLOCK_SIZE` must be greater than zero."),
+            #[cfg(feature = "fs")]
+            block_store_dir: None,
+            #[cfg(feature = "fs")]
+            account_cache_capacity: None,
+            observer: None,
+            notifier: None,
+            partitions: NonZeroUsize::MIN,
+            persist_concurrency,
+            lookup_concurrency: parallelism,
+            channel_capacity: None,
+            memory_budget: None,
+            deterministic_output: false,
+            park_early_disputes: false,
+            chargeback_policy: ChargebackPolicy::default(),
+            #[cfg(feature = "fs")]
+            sort_by_tx: false,
+            #[cfg(feature = "fs")]
+            blocking_parse: false,
+            #[cfg(feature = "simd-csv")]
+            simd_parse: false,
+            require_increasing_tx: false,
+            cancellation_token: None,
+            #[cfg(feature = "tokio")]
+            run_timeout: None,
+            #[cfg(feature = "tokio")]
+            lookup_timeout: None,
+        }
+    }
+}
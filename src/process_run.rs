@@ -0,0 +1,306 @@
+use std::collections::{BTreeMap, HashSet};
+
+use rust_decimal::Decimal;
+
+use crate::{
+    model::{Account, ClientId, Dispute, Transaction, TxId},
+    tx_processor::TxProcessor,
+    AccountStore, ChargebackPolicy, Error, ErrorPolicy, LatencySummary, Notifier, ProcessObserver,
+    RejectionSummary, TxError, TxStore,
+};
+
+/// State of a processing run, updated by each transaction.
+#[derive(Debug)]
+pub(crate) struct ProcessRun<'run, S, A> {
+    /// Applies transactions to accounts.
+    tx_processor: &'run TxProcessor<'run, S>,
+    /// How to handle rejected transactions.
+    error_policy: ErrorPolicy,
+    /// Receives events while transactions are processed.
+    observer: Option<&'run dyn ProcessObserver>,
+    /// Sends alerts about accounts that need attention.
+    notifier: Option<&'run dyn Notifier>,
+    /// Stores accounts while transactions are processed.
+    account_store: A,
+    /// Rejected transactions, when the error policy collects them.
+    rejections: Vec<TxError>,
+    /// Counts and amounts of rejected transactions by error code.
+    rejection_summary: RejectionSummary,
+    /// Processing latencies by transaction type.
+    latency_summary: LatencySummary,
+    /// Disputes of deposits that have not been applied yet, and the resolves
+    /// and chargebacks that follow them, by disputed transaction ID, when
+    /// early disputes are parked.
+    parked_txs: Option<BTreeMap<TxId, Vec<Transaction>>>,
+    /// Deposits in the current window that have not been processed yet, when
+    /// early disputes are parked.
+    ///
+    /// These are already in the transaction store, as a window is persisted
+    /// before any of its transactions are processed.
+    pending_deposits: HashSet<TxId>,
+    /// How to handle chargebacks of transactions that are not in dispute.
+    chargeback_policy: ChargebackPolicy,
+}
+
+impl<'run, S, A> ProcessRun<'run, S, A>
+where
+    S: TxStore,
+    A: AccountStore,
+{
+    /// Returns a new `ProcessRun` with no rejections.
+    pub(crate) fn new(
+        tx_processor: &'run TxProcessor<'run, S>,
+        error_policy: ErrorPolicy,
+        observer: Option<&'run dyn ProcessObserver>,
+        notifier: Option<&'run dyn Notifier>,
+        account_store: A,
+    ) -> Self {
+        Self {
+            tx_processor,
+            error_policy,
+            observer,
+            notifier,
+            account_store,
+            rejections: Vec::new(),
+            rejection_summary: RejectionSummary::new(),
+            latency_summary: LatencySummary::new(),
+            parked_txs: None,
+            pending_deposits: HashSet::new(),
+            chargeback_policy: ChargebackPolicy::default(),
+        }
+    }
+
+    /// Parks disputes of deposits that have not been applied yet, instead of
+    /// rejecting them.
+    ///
+    /// See [`ProcessOptions::with_park_early_disputes`].
+    ///
+    /// [`ProcessOptions::with_park_early_disputes`]: crate::ProcessOptions::with_park_early_disputes
+    pub(crate) fn with_park_early_disputes(mut self, park_early_disputes: bool) -> Self {
+        self.parked_txs = park_early_disputes.then(BTreeMap::new);
+        self
+    }
+
+    /// Sets how to handle chargebacks of transactions that are not in
+    /// dispute.
+    ///
+    /// See [`ProcessOptions::with_chargeback_policy`].
+    ///
+    /// [`ProcessOptions::with_chargeback_policy`]: crate::ProcessOptions::with_chargeback_policy
+    pub(crate) fn with_chargeback_policy(mut self, chargeback_policy: ChargebackPolicy) -> Self {
+        self.chargeback_policy = chargeback_policy;
+        self
+    }
+
+    /// Processes a window of transactions, whose blocks have all been
+    /// persisted to the transaction store.
+    pub(crate) async fn process_window<I>(&mut self, transactions: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = Transaction>,
+    {
+        let transactions = transactions.into_iter().collect::<Vec<_>>();
+        if self.parked_txs.is_some() {
+            self.pending_deposits = transactions
+                .iter()
+                .filter_map(|transaction| match transaction {
+                    Transaction::Deposit(deposit) => Some(deposit.tx()),
+                    _ => None,
+                })
+                .collect();
+        }
+        for transaction in transactions {
+            self.process(transaction).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Processes a transaction.
+    ///
+    /// When a deposit is applied, transactions parked for it are processed
+    /// after it, in the order they were read.
+    async fn process(&mut self, transaction: Transaction) -> Result<(), Error> {
+        if self.should_park(&transaction).await? {
+            if let Some(parked_txs) = self.parked_txs.as_mut() {
+                parked_txs
+                    .entry(transaction.tx())
+                    .or_default()
+                    .push(transaction);
+            }
+            return Ok(());
+        }
+
+        let deposit_tx = match &transaction {
+            Transaction::Deposit(deposit) => Some(deposit.tx()),
+            _ => None,
+        };
+        let applied = self.process_unparked(transaction).await?;
+
+        let parked_txs = match (deposit_tx, self.parked_txs.as_mut()) {
+            (Some(tx), Some(parked_txs)) => {
+                self.pending_deposits.remove(&tx);
+                if applied {
+                    parked_txs.remove(&tx).unwrap_or_default()
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => Vec::new(),
+        };
+        for transaction in parked_txs {
+            self.process_unparked(transaction).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the transaction is parked until its deposit is
+    /// applied.
+    ///
+    /// A dispute is parked when its deposit is later in the current window, or
+    /// is not in the transaction store. Deposits from earlier windows or
+    /// earlier runs are in the store, so their disputes are not parked.
+    /// Resolves and chargebacks are parked when the disputed transaction has
+    /// parked transactions, so they are processed after the parked dispute.
+    async fn should_park(&self, transaction: &Transaction) -> Result<bool, Error> {
+        let Some(parked_txs) = self.parked_txs.as_ref() else {
+            return Ok(false);
+        };
+
+        let tx = transaction.tx();
+        match transaction {
+            Transaction::Dispute(_) => Ok(parked_txs.contains_key(&tx)
+                || self.pending_deposits.contains(&tx)
+                || !self.tx_processor.contains(tx).await?),
+            Transaction::Resolve(_) | Transaction::Chargeback(_) => {
+                Ok(parked_txs.contains_key(&tx))
+            }
+            Transaction::Deposit(_) | Transaction::Withdrawal(_) => Ok(false),
+        }
+    }
+
+    /// Processes a transaction that is not parked, returning whether it was
+    /// applied.
+    ///
+    /// When chargebacks imply a dispute, a chargeback of a transaction that is
+    /// not in dispute is processed after a dispute of it, and only if the
+    /// dispute is applied.
+    async fn process_unparked(&mut self, transaction: Transaction) -> Result<bool, Error> {
+        if let Transaction::Chargeback(chargeback) = &transaction {
+            if self.chargeback_policy == ChargebackPolicy::ImplicitDispute
+                && !self
+                    .tx_disputed(chargeback.client(), chargeback.tx())
+                    .await?
+            {
+                let dispute = Dispute::new(chargeback.client(), chargeback.tx());
+                if !self.process_one(Transaction::Dispute(dispute)).await? {
+                    return Ok(false);
+                }
+            }
+        }
+
+        self.process_one(transaction).await
+    }
+
+    /// Processes transactions that are still parked, and returns the accounts,
+    /// rejections, rejection summary, and latency summary.
+    ///
+    /// Parked transactions are processed in order of the disputed transaction
+    /// ID, and disputes are rejected if the disputed transaction was never
+    /// seen.
+    pub(crate) async fn finish(
+        mut self,
+    ) -> Result<(A, Vec<TxError>, RejectionSummary, LatencySummary), Error> {
+        let parked_txs = self
+            .parked_txs
+            .take()
+            .into_iter()
+            .flat_map(BTreeMap::into_values)
+            .flatten()
+            .collect::<Vec<_>>();
+        for transaction in parked_txs {
+            self.process_unparked(transaction).await?;
+        }
+
+        Ok((
+            self.account_store,
+            self.rejections,
+            self.rejection_summary,
+            self.latency_summary,
+        ))
+    }
+
+    /// Processes a transaction, returning whether it was applied.
+    async fn process_one(&mut self, transaction: Transaction) -> Result<bool, Error> {
+        let mut account = self
+            .account_store
+            .take_account(transaction.client())
+            .await?;
+
+        let locked = account.locked();
+        let negative_before = Self::balance_negative(&account);
+        let started = LatencySummary::start();
+        let tx_result = self
+            .tx_processor
+            .process(&mut account, transaction.clone())
+            .await?;
+        if let Some(started) = started {
+            let latency = started.elapsed();
+            self.latency_summary.record(&transaction, latency);
+            #[cfg(feature = "metrics")]
+            crate::process_metrics::transaction_latency(&transaction, latency);
+            if let Some(observer) = self.observer {
+                observer.on_transaction_latency(&transaction, latency);
+            }
+        }
+        if let Err(tx_error) = &tx_result {
+            self.rejection_summary.record(&transaction, tx_error);
+        }
+        if let (Ok(()), Some(notifier)) = (&tx_result, self.notifier) {
+            if !locked && account.locked() {
+                notifier.notify_account_locked(&account);
+            }
+            if !negative_before && Self::balance_negative(&account) {
+                notifier.notify_balance_negative(&account);
+            }
+        }
+        if let Some(observer) = self.observer {
+            match &tx_result {
+                Ok(()) => {
+                    observer.on_transaction_applied(&transaction, &account);
+                    if !locked && account.locked() {
+                        observer.on_account_locked(&account);
+                    }
+                }
+                Err(tx_error) => observer.on_transaction_rejected(&transaction, tx_error),
+            }
+            observer.on_transaction_processed(&transaction, tx_result.as_ref().copied(), &account);
+        }
+        self.account_store.put_account(account).await?;
+        match tx_result {
+            Ok(()) => Ok(true),
+            Err(tx_error) => match self.error_policy {
+                ErrorPolicy::Strict => Err(Error::TransactionRejected(tx_error)),
+                ErrorPolicy::Lenient => Ok(false),
+                ErrorPolicy::Collect => {
+                    self.rejections.push(tx_error);
+                    Ok(false)
+                }
+            },
+        }
+    }
+
+    /// Returns whether the transaction is in dispute on the client's account.
+    async fn tx_disputed(&mut self, client: ClientId, tx: TxId) -> Result<bool, Error> {
+        let account = self.account_store.take_account(client).await?;
+        let disputed = account.disputed_txs().contains(&tx);
+        self.account_store.put_account(account).await?;
+
+        Ok(disputed)
+    }
+
+    /// Returns whether the account's available or total funds are negative.
+    fn balance_negative(account: &Account) -> bool {
+        account.available() < Decimal::ZERO || account.total() < Decimal::ZERO
+    }
+}
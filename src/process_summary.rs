@@ -0,0 +1,139 @@
+use crate::{model::Accounts, Error, LatencySummary, RejectionSummary, TxError};
+
+/// Result of processing a transactions file.
+#[derive(Debug)]
+pub struct ProcessSummary {
+    /// Final state of all accounts.
+    accounts: Accounts,
+    /// Transactions that were rejected, in the order they were processed.
+    rejections: Vec<TxError>,
+    /// Records that could not be parsed, in the order they were read.
+    record_errors: Vec<Error>,
+    /// Whether processing was cancelled before all records were read.
+    cancelled: bool,
+    /// Counts and amounts of rejected transactions by error code.
+    rejection_summary: RejectionSummary,
+    /// Processing latencies by transaction type.
+    latency_summary: LatencySummary,
+}
+
+impl ProcessSummary {
+    /// Returns a new `ProcessSummary`.
+    pub fn new(
+        accounts: Accounts,
+        rejections: Vec<TxError>,
+        record_errors: Vec<Error>,
+        cancelled: bool,
+    ) -> Self {
+        Self {
+            accounts,
+            rejections,
+            record_errors,
+            cancelled,
+            rejection_summary: RejectionSummary::new(),
+            latency_summary: LatencySummary::new(),
+        }
+    }
+
+    /// Sets the counts and amounts of rejected transactions by error code.
+    pub fn with_rejection_summary(mut self, rejection_summary: RejectionSummary) -> Self {
+        self.rejection_summary = rejection_summary;
+        self
+    }
+
+    /// Sets the processing latencies by transaction type.
+    pub fn with_latency_summary(mut self, latency_summary: LatencySummary) -> Self {
+        self.latency_summary = latency_summary;
+        self
+    }
+
+    /// Returns the final state of all accounts.
+    pub fn accounts(&self) -> &Accounts {
+        &self.accounts
+    }
+
+    /// Returns the transactions that were rejected, in the order they were
+    /// processed.
+    ///
+    /// These are only recorded when using [`ErrorPolicy::Collect`].
+    ///
+    /// [`ErrorPolicy::Collect`]: crate::ErrorPolicy::Collect
+    pub fn rejections(&self) -> &[TxError] {
+        &self.rejections
+    }
+
+    /// Returns the records that could not be parsed, in the order they were
+    /// read.
+    ///
+    /// These are only recorded when using [`ErrorPolicy::Collect`].
+    ///
+    /// [`ErrorPolicy::Collect`]: crate::ErrorPolicy::Collect
+    pub fn record_errors(&self) -> &[Error] {
+        &self.record_errors
+    }
+
+    /// Returns the counts and amounts of rejected transactions by error code.
+    ///
+    /// Unlike [`ProcessSummary::rejections`], this is recorded for every
+    /// [`ErrorPolicy`].
+    ///
+    /// [`ErrorPolicy`]: crate::ErrorPolicy
+    pub fn rejection_summary(&self) -> &RejectionSummary {
+        &self.rejection_summary
+    }
+
+    /// Returns the processing latencies by transaction type.
+    ///
+    /// This is empty on targets without a clock, such as
+    /// `wasm32-unknown-unknown`.
+    pub fn latency_summary(&self) -> &LatencySummary {
+        &self.latency_summary
+    }
+
+    /// Returns whether processing was cancelled before all records were read.
+    ///
+    /// When this is `true`, the accounts only reflect the transactions that
+    /// were read before cancellation.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Merges the summary of another partition of the same run into this
+    /// summary.
+    ///
+    /// Partitions have different clients, so accounts do not conflict.
+    /// Rejections and record errors of `other` are added after those of this
+    /// summary.
+    pub(crate) fn merge(&mut self, other: ProcessSummary) {
+        let ProcessSummary {
+            accounts,
+            rejections,
+            record_errors,
+            cancelled,
+            rejection_summary,
+            latency_summary,
+        } = other;
+
+        let _conflicts = self.accounts.merge(accounts);
+        self.rejections.extend(rejections);
+        self.record_errors.extend(record_errors);
+        self.cancelled |= cancelled;
+        self.rejection_summary.merge(&rejection_summary);
+        self.latency_summary.merge(&latency_summary);
+    }
+
+    /// Sorts the rejections by transaction ID, so they are in the same order
+    /// however the run was partitioned.
+    ///
+    /// Rejections of the same transaction ID are ordered by their message.
+    pub(crate) fn sort_rejections(&mut self) {
+        self.rejections
+            .sort_by_cached_key(|tx_error| (tx_error.tx(), tx_error.to_string()));
+    }
+
+    /// Returns the accounts, rejections, and record errors, consuming this
+    /// summary.
+    pub fn into_inner(self) -> (Accounts, Vec<TxError>, Vec<Error>) {
+        (self.accounts, self.rejections, self.record_errors)
+    }
+}
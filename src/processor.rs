@@ -0,0 +1,416 @@
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use crate::{
+    model::{Account, Accounts, Transaction},
+    tx_processor::TxProcessor,
+    DedupIndex, Error, TxError, TxStore, TX_BLOCK_SIZE,
+};
+#[cfg(feature = "fs")]
+use crate::{processor_snapshot::SnapshotState, tx_block_store::TxBlockStore};
+
+/// Processes transactions one at a time, keeping account state between calls.
+///
+/// This is useful for interactive and server use cases, where transactions
+/// are received individually instead of being read from a file.
+#[derive(Debug)]
+pub struct Processor<S> {
+    /// Account state of all clients seen so far.
+    accounts: Accounts,
+    /// Stores transactions to look up when processing disputes.
+    tx_store: S,
+    /// Transactions that have not yet been persisted to the transaction store.
+    tx_block: Vec<Transaction>,
+    /// Number of transactions applied, including rejected transactions.
+    position: u64,
+    /// Offsets applied from each source partition, to skip duplicate
+    /// deliveries.
+    dedup_index: DedupIndex,
+}
+
+#[cfg(feature = "fs")]
+impl Processor<TxBlockStore> {
+    /// Returns a new `Processor` with no accounts, storing transactions in a
+    /// temporary directory.
+    pub fn try_new() -> Result<Self, Error> {
+        TxBlockStore::try_new().map(Self::new)
+    }
+}
+
+impl<S> Processor<S>
+where
+    S: TxStore,
+{
+    /// Returns a new `Processor` with no accounts, storing transactions in the
+    /// given store.
+    pub fn new(tx_store: S) -> Self {
+        Self::with_accounts(tx_store, Accounts::new())
+    }
+
+    /// Returns a new `Processor` that continues from existing accounts,
+    /// storing transactions in the given store.
+    ///
+    /// Disputes can only be applied to deposits in `tx_store`, or deposits
+    /// applied through this processor.
+    pub fn with_accounts(tx_store: S, accounts: Accounts) -> Self {
+        Self {
+            accounts,
+            tx_store,
+            tx_block: Vec::with_capacity(TX_BLOCK_SIZE),
+            position: 0,
+            dedup_index: DedupIndex::new(),
+        }
+    }
+
+    /// Applies a transaction to its client's account.
+    ///
+    /// The outer `Result` is an application failure, while the inner `Result`
+    /// indicates whether the transaction was rejected. Rejected transactions
+    /// leave the account unchanged.
+    pub async fn apply(&mut self, transaction: Transaction) -> Result<Result<(), TxError>, Error> {
+        match &transaction {
+            Transaction::Deposit(_) | Transaction::Withdrawal(_) => {}
+            Transaction::Dispute(_) | Transaction::Resolve(_) | Transaction::Chargeback(_) => {
+                // The disputed transaction is looked up from the transaction store, so it must
+                // be persisted before it can be found.
+                let tx = transaction.tx();
+                if self
+                    .tx_block
+                    .iter()
+                    .any(|tx_block_transaction| tx_block_transaction.tx() == tx)
+                {
+                    self.persist_block().await?;
+                }
+            }
+        }
+
+        let account = self
+            .accounts
+            .entry(transaction.client())
+            .or_insert_with(|| Account::empty(transaction.client()));
+        let tx_result = TxProcessor::new(&self.tx_store)
+            .process(account, transaction.clone())
+            .await?;
+
+        self.position += 1;
+        self.tx_block.push(transaction);
+        if self.tx_block.len() >= TX_BLOCK_SIZE {
+            self.persist_block().await?;
+        }
+
+        Ok(tx_result)
+    }
+
+    /// Applies a transaction delivered from a source partition at the given
+    /// offset, unless that offset was already applied.
+    ///
+    /// Returns `None` for duplicate deliveries, which should be acknowledged
+    /// to the source without being applied again. Offsets within a partition
+    /// must be applied in increasing order, see [`DedupIndex`].
+    pub async fn apply_from(
+        &mut self,
+        partition: &str,
+        offset: u64,
+        transaction: Transaction,
+    ) -> Result<Option<Result<(), TxError>>, Error> {
+        if self.dedup_index.contains(partition, offset) {
+            return Ok(None);
+        }
+
+        let tx_result = self.apply(transaction).await?;
+        self.dedup_index.insert(partition, offset);
+
+        Ok(Some(tx_result))
+    }
+
+    /// Returns the offsets applied from each source partition.
+    pub fn dedup_index(&self) -> &DedupIndex {
+        &self.dedup_index
+    }
+
+    /// Returns the accounts of all clients seen so far.
+    pub fn accounts(&self) -> &Accounts {
+        &self.accounts
+    }
+
+    /// Returns the number of transactions applied, including rejected
+    /// transactions.
+    ///
+    /// After [`Processor::restore`], this continues from the snapshot's
+    /// position, so it is the number of input transactions to skip when
+    /// resuming.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the accounts of all clients seen so far, consuming this
+    /// processor.
+    pub fn into_accounts(self) -> Accounts {
+        self.accounts
+    }
+
+    /// Writes the processing state to a snapshot file at `path`.
+    ///
+    /// The snapshot holds the accounts including open disputes, the deposits
+    /// in the transaction store, the [`position`], and the [`dedup_index`].
+    /// The file is replaced atomically, so a processor that is killed while
+    /// writing a snapshot leaves the previous snapshot intact.
+    ///
+    /// Pending transactions are persisted to the transaction store first.
+    ///
+    /// [`position`]: Self::position
+    /// [`dedup_index`]: Self::dedup_index
+    #[cfg(feature = "fs")]
+    pub async fn snapshot(&mut self, path: &Path) -> Result<(), Error> {
+        self.persist_block().await?;
+        let deposits = self.tx_store.deposits().await?;
+
+        SnapshotState::write(
+            path,
+            self.position,
+            &self.dedup_index,
+            &self.accounts,
+            deposits,
+        )
+        .await
+    }
+
+    /// Returns a `Processor` restored from a snapshot file written by
+    /// [`Processor::snapshot`].
+    ///
+    /// Deposits in the snapshot are persisted to `tx_store`, so they may be
+    /// disputed after restoring.
+    #[cfg(feature = "fs")]
+    pub async fn restore(tx_store: S, path: &Path) -> Result<Self, Error> {
+        let snapshot_state = SnapshotState::read(path).await?;
+        Self::from_snapshot_state(tx_store, snapshot_state).await
+    }
+
+    /// Returns a `Processor` restored from snapshot state that has been read.
+    #[cfg(feature = "fs")]
+    pub(crate) async fn from_snapshot_state(
+        tx_store: S,
+        snapshot_state: SnapshotState,
+    ) -> Result<Self, Error> {
+        let SnapshotState {
+            position,
+            dedup_index,
+            accounts,
+            deposits,
+        } = snapshot_state;
+        let mut processor = Self::with_accounts(tx_store, accounts);
+        processor.position = position;
+        processor.dedup_index = dedup_index;
+        for deposits_block in deposits.chunks(TX_BLOCK_SIZE) {
+            processor.tx_store.persist_block(deposits_block).await?;
+        }
+
+        Ok(processor)
+    }
+
+    /// Persists the pending transactions to the transaction store.
+    async fn persist_block(&mut self) -> Result<(), Error> {
+        if !self.tx_block.is_empty() {
+            let persist_block = self.tx_store.persist_block(&self.tx_block);
+            #[cfg(feature = "tracing")]
+            let persist_block =
+                tracing::Instrument::instrument(persist_block, crate::chunk_span(&self.tx_block));
+            persist_block.await?;
+            #[cfg(feature = "metrics")]
+            crate::process_metrics::block_persisted(&self.tx_block);
+            #[cfg(feature = "tracing")]
+            tracing::debug!(transactions = self.tx_block.len(), "Block persisted.");
+            self.tx_block.clear();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::Processor;
+    use crate::{
+        model::{Account, Accounts, ClientId, Deposit, Dispute, Transaction, TxId, Withdrawal},
+        test_util::{AccountBuilder, TransactionBuilder},
+        MemTxStore, TxError,
+    };
+
+    #[tokio::test]
+    async fn apply_tracks_accounts_across_calls() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let mut processor = Processor::new(MemTxStore::new());
+
+        let transactions = TransactionBuilder::new(client)
+            .deposit(dec!(3.0))
+            .withdrawal(dec!(1.0))
+            .build();
+        for transaction in transactions {
+            processor.apply(transaction).await??;
+        }
+
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(2.0))
+            .build();
+        assert_eq!(Some(&account_expected), processor.accounts().get(client));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn apply_dispute_finds_transaction_not_yet_persisted()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+        let mut processor = Processor::new(MemTxStore::new());
+
+        processor
+            .apply(Transaction::from(Deposit::new(client, tx, dec!(3.0))))
+            .await??;
+        processor
+            .apply(Transaction::from(Dispute::new(client, tx)))
+            .await??;
+
+        let account_expected = AccountBuilder::new(client)
+            .with_held(dec!(3.0))
+            .with_disputed_txs([tx])
+            .build();
+        assert_eq!(Some(&account_expected), processor.accounts().get(client));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn with_accounts_applies_transactions_to_existing_accounts()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let mut accounts = Accounts::new();
+        accounts.insert(
+            client,
+            AccountBuilder::new(client)
+                .with_available(dec!(5.0))
+                .build(),
+        );
+        let mut processor = Processor::with_accounts(MemTxStore::new(), accounts);
+
+        processor
+            .apply(Transaction::from(Withdrawal::new(
+                client,
+                TxId::new(2),
+                dec!(4.0),
+            )))
+            .await??;
+
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
+        assert_eq!(Some(&account_expected), processor.accounts().get(client));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn apply_returns_tx_error_for_rejected_transaction()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(1);
+        let mut processor = Processor::new(MemTxStore::new());
+
+        let tx_result = processor
+            .apply(Transaction::from(Withdrawal::new(client, tx, dec!(1.0))))
+            .await?;
+
+        assert_eq!(
+            Err(TxError::WithdrawalInsufficientAvailable {
+                client,
+                tx,
+                available: dec!(0.0),
+                amount: dec!(1.0),
+            }),
+            tx_result
+        );
+        assert_eq!(
+            Some(&Account::empty(client)),
+            processor.accounts().get(client)
+        );
+        assert_eq!(
+            Some((1, Some("WITHDRAWAL_INSUFFICIENT_AVAILABLE"))),
+            processor.accounts().get(client).map(|account| {
+                let rejections = account.rejections();
+                (rejections.count(), rejections.last_code())
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn apply_from_skips_offsets_already_applied() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let deposit = Transaction::from(Deposit::new(client, TxId::new(1), dec!(3.0)));
+        let mut processor = Processor::new(MemTxStore::new());
+
+        let delivered = processor.apply_from("p0", 7, deposit.clone()).await?;
+        let redelivered = processor.apply_from("p0", 7, deposit.clone()).await?;
+        let other_partition = processor.apply_from("p1", 7, deposit).await?;
+
+        assert!(matches!(delivered, Some(Ok(()))));
+        assert!(redelivered.is_none());
+        assert!(other_partition.is_some());
+        assert_eq!(2, processor.position());
+        assert_eq!(Some(8), processor.dedup_index().next_offset("p0"));
+        Ok(())
+    }
+
+    #[cfg(feature = "fs")]
+    #[tokio::test]
+    async fn restore_continues_from_snapshot() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let snapshot_dir = tempfile::tempdir()?;
+        let snapshot_path = snapshot_dir.path().join("snapshot.jsonl");
+        let mut processor = Processor::new(crate::TxBlockStore::try_new()?);
+        processor
+            .apply(Transaction::from(Deposit::new(
+                client,
+                TxId::new(1),
+                dec!(1.2345),
+            )))
+            .await??;
+        processor
+            .apply(Transaction::from(Deposit::new(
+                client,
+                TxId::new(2),
+                dec!(123456789.0123),
+            )))
+            .await??;
+        processor
+            .apply_from(
+                "p0",
+                0,
+                Transaction::from(Dispute::new(client, TxId::new(1))),
+            )
+            .await?
+            .expect("Expected offset to not be applied.")?;
+
+        processor.snapshot(&snapshot_path).await?;
+        let mut processor_restored = Processor::restore(MemTxStore::new(), &snapshot_path).await?;
+
+        assert_eq!(3, processor_restored.position());
+        assert_eq!(processor.dedup_index(), processor_restored.dedup_index());
+        assert_eq!(**processor.accounts(), **processor_restored.accounts());
+        processor_restored
+            .apply(Transaction::from(crate::model::Resolve::new(
+                client,
+                TxId::new(1),
+            )))
+            .await??;
+        processor_restored
+            .apply(Transaction::from(Dispute::new(client, TxId::new(2))))
+            .await??;
+        let account = processor_restored
+            .accounts()
+            .get(client)
+            .expect("Expected account to be restored.");
+        assert_eq!(dec!(1.2345), account.available());
+        assert_eq!(dec!(123456789.0123), account.held());
+        Ok(())
+    }
+}
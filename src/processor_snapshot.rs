@@ -0,0 +1,244 @@
+use std::{io, path::Path};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter, Lines},
+};
+
+use crate::{
+    model::{Account, AccountState, Accounts, ClientId, Deposit, Transaction, TxId},
+    DedupIndex, Error,
+};
+
+/// Processing state held in a snapshot file.
+#[derive(Debug, Default)]
+pub(crate) struct SnapshotState {
+    /// Number of transactions applied before the snapshot was taken.
+    pub(crate) position: u64,
+    /// Offsets applied from each source partition.
+    pub(crate) dedup_index: DedupIndex,
+    /// Accounts, including open disputes.
+    pub(crate) accounts: Accounts,
+    /// Deposits that may be disputed.
+    pub(crate) deposits: Vec<Transaction>,
+}
+
+impl SnapshotState {
+    /// Reads the state from the snapshot file at `path`.
+    pub(crate) async fn read(path: &Path) -> Result<Self, Error> {
+        let mut snapshot_state = Self::default();
+        let mut snapshot_reader = SnapshotReader::open(path).await?;
+
+        while let Some(snapshot_record) = snapshot_reader.next_record().await? {
+            match snapshot_record {
+                SnapshotRecord::Position(position) => snapshot_state.position = position,
+                SnapshotRecord::SourceOffset {
+                    partition,
+                    next_offset,
+                } => {
+                    if let Some(offset) = next_offset.checked_sub(1) {
+                        snapshot_state.dedup_index.insert(&partition, offset);
+                    }
+                }
+                SnapshotRecord::Account(account_state) => {
+                    let client = account_state.client();
+                    let account = Account::try_from(account_state).map_err(|_| {
+                        snapshot_reader.record_invalid(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("Available and held amounts overflow for client {client}."),
+                        ))
+                    })?;
+                    snapshot_state.accounts.insert(account.client(), account);
+                }
+                SnapshotRecord::Deposit(deposit_snapshot) => snapshot_state
+                    .deposits
+                    .push(Transaction::from(deposit_snapshot)),
+            }
+        }
+
+        Ok(snapshot_state)
+    }
+
+    /// Writes the state to a snapshot file at `path`, see [`write`].
+    ///
+    /// Deposits are written in transaction ID order.
+    pub(crate) async fn write(
+        path: &Path,
+        position: u64,
+        dedup_index: &DedupIndex,
+        accounts: &Accounts,
+        mut deposits: Vec<Transaction>,
+    ) -> Result<(), Error> {
+        deposits.sort_by_key(Transaction::tx);
+
+        let snapshot_records = std::iter::once(SnapshotRecord::Position(position))
+            .chain(dedup_index.iter().map(|(partition, next_offset)| {
+                SnapshotRecord::SourceOffset {
+                    partition: partition.to_string(),
+                    next_offset,
+                }
+            }))
+            .chain(
+                accounts
+                    .values()
+                    .map(|account| SnapshotRecord::Account(AccountState::from(account))),
+            )
+            .chain(
+                deposits
+                    .iter()
+                    .filter_map(DepositSnapshot::from_transaction)
+                    .map(SnapshotRecord::Deposit),
+            );
+        write(path, snapshot_records).await
+    }
+}
+
+/// Record in a processor snapshot file.
+///
+/// Snapshot files have one JSON object per line. Amounts are written as
+/// strings, so they are restored exactly.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum SnapshotRecord {
+    /// Number of transactions applied before the snapshot was taken.
+    Position(u64),
+    /// Next offset to apply from a source partition.
+    SourceOffset {
+        /// Source partition.
+        partition: String,
+        /// Next offset to apply.
+        next_offset: u64,
+    },
+    /// Account state, including open disputes.
+    Account(AccountState),
+    /// Deposit that may be disputed.
+    Deposit(DepositSnapshot),
+}
+
+/// Deposit in a snapshot.
+#[derive(Debug, Deserialize, Serialize)]
+struct DepositSnapshot {
+    /// Client ID.
+    client: ClientId,
+    /// Transaction ID.
+    tx: TxId,
+    /// Deposited amount.
+    amount: Decimal,
+}
+
+impl DepositSnapshot {
+    /// Returns a `DepositSnapshot` if the transaction is a deposit.
+    fn from_transaction(transaction: &Transaction) -> Option<Self> {
+        match transaction {
+            Transaction::Deposit(deposit) => Some(Self {
+                client: deposit.client(),
+                tx: deposit.tx(),
+                amount: deposit.amount(),
+            }),
+            Transaction::Withdrawal(_)
+            | Transaction::Dispute(_)
+            | Transaction::Resolve(_)
+            | Transaction::Chargeback(_) => None,
+        }
+    }
+}
+
+impl From<DepositSnapshot> for Transaction {
+    fn from(deposit_snapshot: DepositSnapshot) -> Self {
+        let DepositSnapshot { client, tx, amount } = deposit_snapshot;
+        Transaction::from(Deposit::new(client, tx, amount))
+    }
+}
+
+/// Writes snapshot records to `path`.
+///
+/// Records are written to a temporary file next to `path`, which is renamed
+/// to `path` once complete, so an interrupted write leaves any previous
+/// snapshot intact.
+async fn write<I>(path: &Path, snapshot_records: I) -> Result<(), Error>
+where
+    I: IntoIterator<Item = SnapshotRecord>,
+{
+    let snapshot_write = |error| Error::SnapshotWrite {
+        path: path.to_path_buf(),
+        error,
+    };
+    let mut path_partial = path.as_os_str().to_owned();
+    path_partial.push(".partial");
+
+    let mut writer = BufWriter::new(File::create(&path_partial).await.map_err(snapshot_write)?);
+    let mut line = Vec::new();
+    for snapshot_record in snapshot_records {
+        line.clear();
+        serde_json::to_writer(&mut line, &snapshot_record)
+            .map_err(|error| snapshot_write(io::Error::from(error)))?;
+        line.push(b'\n');
+        writer.write_all(&line).await.map_err(snapshot_write)?;
+    }
+    writer.flush().await.map_err(snapshot_write)?;
+    writer.get_ref().sync_all().await.map_err(snapshot_write)?;
+
+    tokio::fs::rename(&path_partial, path)
+        .await
+        .map_err(snapshot_write)
+}
+
+/// Reads snapshot records from a file.
+#[derive(Debug)]
+struct SnapshotReader<'path> {
+    /// Path to the snapshot file.
+    path: &'path Path,
+    /// Lines of the snapshot file.
+    lines: Lines<BufReader<File>>,
+    /// Number of the last line read, starting from 1.
+    line: usize,
+}
+
+impl<'path> SnapshotReader<'path> {
+    /// Opens the snapshot file at `path`.
+    async fn open(path: &'path Path) -> Result<Self, Error> {
+        let file = File::open(path)
+            .await
+            .map_err(|error| Error::SnapshotRead {
+                path: path.to_path_buf(),
+                error,
+            })?;
+
+        Ok(Self {
+            path,
+            lines: BufReader::new(file).lines(),
+            line: 0,
+        })
+    }
+
+    /// Returns the next record, or `None` at the end of the file.
+    async fn next_record(&mut self) -> Result<Option<SnapshotRecord>, Error> {
+        let line = self
+            .lines
+            .next_line()
+            .await
+            .map_err(|error| Error::SnapshotRead {
+                path: self.path.to_path_buf(),
+                error,
+            })?;
+        let Some(line) = line else {
+            return Ok(None);
+        };
+        self.line += 1;
+
+        serde_json::from_str::<SnapshotRecord>(&line)
+            .map(Some)
+            .map_err(|error| self.record_invalid(io::Error::from(error)))
+    }
+
+    /// Returns an error that the last record read is invalid.
+    fn record_invalid(&self, error: io::Error) -> Error {
+        Error::SnapshotRecordInvalid {
+            path: self.path.to_path_buf(),
+            line: self.line,
+            error,
+        }
+    }
+}
@@ -0,0 +1,194 @@
+#[cfg(any(feature = "redis", feature = "nats", feature = "amqp"))]
+use std::{
+    future::Future,
+    pin::{pin, Pin},
+};
+use std::{
+    num::NonZeroU64,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[cfg(any(feature = "redis", feature = "nats", feature = "amqp"))]
+use futures::future::{self, Either};
+
+/// Maximum rate to ingest records and bytes from a network source.
+///
+/// When a source reaches the limit, it waits before reading more, so the
+/// broker or sender holds records back instead of them being dropped. Up to
+/// one second of records and bytes may be ingested in a burst.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Maximum number of records per second.
+    records_per_sec: Option<NonZeroU64>,
+    /// Maximum number of bytes per second.
+    bytes_per_sec: Option<NonZeroU64>,
+}
+
+impl RateLimit {
+    /// Returns a `RateLimit` without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of records per second.
+    pub fn with_records_per_sec(mut self, records_per_sec: NonZeroU64) -> Self {
+        self.records_per_sec = Some(records_per_sec);
+        self
+    }
+
+    /// Sets the maximum number of bytes per second.
+    pub fn with_bytes_per_sec(mut self, bytes_per_sec: NonZeroU64) -> Self {
+        self.bytes_per_sec = Some(bytes_per_sec);
+        self
+    }
+
+    /// Returns the maximum number of records per second.
+    pub fn records_per_sec(&self) -> Option<NonZeroU64> {
+        self.records_per_sec
+    }
+
+    /// Returns the maximum number of bytes per second.
+    pub fn bytes_per_sec(&self) -> Option<NonZeroU64> {
+        self.bytes_per_sec
+    }
+
+    /// Returns whether neither records nor bytes are limited.
+    pub fn is_unlimited(&self) -> bool {
+        self.records_per_sec.is_none() && self.bytes_per_sec.is_none()
+    }
+}
+
+/// Waits so that records and bytes are ingested within a [`RateLimit`].
+///
+/// Each limit tracks the instant its ingested amount would be spread out to
+/// at the limited rate, and callers wait while that is more than one second
+/// ahead of now.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    /// Maximum rate to ingest records and bytes.
+    rate_limit: RateLimit,
+    /// Instants that records and bytes ingested so far are spread out to.
+    ingested_until: Mutex<(Instant, Instant)>,
+}
+
+impl RateLimiter {
+    /// Amount of records and bytes that may be ingested in a burst, as the
+    /// duration it takes at the limited rate.
+    const BURST: Duration = Duration::from_secs(1);
+
+    /// Returns a new `RateLimiter`.
+    pub(crate) fn new(rate_limit: RateLimit) -> Self {
+        let now = Instant::now();
+        Self {
+            rate_limit,
+            ingested_until: Mutex::new((now, now)),
+        }
+    }
+
+    /// Waits until `records` records of `bytes` bytes may be ingested.
+    pub(crate) async fn acquire(&self, records: u64, bytes: u64) {
+        if self.rate_limit.is_unlimited() {
+            return;
+        }
+
+        let wait = self.reserve(records, bytes, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Waits until `records` records of `bytes` bytes may be ingested, or
+    /// until `shutdown` completes.
+    ///
+    /// Returns `false` if `shutdown` completed first.
+    #[cfg(any(feature = "redis", feature = "nats", feature = "amqp"))]
+    pub(crate) async fn acquire_or_shutdown<F>(
+        &self,
+        records: u64,
+        bytes: u64,
+        shutdown: Pin<&mut F>,
+    ) -> bool
+    where
+        F: Future<Output = ()>,
+    {
+        let acquire = pin!(self.acquire(records, bytes));
+        match future::select(acquire, shutdown).await {
+            Either::Left(((), _)) => true,
+            Either::Right(((), _)) => false,
+        }
+    }
+
+    /// Reserves ingestion of `records` records of `bytes` bytes, and returns
+    /// how long to wait before ingesting them.
+    fn reserve(&self, records: u64, bytes: u64, now: Instant) -> Duration {
+        let mut ingested_until = self
+            .ingested_until
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let (records_until, bytes_until) = &mut *ingested_until;
+
+        let records_wait =
+            Self::reserve_one(records_until, self.rate_limit.records_per_sec, records, now);
+        let bytes_wait = Self::reserve_one(bytes_until, self.rate_limit.bytes_per_sec, bytes, now);
+        records_wait.max(bytes_wait)
+    }
+
+    /// Reserves `amount` of one limit, and returns how long to wait before
+    /// ingesting it.
+    fn reserve_one(
+        ingested_until: &mut Instant,
+        per_sec: Option<NonZeroU64>,
+        amount: u64,
+        now: Instant,
+    ) -> Duration {
+        let Some(per_sec) = per_sec else {
+            return Duration::ZERO;
+        };
+
+        let nanos = u128::from(amount) * 1_000_000_000 / u128::from(per_sec.get());
+        let spread = Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX));
+        let ingested_from = (*ingested_until).max(now);
+        *ingested_until = ingested_from.checked_add(spread).unwrap_or(ingested_from);
+        ingested_until
+            .saturating_duration_since(now)
+            .saturating_sub(Self::BURST)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZeroU64,
+        time::{Duration, Instant},
+    };
+
+    use super::{RateLimit, RateLimiter};
+
+    #[test]
+    fn reserve_waits_once_burst_is_used() -> Result<(), Box<dyn std::error::Error>> {
+        let records_per_sec = NonZeroU64::new(10).ok_or("zero")?;
+        let bytes_per_sec = NonZeroU64::new(1000).ok_or("zero")?;
+        let rate_limiter = RateLimiter::new(
+            RateLimit::new()
+                .with_records_per_sec(records_per_sec)
+                .with_bytes_per_sec(bytes_per_sec),
+        );
+        let now = Instant::now();
+
+        // One second of records is a burst.
+        assert_eq!(Duration::ZERO, rate_limiter.reserve(10, 100, now));
+        assert_eq!(Duration::from_millis(100), rate_limiter.reserve(1, 10, now));
+        // Bytes are limited separately, and the longer wait is returned.
+        assert_eq!(
+            Duration::from_millis(1110),
+            rate_limiter.reserve(0, 2000, now)
+        );
+        // Time passing frees up the limit.
+        assert_eq!(
+            Duration::ZERO,
+            rate_limiter.reserve(1, 10, now + Duration::from_secs(10))
+        );
+        Ok(())
+    }
+}
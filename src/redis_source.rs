@@ -0,0 +1,328 @@
+//! Applies transactions read from a Redis Stream.
+//!
+//! Entries are read through a consumer group, and each entry is acknowledged
+//! once its transaction is applied to a [`Processor`]. Entries that were
+//! delivered to the consumer but not acknowledged, such as when the
+//! application stopped while applying them, are read again on the next run.
+//!
+//! Each entry has `type`, `client`, `tx`, and `amount` fields, the same as the
+//! columns of transactions CSV content. `amount` may be omitted for disputes,
+//! resolves, and chargebacks.
+//!
+//! ```text
+//! XADD transactions * type deposit client 1 tx 1 amount 2.0
+//! XADD transactions * type dispute client 1 tx 1
+//! ```
+//!
+//! Reading may be limited to a [`RateLimit`], in which case entries wait in the
+//! stream until they may be applied.
+
+use std::{future::Future, pin::pin, time::Duration};
+
+use futures::future::{self, Either};
+use redis::{
+    aio::MultiplexedConnection,
+    streams::{StreamId, StreamReadOptions, StreamReadReply},
+    AsyncCommands,
+};
+
+use crate::{
+    csv::TransactCsv, rate_limit::RateLimiter, Error, IngestSummary, Processor, RateLimit, TxStore,
+};
+
+/// Reads transactions from a Redis Stream through a consumer group.
+#[derive(Debug)]
+pub struct RedisSource {
+    /// Connection to Redis.
+    connection: MultiplexedConnection,
+    /// Key of the stream to read entries from.
+    stream: String,
+    /// Name of the consumer group.
+    group: String,
+    /// Name of this consumer within the group.
+    consumer: String,
+    /// Maximum number of entries to read per request.
+    batch_size: usize,
+    /// Duration to wait for new entries per request.
+    block: Duration,
+    /// Limits the rate that entries are applied.
+    rate_limiter: RateLimiter,
+}
+
+impl RedisSource {
+    /// Default maximum number of entries to read per request.
+    pub const BATCH_SIZE_DEFAULT: usize = 100;
+    /// Default duration to wait for new entries per request.
+    pub const BLOCK_DEFAULT: Duration = Duration::from_secs(1);
+
+    /// Connects to Redis, and creates the consumer group if it does not
+    /// exist.
+    ///
+    /// A new consumer group reads the stream from its first entry. The stream
+    /// is created if it does not exist.
+    ///
+    /// # Parameters
+    ///
+    /// * `url`: Redis connection URL, e.g. `redis://127.0.0.1/`.
+    /// * `stream`: Key of the stream to read entries from.
+    /// * `group`: Name of the consumer group.
+    /// * `consumer`: Name of this consumer within the group.
+    pub async fn connect(
+        url: &str,
+        stream: String,
+        group: String,
+        consumer: String,
+    ) -> Result<Self, Error> {
+        let mut connection = redis::Client::open(url)
+            .map_err(Error::RedisConnect)?
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(Error::RedisConnect)?;
+
+        let group_create: Result<(), _> = connection
+            .xgroup_create_mkstream(&stream, &group, "0")
+            .await;
+        match group_create {
+            Ok(()) => {}
+            Err(error) if error.code() == Some("BUSYGROUP") => {}
+            Err(error) => return Err(Error::RedisGroupCreate(error)),
+        }
+
+        Ok(Self {
+            connection,
+            stream,
+            group,
+            consumer,
+            batch_size: Self::BATCH_SIZE_DEFAULT,
+            block: Self::BLOCK_DEFAULT,
+            rate_limiter: RateLimiter::new(RateLimit::new()),
+        })
+    }
+
+    /// Sets the maximum number of entries to read per request.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Sets the duration to wait for new entries per request.
+    ///
+    /// This is also the longest `shutdown` may wait for a request to finish.
+    pub fn with_block(mut self, block: Duration) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// Limits the rate that entries are applied.
+    ///
+    /// Entries that were read but not applied when `shutdown` completes are
+    /// not acknowledged, so they are read again on the next run.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limiter = RateLimiter::new(rate_limit);
+        self
+    }
+
+    /// Applies transactions from the stream until `shutdown` completes.
+    ///
+    /// Each entry is acknowledged after its transaction is applied or
+    /// rejected, or if it is malformed. If applying a transaction fails, the
+    /// error is returned and the entry is not acknowledged, so it is read
+    /// again on the next run.
+    ///
+    /// `shutdown` does not interrupt applying a transaction, so each entry is
+    /// either applied and acknowledged, or left pending for the next run.
+    /// Redis has no negative acknowledgement, so pending entries stay with
+    /// this consumer until it runs again.
+    ///
+    /// # Parameters
+    ///
+    /// * `processor`: Processor to apply transactions with.
+    /// * `shutdown`: Future that completes when reading should stop.
+    pub async fn run<S, F>(
+        &mut self,
+        processor: &mut Processor<S>,
+        shutdown: F,
+    ) -> Result<IngestSummary, Error>
+    where
+        S: TxStore,
+        F: Future<Output = ()>,
+    {
+        let mut shutdown = pin!(shutdown);
+        let mut ingest_summary = IngestSummary::default();
+        // Entries delivered to this consumer but not acknowledged are read first,
+        // before new entries.
+        let mut reading_pending = true;
+
+        loop {
+            let (id, options) = if reading_pending {
+                ("0", StreamReadOptions::default())
+            } else {
+                let block_ms = usize::try_from(self.block.as_millis()).unwrap_or(usize::MAX);
+                (">", StreamReadOptions::default().block(block_ms))
+            };
+            let options = options
+                .group(&self.group, &self.consumer)
+                .count(self.batch_size);
+
+            // The read is on a clone of the multiplexed connection, so that it may be
+            // dropped on shutdown without holding `self.connection`.
+            let mut connection = self.connection.clone();
+            let (keys, ids) = ([&self.stream], [id]);
+            let read =
+                pin!(connection
+                    .xread_options::<_, _, Option<StreamReadReply>>(&keys, &ids, &options));
+            let stream_read_reply = match future::select(read, shutdown.as_mut()).await {
+                Either::Left((stream_read_reply, _)) => {
+                    stream_read_reply.map_err(Error::RedisRead)?
+                }
+                Either::Right(((), _)) => return Ok(ingest_summary),
+            };
+
+            let stream_ids = stream_read_reply
+                .into_iter()
+                .flat_map(|stream_read_reply| stream_read_reply.keys)
+                .flat_map(|stream_key| stream_key.ids)
+                .collect::<Vec<StreamId>>();
+            if stream_ids.is_empty() {
+                reading_pending = false;
+                continue;
+            }
+
+            for stream_id in stream_ids {
+                let row = Self::entry_row(&stream_id);
+                if !self
+                    .rate_limiter
+                    .acquire_or_shutdown(1, row.len() as u64, shutdown.as_mut())
+                    .await
+                {
+                    return Ok(ingest_summary);
+                }
+
+                match TransactCsv::row_transaction(&row).await {
+                    Some(Ok(transaction)) => {
+                        let tx_result = processor.apply(transaction).await?;
+                        ingest_summary.record_tx_result(Some(tx_result));
+                    }
+                    Some(Err(error)) if error.is_record_error() => {
+                        ingest_summary.record_error(error);
+                    }
+                    Some(Err(error)) => return Err(error),
+                    None => {}
+                }
+
+                let _: usize = self
+                    .connection
+                    .xack(&self.stream, &self.group, &[&stream_id.id])
+                    .await
+                    .map_err(Error::RedisAck)?;
+            }
+        }
+    }
+
+    /// Returns the transactions CSV row for a stream entry's fields.
+    ///
+    /// Each field is quoted, so values are read as they were written.
+    fn entry_row(stream_id: &StreamId) -> Vec<u8> {
+        let mut row = Vec::new();
+        for (index, field) in ["type", "client", "tx", "amount"].into_iter().enumerate() {
+            if index > 0 {
+                row.push(b',');
+            }
+            let value = stream_id.get::<Vec<u8>>(field).unwrap_or_default();
+            row.push(b'"');
+            for byte in value {
+                if byte == b'"' {
+                    row.push(b'"');
+                }
+                row.push(byte);
+            }
+            row.push(b'"');
+        }
+        row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use redis::{streams::StreamId, Value};
+    use rust_decimal_macros::dec;
+
+    use super::RedisSource;
+    use crate::{
+        csv::{TransactCsv, TxRecord},
+        model::{ClientId, Deposit, Dispute, Transaction, TxId},
+        Error,
+    };
+
+    fn stream_id(fields: &[(&str, &str)]) -> StreamId {
+        StreamId {
+            id: String::from("1-0"),
+            map: fields
+                .iter()
+                .map(|(field, value)| {
+                    (
+                        field.to_string(),
+                        Value::BulkString(value.as_bytes().to_vec()),
+                    )
+                })
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[tokio::test]
+    async fn entry_row_parses_as_transaction() -> Result<(), Box<dyn std::error::Error>> {
+        let deposit = stream_id(&[
+            ("type", "deposit"),
+            ("client", "1"),
+            ("tx", "1"),
+            ("amount", "2.0"),
+        ]);
+        let dispute = stream_id(&[("type", "dispute"), ("client", "1"), ("tx", "1")]);
+        let deposit_without_amount =
+            stream_id(&[("type", "deposit"), ("client", "1"), ("tx", "2")]);
+        let quoted = stream_id(&[
+            ("type", "deposit\",\"1"),
+            ("client", "1"),
+            ("tx", "3"),
+            ("amount", "2.0"),
+        ]);
+
+        let deposit = TransactCsv::row_transaction(&RedisSource::entry_row(&deposit))
+            .await
+            .transpose()?;
+        let dispute = TransactCsv::row_transaction(&RedisSource::entry_row(&dispute))
+            .await
+            .transpose()?;
+        let deposit_without_amount =
+            TransactCsv::row_transaction(&RedisSource::entry_row(&deposit_without_amount)).await;
+        let quoted = TransactCsv::row_transaction(&RedisSource::entry_row(&quoted)).await;
+
+        let client = ClientId::new(1);
+        assert_eq!(
+            vec![
+                TxRecord::from(Transaction::from(Deposit::new(
+                    client,
+                    TxId::new(1),
+                    dec!(2.0)
+                ))),
+                TxRecord::from(Transaction::from(Dispute::new(client, TxId::new(1)))),
+            ],
+            [deposit, dispute]
+                .into_iter()
+                .flatten()
+                .map(TxRecord::from)
+                .collect::<Vec<_>>()
+        );
+        assert!(matches!(
+            deposit_without_amount,
+            Some(Err(Error::DepositAmountNotProvided { tx, .. })) if tx == TxId::new(2)
+        ));
+        assert!(matches!(
+            quoted,
+            Some(Err(error)) if error.is_record_error()
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+use std::{collections::BTreeMap, fmt};
+
+use rust_decimal::Decimal;
+
+use crate::{model::Transaction, TxError};
+
+/// Counts and amounts of rejected transactions, grouped by [`TxError::code`].
+///
+/// Unlike [`ProcessSummary::rejections`], this is recorded for every error
+/// policy, so it stays small even when millions of transactions are rejected.
+///
+/// [`ProcessSummary::rejections`]: crate::ProcessSummary::rejections
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RejectionSummary {
+    /// Count and amount of rejections for each error code.
+    by_code: BTreeMap<&'static str, RejectionCount>,
+}
+
+/// Number of transactions rejected with the same error code, and the total
+/// amount involved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RejectionCount {
+    /// Number of rejected transactions.
+    count: u64,
+    /// Sum of the amounts of the rejected transactions.
+    amount: Decimal,
+}
+
+impl RejectionSummary {
+    /// Returns an empty `RejectionSummary`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a rejected transaction.
+    ///
+    /// The amount involved is the deposit or withdrawal amount, or for
+    /// disputes, resolves, and chargebacks, the disputed amount if the error
+    /// carries it.
+    pub fn record(&mut self, transaction: &Transaction, tx_error: &TxError) {
+        let amount = transaction
+            .amount()
+            .or_else(|| Self::tx_error_amount(tx_error));
+
+        let rejection_count = self.by_code.entry(tx_error.code()).or_default();
+        rejection_count.count += 1;
+        if let Some(amount) = amount {
+            rejection_count.amount = rejection_count.amount.saturating_add(amount);
+        }
+    }
+
+    /// Adds the counts and amounts of `other`, e.g. from another partition.
+    pub fn merge(&mut self, other: &RejectionSummary) {
+        other.iter().for_each(|(code, rejection_count_other)| {
+            let rejection_count = self.by_code.entry(code).or_default();
+            rejection_count.count += rejection_count_other.count;
+            rejection_count.amount = rejection_count
+                .amount
+                .saturating_add(rejection_count_other.amount);
+        });
+    }
+
+    /// Returns the count and amount of rejections with the given error code.
+    pub fn get(&self, code: &str) -> Option<&RejectionCount> {
+        self.by_code.get(code)
+    }
+
+    /// Returns an iterator over each error code and its rejections, ordered by
+    /// code.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &RejectionCount)> {
+        self.by_code
+            .iter()
+            .map(|(code, rejection_count)| (*code, rejection_count))
+    }
+
+    /// Returns the number of rejected transactions across all error codes.
+    pub fn count(&self) -> u64 {
+        self.by_code
+            .values()
+            .map(|rejection_count| rejection_count.count)
+            .sum()
+    }
+
+    /// Returns whether no transactions were rejected.
+    pub fn is_empty(&self) -> bool {
+        self.by_code.is_empty()
+    }
+
+    /// Returns the amount carried by the error, for transactions without an
+    /// amount.
+    fn tx_error_amount(tx_error: &TxError) -> Option<Decimal> {
+        match *tx_error {
+            TxError::DisputeInsufficientAvailable { amount, .. }
+            | TxError::DisputeHeldOverflow { amount, .. }
+            | TxError::ResolveInsufficientHeld { amount, .. }
+            | TxError::ResolveAvailableOverflow { amount, .. }
+            | TxError::ChargebackInsufficientHeld { amount, .. }
+            | TxError::DepositAmountNegative { amount, .. }
+            | TxError::WithdrawalAmountNegative { amount, .. }
+            | TxError::WithdrawalInsufficientAvailable { amount, .. } => Some(amount),
+            TxError::AccountLocked { .. }
+            | TxError::DisputeClientMismatch { .. }
+            | TxError::DisputeTxNotFound { .. }
+            | TxError::TxAlreadyDisputed { .. }
+            | TxError::TxAlreadyChargedBack { .. }
+            | TxError::ResolveClientMismatch { .. }
+            | TxError::ResolveTxNotInDispute { .. }
+            | TxError::ChargebackClientMismatch { .. }
+            | TxError::ChargebackTxNotInDispute { .. }
+            | TxError::DepositAvailableOverflow { .. }
+            | TxError::DepositTotalOverflow { .. } => None,
+        }
+    }
+}
+
+impl RejectionCount {
+    /// Returns the number of rejected transactions.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the sum of the amounts of the rejected transactions.
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+}
+
+/// Writes one line per error code, e.g.
+/// `12431 WITHDRAWAL_INSUFFICIENT_AVAILABLE totalling 1200000.00`.
+impl fmt::Display for RejectionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.iter().try_for_each(|(code, rejection_count)| {
+            let RejectionCount { count, amount } = rejection_count;
+            writeln!(f, "{count} {code} totalling {amount}")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::RejectionSummary;
+    use crate::{
+        model::{ClientId, Dispute, Transaction, TxId, Withdrawal},
+        TxError,
+    };
+
+    #[test]
+    fn record_groups_rejections_by_code() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let mut rejection_summary = RejectionSummary::new();
+
+        [(2, dec!(3.0)), (3, dec!(4.5))]
+            .into_iter()
+            .for_each(|(tx, amount)| {
+                let tx = TxId::new(tx);
+                rejection_summary.record(
+                    &Transaction::from(Withdrawal::new(client, tx, amount)),
+                    &TxError::WithdrawalInsufficientAvailable {
+                        client,
+                        tx,
+                        available: dec!(1.0),
+                        amount,
+                    },
+                );
+            });
+        rejection_summary.record(
+            &Transaction::from(Dispute::new(client, TxId::new(9))),
+            &TxError::DisputeTxNotFound { tx: TxId::new(9) },
+        );
+
+        assert_eq!(3, rejection_summary.count());
+        assert_eq!(
+            "1 DISPUTE_TX_NOT_FOUND totalling 0\n\
+             2 WITHDRAWAL_INSUFFICIENT_AVAILABLE totalling 7.5\n",
+            rejection_summary.to_string()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,934 @@
+//! HTTP server to ingest transactions and query accounts.
+//!
+//! Transactions are applied to a long-lived [`Processor`], so account state is
+//! kept between requests. Deposits are kept in a [`MemTxStore`] to look up
+//! when processing disputes.
+//!
+//! # Endpoints
+//!
+//! * `POST /transactions`: Applies transactions CSV content in the request
+//!   body, and returns an [`IngestSummary`].
+//!
+//!   Senders that retry requests may pass the [`SOURCE_PARTITION_HEADER`] and
+//!   [`SOURCE_OFFSET_HEADER`] headers. Each record's offset is the request's
+//!   offset plus the record's index, and records whose offsets were already
+//!   applied are counted as duplicates instead of being applied again.
+//!
+//!   Requests wait while they exceed the [`RateLimit`] in the
+//!   [`ServerOptions`], before their transactions are applied.
+//! * `GET /accounts`: Returns accounts in order of client ID.
+//!
+//!   The `locked` query parameter returns only locked or unlocked accounts,
+//!   and the `offset` and `limit` query parameters return a page of them,
+//!   e.g. `/accounts?locked=true&offset=100&limit=50`.
+//! * `GET /accounts/{client}`: Returns the client's account.
+//! * `GET /accounts/{client}/disputes`: Returns the client's open disputed and
+//!   charged back transaction IDs, as an [`AccountDisputes`].
+//! * `GET /accounts/updates`: WebSocket that pushes an [`AccountUpdate`] as a
+//!   JSON text message for each applied transaction.
+//!
+//!   The `client` query parameter pushes only that client's updates, e.g.
+//!   `/accounts/updates?client=719`. Updates are sent as they are applied, so
+//!   a subscriber that falls more than [`ACCOUNT_UPDATES_CAPACITY`] updates
+//!   behind skips the oldest ones.
+//! * `GET /healthz`: Returns `200 OK` while the server is running.
+//! * `GET /readyz`: Returns a [`Readiness`] with the last applied transaction,
+//!   the number of pending ingestion requests, and the age and size of the last
+//!   snapshot. The status is `503 Service Unavailable` when the server is not
+//!   ready, so orchestrators may stop routing to it, or restart it.
+//!
+//! Account queries may run concurrently with each other, and wait while
+//! transactions are being applied.
+//!
+//! With the `tracing` feature, each request is handled within a `request`
+//! span with its method and path.
+
+#[cfg(feature = "fs")]
+use std::convert::Infallible;
+use std::{
+    future::{Future, IntoFuture},
+    net::SocketAddr,
+    pin::pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use futures::{
+    future::{self, Either},
+    StreamExt, TryStreamExt,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    net::TcpListener,
+    sync::{
+        broadcast::{self, error::RecvError},
+        Mutex, RwLock,
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    csv::TransactCsv,
+    model::{Account, AccountSummary, ClientId, TxId},
+    rate_limit::RateLimiter,
+    Error, IngestSummary, MemTxStore, Processor, RateLimit,
+};
+
+pub use self::{readiness::Readiness, server_options::ServerOptions};
+
+use self::readiness::IngestHealth;
+
+mod readiness;
+mod server_options;
+
+/// Header with the source partition that a request's transactions are from.
+pub const SOURCE_PARTITION_HEADER: &str = "transact-source-partition";
+/// Header with the offset of the first transaction in a request, within its
+/// source partition.
+pub const SOURCE_OFFSET_HEADER: &str = "transact-source-offset";
+
+/// Number of account updates buffered for each WebSocket subscriber.
+pub const ACCOUNT_UPDATES_CAPACITY: usize = 1024;
+
+/// Duration that `GET /readyz` waits for in-progress ingestion requests,
+/// before reporting the server as not ready.
+pub const READY_LOCK_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// State shared between request handlers.
+#[derive(Debug)]
+struct ServerState {
+    /// Processes transactions and holds account state.
+    processor: RwLock<Processor<MemTxStore>>,
+    /// Sends account updates to WebSocket subscribers.
+    account_updates: broadcast::Sender<AccountUpdate>,
+    /// Ingestion and snapshot progress.
+    ingest_health: Mutex<IngestHealth>,
+    /// Number of `POST /transactions` requests being applied or waiting to be
+    /// applied.
+    ingest_requests_pending: AtomicUsize,
+    /// Limits the rate that transactions from `POST /transactions` are
+    /// applied.
+    rate_limiter: RateLimiter,
+}
+
+impl ServerState {
+    /// Returns new `ServerState` for the processor.
+    fn new(processor: Processor<MemTxStore>, rate_limit: RateLimit) -> Arc<Self> {
+        let (account_updates, _) = broadcast::channel(ACCOUNT_UPDATES_CAPACITY);
+        Arc::new(Self {
+            processor: RwLock::new(processor),
+            account_updates,
+            ingest_health: Mutex::new(IngestHealth::default()),
+            ingest_requests_pending: AtomicUsize::new(0),
+            rate_limiter: RateLimiter::new(rate_limit),
+        })
+    }
+
+    /// Writes a processor snapshot to `path`, and records its outcome.
+    #[cfg(feature = "fs")]
+    async fn snapshot(&self, path: &std::path::Path) -> Result<(), Error> {
+        let snapshot_result = self.processor.write().await.snapshot(path).await;
+        let mut ingest_health = self.ingest_health.lock().await;
+        match &snapshot_result {
+            Ok(()) => {
+                let snapshot_bytes = tokio::fs::metadata(path)
+                    .await
+                    .ok()
+                    .map(|metadata| metadata.len());
+                ingest_health.snapshot_written(snapshot_bytes);
+            }
+            Err(error) => ingest_health.snapshot_failed(error.to_string()),
+        }
+        snapshot_result
+    }
+}
+
+/// Counts a `POST /transactions` request as pending until it is dropped.
+struct IngestRequestPending<'state>(&'state AtomicUsize);
+
+impl<'state> IngestRequestPending<'state> {
+    /// Counts a request as pending.
+    fn new(ingest_requests_pending: &'state AtomicUsize) -> Self {
+        ingest_requests_pending.fetch_add(1, Ordering::Relaxed);
+        Self(ingest_requests_pending)
+    }
+}
+
+impl Drop for IngestRequestPending<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Query parameters of `GET /accounts/updates`.
+#[derive(Debug, Deserialize)]
+struct AccountUpdatesQuery {
+    /// Pushes only this client's updates.
+    client: Option<u16>,
+}
+
+/// Account balances after a transaction was applied.
+#[derive(Clone, Debug, Serialize)]
+pub struct AccountUpdate {
+    /// Client ID.
+    client: ClientId,
+    /// Transaction that changed the account.
+    tx: TxId,
+    /// Available funds after the transaction.
+    #[serde(with = "rust_decimal::serde::float")]
+    available: Decimal,
+    /// Held funds after the transaction.
+    #[serde(with = "rust_decimal::serde::float")]
+    held: Decimal,
+    /// Total funds after the transaction.
+    #[serde(with = "rust_decimal::serde::float")]
+    total: Decimal,
+    /// Whether the account is locked after the transaction.
+    locked: bool,
+}
+
+impl AccountUpdate {
+    /// Returns a new `AccountUpdate` for the account after `tx` was applied.
+    pub fn new(account: &Account, tx: TxId) -> Self {
+        Self {
+            client: account.client(),
+            tx,
+            available: account.available(),
+            held: account.held(),
+            total: account.total(),
+            locked: account.locked(),
+        }
+    }
+
+    /// Returns the client ID.
+    pub fn client(&self) -> ClientId {
+        self.client
+    }
+
+    /// Returns the transaction that changed the account.
+    pub fn tx(&self) -> TxId {
+        self.tx
+    }
+
+    /// Returns the available funds after the transaction.
+    pub fn available(&self) -> Decimal {
+        self.available
+    }
+
+    /// Returns the held funds after the transaction.
+    pub fn held(&self) -> Decimal {
+        self.held
+    }
+
+    /// Returns the total funds after the transaction.
+    pub fn total(&self) -> Decimal {
+        self.total
+    }
+
+    /// Returns whether the account is locked after the transaction.
+    pub fn locked(&self) -> bool {
+        self.locked
+    }
+}
+
+/// Query parameters of `GET /accounts`.
+#[derive(Debug, Deserialize)]
+struct AccountsQuery {
+    /// Returns only locked accounts if `true`, or unlocked accounts if
+    /// `false`.
+    locked: Option<bool>,
+    /// Number of accounts to skip.
+    #[serde(default)]
+    offset: usize,
+    /// Maximum number of accounts to return.
+    limit: Option<usize>,
+}
+
+/// Open disputed and charged back transactions of a client's account.
+#[derive(Debug, Serialize)]
+pub struct AccountDisputes {
+    /// Client ID.
+    client: ClientId,
+    /// Transactions that are disputed, in order of transaction ID.
+    disputed: Vec<TxId>,
+    /// Transactions that were charged back, in order of transaction ID.
+    charged_back: Vec<TxId>,
+}
+
+impl AccountDisputes {
+    /// Returns the client ID.
+    pub fn client(&self) -> ClientId {
+        self.client
+    }
+
+    /// Returns the transactions that are disputed, in order of transaction
+    /// ID.
+    pub fn disputed(&self) -> &[TxId] {
+        &self.disputed
+    }
+
+    /// Returns the transactions that were charged back, in order of
+    /// transaction ID.
+    pub fn charged_back(&self) -> &[TxId] {
+        &self.charged_back
+    }
+}
+
+impl From<&Account> for AccountDisputes {
+    fn from(account: &Account) -> Self {
+        let mut disputed = account.disputed_txs().iter().copied().collect::<Vec<_>>();
+        disputed.sort_unstable();
+        let mut charged_back = account
+            .charged_back_txs()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        charged_back.sort_unstable();
+
+        Self {
+            client: account.client(),
+            disputed,
+            charged_back,
+        }
+    }
+}
+
+/// Serves the HTTP endpoints on `addr` until `shutdown` completes.
+///
+/// Requests that are in progress when `shutdown` completes are finished
+/// before this returns, waiting at most the drain timeout in the `options`.
+/// If a snapshot file is set in the `options`, a final snapshot is written
+/// after that, even when the drain timed out. A request that is applying
+/// transactions holds the processor until it finishes, so the snapshot
+/// includes all of its transactions or none.
+///
+/// # Parameters
+///
+/// * `addr`: Address to listen on.
+/// * `processor`: Processor to apply transactions with.
+/// * `options`: Snapshot and rate limit options, see [`ServerOptions`].
+/// * `shutdown`: Future that completes when the server should stop.
+pub async fn serve<F>(
+    addr: SocketAddr,
+    processor: Processor<MemTxStore>,
+    options: ServerOptions,
+    shutdown: F,
+) -> Result<(), Error>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let listener = TcpListener::bind(addr)
+        .await
+        .map_err(|error| Error::ServerBind { addr, error })?;
+    let server_state = ServerState::new(processor, options.rate_limit());
+    let draining = CancellationToken::new();
+    let shutdown = {
+        let draining = draining.clone();
+        async move {
+            shutdown.await;
+            draining.cancel();
+        }
+    };
+    let server = axum::serve(listener, router_with_state(server_state.clone()))
+        .with_graceful_shutdown(shutdown)
+        .into_future();
+    let served = async {
+        let drain_timed_out = pin!(drain_timed_out(&draining, options.drain_timeout()));
+        match future::select(pin!(server), drain_timed_out).await {
+            Either::Left((served, _)) => served.map_err(Error::ServerServe),
+            Either::Right((timeout, _)) => Err(Error::ServerDrainTimeout { timeout }),
+        }
+    };
+
+    #[cfg(feature = "fs")]
+    if let Some((path, interval)) = options.snapshot() {
+        let snapshots = pin!(snapshot_periodically(&server_state, path, interval));
+        let served = match future::select(pin!(served), snapshots).await {
+            Either::Left((served, _)) => served,
+            Either::Right((infallible, _)) => match infallible {},
+        };
+        server_state.snapshot(path).await?;
+        return served;
+    }
+    served.await
+}
+
+/// Completes with the `drain_timeout` once it has passed since `draining` was
+/// cancelled, or never if there is no timeout.
+async fn drain_timed_out(
+    draining: &CancellationToken,
+    drain_timeout: Option<Duration>,
+) -> Duration {
+    match drain_timeout {
+        Some(drain_timeout) => {
+            draining.cancelled().await;
+            tokio::time::sleep(drain_timeout).await;
+            drain_timeout
+        }
+        None => future::pending().await,
+    }
+}
+
+/// Writes a processor snapshot to `path` every `interval`.
+///
+/// Snapshot failures are reported by `GET /readyz`, and the snapshot is
+/// attempted again after the next `interval`.
+#[cfg(feature = "fs")]
+async fn snapshot_periodically(
+    server_state: &ServerState,
+    path: &std::path::Path,
+    interval: Duration,
+) -> Infallible {
+    loop {
+        tokio::time::sleep(interval).await;
+        // Failures are recorded in the ingest health.
+        let _ = server_state.snapshot(path).await;
+    }
+}
+
+/// Returns the [`Router`] for the HTTP endpoints.
+///
+/// This may be used to serve the endpoints with a custom listener, or nest
+/// them within another application.
+pub fn router(processor: Processor<MemTxStore>) -> Router {
+    router_with_state(ServerState::new(processor, RateLimit::new()))
+}
+
+/// Returns the [`Router`] for the HTTP endpoints, sharing the `server_state`.
+fn router_with_state(server_state: Arc<ServerState>) -> Router {
+    let router = Router::new()
+        .route("/healthz", get(healthz_get))
+        .route("/readyz", get(readyz_get))
+        .route("/transactions", post(transactions_post))
+        .route("/accounts", get(accounts_get))
+        .route("/accounts/updates", get(account_updates_get))
+        .route("/accounts/{client}", get(account_get))
+        .route("/accounts/{client}/disputes", get(account_disputes_get))
+        .with_state(server_state);
+    #[cfg(feature = "tracing")]
+    let router = router.layer(axum::middleware::from_fn(request_span));
+
+    router
+}
+
+/// Handles the request within a span with its method and path.
+#[cfg(feature = "tracing")]
+async fn request_span(request: axum::extract::Request, next: axum::middleware::Next) -> Response {
+    let span = tracing::info_span!(
+        "request",
+        method = %request.method(),
+        path = request.uri().path(),
+    );
+    tracing::Instrument::instrument(next.run(request), span).await
+}
+
+/// Applies transactions CSV content in the request body.
+async fn transactions_post(
+    State(server_state): State<Arc<ServerState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<IngestSummary>, Response> {
+    let source = source_offset(&headers)
+        .map_err(|message| (StatusCode::BAD_REQUEST, message).into_response())?;
+    let transactions = TransactCsv::stream_reader(&body[..])
+        .into_stream()
+        .collect::<Vec<_>>()
+        .await;
+    let mut ingest_summary = IngestSummary::default();
+    let _ingest_request_pending = IngestRequestPending::new(&server_state.ingest_requests_pending);
+    // Waits before taking the processor, so account queries are not blocked.
+    server_state
+        .rate_limiter
+        .acquire(transactions.len() as u64, body.len() as u64)
+        .await;
+    let mut processor = server_state.processor.write().await;
+    let mut last_applied_tx = None;
+
+    for (index, transaction) in (0u64..).zip(transactions) {
+        match transaction {
+            Ok(transaction) => {
+                let (client, tx) = (transaction.client(), transaction.tx());
+                let tx_result = match &source {
+                    Some((partition, offset)) => {
+                        processor
+                            .apply_from(partition, offset.saturating_add(index), transaction)
+                            .await
+                    }
+                    None => processor.apply(transaction).await.map(Some),
+                };
+                let tx_result = tx_result.map_err(IntoResponse::into_response)?;
+                if matches!(tx_result, Some(Ok(()))) {
+                    last_applied_tx = Some(tx);
+                    if let Some(account) = processor.accounts().get(client) {
+                        // Sending only fails when there are no subscribers.
+                        let _ = server_state
+                            .account_updates
+                            .send(AccountUpdate::new(account, tx));
+                    }
+                }
+                ingest_summary.record_tx_result(tx_result);
+            }
+            Err(error) if error.is_record_error() => ingest_summary.record_error(error),
+            Err(error) => return Err(error.into_response()),
+        }
+    }
+
+    if let Some(tx) = last_applied_tx {
+        server_state.ingest_health.lock().await.tx_applied(tx);
+    }
+    Ok(Json(ingest_summary))
+}
+
+/// Returns `200 OK` while the server is running.
+async fn healthz_get() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Returns whether the server is ready to ingest transactions.
+async fn readyz_get(State(server_state): State<Arc<ServerState>>) -> Response {
+    let processor_available =
+        tokio::time::timeout(READY_LOCK_TIMEOUT, server_state.processor.read())
+            .await
+            .is_ok();
+    let readiness = Readiness::new(
+        &*server_state.ingest_health.lock().await,
+        server_state.ingest_requests_pending.load(Ordering::Relaxed),
+        processor_available,
+    );
+    let status_code = if readiness.ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status_code, Json(readiness)).into_response()
+}
+
+/// Returns the source partition and offset from the request headers, if
+/// present.
+fn source_offset(headers: &HeaderMap) -> Result<Option<(String, u64)>, String> {
+    let header_str = |name: &str| {
+        headers
+            .get(name)
+            .map(|value| {
+                value
+                    .to_str()
+                    .map_err(|_| format!("`{name}` header is not valid UTF-8."))
+            })
+            .transpose()
+    };
+
+    match (
+        header_str(SOURCE_PARTITION_HEADER)?,
+        header_str(SOURCE_OFFSET_HEADER)?,
+    ) {
+        (Some(partition), Some(offset)) => offset
+            .parse::<u64>()
+            .map(|offset| Some((partition.to_string(), offset)))
+            .map_err(|_| format!("`{SOURCE_OFFSET_HEADER}` header is not a number: `{offset}`.")),
+        (None, None) => Ok(None),
+        (Some(_), None) | (None, Some(_)) => Err(format!(
+            "`{SOURCE_PARTITION_HEADER}` and `{SOURCE_OFFSET_HEADER}` headers must be passed together."
+        )),
+    }
+}
+
+/// Returns accounts in order of client ID, filtered and paged by the query.
+async fn accounts_get(
+    State(server_state): State<Arc<ServerState>>,
+    Query(accounts_query): Query<AccountsQuery>,
+) -> Response {
+    let AccountsQuery {
+        locked,
+        offset,
+        limit,
+    } = accounts_query;
+    let processor = server_state.processor.read().await;
+    let accounts = processor
+        .accounts()
+        .iter_sorted()
+        .filter(|account| locked.is_none_or(|locked| account.locked() == locked))
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .map(AccountSummary::from)
+        .collect::<Vec<AccountSummary>>();
+
+    Json(accounts).into_response()
+}
+
+/// Returns the client's account.
+async fn account_get(
+    State(server_state): State<Arc<ServerState>>,
+    Path(client): Path<u16>,
+) -> Response {
+    let processor = server_state.processor.read().await;
+    match processor.accounts().get(ClientId::new(client)) {
+        Some(account) => Json(AccountSummary::from(account)).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Returns the client's open disputed and charged back transactions.
+async fn account_disputes_get(
+    State(server_state): State<Arc<ServerState>>,
+    Path(client): Path<u16>,
+) -> Response {
+    let processor = server_state.processor.read().await;
+    match processor.accounts().get(ClientId::new(client)) {
+        Some(account) => Json(AccountDisputes::from(account)).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Upgrades the request to a WebSocket that pushes account updates.
+async fn account_updates_get(
+    State(server_state): State<Arc<ServerState>>,
+    Query(account_updates_query): Query<AccountUpdatesQuery>,
+    web_socket_upgrade: WebSocketUpgrade,
+) -> Response {
+    // Subscribe before upgrading, so updates applied after the handshake
+    // completes are pushed.
+    let account_updates = server_state.account_updates.subscribe();
+    let client = account_updates_query.client.map(ClientId::new);
+    web_socket_upgrade
+        .on_upgrade(move |web_socket| account_updates_push(web_socket, account_updates, client))
+}
+
+/// Pushes account updates to the WebSocket until it is closed.
+async fn account_updates_push(
+    mut web_socket: WebSocket,
+    mut account_updates: broadcast::Receiver<AccountUpdate>,
+    client: Option<ClientId>,
+) {
+    loop {
+        let account_update = tokio::select! {
+            account_update = account_updates.recv() => match account_update {
+                Ok(account_update) => account_update,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            },
+            message = web_socket.recv() => match message {
+                // Messages from the subscriber are ignored.
+                Some(Ok(_)) => continue,
+                Some(Err(_)) | None => return,
+            },
+        };
+        if client.is_some_and(|client| client != account_update.client) {
+            continue;
+        }
+
+        let Ok(account_update) = serde_json::to_string(&account_update) else {
+            continue;
+        };
+        if web_socket
+            .send(Message::Text(account_update.into()))
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use futures::StreamExt;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+    use tower::ServiceExt;
+
+    use super::{router, SOURCE_OFFSET_HEADER, SOURCE_PARTITION_HEADER};
+    use crate::{MemTxStore, Processor};
+
+    #[tokio::test]
+    async fn transactions_post_applies_transactions_and_account_get_returns_account()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let router = router(Processor::new(MemTxStore::new()));
+
+        let response = router
+            .clone()
+            .oneshot(Request::post("/transactions").body(Body::from(
+                "type, client, tx, amount\n\
+                 deposit, 1, 1, 2.0\n\
+                 withdrawal, 1, 2, 5.0\n",
+            ))?)
+            .await?;
+        assert_eq!(StatusCode::OK, response.status());
+        let ingest_summary: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+        assert_eq!(1, ingest_summary["applied"]);
+        assert_eq!(
+            "WITHDRAWAL_INSUFFICIENT_AVAILABLE",
+            ingest_summary["rejections"][0]["code"]
+        );
+
+        let response = router
+            .clone()
+            .oneshot(Request::get("/accounts/1").body(Body::empty())?)
+            .await?;
+        assert_eq!(StatusCode::OK, response.status());
+        let account: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+        assert_eq!(2.0, account["available"]);
+
+        let response = router
+            .oneshot(Request::get("/accounts/2").body(Body::empty())?)
+            .await?;
+        assert_eq!(StatusCode::NOT_FOUND, response.status());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn transactions_post_skips_offsets_already_applied()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let router = router(Processor::new(MemTxStore::new()));
+        let request = || {
+            Request::post("/transactions")
+                .header(SOURCE_PARTITION_HEADER, "p0")
+                .header(SOURCE_OFFSET_HEADER, "10")
+                .body(Body::from(
+                    "type, client, tx, amount\n\
+                     deposit, 1, 1, 2.0\n\
+                     deposit, 1, 2, 3.0\n",
+                ))
+        };
+
+        router.clone().oneshot(request()?).await?;
+        let response = router.clone().oneshot(request()?).await?;
+        assert_eq!(StatusCode::OK, response.status());
+        let ingest_summary: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+        assert_eq!(0, ingest_summary["applied"]);
+        assert_eq!(2, ingest_summary["duplicates"]);
+
+        let response = router
+            .oneshot(Request::get("/accounts/1").body(Body::empty())?)
+            .await?;
+        let account: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+        assert_eq!(5.0, account["available"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn accounts_get_filters_and_pages_and_account_disputes_get_returns_disputes()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let router = router(Processor::new(MemTxStore::new()));
+        router
+            .clone()
+            .oneshot(Request::post("/transactions").body(Body::from(
+                "type, client, tx, amount\n\
+                 deposit, 3, 1, 1.0\n\
+                 deposit, 1, 2, 1.0\n\
+                 deposit, 2, 3, 1.0\n\
+                 deposit, 2, 4, 1.0\n\
+                 dispute, 2, 3\n\
+                 dispute, 2, 4\n\
+                 chargeback, 2, 4\n",
+            ))?)
+            .await?;
+        let get_json = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                let response = router
+                    .oneshot(Request::get(uri).body(Body::empty())?)
+                    .await?;
+                let json: serde_json::Value =
+                    serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+                Ok::<_, Box<dyn std::error::Error>>(json)
+            }
+        };
+
+        let clients = |accounts: serde_json::Value| {
+            accounts
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|account| account["client"].clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![1, 2, 3], clients(get_json("/accounts").await?));
+        assert_eq!(
+            vec![3],
+            clients(get_json("/accounts?offset=1&limit=1&locked=false").await?)
+        );
+        assert_eq!(vec![2], clients(get_json("/accounts?locked=true").await?));
+
+        let account_disputes = get_json("/accounts/2/disputes").await?;
+        assert_eq!(
+            serde_json::json!({"client": 2, "disputed": [3], "charged_back": [4]}),
+            account_disputes
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn healthz_get_and_readyz_get_report_ingestion_progress()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let router = router(Processor::new(MemTxStore::new()));
+
+        let response = router
+            .clone()
+            .oneshot(Request::get("/healthz").body(Body::empty())?)
+            .await?;
+        assert_eq!(StatusCode::OK, response.status());
+
+        router
+            .clone()
+            .oneshot(Request::post("/transactions").body(Body::from(
+                "type, client, tx, amount\n\
+                 deposit, 1, 1, 2.0\n\
+                 deposit, 1, 2, 3.0\n\
+                 withdrawal, 1, 3, 9.0\n",
+            ))?)
+            .await?;
+        let response = router
+            .oneshot(Request::get("/readyz").body(Body::empty())?)
+            .await?;
+        assert_eq!(StatusCode::OK, response.status());
+        let readiness: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+        assert_eq!(true, readiness["ready"]);
+        assert_eq!(2, readiness["last_applied_tx"]);
+        assert!(readiness["last_applied_age_ms"].is_u64());
+        assert_eq!(0, readiness["ingest_requests_pending"]);
+        assert!(readiness["snapshot_age_ms"].is_null());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn account_updates_get_pushes_updates_for_the_client()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let router = router(Processor::new(MemTxStore::new()));
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        tokio::spawn(std::future::IntoFuture::into_future(axum::serve(
+            listener,
+            router.clone(),
+        )));
+
+        let (mut web_socket, _) =
+            tokio_tungstenite::connect_async(format!("ws://{addr}/accounts/updates?client=2"))
+                .await?;
+        router
+            .oneshot(Request::post("/transactions").body(Body::from(
+                "type, client, tx, amount\n\
+                 deposit, 1, 1, 1.0\n\
+                 deposit, 2, 2, 3.0\n\
+                 withdrawal, 2, 3, 5.0\n\
+                 dispute, 2, 2\n",
+            ))?)
+            .await?;
+
+        let mut account_updates = Vec::new();
+        while account_updates.len() < 2 {
+            match web_socket.next().await.transpose()? {
+                Some(Message::Text(text)) => {
+                    account_updates.push(serde_json::from_str::<serde_json::Value>(&text)?);
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+
+        assert_eq!(
+            vec![
+                serde_json::json!({
+                    "client": 2, "tx": 2, "available": 3.0, "held": 0.0, "total": 3.0, "locked": false
+                }),
+                serde_json::json!({
+                    "client": 2, "tx": 2, "available": 0.0, "held": 3.0, "total": 3.0, "locked": false
+                }),
+            ],
+            account_updates
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "fs")]
+    #[tokio::test]
+    async fn serve_writes_snapshot_when_drain_times_out() -> Result<(), Box<dyn std::error::Error>>
+    {
+        use std::{num::NonZeroU64, time::Duration};
+
+        use tokio::{io::AsyncWriteExt, net::TcpStream, sync::oneshot};
+
+        use super::{serve, ServerOptions};
+        use crate::{Error, RateLimit};
+
+        let snapshot_dir = tempfile::tempdir()?;
+        let snapshot = snapshot_dir.path().join("snapshot.jsonl");
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        // The request's 3 records wait for the rate limit for longer than the
+        // drain timeout.
+        let options = ServerOptions::new()
+            .with_snapshot(snapshot.clone(), Duration::from_secs(3600))
+            .with_rate_limit(RateLimit::new().with_records_per_sec(NonZeroU64::MIN))
+            .with_drain_timeout(Duration::from_millis(100));
+        let served = serve(
+            addr,
+            Processor::new(MemTxStore::new()),
+            options,
+            async move {
+                let _ = shutdown_rx.await;
+            },
+        );
+        let request = async {
+            let mut stream = loop {
+                match TcpStream::connect(addr).await {
+                    Ok(stream) => break stream,
+                    Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+                }
+            };
+            let body = "type, client, tx, amount\n\
+                        deposit, 1, 1, 1.0\n\
+                        deposit, 1, 2, 1.0\n\
+                        deposit, 1, 3, 1.0\n";
+            let request = format!(
+                "POST /transactions HTTP/1.1\r\nHost: {addr}\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(request.as_bytes()).await?;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            shutdown_tx.send(()).map_err(|()| "Server stopped early.")?;
+
+            // The connection is kept open, so the request is not cancelled.
+            Ok::<_, Box<dyn std::error::Error>>(stream)
+        };
+
+        let (served, stream) = tokio::join!(served, request);
+        let _stream = stream?;
+        assert!(
+            matches!(served, Err(Error::ServerDrainTimeout { timeout }) if timeout == Duration::from_millis(100)),
+            "{served:?}"
+        );
+        assert!(snapshot.is_file());
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::model::TxId;
+
+/// Ingestion and snapshot progress, reported by `GET /readyz`.
+#[derive(Debug, Default)]
+pub(crate) struct IngestHealth {
+    /// Last applied transaction, and when it was applied.
+    last_applied: Option<(TxId, Instant)>,
+    /// When the last snapshot was written, and its size in bytes.
+    last_snapshot: Option<(Instant, Option<u64>)>,
+    /// Error from the last snapshot attempt, if it failed.
+    snapshot_error: Option<String>,
+}
+
+impl IngestHealth {
+    /// Records that `tx` was applied.
+    pub(crate) fn tx_applied(&mut self, tx: TxId) {
+        self.last_applied = Some((tx, Instant::now()));
+    }
+
+    /// Records that a snapshot of `snapshot_bytes` bytes was written.
+    #[cfg(feature = "fs")]
+    pub(crate) fn snapshot_written(&mut self, snapshot_bytes: Option<u64>) {
+        self.last_snapshot = Some((Instant::now(), snapshot_bytes));
+        self.snapshot_error = None;
+    }
+
+    /// Records that writing a snapshot failed.
+    #[cfg(feature = "fs")]
+    pub(crate) fn snapshot_failed(&mut self, error: String) {
+        self.snapshot_error = Some(error);
+    }
+}
+
+/// Whether the server is ready to ingest transactions, returned by
+/// `GET /readyz`.
+///
+/// The server is ready when transactions may be applied within
+/// [`READY_LOCK_TIMEOUT`], and the last snapshot, if any, was written
+/// successfully.
+///
+/// [`READY_LOCK_TIMEOUT`]: super::READY_LOCK_TIMEOUT
+#[derive(Clone, Debug, Serialize)]
+pub struct Readiness {
+    /// Whether the server is ready.
+    ready: bool,
+    /// Last applied transaction.
+    last_applied_tx: Option<TxId>,
+    /// Milliseconds since the last transaction was applied.
+    last_applied_age_ms: Option<u64>,
+    /// Number of `POST /transactions` requests being applied or waiting to
+    /// be applied.
+    ingest_requests_pending: usize,
+    /// Milliseconds since the last snapshot was written.
+    snapshot_age_ms: Option<u64>,
+    /// Size of the last snapshot file in bytes.
+    snapshot_bytes: Option<u64>,
+    /// Error from the last snapshot attempt, if it failed.
+    snapshot_error: Option<String>,
+}
+
+impl Readiness {
+    /// Returns the `Readiness` for the recorded ingestion progress.
+    pub(crate) fn new(
+        ingest_health: &IngestHealth,
+        ingest_requests_pending: usize,
+        processor_available: bool,
+    ) -> Self {
+        let age_ms =
+            |instant: Instant| u64::try_from(instant.elapsed().as_millis()).unwrap_or(u64::MAX);
+        let IngestHealth {
+            last_applied,
+            last_snapshot,
+            snapshot_error,
+        } = ingest_health;
+
+        Self {
+            ready: processor_available && snapshot_error.is_none(),
+            last_applied_tx: last_applied.map(|(tx, _)| tx),
+            last_applied_age_ms: last_applied.map(|(_, applied_at)| age_ms(applied_at)),
+            ingest_requests_pending,
+            snapshot_age_ms: last_snapshot.map(|(written_at, _)| age_ms(written_at)),
+            snapshot_bytes: last_snapshot.and_then(|(_, snapshot_bytes)| snapshot_bytes),
+            snapshot_error: snapshot_error.clone(),
+        }
+    }
+
+    /// Returns whether the server is ready.
+    pub fn ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Returns the last applied transaction.
+    pub fn last_applied_tx(&self) -> Option<TxId> {
+        self.last_applied_tx
+    }
+
+    /// Returns the time since the last transaction was applied.
+    ///
+    /// This grows while no transactions are sent, so it should be compared
+    /// with [`Readiness::ingest_requests_pending`] to tell whether ingestion
+    /// is lagging.
+    pub fn last_applied_age(&self) -> Option<Duration> {
+        self.last_applied_age_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the number of `POST /transactions` requests being applied or
+    /// waiting to be applied.
+    pub fn ingest_requests_pending(&self) -> usize {
+        self.ingest_requests_pending
+    }
+
+    /// Returns the time since the last snapshot was written.
+    pub fn snapshot_age(&self) -> Option<Duration> {
+        self.snapshot_age_ms.map(Duration::from_millis)
+    }
+
+    /// Returns the size of the last snapshot file in bytes.
+    pub fn snapshot_bytes(&self) -> Option<u64> {
+        self.snapshot_bytes
+    }
+
+    /// Returns the error from the last snapshot attempt, if it failed.
+    pub fn snapshot_error(&self) -> Option<&str> {
+        self.snapshot_error.as_deref()
+    }
+}
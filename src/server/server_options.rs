@@ -0,0 +1,81 @@
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::RateLimit;
+
+/// Options for [`serve`].
+///
+/// [`serve`]: super::serve
+#[derive(Clone, Debug, Default)]
+pub struct ServerOptions {
+    /// File to write processor snapshots to, and the interval between them.
+    #[cfg(feature = "fs")]
+    snapshot: Option<(PathBuf, Duration)>,
+    /// Maximum rate to apply transactions from `POST /transactions`.
+    rate_limit: RateLimit,
+    /// Maximum duration to wait for in-progress requests after shutdown.
+    drain_timeout: Option<Duration>,
+}
+
+impl ServerOptions {
+    /// Returns new `ServerOptions` with defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the rate that transactions from `POST /transactions` are
+    /// applied.
+    ///
+    /// Requests over the limit wait before their transactions are applied,
+    /// so senders see slower responses instead of rejected records.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Returns the maximum rate to apply transactions from
+    /// `POST /transactions`.
+    pub fn rate_limit(&self) -> RateLimit {
+        self.rate_limit
+    }
+
+    /// Sets the maximum duration to wait for in-progress requests to finish
+    /// after shutdown.
+    ///
+    /// When this is exceeded, [`serve`] stops waiting, writes the final
+    /// snapshot if one is set, and fails with [`Error::ServerDrainTimeout`].
+    /// Defaults to waiting until every request finishes.
+    ///
+    /// [`serve`]: super::serve
+    /// [`Error::ServerDrainTimeout`]: crate::Error::ServerDrainTimeout
+    pub fn with_drain_timeout(mut self, drain_timeout: Duration) -> Self {
+        self.drain_timeout = Some(drain_timeout);
+        self
+    }
+
+    /// Returns the maximum duration to wait for in-progress requests after
+    /// shutdown.
+    pub fn drain_timeout(&self) -> Option<Duration> {
+        self.drain_timeout
+    }
+
+    /// Writes a processor snapshot to `path` every `interval`, and when the
+    /// server stops.
+    ///
+    /// The snapshot's age and size are reported by `GET /readyz`.
+    #[cfg(feature = "fs")]
+    pub fn with_snapshot(mut self, path: PathBuf, interval: Duration) -> Self {
+        self.snapshot = Some((path, interval));
+        self
+    }
+
+    /// Returns the file to write processor snapshots to, and the interval
+    /// between them.
+    #[cfg(feature = "fs")]
+    pub fn snapshot(&self) -> Option<(&Path, Duration)> {
+        self.snapshot
+            .as_ref()
+            .map(|(path, interval)| (path.as_path(), *interval))
+    }
+}
@@ -0,0 +1,33 @@
+//! Builders to construct accounts and transactions in tests.
+//!
+//! These are enabled by the `test-util` feature, so downstream crates may use
+//! them in their own tests.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use rust_decimal_macros::dec;
+//! use transact::{
+//!     model::{ClientId, TxId},
+//!     test_util::{AccountBuilder, TransactionBuilder},
+//! };
+//!
+//! let account = AccountBuilder::new(ClientId::new(1))
+//!     .with_available(dec!(2.0))
+//!     .with_held(dec!(3.0))
+//!     .with_disputed_txs([TxId::new(1)])
+//!     .build();
+//! let transactions = TransactionBuilder::new(ClientId::new(1))
+//!     .deposit(dec!(5.0))
+//!     .withdrawal(dec!(1.0))
+//!     .dispute(TxId::new(1))
+//!     .build();
+//!
+//! assert_eq!(dec!(5.0), account.total());
+//! assert_eq!(3, transactions.len());
+//! ```
+
+pub use self::{account_builder::AccountBuilder, transaction_builder::TransactionBuilder};
+
+mod account_builder;
+mod transaction_builder;
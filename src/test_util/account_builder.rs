@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use rust_decimal::Decimal;
+
+use crate::model::{Account, ClientId, TxId};
+
+/// Builds an [`Account`] with specific balances and disputes.
+///
+/// Unset fields default to an empty, unlocked account.
+#[derive(Clone, Debug)]
+pub struct AccountBuilder {
+    /// Client ID.
+    client: ClientId,
+    /// Available funds.
+    available: Decimal,
+    /// Held funds.
+    held: Decimal,
+    /// Whether the account is locked.
+    locked: bool,
+    /// Deposits in dispute.
+    disputed_txs: HashSet<TxId>,
+    /// Deposits that have been charged back.
+    charged_back_txs: HashSet<TxId>,
+}
+
+impl AccountBuilder {
+    /// Returns a new `AccountBuilder` for an empty account.
+    pub fn new(client: ClientId) -> Self {
+        Self {
+            client,
+            available: Decimal::ZERO,
+            held: Decimal::ZERO,
+            locked: false,
+            disputed_txs: HashSet::new(),
+            charged_back_txs: HashSet::new(),
+        }
+    }
+
+    /// Sets the available funds.
+    pub fn with_available(mut self, available: Decimal) -> Self {
+        self.available = available;
+        self
+    }
+
+    /// Sets the held funds.
+    pub fn with_held(mut self, held: Decimal) -> Self {
+        self.held = held;
+        self
+    }
+
+    /// Sets whether the account is locked.
+    pub fn with_locked(mut self, locked: bool) -> Self {
+        self.locked = locked;
+        self
+    }
+
+    /// Sets the deposits in dispute.
+    pub fn with_disputed_txs<I>(mut self, disputed_txs: I) -> Self
+    where
+        I: IntoIterator<Item = TxId>,
+    {
+        self.disputed_txs = disputed_txs.into_iter().collect();
+        self
+    }
+
+    /// Sets the deposits that have been charged back.
+    pub fn with_charged_back_txs<I>(mut self, charged_back_txs: I) -> Self
+    where
+        I: IntoIterator<Item = TxId>,
+    {
+        self.charged_back_txs = charged_back_txs.into_iter().collect();
+        self
+    }
+
+    /// Returns the account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the available and held funds overflow when added together.
+    pub fn build(self) -> Account {
+        let Self {
+            client,
+            available,
+            held,
+            locked,
+            disputed_txs,
+            charged_back_txs,
+        } = self;
+
+        Account::try_new(client, available, held, locked, disputed_txs)
+            .expect("Account available and held funds overflow when added together.")
+            .with_charged_back_txs(charged_back_txs)
+    }
+}
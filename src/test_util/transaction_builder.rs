@@ -0,0 +1,99 @@
+use rust_decimal::Decimal;
+
+use crate::model::{
+    Chargeback, ClientId, Deposit, Dispute, Resolve, Transaction, TxId, Withdrawal,
+};
+
+/// Builds a sequence of [`Transaction`]s.
+///
+/// Deposits and withdrawals are given increasing transaction IDs, starting
+/// from `1`. Disputes, resolves, and chargebacks refer to the transaction ID
+/// passed to them. Transactions are for the current client, which may be
+/// changed with [`with_client`](Self::with_client).
+#[derive(Clone, Debug)]
+pub struct TransactionBuilder {
+    /// Client of the next transactions.
+    client: ClientId,
+    /// Transaction ID of the next deposit or withdrawal.
+    tx_next: TxId,
+    /// Transactions built so far.
+    transactions: Vec<Transaction>,
+}
+
+impl TransactionBuilder {
+    /// Returns a new `TransactionBuilder` for the given client.
+    pub fn new(client: ClientId) -> Self {
+        Self {
+            client,
+            tx_next: TxId::new(1),
+            transactions: Vec::new(),
+        }
+    }
+
+    /// Sets the client of the next transactions.
+    pub fn with_client(mut self, client: ClientId) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Sets the transaction ID of the next deposit or withdrawal.
+    pub fn with_tx_next(mut self, tx_next: TxId) -> Self {
+        self.tx_next = tx_next;
+        self
+    }
+
+    /// Returns the transaction ID of the next deposit or withdrawal.
+    pub fn tx_next(&self) -> TxId {
+        self.tx_next
+    }
+
+    /// Adds a deposit of `amount`.
+    pub fn deposit(mut self, amount: Decimal) -> Self {
+        let tx = self.tx_take();
+        let client = self.client;
+        self.push(Deposit::new(client, tx, amount))
+    }
+
+    /// Adds a withdrawal of `amount`.
+    pub fn withdrawal(mut self, amount: Decimal) -> Self {
+        let tx = self.tx_take();
+        let client = self.client;
+        self.push(Withdrawal::new(client, tx, amount))
+    }
+
+    /// Adds a dispute of transaction `tx`.
+    pub fn dispute(self, tx: TxId) -> Self {
+        let client = self.client;
+        self.push(Dispute::new(client, tx))
+    }
+
+    /// Adds a resolve of transaction `tx`.
+    pub fn resolve(self, tx: TxId) -> Self {
+        let client = self.client;
+        self.push(Resolve::new(client, tx))
+    }
+
+    /// Adds a chargeback of transaction `tx`.
+    pub fn chargeback(self, tx: TxId) -> Self {
+        let client = self.client;
+        self.push(Chargeback::new(client, tx))
+    }
+
+    /// Returns the transactions in the order they were added.
+    pub fn build(self) -> Vec<Transaction> {
+        self.transactions
+    }
+
+    /// Returns the next transaction ID, and increments it.
+    fn tx_take(&mut self) -> TxId {
+        let tx = self.tx_next;
+        self.tx_next = TxId::new(tx.into_inner().saturating_add(1));
+        tx
+    }
+
+    /// Adds a transaction.
+    fn push(mut self, transaction: impl Into<Transaction>) -> Self {
+        self.transactions.push(transaction.into());
+        self
+    }
+}
@@ -1,6 +1,11 @@
-use std::cmp::{max, min};
+use std::{
+    cmp::{max, min},
+    ops::Range,
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use futures::{stream, StreamExt, TryStreamExt};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 use tempfile::TempDir;
 use tokio::fs::{DirEntry, File};
 use tokio_stream::wrappers::ReadDirStream;
@@ -8,23 +13,104 @@ use tokio_stream::wrappers::ReadDirStream;
 use crate::{
     csv::TxRecord,
     model::{Transaction, TxId},
-    Error, TransactCsv,
+    Error, TransactCsv, TxStore,
 };
 
+/// Stores transactions in block files in a temporary directory.
+///
+/// Block files are written under a temporary name, and renamed to their
+/// `{tx_min}_{tx_max}_{block_id}.csv` name once flushed, so concurrent lookups,
+/// such as from other partitions, never read a partially written block.
 #[derive(Debug)]
 pub struct TxBlockStore {
     temp_dir: TempDir,
+    /// Number of blocks persisted, used to keep block file names unique.
+    blocks_persisted: AtomicU64,
 }
 
 impl TxBlockStore {
     /// Initializes a new transaction block store.
     pub fn try_new() -> Result<Self, Error> {
         let temp_dir = tempfile::tempdir().map_err(Error::BlockStoreDirCreate)?;
-        Ok(Self { temp_dir })
+        Ok(Self::from_temp_dir(temp_dir))
+    }
+
+    /// Initializes a new transaction block store in the given directory.
+    ///
+    /// # Parameters
+    ///
+    /// * `dir`: Directory to create the block store directory in.
+    pub fn try_new_in(dir: &Path) -> Result<Self, Error> {
+        let temp_dir = tempfile::tempdir_in(dir).map_err(Error::BlockStoreDirCreate)?;
+        Ok(Self::from_temp_dir(temp_dir))
+    }
+
+    /// Returns a block store that stores block files in `temp_dir`.
+    fn from_temp_dir(temp_dir: TempDir) -> Self {
+        Self {
+            temp_dir,
+            blocks_persisted: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the block files that have been fully written.
+    ///
+    /// Block files that are still being written have a `.tmp` extension, and
+    /// are skipped.
+    async fn block_files(&self) -> Result<impl Stream<Item = Result<DirEntry, Error>>, Error> {
+        let block_files = tokio::fs::read_dir(self.temp_dir.path())
+            .await
+            .map(ReadDirStream::new)
+            .map_err(Error::BlockStoreDirRead)?
+            .map_err(Error::BlockStoreDirRead)
+            .try_filter(|dir_entry| {
+                let is_block_file = dir_entry
+                    .path()
+                    .extension()
+                    .is_some_and(|extension| extension == "csv");
+                async move { is_block_file }
+            });
+
+        Ok(block_files)
+    }
+
+    /// Returns the min and max transaction IDs associated with a dir entry.
+    async fn parse_min_max_tx(dir_entry: DirEntry) -> Result<(DirEntry, TxId, TxId), Error> {
+        let file_name = dir_entry.file_name();
+        let file_name_lossy = file_name.to_string_lossy();
+        let mut plain_name = file_name_lossy.splitn(2, '.');
+        let file_name_invalid = || Error::BlockFileNameInvalid {
+            file_name: file_name.clone(),
+        };
+        let file_name_invalid_err = |_| Error::BlockFileNameInvalid {
+            file_name: file_name.clone(),
+        };
+        let mut split = plain_name
+            .next()
+            .ok_or_else(file_name_invalid)?
+            .splitn(3, '_');
+        let tx_min = TxId::from(
+            split
+                .next()
+                .ok_or_else(file_name_invalid)?
+                .parse::<u32>()
+                .map_err(file_name_invalid_err)?,
+        );
+        let tx_max = TxId::from(
+            split
+                .next()
+                .ok_or_else(file_name_invalid)?
+                .parse::<u32>()
+                .map_err(file_name_invalid_err)?,
+        );
+
+        Ok((dir_entry, tx_min, tx_max))
     }
+}
 
+impl TxStore for TxBlockStore {
     /// Persists the given block of transactions in this store.
-    pub async fn persist_block(&self, transactions: &[Transaction]) -> Result<(), Error> {
+    async fn persist_block(&self, transactions: &[Transaction]) -> Result<(), Error> {
         // * find smallest and largest transaction id
         // * TODO (optimization): map to client,tx,amt -- only possible if we can only
         //   reverse one kind of transaction
@@ -39,11 +125,13 @@ impl TxBlockStore {
             .expect("expected at least one transaction")
             .tx();
 
-        // Use `{tx_min}_{tx_max}.csv` as file name as transaction IDs are unique, and
-        // the only way this can collide is when two blocks have the same tx_min and
-        // tx_max values -- e.g. disputed transaction IDs perfectly align at the block
-        // boundaries.
-        let file_name = format!("{tx_min}_{tx_max}.csv");
+        // Write to `{block_id}.tmp`, and rename to `{tx_min}_{tx_max}_{block_id}.csv`
+        // once flushed. The block ID keeps names unique when two blocks have the
+        // same tx_min and tx_max values -- e.g. disputed transaction IDs perfectly
+        // align at the block boundaries, or a block of disputes refers to deposits
+        // from a restored snapshot.
+        let block_id = self.blocks_persisted.fetch_add(1, Ordering::Relaxed);
+        let file_name = format!("{block_id}.tmp");
         let file_path = self.temp_dir.path().join(&file_name);
         let block_file = File::create(&file_path)
             .await
@@ -74,18 +162,18 @@ impl TxBlockStore {
         )
         .await?;
         block_writer.flush().await.map_err(Error::BlockFileFlush)?;
-        let min_max_file_name = format!("{tx_min}_{tx_max}.csv");
+        let min_max_file_name = format!("{tx_min}_{tx_max}_{block_id}.csv");
         let min_max_file_path = self.temp_dir.path().join(&min_max_file_name);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(file_name = min_max_file_name, "Block file written.");
 
-        if file_name != min_max_file_name {
-            tokio::fs::rename(&file_path, &min_max_file_path)
-                .await
-                .map_err(|error| Error::BlockFileRename {
-                    from: file_name,
-                    to: min_max_file_name,
-                    error,
-                })?;
-        }
+        tokio::fs::rename(&file_path, &min_max_file_path)
+            .await
+            .map_err(|error| Error::BlockFileRename {
+                from: file_name,
+                to: min_max_file_name,
+                error,
+            })?;
 
         Ok(())
     }
@@ -96,12 +184,10 @@ impl TxBlockStore {
     ///
     /// An optimization is to store disputed transactions and their amounts
     /// separately.
-    pub async fn find_transaction(&self, tx: TxId) -> Result<Option<Transaction>, Error> {
-        let block_transaction_match = tokio::fs::read_dir(self.temp_dir.path())
-            .await
-            .map(ReadDirStream::new)
-            .map_err(Error::BlockStoreDirRead)?
-            .map_err(Error::BlockStoreDirRead)
+    async fn find_transaction(&self, tx: TxId) -> Result<Option<Transaction>, Error> {
+        let block_transaction_match = self
+            .block_files()
+            .await?
             .and_then(Self::parse_min_max_tx)
             .try_filter_map(|(dir_entry, tx_min, tx_max)| {
                 // Checks if the block file may contain the transaction.
@@ -137,36 +223,99 @@ impl TxBlockStore {
         Box::pin(block_transaction_match).next().await.transpose()
     }
 
-    /// Returns the min and max transaction IDs associated with a dir entry.
-    async fn parse_min_max_tx(dir_entry: DirEntry) -> Result<(DirEntry, TxId, TxId), Error> {
-        let file_name = dir_entry.file_name();
-        let file_name_lossy = file_name.to_string_lossy();
-        let mut plain_name = file_name_lossy.splitn(2, '.');
-        let file_name_invalid = || Error::BlockFileNameInvalid {
-            file_name: file_name.clone(),
-        };
-        let file_name_invalid_err = |_| Error::BlockFileNameInvalid {
-            file_name: file_name.clone(),
-        };
-        let mut split = plain_name
-            .next()
-            .ok_or_else(file_name_invalid)?
-            .splitn(2, '_');
-        let tx_min = TxId::from(
-            split
-                .next()
-                .ok_or_else(file_name_invalid)?
-                .parse::<u32>()
-                .map_err(file_name_invalid_err)?,
-        );
-        let tx_max = TxId::from(
-            split
-                .next()
-                .ok_or_else(file_name_invalid)?
-                .parse::<u32>()
-                .map_err(file_name_invalid_err)?,
-        );
+    /// Returns the deposits whose IDs are within `range`, ordered by
+    /// transaction ID.
+    ///
+    /// Only block files whose transaction IDs overlap `range` are read.
+    async fn iter_range(&self, range: Range<TxId>) -> Result<Vec<Transaction>, Error> {
+        let mut deposits = self
+            .block_files()
+            .await?
+            .and_then(Self::parse_min_max_tx)
+            .try_filter_map(|(dir_entry, tx_min, tx_max)| {
+                // Checks if the block file may contain transactions in the range.
+                let block_overlaps_range = tx_min < range.end && tx_max >= range.start;
+                async move {
+                    if block_overlaps_range {
+                        Ok(Some(dir_entry))
+                    } else {
+                        Ok(None)
+                    }
+                }
+            })
+            .and_then(|dir_entry| async move { TransactCsv::stream(&dir_entry.path()).await })
+            .try_flatten()
+            .try_filter(|transaction| {
+                // Block files only contain deposits, see `TxBlockStore::persist_block`.
+                let in_range = matches!(transaction, Transaction::Deposit(_))
+                    && range.contains(&transaction.tx());
+                async move { in_range }
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+        deposits.sort_by_key(Transaction::tx);
 
-        Ok((dir_entry, tx_min, tx_max))
+        Ok(deposits)
+    }
+
+    /// Returns all deposits in this block store.
+    ///
+    /// This reads every block file.
+    async fn deposits(&self) -> Result<Vec<Transaction>, Error> {
+        self.block_files()
+            .await?
+            .and_then(|dir_entry| async move { TransactCsv::stream(&dir_entry.path()).await })
+            .try_flatten()
+            .try_filter(|transaction| {
+                // Block files only contain deposits, see `TxBlockStore::persist_block`.
+                let is_deposit = matches!(transaction, Transaction::Deposit(_));
+                async move { is_deposit }
+            })
+            .try_collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::TxBlockStore;
+    use crate::{
+        model::{ClientId, TxId},
+        test_util::TransactionBuilder,
+        TxStore,
+    };
+
+    #[tokio::test]
+    async fn contains_and_iter_range_return_deposits() -> Result<(), Box<dyn std::error::Error>> {
+        let transactions = TransactionBuilder::new(ClientId::new(1))
+            .deposit(dec!(1.0))
+            .deposit(dec!(2.0))
+            .deposit(dec!(3.0))
+            .withdrawal(dec!(1.0))
+            .deposit(dec!(5.0))
+            .deposit(dec!(6.0))
+            .build();
+        let tx_store = TxBlockStore::try_new()?;
+        tx_store.persist_block(&transactions[..4]).await?;
+        tx_store.persist_block(&transactions[4..]).await?;
+
+        assert!(tx_store.contains(TxId::new(5)).await?);
+        assert!(!tx_store.contains(TxId::new(4)).await?);
+        assert!(!tx_store.contains(TxId::new(7)).await?);
+        assert_eq!(
+            vec![
+                transactions[1].clone(),
+                transactions[2].clone(),
+                transactions[4].clone()
+            ],
+            tx_store.iter_range(TxId::new(2)..TxId::new(6)).await?
+        );
+        assert!(tx_store
+            .iter_range(TxId::new(7)..TxId::new(10))
+            .await?
+            .is_empty());
+        Ok(())
     }
 }
@@ -1,6 +1,7 @@
 use std::fmt;
 
 use rust_decimal::Decimal;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 
 use crate::model::{ClientId, TxId};
 
@@ -51,6 +52,20 @@ pub enum TxError {
         /// Amount that is disputed.
         amount: Decimal,
     },
+    /// Dispute of a transaction that is already in dispute.
+    TxAlreadyDisputed {
+        /// Client ID.
+        client: ClientId,
+        /// Transaction ID that is disputed.
+        tx: TxId,
+    },
+    /// Dispute of a transaction that has already been charged back.
+    TxAlreadyChargedBack {
+        /// Client ID.
+        client: ClientId,
+        /// Transaction ID that is disputed.
+        tx: TxId,
+    },
     /// Resolve transaction client ID does not match client ID of the disputed
     /// transaction.
     ResolveClientMismatch {
@@ -166,6 +181,65 @@ pub enum TxError {
     },
 }
 
+impl TxError {
+    /// Returns the ID of the rejected transaction.
+    ///
+    /// For disputes, resolves, and chargebacks, this is the ID of the disputed
+    /// transaction.
+    pub fn tx(&self) -> TxId {
+        match self {
+            Self::AccountLocked { tx, .. }
+            | Self::DisputeClientMismatch { tx, .. }
+            | Self::DisputeTxNotFound { tx, .. }
+            | Self::DisputeInsufficientAvailable { tx, .. }
+            | Self::DisputeHeldOverflow { tx, .. }
+            | Self::TxAlreadyDisputed { tx, .. }
+            | Self::TxAlreadyChargedBack { tx, .. }
+            | Self::ResolveClientMismatch { tx, .. }
+            | Self::ResolveInsufficientHeld { tx, .. }
+            | Self::ResolveAvailableOverflow { tx, .. }
+            | Self::ResolveTxNotInDispute { tx, .. }
+            | Self::ChargebackClientMismatch { tx, .. }
+            | Self::ChargebackInsufficientHeld { tx, .. }
+            | Self::ChargebackTxNotInDispute { tx, .. }
+            | Self::DepositAmountNegative { tx, .. }
+            | Self::DepositAvailableOverflow { tx, .. }
+            | Self::DepositTotalOverflow { tx, .. }
+            | Self::WithdrawalAmountNegative { tx, .. }
+            | Self::WithdrawalInsufficientAvailable { tx, .. } => *tx,
+        }
+    }
+
+    /// Returns a stable code for this error, e.g.
+    /// `"WITHDRAWAL_INSUFFICIENT_AVAILABLE"`.
+    ///
+    /// Codes do not change when the error message wording changes, so they may
+    /// be used to identify the error in reports and responses.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::AccountLocked { .. } => "ACCOUNT_LOCKED",
+            Self::DisputeClientMismatch { .. } => "DISPUTE_CLIENT_MISMATCH",
+            Self::DisputeTxNotFound { .. } => "DISPUTE_TX_NOT_FOUND",
+            Self::DisputeInsufficientAvailable { .. } => "DISPUTE_INSUFFICIENT_AVAILABLE",
+            Self::DisputeHeldOverflow { .. } => "DISPUTE_HELD_OVERFLOW",
+            Self::TxAlreadyDisputed { .. } => "TX_ALREADY_DISPUTED",
+            Self::TxAlreadyChargedBack { .. } => "TX_ALREADY_CHARGED_BACK",
+            Self::ResolveClientMismatch { .. } => "RESOLVE_CLIENT_MISMATCH",
+            Self::ResolveInsufficientHeld { .. } => "RESOLVE_INSUFFICIENT_HELD",
+            Self::ResolveAvailableOverflow { .. } => "RESOLVE_AVAILABLE_OVERFLOW",
+            Self::ResolveTxNotInDispute { .. } => "RESOLVE_TX_NOT_IN_DISPUTE",
+            Self::ChargebackClientMismatch { .. } => "CHARGEBACK_CLIENT_MISMATCH",
+            Self::ChargebackInsufficientHeld { .. } => "CHARGEBACK_INSUFFICIENT_HELD",
+            Self::ChargebackTxNotInDispute { .. } => "CHARGEBACK_TX_NOT_IN_DISPUTE",
+            Self::DepositAmountNegative { .. } => "DEPOSIT_AMOUNT_NEGATIVE",
+            Self::DepositAvailableOverflow { .. } => "DEPOSIT_AVAILABLE_OVERFLOW",
+            Self::DepositTotalOverflow { .. } => "DEPOSIT_TOTAL_OVERFLOW",
+            Self::WithdrawalAmountNegative { .. } => "WITHDRAWAL_AMOUNT_NEGATIVE",
+            Self::WithdrawalInsufficientAvailable { .. } => "WITHDRAWAL_INSUFFICIENT_AVAILABLE",
+        }
+    }
+}
+
 impl fmt::Display for TxError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -206,6 +280,14 @@ impl fmt::Display for TxError {
                 "Account held amount would overflow for dispute:\n\
                  client {client}, transaction {tx}, held {held}, amount {amount}.",
             ),
+            Self::TxAlreadyDisputed { client, tx } => write!(
+                f,
+                "Dispute transaction already in dispute: client {client}, transaction {tx}.",
+            ),
+            Self::TxAlreadyChargedBack { client, tx } => write!(
+                f,
+                "Dispute transaction already charged back: client {client}, transaction {tx}.",
+            ),
             Self::ResolveClientMismatch {
                 tx,
                 resolve_tx_client,
@@ -300,6 +382,8 @@ impl std::error::Error for TxError {
             Self::DisputeTxNotFound { .. } => None,
             Self::DisputeInsufficientAvailable { .. } => None,
             Self::DisputeHeldOverflow { .. } => None,
+            Self::TxAlreadyDisputed { .. } => None,
+            Self::TxAlreadyChargedBack { .. } => None,
             Self::ResolveClientMismatch { .. } => None,
             Self::ResolveInsufficientHeld { .. } => None,
             Self::ResolveAvailableOverflow { .. } => None,
@@ -315,3 +399,45 @@ impl std::error::Error for TxError {
         }
     }
 }
+
+/// Serializes as the error `code` and `message`.
+impl Serialize for TxError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("TxError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use super::TxError;
+    use crate::model::{ClientId, TxId};
+
+    #[test]
+    fn serializes_code_and_message() -> Result<(), Box<dyn std::error::Error>> {
+        let tx_error = TxError::WithdrawalInsufficientAvailable {
+            client: ClientId::new(1),
+            tx: TxId::new(2),
+            available: dec!(1.0),
+            amount: dec!(2.0),
+        };
+
+        let value = serde_json::to_value(&tx_error)?;
+
+        assert_eq!(
+            serde_json::json!({
+                "code": "WITHDRAWAL_INSUFFICIENT_AVAILABLE",
+                "message": tx_error.to_string(),
+            }),
+            value
+        );
+        Ok(())
+    }
+}
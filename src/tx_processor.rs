@@ -1,21 +1,89 @@
-use std::cmp::Ordering;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    sync::{Mutex, PoisonError},
+};
+
+use futures::{stream, StreamExt, TryStreamExt};
 
+#[cfg(feature = "metrics")]
+use crate::process_metrics;
 use crate::{
-    model::{Account, Chargeback, Deposit, Dispute, Resolve, Transaction, Withdrawal},
-    Error, TxBlockStore, TxError,
+    model::{Account, Chargeback, Deposit, Dispute, Resolve, Transaction, TxId, Withdrawal},
+    Error, TxError, TxStore,
 };
 
 /// Processes transactions for an account.
 #[derive(Debug)]
-pub struct TxProcessor<'block_store> {
+pub struct TxProcessor<'tx_store, S> {
     /// Stores transactions.
-    block_store: &'block_store TxBlockStore,
+    tx_store: &'tx_store S,
+    /// Maximum duration to wait for a transaction lookup.
+    #[cfg(feature = "tokio")]
+    lookup_timeout: Option<Duration>,
+    /// Transactions looked up ahead of being processed, by transaction ID.
+    prefetched: Mutex<HashMap<TxId, Option<Transaction>>>,
 }
 
-impl<'block_store> TxProcessor<'block_store> {
+impl<'tx_store, S> TxProcessor<'tx_store, S>
+where
+    S: TxStore,
+{
     /// Returns a new `TxProcessor`.
-    pub fn new(block_store: &'block_store TxBlockStore) -> Self {
-        Self { block_store }
+    pub fn new(tx_store: &'tx_store S) -> Self {
+        Self {
+            tx_store,
+            #[cfg(feature = "tokio")]
+            lookup_timeout: None,
+            prefetched: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the maximum duration to wait for a transaction lookup.
+    #[cfg(feature = "tokio")]
+    pub fn with_lookup_timeout(mut self, lookup_timeout: Option<Duration>) -> Self {
+        self.lookup_timeout = lookup_timeout;
+        self
+    }
+
+    /// Looks up the transactions referred to by disputes, resolves, and
+    /// chargebacks in `transactions`, with up to `concurrency` lookups at a
+    /// time.
+    ///
+    /// Processing these transactions then uses the prefetched lookups instead
+    /// of waiting for each lookup in turn. Lookups prefetched earlier are
+    /// discarded, so this must be called again after the transaction store
+    /// changes.
+    pub(crate) async fn prefetch<'t, I>(
+        &self,
+        transactions: I,
+        concurrency: usize,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = &'t Transaction>,
+    {
+        let txs = transactions
+            .into_iter()
+            .filter_map(|transaction| match transaction {
+                Transaction::Deposit(_) | Transaction::Withdrawal(_) => None,
+                Transaction::Dispute(_) | Transaction::Resolve(_) | Transaction::Chargeback(_) => {
+                    Some(transaction.tx())
+                }
+            })
+            .collect::<BTreeSet<TxId>>();
+        let prefetched = stream::iter(txs)
+            .map(|tx| async move { Ok((tx, self.lookup(tx).await?)) })
+            .buffer_unordered(concurrency)
+            .try_collect::<HashMap<_, _>>()
+            .await?;
+        *self
+            .prefetched
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = prefetched;
+
+        Ok(())
     }
 
     /// Processes a transaction for an account.
@@ -24,6 +92,65 @@ impl<'block_store> TxProcessor<'block_store> {
         account: &mut Account,
         transaction: Transaction,
     ) -> Result<Result<(), TxError>, Error> {
+        let tx_result = self
+            .process_transaction(account, transaction.clone())
+            .await?;
+
+        Ok(Self::record_outcome(account, &transaction, tx_result))
+    }
+
+    /// Records a rejection against the account, and emits the outcome of a
+    /// processed transaction.
+    fn record_outcome(
+        account: &mut Account,
+        transaction: &Transaction,
+        tx_result: Result<(), TxError>,
+    ) -> Result<(), TxError> {
+        if let Err(tx_error) = &tx_result {
+            account.rejections_mut().record(tx_error.code());
+        }
+
+        #[cfg(feature = "metrics")]
+        process_metrics::transaction_processed(transaction.type_name(), &tx_result);
+        #[cfg(feature = "tracing")]
+        {
+            let (client, tx) = (transaction.client(), transaction.tx());
+            match &tx_result {
+                Ok(()) => tracing::trace!(%client, %tx, "Transaction applied."),
+                Err(tx_error) => tracing::info!(
+                    %client,
+                    %tx,
+                    code = tx_error.code(),
+                    error = %tx_error,
+                    "Transaction rejected."
+                ),
+            }
+        }
+        #[cfg(not(any(feature = "metrics", feature = "tracing")))]
+        let _ = transaction;
+
+        tx_result
+    }
+
+    async fn process_transaction(
+        &self,
+        account: &mut Account,
+        transaction: Transaction,
+    ) -> Result<Result<(), TxError>, Error> {
+        // This is checked before whether the account is locked on purpose. A
+        // chargeback locks the account, so checking the lock first would
+        // report every dispute of a charged back deposit as `AccountLocked`.
+        if let Transaction::Dispute(dispute) = &transaction {
+            if account.charged_back_txs().contains(&dispute.tx()) {
+                // Charged back deposits may not be held again, so there is no
+                // need to look them up.
+                return Ok(Err(TxError::TxAlreadyChargedBack {
+                    client: account.client(),
+                    tx: dispute.tx(),
+                }));
+            }
+        }
+
         if account.locked() {
             // Don't process locked accounts.
             return Ok(Err(TxError::AccountLocked {
@@ -65,16 +192,11 @@ impl<'block_store> TxProcessor<'block_store> {
         let updated_result = available_next
             .ok_or(TxError::DepositAvailableOverflow { client, tx })
             .and_then(|available_next| {
-                Account::try_new(
-                    client,
-                    available_next,
-                    account.held(),
-                    account.locked(),
-                    account.disputed_txs().clone(),
-                )
-                .map_err(|_| TxError::DepositTotalOverflow { client, tx })
-            })
-            .map(|account_updated| *account = account_updated);
+                let held = account.held();
+                account
+                    .try_set_balances(available_next, held)
+                    .map_err(|_| TxError::DepositTotalOverflow { client, tx })
+            });
         Ok(updated_result)
     }
 
@@ -104,18 +226,11 @@ impl<'block_store> TxProcessor<'block_store> {
             }))
         } else {
             let available_next = available.saturating_sub(withdrawal_amount);
-            let account_updated = Account::try_new(
-                client,
-                available_next,
-                account.held(),
-                account.locked(),
-                account.disputed_txs().clone(),
-            )
-            .expect(
+            let held = account.held();
+            account.try_set_balances(available_next, held).expect(
                 "Overflow impossible: Withdrawal amount is less than or equal to available amount, \
                         and is non-negative.",
             );
-            *account = account_updated;
 
             Ok(Ok(()))
         }
@@ -126,8 +241,15 @@ impl<'block_store> TxProcessor<'block_store> {
         account: &mut Account,
         dispute: Dispute,
     ) -> Result<Result<(), TxError>, Error> {
+        if account.disputed_txs().contains(&dispute.tx()) {
+            // Holding the amount again would hold it twice for one deposit.
+            return Ok(Err(TxError::TxAlreadyDisputed {
+                client: account.client(),
+                tx: dispute.tx(),
+            }));
+        }
+
         let transaction = self
-            .block_store
             .find_transaction(dispute.tx())
             .await?
             .ok_or(TxError::DisputeTxNotFound { tx: dispute.tx() });
@@ -149,7 +271,7 @@ impl<'block_store> TxProcessor<'block_store> {
                         (tx, amount)
                     }
                     _ => unreachable!(
-                        "Only deposits may be disputed -- see `TxBlockStore::find_transaction`."
+                        "Only deposits may be disputed -- see `TxStore::find_transaction`."
                     ),
                 };
 
@@ -180,21 +302,13 @@ impl<'block_store> TxProcessor<'block_store> {
                     });
 
                 let update_result = held_next.map(|held_next| {
-                    let mut disputed_txs = account.disputed_txs().clone();
-                    disputed_txs.insert(tx);
-                    let account_updated = Account::try_new(
-                        client,
-                        available_next,
-                        held_next,
-                        account.locked(),
-                        disputed_txs,
-                    )
-                    .expect(
-                        "Overflow impossible: available and held amounts should equal previous total.",
-                    );
-
-                        *account = account_updated
-                    });
+                    account
+                        .try_set_balances(available_next, held_next)
+                        .expect(
+                            "Overflow impossible: available and held amounts should equal previous total.",
+                        );
+                    account.disputed_txs_mut().insert(tx);
+                });
 
                 Ok(update_result)
             }
@@ -208,14 +322,8 @@ impl<'block_store> TxProcessor<'block_store> {
         resolve: Resolve,
     ) -> Result<Result<(), TxError>, Error> {
         let resolve_tx = resolve.tx();
-        let disputed_tx = account
-            .disputed_txs()
-            .iter()
-            .find(|disputed_tx| **disputed_tx == resolve_tx);
-
-        if let Some(disputed_tx) = disputed_tx {
+        if account.disputed_txs().contains(&resolve_tx) {
             let transaction = self
-                .block_store
                 .find_transaction(resolve.tx())
                 .await?
                 .ok_or(TxError::DisputeTxNotFound { tx: resolve.tx() });
@@ -238,7 +346,7 @@ impl<'block_store> TxProcessor<'block_store> {
                             (tx, amount)
                         }
                         _ => unreachable!(
-                            "Only deposits may be disputed -- see `TxBlockStore::find_transaction`."
+                            "Only deposits may be disputed -- see `TxStore::find_transaction`."
                         ),
                     };
 
@@ -271,18 +379,10 @@ impl<'block_store> TxProcessor<'block_store> {
 
                     match available_next {
                         Ok(available_next) => {
-                            let mut disputed_txs = account.disputed_txs().clone();
-                            disputed_txs.remove(disputed_tx);
-                            let account_updated = Account::try_new(
-                                client,
-                                available_next,
-                                held_next,
-                                account.locked(),
-                                disputed_txs,
-                            )
-                            .expect("Overflow impossible: available and held amounts should equal previous total.");
-
-                            *account = account_updated;
+                            account
+                                .try_set_balances(available_next, held_next)
+                                .expect("Overflow impossible: available and held amounts should equal previous total.");
+                            account.disputed_txs_mut().remove(&tx);
                             Ok(Ok(()))
                         }
                         Err(e) => Ok(Err(e)),
@@ -305,19 +405,13 @@ impl<'block_store> TxProcessor<'block_store> {
         chargeback: Chargeback,
     ) -> Result<Result<(), TxError>, Error> {
         let chargeback_tx = chargeback.tx();
-        let disputed_tx = account
-            .disputed_txs()
-            .iter()
-            .find(|disputed_tx| **disputed_tx == chargeback_tx);
-
-        if let Some(disputed_tx) = disputed_tx {
-            let transaction = self
-                .block_store
-                .find_transaction(chargeback.tx())
-                .await?
-                .ok_or(TxError::DisputeTxNotFound {
-                    tx: chargeback.tx(),
-                });
+        if account.disputed_txs().contains(&chargeback_tx) {
+            let transaction =
+                self.find_transaction(chargeback.tx())
+                    .await?
+                    .ok_or(TxError::DisputeTxNotFound {
+                        tx: chargeback.tx(),
+                    });
             match transaction {
                 Ok(transaction) => {
                     if transaction.client() != chargeback.client() {
@@ -336,7 +430,7 @@ impl<'block_store> TxProcessor<'block_store> {
                             (tx, amount)
                         }
                         _ => unreachable!(
-                            "Only deposits may be disputed -- see `TxBlockStore::find_transaction`."
+                            "Only deposits may be disputed -- see `TxStore::find_transaction`."
                         ),
                     };
 
@@ -357,18 +451,12 @@ impl<'block_store> TxProcessor<'block_store> {
                     // never negative, as we've done the comparison above
                     let held_next = held.saturating_sub(amount);
 
-                    let mut disputed_txs = account.disputed_txs().clone();
-                    disputed_txs.remove(disputed_tx);
-                    let account_updated = Account::try_new(
-                        client,
-                        available,
-                        held_next,
-                        true,
-                        disputed_txs,
-                    )
-                    .expect("Overflow impossible: available and held amounts should be less than previous total.");
-
-                    *account = account_updated;
+                    account
+                        .try_set_balances(available, held_next)
+                        .expect("Overflow impossible: available and held amounts should be less than previous total.");
+                    account.disputed_txs_mut().remove(&tx);
+                    account.charged_back_txs_mut().insert(tx);
+                    account.lock();
 
                     Ok(Ok(()))
                 }
@@ -382,9 +470,72 @@ impl<'block_store> TxProcessor<'block_store> {
             }))
         }
     }
+
+    /// Returns whether the deposit transaction is in the transaction store,
+    /// using the prefetched lookups if it was prefetched.
+    pub(crate) async fn contains(&self, tx: TxId) -> Result<bool, Error> {
+        self.find_transaction(tx)
+            .await
+            .map(|transaction| transaction.is_some())
+    }
+
+    /// Returns the transaction, from the prefetched lookups if it was
+    /// prefetched, otherwise from the transaction store.
+    async fn find_transaction(&self, tx: TxId) -> Result<Option<Transaction>, Error> {
+        let prefetched = self
+            .prefetched
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&tx)
+            .cloned();
+        match prefetched {
+            Some(transaction) => Ok(transaction),
+            None => self.lookup(tx).await,
+        }
+    }
+
+    /// Returns the transaction from the transaction store, waiting at most the
+    /// lookup timeout if one is set.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "tx_lookup", level = "debug", skip_all, fields(%tx))
+    )]
+    async fn lookup(&self, tx: TxId) -> Result<Option<Transaction>, Error> {
+        #[cfg(feature = "tokio")]
+        if let Some(timeout) = self.lookup_timeout {
+            let lookup_result = tokio::time::timeout(timeout, self.tx_store.find_transaction(tx))
+                .await
+                .map_err(|_| Error::BlockStoreLookupTimeout { tx, timeout })?;
+            #[cfg(feature = "metrics")]
+            process_metrics::block_lookup(&lookup_result);
+            #[cfg(feature = "tracing")]
+            Self::trace_lookup(tx, &lookup_result);
+
+            return lookup_result;
+        }
+
+        let lookup_result = self.tx_store.find_transaction(tx).await;
+        #[cfg(feature = "metrics")]
+        process_metrics::block_lookup(&lookup_result);
+        #[cfg(feature = "tracing")]
+        Self::trace_lookup(tx, &lookup_result);
+
+        lookup_result
+    }
+
+    /// Logs the outcome of a transaction lookup in the transaction store.
+    #[cfg(feature = "tracing")]
+    fn trace_lookup(tx: TxId, lookup_result: &Result<Option<Transaction>, Error>) {
+        match lookup_result {
+            Ok(transaction) => {
+                tracing::debug!(%tx, found = transaction.is_some(), "Transaction looked up.")
+            }
+            Err(error) => tracing::debug!(%tx, %error, "Transaction lookup failed."),
+        }
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "fs"))]
 mod tests {
     use std::collections::HashSet;
 
@@ -396,7 +547,8 @@ mod tests {
         model::{
             Account, Chargeback, ClientId, Deposit, Dispute, Resolve, Transaction, TxId, Withdrawal,
         },
-        TxBlockStore, TxError,
+        test_util::AccountBuilder,
+        TxBlockStore, TxError, TxStore,
     };
 
     #[test]
@@ -411,14 +563,32 @@ mod tests {
         let process_result =
             tx_processor.handle_deposit(&mut account, Deposit::new(client, tx, amount))?;
 
-        let account_expected =
-            Account::try_new(client, dec!(1.0), dec!(0.0), false, HashSet::new())
-                .expect("Test data invalid.");
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
         assert_eq!(Ok(()), process_result);
         assert_eq!(account_expected, account);
         Ok(())
     }
 
+    #[test]
+    fn deposit_negative_zero_amount_is_applied() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+        let mut account = Account::empty(client);
+
+        let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
+        let tx_processor = TxProcessor::new(tx_block_store);
+        let deposit = Deposit::new(client, tx, dec!(-0.00));
+        let amount = deposit.amount();
+        let result = tx_processor.handle_deposit(&mut account, deposit);
+
+        assert!(matches!(result, Ok(Ok(()))));
+        assert!(amount.is_zero() && !amount.is_sign_negative() && amount.scale() == 0);
+        assert_eq!(Decimal::ZERO, account.available());
+        Ok(())
+    }
+
     #[test]
     fn deposit_negative_amount_returns_err() -> Result<(), Box<dyn std::error::Error>> {
         let client = ClientId::new(1);
@@ -443,8 +613,9 @@ mod tests {
         let client = ClientId::new(1);
         let tx = TxId::new(2);
         let amount = Decimal::MAX;
-        let mut account = Account::try_new(client, dec!(1.0), dec!(0.0), false, HashSet::new())
-            .expect("Test data invalid.");
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
         let tx_processor = TxProcessor::new(tx_block_store);
@@ -463,8 +634,10 @@ mod tests {
         let client = ClientId::new(1);
         let tx = TxId::new(2);
         let amount = Decimal::MAX.saturating_sub(dec!(1.0));
-        let mut account = Account::try_new(client, dec!(1.0), dec!(2.0), false, HashSet::new())
-            .expect("Test data invalid.");
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .with_held(dec!(2.0))
+            .build();
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
         let tx_processor = TxProcessor::new(tx_block_store);
@@ -484,17 +657,18 @@ mod tests {
         let client = ClientId::new(1);
         let tx = TxId::new(2);
         let amount = dec!(1.0);
-        let mut account = Account::try_new(client, dec!(2.0), dec!(0.0), false, HashSet::new())
-            .expect("Test data invalid.");
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(2.0))
+            .build();
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
         let tx_processor = TxProcessor::new(tx_block_store);
         let process_result =
             tx_processor.handle_withdrawal(&mut account, Withdrawal::new(client, tx, amount))?;
 
-        let account_expected =
-            Account::try_new(client, dec!(1.0), dec!(0.0), false, HashSet::new())
-                .expect("Test data invalid.");
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
         assert_eq!(Ok(()), process_result);
         assert_eq!(account_expected, account);
         Ok(())
@@ -506,17 +680,16 @@ mod tests {
         let client = ClientId::new(1);
         let tx = TxId::new(2);
         let amount = dec!(1.0);
-        let mut account = Account::try_new(client, dec!(1.0), dec!(0.0), false, HashSet::new())
-            .expect("Test data invalid.");
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
         let tx_processor = TxProcessor::new(tx_block_store);
         let process_result =
             tx_processor.handle_withdrawal(&mut account, Withdrawal::new(client, tx, amount))?;
 
-        let account_expected =
-            Account::try_new(client, dec!(0.0), dec!(0.0), false, HashSet::new())
-                .expect("Test data invalid.");
+        let account_expected = AccountBuilder::new(client).build();
         assert_eq!(Ok(()), process_result);
         assert_eq!(account_expected, account);
         Ok(())
@@ -528,17 +701,18 @@ mod tests {
         let client = ClientId::new(1);
         let tx = TxId::new(2);
         let amount = dec!(2.0);
-        let mut account = Account::try_new(client, dec!(1.0), dec!(0.0), false, HashSet::new())
-            .expect("Test data invalid.");
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
         let tx_processor = TxProcessor::new(tx_block_store);
         let process_result =
             tx_processor.handle_withdrawal(&mut account, Withdrawal::new(client, tx, amount))?;
 
-        let account_expected =
-            Account::try_new(client, dec!(1.0), dec!(0.0), false, HashSet::new())
-                .expect("Test data invalid.");
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(1.0))
+            .build();
         assert_eq!(
             Err(TxError::WithdrawalInsufficientAvailable {
                 client,
@@ -585,7 +759,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         tx_processor
@@ -611,7 +787,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         let process_result = tx_processor
@@ -636,7 +814,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client_one, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         let process_result = tx_processor
@@ -690,6 +870,88 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn dispute_rejected_when_tx_already_disputed() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(5.0))
+            .build();
+        let deposit = Transaction::from(Deposit::new(client, tx, dec!(2.0)));
+
+        let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
+        let tx_processor = TxProcessor::new(tx_block_store);
+        tx_processor.process(&mut account, deposit).await??;
+        tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await??;
+        let process_result = tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await?;
+
+        assert_eq!(
+            Err(TxError::TxAlreadyDisputed { client, tx }),
+            process_result
+        );
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(5.0))
+            .with_held(dec!(2.0))
+            .with_disputed_txs([tx])
+            .build();
+        assert_eq!(account_expected, account);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dispute_dispute_resolve_releases_funds_once() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(5.0))
+            .build();
+        let deposit = Transaction::from(Deposit::new(client, tx, dec!(2.0)));
+
+        let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
+        let tx_processor = TxProcessor::new(tx_block_store);
+        tx_processor.process(&mut account, deposit).await??;
+        tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await??;
+        tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await?
+            .expect_err("Expected duplicate dispute to be rejected.");
+        tx_processor
+            .process(&mut account, Transaction::from(Resolve::new(client, tx)))
+            .await??;
+        let process_result = tx_processor
+            .process(&mut account, Transaction::from(Resolve::new(client, tx)))
+            .await?;
+
+        assert_eq!(
+            Err(TxError::ResolveTxNotInDispute { client, tx }),
+            process_result
+        );
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(7.0))
+            .build();
+        assert_eq!(account_expected, account);
+
+        // The transaction may be disputed again once the dispute is resolved.
+        tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await??;
+        assert_eq!(dec!(2.0), account.held());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn resolve_returns_held_funds_to_available() -> Result<(), Box<dyn std::error::Error>> {
         let client = ClientId::new(1);
@@ -702,7 +964,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         tx_processor
@@ -730,7 +994,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         let process_result = tx_processor
@@ -758,7 +1024,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client_one, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         tx_processor
@@ -795,7 +1063,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         tx_processor
@@ -807,7 +1077,48 @@ mod tests {
 
         // Start with 5.0, deposit 2.0, dispute 2.0, chargeback 2.0
         let account_expected = Account::try_new(client, dec!(5.0), dec!(0.0), true, HashSet::new())
-            .expect("test_data_invalid");
+            .expect("test_data_invalid")
+            .with_charged_back_txs(HashSet::from([tx]));
+        assert_eq!(account_expected, account);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dispute_rejected_when_tx_already_charged_back()
+    -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let tx = TxId::new(2);
+        let amount = dec!(2.0);
+        let mut account = AccountBuilder::new(client)
+            .with_available(dec!(5.0))
+            .build();
+        let deposit = Transaction::from(Deposit::new(client, tx, amount));
+
+        let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
+        let tx_processor = TxProcessor::new(tx_block_store);
+        tx_processor.process(&mut account, deposit).await??;
+        tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await??;
+        tx_processor
+            .process(&mut account, Transaction::from(Chargeback::new(client, tx)))
+            .await??;
+        let process_result = tx_processor
+            .process(&mut account, Transaction::from(Dispute::new(client, tx)))
+            .await?;
+
+        assert_eq!(
+            Err(TxError::TxAlreadyChargedBack { client, tx }),
+            process_result
+        );
+        let account_expected = AccountBuilder::new(client)
+            .with_available(dec!(5.0))
+            .with_locked(true)
+            .build()
+            .with_charged_back_txs(HashSet::from([tx]));
         assert_eq!(account_expected, account);
         Ok(())
     }
@@ -822,7 +1133,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         let process_result = tx_processor
@@ -850,7 +1163,9 @@ mod tests {
         let deposit = Transaction::from(Deposit::new(client_one, tx, amount));
 
         let tx_block_store = &TxBlockStore::try_new().expect("Failed to initialize block store.");
-        tx_block_store.persist_block(&[deposit.clone()]).await?;
+        tx_block_store
+            .persist_block(std::slice::from_ref(&deposit))
+            .await?;
         let tx_processor = TxProcessor::new(tx_block_store);
         tx_processor.process(&mut account, deposit).await??;
         tx_processor
@@ -0,0 +1,227 @@
+use std::{
+    path::{Path, PathBuf},
+    pin::pin,
+};
+
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStream, TryStreamExt,
+};
+use tempfile::TempDir;
+use tokio::fs::File;
+
+use crate::{
+    csv::TxRecord,
+    model::{Transaction, TxId},
+    Error, TransactCsv,
+};
+
+/// Number of transactions to sort in memory per run file.
+pub(crate) const SORT_RUN_SIZE: usize = 100_000;
+
+/// Transactions sorted by transaction ID in run files in a temporary
+/// directory.
+///
+/// Transactions are read in runs of up to `run_size` transactions, and each
+/// run is sorted in memory and written to its own file. The runs are then
+/// merged when streamed, so only one transaction per run is held in memory.
+///
+/// Disputes, resolves, and chargebacks share the ID of the transaction they
+/// refer to, and are ordered after it. Otherwise sorting is stable, so a
+/// dispute and its resolve stay in the order they were read.
+#[derive(Debug)]
+pub(crate) struct SortedRuns {
+    /// Directory holding the run files.
+    temp_dir: TempDir,
+    /// Number of run files written.
+    run_count: usize,
+    /// Malformed records read from the input, in input order.
+    record_errors: Vec<Error>,
+}
+
+impl SortedRuns {
+    /// Reads all transactions and writes them to sorted run files.
+    ///
+    /// Malformed records are kept to be streamed before the transactions, and
+    /// any other error stops reading.
+    ///
+    /// # Parameters
+    ///
+    /// * `transactions`: Stream of transactions to sort.
+    /// * `run_size`: Maximum number of transactions to sort in memory.
+    /// * `dir`: Directory to create the run directory in, defaults to the
+    ///   system temporary directory.
+    pub(crate) async fn write<T>(
+        transactions: T,
+        run_size: usize,
+        dir: Option<&Path>,
+    ) -> Result<Self, Error>
+    where
+        T: TryStream<Ok = Transaction, Error = Error>,
+    {
+        let temp_dir = match dir {
+            Some(dir) => tempfile::tempdir_in(dir),
+            None => tempfile::tempdir(),
+        }
+        .map_err(Error::SortDirCreate)?;
+        let mut sorted_runs = Self {
+            temp_dir,
+            run_count: 0,
+            record_errors: Vec::new(),
+        };
+
+        let mut transactions = pin!(transactions.into_stream());
+        let mut run = Vec::with_capacity(run_size);
+        while let Some(transaction) = transactions.next().await {
+            match transaction {
+                Ok(transaction) => {
+                    run.push(transaction);
+                    if run.len() >= run_size {
+                        sorted_runs.write_run(&mut run).await?;
+                    }
+                }
+                Err(error) if error.is_record_error() => sorted_runs.record_errors.push(error),
+                Err(error) => return Err(error),
+            }
+        }
+        if !run.is_empty() {
+            sorted_runs.write_run(&mut run).await?;
+        }
+
+        Ok(sorted_runs)
+    }
+
+    /// Returns a stream of the malformed records, followed by the
+    /// transactions in order of transaction ID.
+    ///
+    /// The run files are removed when the stream is dropped.
+    pub(crate) async fn into_stream(
+        self,
+    ) -> Result<impl TryStream<Ok = Transaction, Error = Error>, Error> {
+        let Self {
+            temp_dir,
+            run_count,
+            record_errors,
+        } = self;
+
+        let mut runs = Vec::with_capacity(run_count);
+        for run_index in 0..run_count {
+            let mut run = TransactCsv::stream(&Self::run_path(&temp_dir, run_index))
+                .await?
+                .into_stream()
+                .boxed();
+            let head = run.next().await;
+            runs.push((run, head));
+        }
+
+        let transactions = stream::unfold((temp_dir, runs), |(temp_dir, mut runs)| async move {
+            let run_index = Self::next_run_index(&runs)?;
+            let (run, head) = &mut runs[run_index];
+            let next = run.next().await;
+            let transaction = std::mem::replace(head, next)?;
+
+            Some((transaction, (temp_dir, runs)))
+        });
+
+        Ok(stream::iter(record_errors.into_iter().map(Err)).chain(transactions))
+    }
+
+    /// Sorts the run by transaction ID and writes it to the next run file.
+    async fn write_run(&mut self, run: &mut Vec<Transaction>) -> Result<(), Error> {
+        run.sort_by_key(Self::sort_key);
+
+        let run_path = Self::run_path(&self.temp_dir, self.run_count);
+        let run_file = File::create(&run_path)
+            .await
+            .map_err(Error::SortRunCreate)?;
+        let mut run_writer = TransactCsv::csv_writer(run_file);
+        for transaction in run.drain(..) {
+            run_writer
+                .serialize(TxRecord::from(transaction))
+                .await
+                .map_err(Error::SortRunWrite)?;
+        }
+        run_writer.flush().await.map_err(Error::SortRunFlush)?;
+        self.run_count += 1;
+
+        Ok(())
+    }
+
+    /// Returns the index of the run whose head is next in the merged stream.
+    ///
+    /// Errors reading a run come first. Transactions with the same sort key
+    /// are taken from the earliest run, which keeps them in input order.
+    fn next_run_index(runs: &[(RunStream, Option<Result<Transaction, Error>>)]) -> Option<usize> {
+        runs.iter()
+            .enumerate()
+            .filter_map(|(run_index, (_run, head))| {
+                let head = head.as_ref()?;
+                Some((head.as_ref().ok().map(Self::sort_key), run_index))
+            })
+            .min()
+            .map(|(_tx, run_index)| run_index)
+    }
+
+    /// Returns the key to sort a transaction by.
+    ///
+    /// This is the transaction ID, and whether the transaction refers to an
+    /// earlier transaction, so that a deposit or withdrawal comes before the
+    /// disputes, resolves, and chargebacks of it.
+    fn sort_key(transaction: &Transaction) -> (TxId, bool) {
+        let refers_to_tx = match transaction {
+            Transaction::Deposit(_) | Transaction::Withdrawal(_) => false,
+            Transaction::Dispute(_) | Transaction::Resolve(_) | Transaction::Chargeback(_) => true,
+        };
+        (transaction.tx(), refers_to_tx)
+    }
+
+    /// Returns the path to a run file.
+    fn run_path(temp_dir: &TempDir, run_index: usize) -> PathBuf {
+        temp_dir.path().join(format!("{run_index}.csv"))
+    }
+}
+
+/// Stream of transactions read from a run file.
+type RunStream = BoxStream<'static, Result<Transaction, Error>>;
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, TryStreamExt};
+    use rust_decimal_macros::dec;
+
+    use super::SortedRuns;
+    use crate::{
+        csv::TxRecord,
+        model::{ClientId, Deposit, Dispute, Resolve, Transaction, TxId},
+    };
+
+    #[tokio::test]
+    async fn into_stream_merges_runs_in_tx_order() -> Result<(), Box<dyn std::error::Error>> {
+        let client = ClientId::new(1);
+        let transactions = [
+            Transaction::from(Dispute::new(client, TxId::new(3))),
+            Transaction::from(Deposit::new(client, TxId::new(1), dec!(2.0))),
+            Transaction::from(Deposit::new(client, TxId::new(3), dec!(1.0))),
+            Transaction::from(Deposit::new(client, TxId::new(2), dec!(3.0))),
+            Transaction::from(Resolve::new(client, TxId::new(3))),
+        ];
+
+        let sorted_runs =
+            SortedRuns::write(stream::iter(transactions.clone().map(Ok)), 2, None).await?;
+        let sorted = sorted_runs
+            .into_stream()
+            .await?
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let [dispute_3, deposit_1, deposit_3, deposit_2, resolve_3] = transactions;
+        assert_eq!(
+            [deposit_1, deposit_2, deposit_3, dispute_3, resolve_3]
+                .into_iter()
+                .map(TxRecord::from)
+                .collect::<Vec<_>>(),
+            sorted.into_iter().map(TxRecord::from).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use std::{fmt, future::Future, ops::Range};
+
+use crate::{
+    model::{Transaction, TxId},
+    Error,
+};
+
+/// Stores transactions so they can be looked up when processing disputes.
+///
+/// Only deposits need to be found, as they are the only transactions that may
+/// be disputed.
+///
+/// After processing, the store may also be queried for the original details of
+/// a deposit, e.g. the amount of a disputed transaction.
+pub trait TxStore: fmt::Debug {
+    /// Persists the given block of transactions in this store.
+    fn persist_block(
+        &self,
+        transactions: &[Transaction],
+    ) -> impl Future<Output = Result<(), Error>>;
+
+    /// Returns the deposit transaction with the given ID if it is in this
+    /// store.
+    fn find_transaction(
+        &self,
+        tx: TxId,
+    ) -> impl Future<Output = Result<Option<Transaction>, Error>>;
+
+    /// Returns whether the deposit transaction with the given ID is in this
+    /// store.
+    fn contains(&self, tx: TxId) -> impl Future<Output = Result<bool, Error>> {
+        async move {
+            self.find_transaction(tx)
+                .await
+                .map(|transaction| transaction.is_some())
+        }
+    }
+
+    /// Returns the deposits whose IDs are within `range`, ordered by
+    /// transaction ID.
+    ///
+    /// The default implementation filters [`TxStore::deposits`].
+    fn iter_range(
+        &self,
+        range: Range<TxId>,
+    ) -> impl Future<Output = Result<Vec<Transaction>, Error>> {
+        async move {
+            let mut deposits = self.deposits().await?;
+            deposits.retain(|transaction| range.contains(&transaction.tx()));
+            deposits.sort_by_key(Transaction::tx);
+            Ok(deposits)
+        }
+    }
+
+    /// Returns all deposits in this store, in no particular order.
+    ///
+    /// This is used to snapshot the store, and reads every stored deposit.
+    fn deposits(&self) -> impl Future<Output = Result<Vec<Transaction>, Error>>;
+}